@@ -0,0 +1,119 @@
+//! Automated players for the headless `flappy_core` simulation.
+
+use std::num::NonZeroUsize;
+use std::thread;
+
+use flappy_core::sim::{SimConfig, Simulation};
+
+/// Something that decides whether to flap each tick.
+pub trait Agent {
+    fn flap(&mut self, sim: &Simulation) -> bool;
+}
+
+/// Flaps whenever the bird is falling below the centre of the next gap.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GapFollower;
+
+impl Agent for GapFollower {
+    fn flap(&mut self, sim: &Simulation) -> bool {
+        let target = sim.next_gap().map_or(0.0, |(_, gap)| gap.center);
+        sim.bird.position.y < target - 8.0 && sim.bird.velocity <= 0.0
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EpisodeResult {
+    /// Seconds survived, capped at the episode length.
+    pub survived: f32,
+    pub alive: bool,
+    pub score: u32,
+}
+
+/// Plays one game at a fixed timestep until the bird dies or `max_seconds` pass.
+pub fn run_episode(
+    agent: &mut impl Agent,
+    config: SimConfig,
+    seed: u64,
+    dt: f32,
+    max_seconds: f32,
+) -> EpisodeResult {
+    let mut sim = Simulation::new(config, seed);
+    while sim.alive && sim.elapsed < max_seconds {
+        let flap = agent.flap(&sim);
+        sim.step(dt, flap);
+    }
+    EpisodeResult {
+        survived: sim.elapsed,
+        alive: sim.alive,
+        score: sim.score,
+    }
+}
+
+/// Plays each episode, an agent and the seed of its game, spread across a
+/// thread per core. Results come back in the same order as the episodes, and
+/// are the same as playing them one at a time. An agent's panic is carried
+/// on to the caller.
+pub fn run_batch<A: Agent + Send>(
+    episodes: &mut [(A, u64)],
+    config: SimConfig,
+    dt: f32,
+    max_seconds: f32,
+) -> Vec<EpisodeResult> {
+    if episodes.is_empty() {
+        return Vec::new();
+    }
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk = episodes.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let workers: Vec<_> = episodes
+            .chunks_mut(chunk)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter_mut()
+                        .map(|(agent, seed)| run_episode(agent, config, *seed, dt, max_seconds))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
+/// Totals over a batch of episodes, for comparing agents or balance changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BatchSummary {
+    pub episodes: usize,
+    /// Episodes still alive when their time ran out.
+    pub alive: usize,
+    pub mean_survived: f32,
+    pub mean_score: f32,
+    pub best_score: u32,
+}
+
+impl BatchSummary {
+    pub fn of(results: &[EpisodeResult]) -> Self {
+        if results.is_empty() {
+            return Self::default();
+        }
+        let count = results.len() as f32;
+        Self {
+            episodes: results.len(),
+            alive: results.iter().filter(|result| result.alive).count(),
+            mean_survived: results.iter().map(|result| result.survived).sum::<f32>() / count,
+            mean_score: results
+                .iter()
+                .map(|result| result.score as f32)
+                .sum::<f32>()
+                / count,
+            best_score: results.iter().map(|result| result.score).max().unwrap_or(0),
+        }
+    }
+}
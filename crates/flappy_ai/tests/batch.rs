@@ -0,0 +1,36 @@
+use flappy_ai::{run_batch, run_episode, Agent, BatchSummary, GapFollower};
+use flappy_core::sim::{SimConfig, Simulation};
+
+const DT: f32 = 1.0 / 60.0;
+
+#[test]
+fn batches_match_episodes_played_one_at_a_time() {
+    let config = SimConfig::default();
+    let mut episodes: Vec<_> = (0..13).map(|seed| (GapFollower, seed)).collect();
+    let batch = run_batch(&mut episodes, config, DT, 20.0);
+    let sequential: Vec<_> = (0..13)
+        .map(|seed| run_episode(&mut GapFollower, config, seed, DT, 20.0))
+        .collect();
+    assert_eq!(batch, sequential);
+    assert_eq!(BatchSummary::of(&batch), BatchSummary::of(&sequential));
+    assert_eq!(BatchSummary::of(&batch).episodes, 13);
+    assert!(run_batch::<GapFollower>(&mut [], config, DT, 20.0).is_empty());
+}
+
+/// Gives up partway through its episode.
+struct Quitter;
+
+impl Agent for Quitter {
+    fn flap(&mut self, sim: &Simulation) -> bool {
+        if sim.elapsed > 1.0 {
+            panic!("quitter quit");
+        }
+        false
+    }
+}
+
+#[test]
+#[should_panic(expected = "quitter quit")]
+fn a_panicking_agent_panics_the_batch_with_its_message() {
+    run_batch(&mut [(Quitter, 1)], SimConfig::default(), DT, 5.0);
+}
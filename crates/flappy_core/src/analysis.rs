@@ -0,0 +1,167 @@
+//! Statistics over many generated pipe gaps, for tuning `GapParams`: how gap
+//! sizes and heights are spread, and how often one gap follows another too far
+//! away for the bird to reach comfortably.
+
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::physics::VELOCITY_SCALE;
+use crate::pipes::Gap;
+use crate::sim::SimConfig;
+use crate::PIPE_SIZE;
+
+/// Buckets in each `Distribution`'s histogram.
+pub const BUCKETS: usize = 10;
+/// Transitions needing more than this fraction of the bird's fastest climb or
+/// dive count as borderline.
+pub const BORDERLINE: f32 = 0.8;
+
+/// How a value was spread over a range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Distribution {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// Counts in `BUCKETS` equal slices of `range`.
+    pub histogram: [usize; BUCKETS],
+    /// The range the values were drawn from.
+    pub range: (f32, f32),
+}
+
+impl Distribution {
+    fn of(values: impl Iterator<Item = f32>, range: (f32, f32)) -> Self {
+        let mut distribution = Distribution {
+            min: f32::INFINITY,
+            max: f32::NEG_INFINITY,
+            mean: 0.0,
+            histogram: [0; BUCKETS],
+            range,
+        };
+        let mut count = 0;
+        for value in values {
+            count += 1;
+            distribution.min = distribution.min.min(value);
+            distribution.max = distribution.max.max(value);
+            distribution.mean += value;
+            let along = (value - range.0) / (range.1 - range.0);
+            let bucket = (along * BUCKETS as f32).clamp(0.0, (BUCKETS - 1) as f32) as usize;
+            distribution.histogram[bucket] += 1;
+        }
+        distribution.mean /= count.max(1) as f32;
+        distribution
+    }
+}
+
+/// The findings of `analyse_gaps`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GapReport {
+    pub pairs: usize,
+    pub sizes: Distribution,
+    pub centers: Distribution,
+    /// Transitions needing over `BORDERLINE` of the bird's reach, but no more
+    /// than all of it.
+    pub borderline: usize,
+    /// Transitions beyond the bird's reach, or into a gap it doesn't fit.
+    pub impossible: usize,
+}
+
+/// Generates `pairs` gaps as a run with `seed` would, and studies them.
+pub fn analyse_gaps(config: &SimConfig, pairs: usize, seed: u64) -> GapReport {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let gaps: Vec<Gap> = (0..pairs).map(|_| config.gaps.sample(&mut rng)).collect();
+    let mut interval = config.pacing.start;
+    let efforts: Vec<f32> = gaps
+        .windows(2)
+        .map(|pair| {
+            interval = config.pacing.next(interval);
+            transition_effort(pair[0], pair[1], interval, config)
+        })
+        .collect();
+    let params = config.gaps;
+    GapReport {
+        pairs,
+        sizes: Distribution::of(
+            gaps.iter().map(|gap| gap.size),
+            (params.min_size, params.max_size),
+        ),
+        centers: Distribution::of(
+            gaps.iter().map(|gap| gap.center),
+            (params.min_center, params.max_center),
+        ),
+        borderline: efforts
+            .iter()
+            .filter(|effort| (BORDERLINE..=1.0).contains(*effort))
+            .count(),
+        impossible: efforts.iter().filter(|effort| **effort > 1.0).count(),
+    }
+}
+
+/// How much of the bird's reach getting from `from` to the gap `to`,
+/// `interval` seconds behind it, takes: the height it has to cover between
+/// leaving one and entering the other, over the furthest it can climb or fall
+/// in that time. Over 1 can't be done, and infinite if the bird doesn't fit
+/// through `to`.
+pub fn transition_effort(from: Gap, to: Gap, interval: f32, config: &SimConfig) -> f32 {
+    let bird = config.rules.bird_hitbox();
+    if from.size <= bird.y || to.size <= bird.y {
+        return f32::INFINITY;
+    }
+    // the bird can be anywhere it fits inside each gap
+    let slack = (from.size - bird.y) / 2.0 + (to.size - bird.y) / 2.0;
+    let height = ((to.center - from.center).abs() - slack).max(0.0);
+    if height == 0.0 {
+        return 0.0;
+    }
+
+    let physics = config.physics;
+    let speed = physics.pipe_speed * VELOCITY_SCALE;
+    let open = speed * interval - PIPE_SIZE.x - bird.x;
+    let seconds = (open / speed).max(0.0);
+    let reach = if to.center > from.center {
+        // flapping every frame holds the jump velocity
+        physics.jump_velocity * VELOCITY_SCALE * seconds
+    } else {
+        // falling from a standstill
+        physics.gravity * VELOCITY_SCALE * seconds * seconds / 2.0
+    };
+    height / reach
+}
+
+impl fmt::Display for GapReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let transitions = self.pairs.saturating_sub(1).max(1) as f32;
+        let percent = |count: usize| 100.0 * count as f32 / transitions;
+        writeln!(f, "{} pairs", self.pairs)?;
+        for (name, distribution) in [("size", &self.sizes), ("centre", &self.centers)] {
+            let histogram: Vec<String> = distribution
+                .histogram
+                .iter()
+                .map(|count| format!("{:.0}", 100.0 * *count as f32 / self.pairs.max(1) as f32))
+                .collect();
+            writeln!(
+                f,
+                "{name}: min {:.1} mean {:.1} max {:.1}, % per tenth of {:.0}..{:.0}: {}",
+                distribution.min,
+                distribution.mean,
+                distribution.max,
+                distribution.range.0,
+                distribution.range.1,
+                histogram.join(" "),
+            )?;
+        }
+        writeln!(
+            f,
+            "borderline transitions: {} ({:.1}%)",
+            self.borderline,
+            percent(self.borderline)
+        )?;
+        write!(
+            f,
+            "impossible transitions: {} ({:.1}%)",
+            self.impossible,
+            percent(self.impossible)
+        )
+    }
+}
@@ -0,0 +1,136 @@
+use glam::Vec2;
+
+use crate::pipes::GapParams;
+
+/// Axis-aligned box described by its centre and full size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Aabb {
+    pub center: Vec2,
+    pub size: Vec2,
+}
+
+impl Aabb {
+    pub fn new(center: Vec2, size: Vec2) -> Self {
+        Self { center, size }
+    }
+
+    pub fn min(&self) -> Vec2 {
+        self.center - self.size / 2.0
+    }
+
+    pub fn max(&self) -> Vec2 {
+        self.center + self.size / 2.0
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.max().x > other.min().x
+            && self.min().x < other.max().x
+            && self.max().y > other.min().y
+            && self.min().y < other.max().y
+    }
+
+    /// How far apart the edges of the boxes are along whichever axis they're
+    /// furthest apart on. Negative when they overlap.
+    pub fn distance(&self, other: &Aabb) -> f32 {
+        let apart = (self.center - other.center).abs() - (self.size + other.size) / 2.0;
+        apart.max_element()
+    }
+
+    /// Unit normal pointing from `other` towards `self` along the axis of least
+    /// penetration: the direction `self` was hit from.
+    pub fn contact_normal(&self, other: &Aabb) -> Vec2 {
+        let delta = self.center - other.center;
+        let overlap = (self.size + other.size) / 2.0 - delta.abs();
+        if overlap.x < overlap.y {
+            Vec2::new(delta.x.signum(), 0.0)
+        } else {
+            Vec2::new(0.0, delta.y.signum())
+        }
+    }
+}
+
+/// How far the floor and ceiling boxes extend beyond the playfield.
+pub const BOUNDARY_THICKNESS: f32 = 100.0;
+
+/// The (floor, ceiling) of a playfield of `size` centred on the origin: boxes
+/// spanning its width whose inner edges sit on the bottom and top of the screen.
+pub fn boundaries(size: Vec2) -> (Aabb, Aabb) {
+    let offset = (size.y + BOUNDARY_THICKNESS) / 2.0;
+    let box_size = Vec2::new(size.x, BOUNDARY_THICKNESS);
+    (
+        Aabb::new(Vec2::new(0.0, -offset), box_size),
+        Aabb::new(Vec2::new(0.0, offset), box_size),
+    )
+}
+
+/// Rolling hills along the floor. The ground rises and falls by up to
+/// `amplitude` above the bottom of the playfield as the level scrolls by.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroundParams {
+    pub amplitude: f32,
+    /// World units from one crest of the longer wave to the next.
+    pub wavelength: f32,
+}
+
+impl GroundParams {
+    /// Ground that stays on the bottom edge, as in the original game.
+    pub const FLAT: GroundParams = GroundParams {
+        amplitude: 0.0,
+        wavelength: 1.0,
+    };
+
+    /// Hills rising halfway up to the bottom of the lowest, widest gap from
+    /// `GapParams::for_height`, so they never block one. Flat on playfields
+    /// too short to leave room under that gap.
+    pub fn for_height(height: f32) -> Self {
+        let gaps = GapParams::for_height(height);
+        let lowest = gaps.min_center - gaps.max_size / 2.0;
+        Self {
+            amplitude: (lowest + height / 2.0).max(0.0) / 2.0,
+            wavelength: height * 4.0,
+        }
+    }
+
+    /// Height of the ground above the bottom of the playfield, `distance`
+    /// world units into the level. Starts at zero. Two waves are mixed so
+    /// the hills don't repeat too plainly.
+    pub fn rise(&self, distance: f32) -> f32 {
+        let turns = distance / self.wavelength;
+        let wave = 0.6 * cos_turns(turns) + 0.4 * cos_turns(turns * 2.3);
+        self.amplitude * (1.0 - wave) / 2.0
+    }
+
+    /// The floor from `boundaries`, raised to the ground `distance` world
+    /// units into the level.
+    pub fn floor(&self, size: Vec2, distance: f32) -> Aabb {
+        let (mut floor, _) = boundaries(size);
+        floor.center.y += self.rise(distance);
+        floor
+    }
+}
+
+/// Cosine of an angle of `turns` whole turns. Worked out with a polynomial
+/// rather than the platform's `cos`, whose last bits differ from one target
+/// to the next, so the hills are the same everywhere. Within 1e-4 of the
+/// real thing.
+pub fn cos_turns(turns: f32) -> f32 {
+    // fold into the first quarter turn, where the series converges quickly:
+    // cos is even and mirrored about each quarter
+    let half = (turns - turns.round()).abs();
+    let (quarter, sign) = if half > 0.25 {
+        (0.5 - half, -1.0)
+    } else {
+        (half, 1.0)
+    };
+    let x = quarter * std::f32::consts::TAU;
+    let x2 = x * x;
+    let series = 1.0 + x2 * (-1.0 / 2.0 + x2 * (1.0 / 24.0 + x2 * (-1.0 / 720.0 + x2 / 40320.0)));
+    sign * series
+}
+
+/// Returns true if `position` is further than `margin` outside a playfield of
+/// `size` centred on the origin.
+pub fn offscreen(position: Vec2, size: Vec2, margin: f32) -> bool {
+    let half = size / 2.0 + Vec2::splat(margin);
+    position.x < -half.x || position.x > half.x || position.y < -half.y || position.y > half.y
+}
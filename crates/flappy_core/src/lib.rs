@@ -0,0 +1,21 @@
+//! Rendering-free rules for Flappy Bevy.
+//!
+//! Everything in here is plain Rust so it can be unit tested and simulated
+//! headlessly; `flappy_game` calls into it from its Bevy systems.
+
+pub mod analysis;
+pub mod collision;
+pub mod mask;
+pub mod medal;
+pub mod physics;
+pub mod pipes;
+pub mod replay;
+pub mod rules;
+pub mod sim;
+
+pub use glam::Vec2;
+
+/// Size of the pipe sprites, in world units.
+pub const PIPE_SIZE: Vec2 = Vec2::new(26.0, 160.0);
+/// Size of the bird sprite, in world units.
+pub const BIRD_SIZE: Vec2 = Vec2::new(17.0, 12.0);
@@ -0,0 +1,58 @@
+use glam::Vec2;
+
+/// Pixels with at least this alpha count as solid.
+pub const ALPHA_THRESHOLD: u8 = 128;
+
+/// Which pixels of a sprite are opaque, for collisions that ignore the
+/// transparent corners of its bounding box. Row 0 is the top of the image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PixelMask {
+    width: u32,
+    height: u32,
+    opaque: Vec<bool>,
+}
+
+impl PixelMask {
+    /// Builds a mask from one alpha value per pixel, in row-major order.
+    pub fn from_alpha(width: u32, height: u32, alpha: impl IntoIterator<Item = u8>) -> Self {
+        let opaque: Vec<bool> = alpha.into_iter().map(|a| a >= ALPHA_THRESHOLD).collect();
+        assert_eq!(opaque.len(), (width * height) as usize, "wrong pixel count");
+        Self {
+            width,
+            height,
+            opaque,
+        }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width as f32, self.height as f32)
+    }
+
+    /// Whether the pixel at column `x`, row `y` is opaque. Pixels outside the
+    /// image are transparent.
+    pub fn opaque_at(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return false;
+        }
+        self.opaque[(y as u32 * self.width + x as u32) as usize]
+    }
+
+    /// Whether any opaque pixel of this mask centred on `center` covers an
+    /// opaque pixel of `other` centred on `other_center`. Both sprites are
+    /// assumed to be drawn at one pixel per world unit.
+    pub fn overlaps(&self, center: Vec2, other: &PixelMask, other_center: Vec2) -> bool {
+        let top_left = center + Vec2::new(-self.size().x, self.size().y) / 2.0;
+        let other_top_left = other_center + Vec2::new(-other.size().x, other.size().y) / 2.0;
+        (0..self.height as i32).any(|y| {
+            (0..self.width as i32).any(|x| {
+                if !self.opaque_at(x, y) {
+                    return false;
+                }
+                let world = top_left + Vec2::new(x as f32 + 0.5, -(y as f32 + 0.5));
+                let other_x = (world.x - other_top_left.x).floor() as i32;
+                let other_y = (other_top_left.y - world.y).floor() as i32;
+                other.opaque_at(other_x, other_y)
+            })
+        })
+    }
+}
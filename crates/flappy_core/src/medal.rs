@@ -0,0 +1,24 @@
+//! The medals awarded on the game over screen, one every ten points up to
+//! platinum at forty.
+
+/// Award for a run's final score, with the original game's thresholds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+}
+
+impl Medal {
+    /// Best medal earned by `score`, if any.
+    pub fn for_score(score: u32) -> Option<Medal> {
+        match score {
+            40.. => Some(Medal::Platinum),
+            30.. => Some(Medal::Gold),
+            20.. => Some(Medal::Silver),
+            10.. => Some(Medal::Bronze),
+            _ => None,
+        }
+    }
+}
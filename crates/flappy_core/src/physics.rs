@@ -0,0 +1,32 @@
+/// Velocities are stored in units per 1/100th of a second, as the original
+/// tuning was done that way.
+pub const VELOCITY_SCALE: f32 = 100.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Physics {
+    pub gravity: f32,
+    pub jump_velocity: f32,
+    pub pipe_speed: f32,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            gravity: 7.0,
+            jump_velocity: 2.0,
+            pipe_speed: 2.0,
+        }
+    }
+}
+
+impl Physics {
+    /// Vertical velocity after falling for `dt` seconds.
+    pub fn fall(&self, velocity_y: f32, dt: f32) -> f32 {
+        velocity_y - self.gravity * dt
+    }
+}
+
+/// Distance travelled in `dt` seconds at `velocity`.
+pub fn displacement(velocity: f32, dt: f32) -> f32 {
+    velocity * dt * VELOCITY_SCALE
+}
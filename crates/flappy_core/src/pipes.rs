@@ -0,0 +1,161 @@
+use glam::Vec2;
+use rand::Rng;
+
+use crate::collision::Aabb;
+use crate::sim::PIPE_INTERVAL;
+
+use crate::{BIRD_SIZE, PIPE_SIZE};
+
+/// A hole between a top and bottom pipe.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gap {
+    /// Vertical position of the centre of the hole.
+    pub center: f32,
+    pub size: f32,
+}
+
+impl Gap {
+    /// Centre heights of the (top, bottom) pipe sprites framing this gap.
+    pub fn pipe_centers(&self) -> (f32, f32) {
+        let top = PIPE_SIZE.y / 2.0 + self.center + self.size / 2.0;
+        let bottom = -PIPE_SIZE.y / 2.0 + self.center - self.size / 2.0;
+        (top, bottom)
+    }
+
+    /// The open area between the pipes of a pair centred at `x`. Passing
+    /// through it scores a point.
+    pub fn sensor(&self, x: f32) -> Aabb {
+        Aabb::new(Vec2::new(x, self.center), Vec2::new(PIPE_SIZE.x, self.size))
+    }
+
+    fn bottom(&self) -> f32 {
+        self.center - self.size / 2.0
+    }
+
+    fn top(&self) -> f32 {
+        self.center + self.size / 2.0
+    }
+
+    /// The smallest gap covering this one and `other`.
+    pub fn span(&self, other: Gap) -> Gap {
+        let (bottom, top) = (
+            self.bottom().min(other.bottom()),
+            self.top().max(other.top()),
+        );
+        Gap {
+            center: (bottom + top) / 2.0,
+            size: top - bottom,
+        }
+    }
+
+    /// The space between this gap and `other`, which don't overlap.
+    pub fn between(&self, other: Gap) -> Gap {
+        let (lower, upper) = if self.center < other.center {
+            (self, other)
+        } else {
+            (&other, *self)
+        };
+        Gap {
+            center: (lower.top() + upper.bottom()) / 2.0,
+            size: upper.bottom() - lower.top(),
+        }
+    }
+}
+
+/// Ranges gaps are sampled from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GapParams {
+    pub min_size: f32,
+    pub max_size: f32,
+    pub min_center: f32,
+    pub max_center: f32,
+}
+
+impl GapParams {
+    /// The default ranges for a playfield of the given height.
+    pub fn for_height(height: f32) -> Self {
+        Self {
+            min_size: 40.0,
+            max_size: 100.0,
+            min_center: -height / 4.0,
+            max_center: height / 4.0,
+        }
+    }
+
+    pub fn sample(&self, rng: &mut impl Rng) -> Gap {
+        Gap {
+            size: rng.gen_range(self.min_size..self.max_size),
+            center: rng.gen_range(self.min_center..self.max_center),
+        }
+    }
+}
+
+/// Occasional golden gaps: smaller second holes beside a pair's main gap, past
+/// a short stretch of pipe, worth more to get through.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GoldenParams {
+    /// Chance of each pair having one.
+    pub chance: f64,
+    pub size: f32,
+    /// Height of the pipe between the two gaps.
+    pub divider: f32,
+}
+
+impl Default for GoldenParams {
+    fn default() -> Self {
+        Self {
+            chance: 0.1,
+            size: BIRD_SIZE.y * 2.0,
+            divider: 16.0,
+        }
+    }
+}
+
+impl GoldenParams {
+    /// The golden gap beside `main`, if there's to be one, on whichever side
+    /// is nearer the middle of the playfield.
+    pub fn sample(&self, main: Gap, rng: &mut impl Rng) -> Option<Gap> {
+        if !rng.gen_bool(self.chance) {
+            return None;
+        }
+        let offset = main.size / 2.0 + self.divider + self.size / 2.0;
+        Some(Gap {
+            center: main.center - offset * main.center.signum(),
+            size: self.size,
+        })
+    }
+}
+
+/// How the time between pipe pairs shrinks through a run, so the pace picks
+/// up. The default keeps the original one pair a second throughout.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pacing {
+    /// Seconds before the first pair.
+    pub start: f32,
+    /// Seconds taken off the interval with each pair.
+    pub step: f32,
+    /// Shortest interval.
+    pub floor: f32,
+}
+
+impl Default for Pacing {
+    fn default() -> Self {
+        Self {
+            start: PIPE_INTERVAL,
+            step: 0.0,
+            floor: PIPE_INTERVAL,
+        }
+    }
+}
+
+impl Pacing {
+    /// Seconds until the pair after one that came `interval` after the last.
+    pub fn next(&self, interval: f32) -> f32 {
+        (interval - self.step).max(self.floor)
+    }
+}
+
+/// Returns true once a pipe at `pipe_x` is entirely behind a bird at `bird_x`.
+pub fn cleared(pipe_x: f32, bird_x: f32) -> bool {
+    pipe_x + PIPE_SIZE.x / 2.0 < bird_x - BIRD_SIZE.x / 2.0
+}
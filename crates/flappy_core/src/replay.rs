@@ -0,0 +1,184 @@
+//! Recorded runs, as `InputLog`s small enough to paste into a message. A log
+//! holds the run's seed, a hash of its config, how many ticks it lasted and
+//! the ticks the bird flapped on. Played back with the same config, it gives
+//! the same run bit for bit, so a server can check a claimed score by
+//! replaying it. Logs of the lockstep `Simulation` hash its `SimConfig` with
+//! `config_hash`; the game records its own runs the same way, hashing what
+//! its runs depend on with `hash_words`.
+//!
+//! Written out, a log reads
+//!
+//! ```text
+//! FBR1:<seed>:<config hash>:<ticks>:<flaps>
+//! ```
+//!
+//! The seed and tick count are in decimal and the config hash is 16 hex
+//! digits. Flaps are separated by dots, each written in base 36 as the ticks
+//! since the one before it, or since the start for the first. So flaps on
+//! ticks 0, 40 and 75 are `0.14.z`. A log with no flaps ends in a colon.
+//! The leading `FBR1` names the version of the format.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::sim::{hash_words, SimConfig, Simulation};
+
+const PREFIX: &str = "FBR1";
+const RADIX: u32 = 36;
+
+/// Hash of the bits of everything in `config` that changes how a run plays
+/// out, field by field in a fixed order, so it only changes with the config.
+pub fn config_hash(config: &SimConfig) -> u64 {
+    // destructured so a new field can't be left out of the hash
+    let SimConfig {
+        playfield,
+        physics,
+        gaps,
+        pacing,
+        ground,
+        rules,
+    } = config;
+    hash_words(&[
+        playfield.x.to_bits(),
+        playfield.y.to_bits(),
+        physics.gravity.to_bits(),
+        physics.jump_velocity.to_bits(),
+        physics.pipe_speed.to_bits(),
+        gaps.min_size.to_bits(),
+        gaps.max_size.to_bits(),
+        gaps.min_center.to_bits(),
+        gaps.max_center.to_bits(),
+        pacing.start.to_bits(),
+        pacing.step.to_bits(),
+        pacing.floor.to_bits(),
+        ground.amplitude.to_bits(),
+        ground.wavelength.to_bits(),
+        rules.lethal_ceiling as u32,
+        rules.hitbox_scale.to_bits(),
+    ])
+}
+
+/// A recorded run: what it takes to play it again.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputLog {
+    pub seed: u64,
+    /// `config_hash` of the config the run was played with.
+    pub config_hash: u64,
+    /// Ticks the run lasted.
+    pub ticks: u32,
+    /// Ticks the bird flapped on, in order.
+    pub flaps: Vec<u32>,
+}
+
+impl InputLog {
+    /// An empty log for a run of `config` from `seed`, to `record` into.
+    pub fn new(config: &SimConfig, seed: u64) -> Self {
+        Self {
+            seed,
+            config_hash: config_hash(config),
+            ticks: 0,
+            flaps: Vec::new(),
+        }
+    }
+
+    /// Notes one tick of the run, taken with `flap`.
+    pub fn record(&mut self, flap: bool) {
+        if flap {
+            self.flaps.push(self.ticks);
+        }
+        self.ticks += 1;
+    }
+
+    /// Plays the run again from the start. Refused if `config` isn't the one
+    /// it was recorded with.
+    pub fn replay(&self, config: SimConfig) -> Result<Simulation, String> {
+        if config_hash(&config) != self.config_hash {
+            return Err(format!(
+                "recorded with config {:016x}, not {:016x}",
+                self.config_hash,
+                config_hash(&config)
+            ));
+        }
+        let mut sim = Simulation::new(config, self.seed);
+        let mut flaps = self.flaps.iter().peekable();
+        for tick in 0..self.ticks {
+            let flap = flaps.next_if_eq(&&tick).is_some();
+            sim.tick(flap);
+        }
+        Ok(sim)
+    }
+}
+
+impl fmt::Display for InputLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{PREFIX}:{}:{:016x}:{}:",
+            self.seed, self.config_hash, self.ticks
+        )?;
+        let mut previous = 0;
+        for (index, tick) in self.flaps.iter().enumerate() {
+            if index > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", base36(tick - previous))?;
+            previous = *tick;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for InputLog {
+    type Err = String;
+
+    fn from_str(log: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = log.trim().split(':').collect();
+        let [prefix, seed, config_hash, ticks, flaps] = parts[..] else {
+            return Err(format!("expected {PREFIX}:SEED:CONFIG:TICKS:FLAPS"));
+        };
+        if prefix != PREFIX {
+            return Err(format!("logs start with {PREFIX}:"));
+        }
+        let seed = seed.parse().map_err(|_| format!("invalid seed '{seed}'"))?;
+        let config_hash = u64::from_str_radix(config_hash, 16)
+            .map_err(|_| format!("invalid config hash '{config_hash}'"))?;
+        let ticks = ticks
+            .parse()
+            .map_err(|_| format!("invalid tick count '{ticks}'"))?;
+        let mut log = InputLog {
+            seed,
+            config_hash,
+            ticks,
+            flaps: Vec::new(),
+        };
+        if flaps.is_empty() {
+            return Ok(log);
+        }
+        let mut tick: u32 = 0;
+        for delta in flaps.split('.') {
+            let delta =
+                u32::from_str_radix(delta, RADIX).map_err(|_| format!("invalid flap '{delta}'"))?;
+            tick = tick
+                .checked_add(delta)
+                .filter(|tick| *tick < ticks)
+                .ok_or_else(|| format!("flap after the run's {ticks} ticks"))?;
+            if !log.flaps.is_empty() && delta == 0 {
+                return Err(format!("two flaps on tick {tick}"));
+            }
+            log.flaps.push(tick);
+        }
+        Ok(log)
+    }
+}
+
+fn base36(mut value: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(char::from_digit(value % RADIX, RADIX).unwrap());
+        value /= RADIX;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
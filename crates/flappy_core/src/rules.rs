@@ -0,0 +1,30 @@
+use glam::Vec2;
+
+use crate::BIRD_SIZE;
+
+/// Toggles for rules that differ between versions of the game.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    /// End the run when the bird touches the ceiling. When false the bird is
+    /// held at the top of the screen instead, as in the original game.
+    pub lethal_ceiling: bool,
+    /// Size of the bird's hitbox relative to its sprite. Slightly under 1 lets
+    /// near misses around the sprite's rounded corners through.
+    pub hitbox_scale: f32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            lethal_ceiling: false,
+            hitbox_scale: 0.8,
+        }
+    }
+}
+
+impl Rules {
+    /// Size of the box the bird collides and scores with.
+    pub fn bird_hitbox(&self) -> Vec2 {
+        BIRD_SIZE * self.hitbox_scale
+    }
+}
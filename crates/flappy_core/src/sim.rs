@@ -0,0 +1,262 @@
+//! The headless game, for agents, analysis and checking runs.
+//!
+//! Stepped a fixed `TICK` at a time with `Simulation::tick`, a run is a pure
+//! function of its config, seed and flaps: the generator is seeded, nothing
+//! reads the clock, and the floats only go through plain arithmetic, which
+//! rounds the same everywhere. Even the ground's hills keep to it, through
+//! `cos_turns`. `Simulation::state_hash` sums a run up for comparing.
+
+use glam::Vec2;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::collision::{boundaries, Aabb, GroundParams};
+use crate::physics::{displacement, Physics};
+use crate::pipes::{cleared, Gap, GapParams, Pacing};
+use crate::rules::Rules;
+use crate::PIPE_SIZE;
+
+/// Seconds between pipe pairs, unless `Pacing` shortens it.
+pub const PIPE_INTERVAL: f32 = 1.0;
+/// Ticks per second of a lockstep run.
+pub const TICK_RATE: u32 = 60;
+/// Seconds each `Simulation::tick` advances.
+pub const TICK: f32 = 1.0 / TICK_RATE as f32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimConfig {
+    pub playfield: Vec2,
+    pub physics: Physics,
+    pub gaps: GapParams,
+    pub pacing: Pacing,
+    pub ground: GroundParams,
+    pub rules: Rules,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        let playfield = Vec2::new(1280.0 / 3.0, 720.0 / 3.0);
+        Self {
+            playfield,
+            physics: Physics::default(),
+            gaps: GapParams::for_height(playfield.y),
+            pacing: Pacing::default(),
+            ground: GroundParams::for_height(playfield.y),
+            rules: Rules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimBird {
+    pub position: Vec2,
+    pub velocity: f32,
+    /// Gravity only starts applying after the first flap.
+    pub falling: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimPipe {
+    pub position: Vec2,
+    /// Set once the bird has touched this pair's gap and been awarded a point.
+    pub scored: bool,
+}
+
+/// A complete headless game: one bird, a stream of pipes and the rules that
+/// end a run. Mirrors the behaviour of the Bevy systems in `flappy_game`.
+#[derive(Clone, Debug)]
+pub struct Simulation {
+    pub config: SimConfig,
+    pub bird: SimBird,
+    pub pipes: Vec<SimPipe>,
+    pub alive: bool,
+    pub score: u32,
+    pub elapsed: f32,
+    /// Fixed ticks taken by `tick`.
+    pub ticks: u32,
+    /// World units the level has scrolled past the bird, which sets the
+    /// ground's height under it.
+    pub distance: f32,
+    spawn_timer: f32,
+    /// Seconds from one pair to the next, see `Pacing`.
+    spawn_interval: f32,
+    rng: StdRng,
+}
+
+impl Simulation {
+    pub fn new(config: SimConfig, seed: u64) -> Self {
+        Self {
+            bird: SimBird {
+                position: Vec2::new(-config.playfield.x / 4.0, 0.0),
+                velocity: 0.0,
+                falling: false,
+            },
+            config,
+            pipes: Vec::new(),
+            alive: true,
+            score: 0,
+            elapsed: 0.0,
+            ticks: 0,
+            distance: 0.0,
+            spawn_timer: 0.0,
+            spawn_interval: config.pacing.start,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Advances the game by one `TICK`, counting it in `ticks`. Does nothing
+    /// once the bird is dead.
+    pub fn tick(&mut self, flap: bool) {
+        if self.alive {
+            self.step(TICK, flap);
+            self.ticks += 1;
+        }
+    }
+
+    /// Advances the game by `dt` seconds. Does nothing once the bird is dead.
+    pub fn step(&mut self, dt: f32, flap: bool) {
+        if !self.alive {
+            return;
+        }
+        self.elapsed += dt;
+
+        let physics = self.config.physics;
+        if flap {
+            self.bird.falling = true;
+            self.bird.velocity = physics.jump_velocity;
+        }
+
+        self.spawn_timer += dt;
+        while self.spawn_timer >= self.spawn_interval {
+            self.spawn_timer -= self.spawn_interval;
+            self.spawn_interval = self.config.pacing.next(self.spawn_interval);
+            self.spawn_pipe_pair();
+        }
+
+        if self.bird.falling {
+            self.bird.velocity = physics.fall(self.bird.velocity, dt);
+        }
+        self.bird.position.y += displacement(self.bird.velocity, dt);
+        if !self.config.rules.lethal_ceiling {
+            let (_, ceiling) = boundaries(self.config.playfield);
+            let top = ceiling.min().y - self.config.rules.bird_hitbox().y / 2.0;
+            if self.bird.position.y > top {
+                self.bird.position.y = top;
+                self.bird.velocity = self.bird.velocity.min(0.0);
+            }
+        }
+        let scrolled = displacement(physics.pipe_speed, dt);
+        self.distance += scrolled;
+        for pipe in &mut self.pipes {
+            pipe.position.x -= scrolled;
+        }
+
+        let bird = Aabb::new(self.bird.position, self.config.rules.bird_hitbox());
+        for pair in self.pipes.chunks_exact_mut(2) {
+            let sensor = pair_gap(pair).sensor(pair[0].position.x);
+            if !pair[0].scored && sensor.intersects(&bird) {
+                pair[0].scored = true;
+                pair[1].scored = true;
+                self.score += 1;
+            }
+        }
+
+        let despawn_x = -self.config.playfield.x / 1.5;
+        self.pipes.retain(|pipe| pipe.position.x >= despawn_x);
+
+        if self.bird_collides() {
+            self.alive = false;
+        }
+    }
+
+    /// A hash of the bits of everything that changes during a run, equal
+    /// for two runs exactly when they've played out the same so far.
+    pub fn state_hash(&self) -> u64 {
+        let mut words = vec![
+            self.bird.position.x.to_bits(),
+            self.bird.position.y.to_bits(),
+            self.bird.velocity.to_bits(),
+            self.bird.falling as u32,
+            self.alive as u32,
+            self.score,
+            self.elapsed.to_bits(),
+            self.ticks,
+            self.distance.to_bits(),
+            self.spawn_timer.to_bits(),
+            self.spawn_interval.to_bits(),
+        ];
+        for pipe in &self.pipes {
+            words.extend([
+                pipe.position.x.to_bits(),
+                pipe.position.y.to_bits(),
+                pipe.scored as u32,
+            ]);
+        }
+        hash_words(&words)
+    }
+
+    pub fn bird_collides(&self) -> bool {
+        let bird = Aabb::new(self.bird.position, self.config.rules.bird_hitbox());
+        let (_, ceiling) = boundaries(self.config.playfield);
+        let floor = self.ground_under_bird();
+        floor.intersects(&bird)
+            || (self.config.rules.lethal_ceiling && ceiling.intersects(&bird))
+            || self
+                .pipes
+                .iter()
+                .any(|pipe| Aabb::new(pipe.position, PIPE_SIZE).intersects(&bird))
+    }
+
+    /// The floor box below the bird, raised to the ground there.
+    pub fn ground_under_bird(&self) -> Aabb {
+        self.config
+            .ground
+            .floor(self.config.playfield, self.distance)
+    }
+
+    /// Horizontal position and gap of the first pipe pair the bird has not
+    /// yet cleared.
+    pub fn next_gap(&self) -> Option<(f32, Gap)> {
+        self.pipes
+            .chunks_exact(2)
+            .find(|pair| !cleared(pair[0].position.x, self.bird.position.x))
+            .map(|pair| (pair[0].position.x, pair_gap(pair)))
+    }
+
+    fn spawn_pipe_pair(&mut self) {
+        let gap = self.config.gaps.sample(&mut self.rng);
+        let (top, bottom) = gap.pipe_centers();
+        let x = self.config.playfield.x / 2.0;
+        self.pipes.push(SimPipe {
+            position: Vec2::new(x, top),
+            scored: false,
+        });
+        self.pipes.push(SimPipe {
+            position: Vec2::new(x, bottom),
+            scored: false,
+        });
+    }
+}
+
+/// FNV-1a hash of `words`, each taken as its four little-endian bytes, so the
+/// same words hash the same on any target. Floats go in by their bits.
+pub fn hash_words(words: &[u32]) -> u64 {
+    fnv1a(words.iter().flat_map(|word| word.to_le_bytes()))
+}
+
+/// 64-bit FNV-1a, which is quick and the same on every platform.
+fn fnv1a(bytes: impl IntoIterator<Item = u8>) -> u64 {
+    bytes.into_iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Recovers the gap from a (top, bottom) pair of pipes.
+fn pair_gap(pair: &[SimPipe]) -> Gap {
+    let top = pair[0].position.y - PIPE_SIZE.y / 2.0;
+    let bottom = pair[1].position.y + PIPE_SIZE.y / 2.0;
+    Gap {
+        center: (top + bottom) / 2.0,
+        size: top - bottom,
+    }
+}
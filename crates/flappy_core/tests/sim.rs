@@ -0,0 +1,387 @@
+use flappy_core::analysis::{analyse_gaps, transition_effort};
+use flappy_core::collision::{boundaries, cos_turns, Aabb, GroundParams};
+use flappy_core::mask::PixelMask;
+use flappy_core::medal::Medal;
+use flappy_core::physics::Physics;
+use flappy_core::pipes::{Gap, GapParams, GoldenParams, Pacing};
+use flappy_core::replay::{config_hash, InputLog};
+use flappy_core::rules::Rules;
+use flappy_core::sim::{SimConfig, SimPipe, Simulation, PIPE_INTERVAL, TICK_RATE};
+use flappy_core::{Vec2, PIPE_SIZE};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+const DT: f32 = 1.0 / 60.0;
+
+#[test]
+fn bird_hovers_until_first_flap() {
+    let mut sim = Simulation::new(SimConfig::default(), 1);
+    for _ in 0..30 {
+        sim.step(DT, false);
+    }
+    assert_eq!(sim.bird.position.y, 0.0);
+    assert!(sim.alive);
+}
+
+#[test]
+fn bird_dies_after_falling_off_the_bottom() {
+    let mut sim = Simulation::new(SimConfig::default(), 1);
+    sim.step(DT, true);
+    for _ in 0..600 {
+        sim.step(DT, false);
+    }
+    assert!(!sim.alive);
+}
+
+#[test]
+fn same_seed_produces_same_pipes() {
+    let mut a = Simulation::new(SimConfig::default(), 42);
+    let mut b = Simulation::new(SimConfig::default(), 42);
+    for _ in 0..200 {
+        a.step(DT, false);
+        b.step(DT, false);
+    }
+    assert!(!a.pipes.is_empty());
+    assert_eq!(a.pipes, b.pipes);
+}
+
+#[test]
+fn lockstep_runs_play_out_bit_for_bit() {
+    let play = |seed| {
+        let mut sim = Simulation::new(SimConfig::default(), seed);
+        let mut hashes = Vec::new();
+        for tick in 0..10 * TICK_RATE {
+            sim.tick(tick % 25 == 0);
+            hashes.push(sim.state_hash());
+        }
+        hashes
+    };
+    assert_eq!(play(7), play(7));
+    assert_ne!(play(7), play(8));
+
+    // ticks stop with the bird
+    let mut sim = Simulation::new(SimConfig::default(), 1);
+    sim.tick(true);
+    while sim.alive && sim.ticks < 60 * TICK_RATE {
+        sim.tick(false);
+    }
+    let ticks = sim.ticks;
+    sim.tick(true);
+    assert_eq!(sim.ticks, ticks);
+}
+
+#[test]
+fn config_hashes_are_pinned_to_the_configs_bits() {
+    // shared logs carry this hash, so it mustn't change for the same config
+    let config = SimConfig {
+        playfield: Vec2::new(400.0, 240.0),
+        physics: Physics {
+            gravity: 7.0,
+            jump_velocity: 2.0,
+            pipe_speed: 2.0,
+        },
+        gaps: GapParams {
+            min_size: 40.0,
+            max_size: 100.0,
+            min_center: -60.0,
+            max_center: 60.0,
+        },
+        pacing: Pacing {
+            start: 1.0,
+            step: 0.0,
+            floor: 1.0,
+        },
+        ground: GroundParams {
+            amplitude: 15.0,
+            wavelength: 960.0,
+        },
+        rules: Rules {
+            lethal_ceiling: false,
+            hitbox_scale: 0.8,
+        },
+    };
+    assert_eq!(config_hash(&config), 0x77f2_73f2_3c79_dcca);
+    let steeper = SimConfig {
+        physics: Physics {
+            gravity: 7.5,
+            ..config.physics
+        },
+        ..config
+    };
+    assert_ne!(config_hash(&steeper), config_hash(&config));
+}
+
+#[test]
+fn input_logs_replay_the_run_they_recorded() {
+    let config = SimConfig::default();
+    let mut sim = Simulation::new(config, 11);
+    let mut log = InputLog::new(&config, 11);
+    while sim.alive && sim.ticks < 30 * TICK_RATE {
+        let flap = sim.ticks.is_multiple_of(25);
+        sim.tick(flap);
+        log.record(flap);
+    }
+
+    let shared = log.to_string();
+    assert!(shared.starts_with("FBR1:11:"));
+    let imported: InputLog = shared.parse().unwrap();
+    assert_eq!(imported, log);
+    let replayed = imported.replay(config).unwrap();
+    assert_eq!(replayed.state_hash(), sim.state_hash());
+
+    let easier = SimConfig {
+        rules: Rules {
+            lethal_ceiling: !config.rules.lethal_ceiling,
+            ..config.rules
+        },
+        ..config
+    };
+    assert!(imported.replay(easier).is_err());
+
+    let flaps: InputLog = "FBR1:3:00000000000000ff:100:0.14.z".parse().unwrap();
+    assert_eq!(flaps.flaps, vec![0, 40, 75]);
+    assert_eq!(flaps.to_string(), "FBR1:3:00000000000000ff:100:0.14.z");
+    let empty: InputLog = "FBR1:3:00000000000000ff:0:".parse().unwrap();
+    assert!(empty.flaps.is_empty());
+    assert!(
+        "FBR1:3:ff:10:0.14".parse::<InputLog>().is_err(),
+        "past the end"
+    );
+    assert!(
+        "FBR1:3:ff:100:4.0".parse::<InputLog>().is_err(),
+        "same tick twice"
+    );
+    assert!("FBR2:3:ff:100:".parse::<InputLog>().is_err());
+}
+
+#[test]
+fn pacing_brings_pairs_closer_down_to_a_floor() {
+    let pacing = Pacing {
+        start: 1.0,
+        step: 0.25,
+        floor: 0.5,
+    };
+    let mut sim = Simulation::new(
+        SimConfig {
+            pacing,
+            ..SimConfig::default()
+        },
+        1,
+    );
+    // quarter second steps, so the timings add up exactly
+    let mut spawned_at = Vec::new();
+    for step in 1..=12 {
+        let pipes = sim.pipes.len();
+        sim.step(0.25, false);
+        if sim.pipes.len() > pipes {
+            spawned_at.push(step as f32 * 0.25);
+        }
+    }
+    assert_eq!(spawned_at, [1.0, 1.75, 2.25, 2.75]);
+    assert_eq!(pacing.next(0.6), 0.5);
+}
+
+#[test]
+fn gap_pipes_leave_exactly_the_gap_open() {
+    let gap = Gap {
+        center: 10.0,
+        size: 50.0,
+    };
+    let (top, bottom) = gap.pipe_centers();
+    assert_eq!(top - PIPE_SIZE.y / 2.0, 35.0);
+    assert_eq!(bottom + PIPE_SIZE.y / 2.0, -15.0);
+}
+
+#[test]
+fn golden_gaps_sit_past_a_divider_towards_the_middle() {
+    let golden = GoldenParams {
+        chance: 1.0,
+        ..GoldenParams::default()
+    };
+    let mut rng = StdRng::seed_from_u64(3);
+    let main = Gap {
+        center: 30.0,
+        size: 60.0,
+    };
+    let beside = golden.sample(main, &mut rng).unwrap();
+    assert!(beside.center < main.center);
+    assert_eq!(beside.size, golden.size);
+    let divider = main.between(beside);
+    assert_eq!(divider.size, golden.divider);
+    assert_eq!(beside.between(main), divider);
+    let span = main.span(beside);
+    assert_eq!(span.size, main.size + golden.divider + golden.size);
+    assert_eq!(span.center + span.size / 2.0, 60.0);
+
+    let never = GoldenParams {
+        chance: 0.0,
+        ..golden
+    };
+    assert_eq!(never.sample(main, &mut rng), None);
+}
+
+#[test]
+fn touching_boxes_do_not_intersect() {
+    let a = Aabb::new(Vec2::ZERO, Vec2::splat(2.0));
+    let b = Aabb::new(Vec2::new(2.0, 0.0), Vec2::splat(2.0));
+    assert!(!a.intersects(&b));
+    assert!(a.intersects(&Aabb::new(Vec2::new(1.9, 0.0), Vec2::splat(2.0))));
+}
+
+#[test]
+fn passing_a_pair_scores_once() {
+    let mut sim = Simulation::new(SimConfig::default(), 1);
+    // a pair whose gap the bird is sitting in, with plenty of room around it
+    let x = sim.bird.position.x;
+    for y in [200.0, -200.0] {
+        sim.pipes.push(SimPipe {
+            position: Vec2::new(x, y),
+            scored: false,
+        });
+    }
+    for _ in 0..5 {
+        sim.step(DT, false);
+    }
+    assert!(sim.alive);
+    assert_eq!(sim.score, 1);
+}
+
+#[test]
+fn contact_normal_follows_the_shallowest_axis() {
+    let pipe = Aabb::new(Vec2::ZERO, Vec2::new(26.0, 160.0));
+    let from_left = Aabb::new(Vec2::new(-20.0, 10.0), Vec2::new(17.0, 12.0));
+    let from_below = Aabb::new(Vec2::new(0.0, -84.0), Vec2::new(17.0, 12.0));
+    assert_eq!(from_left.contact_normal(&pipe), Vec2::new(-1.0, 0.0));
+    assert_eq!(from_below.contact_normal(&pipe), Vec2::new(0.0, -1.0));
+}
+
+#[test]
+fn boundaries_start_at_the_edges_of_the_playfield() {
+    let (floor, ceiling) = boundaries(Vec2::new(400.0, 200.0));
+    assert_eq!(floor.max().y, -100.0);
+    assert_eq!(ceiling.min().y, 100.0);
+    assert_eq!(floor.size.x, 400.0);
+}
+
+#[test]
+fn cos_turns_keeps_close_to_cos() {
+    for step in -2000..2000 {
+        let turns = step as f32 / 397.0;
+        let expected = (turns * std::f32::consts::TAU).cos();
+        let error = (cos_turns(turns) - expected).abs();
+        assert!(error < 1e-4, "cos of {turns} turns off by {error}");
+    }
+    assert_eq!(cos_turns(0.0), 1.0);
+    assert_eq!(cos_turns(0.5), -1.0);
+}
+
+#[test]
+fn ground_rolls_within_its_amplitude() {
+    let playfield = Vec2::new(400.0, 320.0);
+    let ground = GroundParams::for_height(playfield.y);
+    assert_eq!(ground.rise(0.0), 0.0);
+    // the hills stay under the lowest gaps
+    let gaps = GapParams::for_height(playfield.y);
+    let hill_top = -playfield.y / 2.0 + ground.amplitude;
+    assert!(hill_top < gaps.min_center - gaps.max_size / 2.0);
+    assert_eq!(GroundParams::for_height(200.0).amplitude, 0.0);
+    let rises: Vec<f32> = (0..400)
+        .map(|step| ground.rise(step as f32 * 10.0))
+        .collect();
+    assert!(rises
+        .iter()
+        .all(|rise| (0.0..=ground.amplitude + 1e-3).contains(rise)));
+    assert!(rises.iter().any(|rise| *rise > ground.amplitude * 0.9));
+    let flat = GroundParams::FLAT.floor(playfield, 1234.0);
+    assert_eq!(flat, boundaries(playfield).0);
+
+    // a bird resting just above the bottom edge is caught by a hill
+    let mut sim = Simulation::new(SimConfig::default(), 1);
+    let bottom = -sim.config.playfield.y / 2.0;
+    sim.bird.position.y = bottom + sim.config.rules.bird_hitbox().y / 2.0 + 1.0;
+    assert!(!sim.bird_collides());
+    sim.distance = sim.config.ground.wavelength / 2.0;
+    assert!(sim.bird_collides());
+}
+
+#[test]
+fn ceiling_holds_the_bird_unless_lethal() {
+    let mut held = Simulation::new(SimConfig::default(), 1);
+    let mut killed = Simulation::new(
+        SimConfig {
+            rules: Rules {
+                lethal_ceiling: true,
+                ..Rules::default()
+            },
+            ..SimConfig::default()
+        },
+        1,
+    );
+    for sim in [&mut held, &mut killed] {
+        // flap every frame to climb straight into the ceiling
+        for _ in 0..120 {
+            sim.step(DT, true);
+        }
+    }
+
+    assert!(held.alive);
+    assert_eq!(held.bird.velocity, 0.0);
+    assert!(held.bird.position.y < held.config.playfield.y / 2.0);
+    assert!(!killed.alive);
+}
+
+#[test]
+fn pixel_masks_ignore_transparent_corners() {
+    // a 3x3 plus sign and a solid 3x3 block
+    let plus = PixelMask::from_alpha(3, 3, [0, 255, 0, 255, 255, 255, 0, 255, 0]);
+    let block = PixelMask::from_alpha(3, 3, [255; 9]);
+
+    // only the corners of the boxes overlap
+    assert!(!plus.overlaps(Vec2::ZERO, &block, Vec2::new(2.0, 2.0)));
+    assert!(plus.overlaps(Vec2::ZERO, &block, Vec2::new(2.0, 0.0)));
+    assert!(!plus.overlaps(Vec2::ZERO, &block, Vec2::new(3.0, 0.0)));
+}
+
+#[test]
+fn medals_follow_the_original_thresholds() {
+    assert_eq!(Medal::for_score(9), None);
+    assert_eq!(Medal::for_score(10), Some(Medal::Bronze));
+    assert_eq!(Medal::for_score(29), Some(Medal::Silver));
+    assert_eq!(Medal::for_score(30), Some(Medal::Gold));
+    assert_eq!(Medal::for_score(400), Some(Medal::Platinum));
+}
+
+#[test]
+fn gap_analysis_flags_transitions_out_of_reach() {
+    let config = SimConfig::default();
+    let report = analyse_gaps(&config, 5000, 7);
+    assert_eq!(report, analyse_gaps(&config, 5000, 7));
+    assert_eq!(report.sizes.histogram.iter().sum::<usize>(), 5000);
+    assert_eq!(report.centers.histogram.iter().sum::<usize>(), 5000);
+    assert!(report.sizes.min >= config.gaps.min_size && report.sizes.max < config.gaps.max_size);
+    // the default tuning never asks too much
+    assert_eq!((report.borderline, report.impossible), (0, 0));
+
+    let low = Gap {
+        center: -60.0,
+        size: 40.0,
+    };
+    let high = Gap {
+        center: 60.0,
+        ..low
+    };
+    assert_eq!(transition_effort(low, low, PIPE_INTERVAL, &config), 0.0);
+    let climb = transition_effort(low, high, PIPE_INTERVAL, &config);
+    assert!(climb > 0.0 && climb < 1.0, "{climb}");
+    let tiny = Gap { size: 5.0, ..high };
+    assert_eq!(
+        transition_effort(low, tiny, PIPE_INTERVAL, &config),
+        f32::INFINITY
+    );
+
+    let mut weak = config;
+    weak.physics.jump_velocity /= 4.0;
+    assert!(transition_effort(low, high, PIPE_INTERVAL, &weak) > 1.0);
+    let report = analyse_gaps(&weak, 5000, 7);
+    assert!(report.borderline > 0 && report.impossible > 0, "{report}");
+}
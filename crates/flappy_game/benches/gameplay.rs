@@ -0,0 +1,93 @@
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use flappy_core::{BIRD_SIZE, PIPE_SIZE};
+use flappy_game::{
+    check_collisions, spawn_pipe_couple, Bird, Collider, DeathEvent, FlappyConfig, Hitbox, Pipe,
+    PixelMasks, Settings,
+};
+
+const PIPE_COUNTS: [usize; 3] = [100, 500, 1000];
+
+/// A world with a bird and `pipes` colliders spread along the playfield,
+/// none of which overlap the bird.
+fn collision_world(pipes: usize) -> World {
+    let config = FlappyConfig::default();
+    let mut world = World::new();
+    world.init_resource::<Events<DeathEvent>>();
+    world.init_resource::<Settings>();
+    world.init_resource::<PixelMasks>();
+
+    world.spawn((
+        GlobalTransform::from_xyz(-config.width() / 4.0, 0.0, 0.0),
+        Bird,
+        Hitbox { size: BIRD_SIZE },
+    ));
+    for i in 0..pipes {
+        let x = -config.width() / 2.0 + (i as f32 / pipes as f32) * config.width();
+        let y = if i % 2 == 0 { 150.0 } else { -150.0 };
+        world.spawn((
+            GlobalTransform::from_xyz(x, y, 0.0),
+            Pipe,
+            Collider { size: PIPE_SIZE },
+        ));
+    }
+    world.insert_resource(config);
+    world
+}
+
+fn bench_collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check_collisions");
+    for pipes in PIPE_COUNTS {
+        let mut world = collision_world(pipes);
+        let mut schedule = Schedule::new();
+        schedule
+            .set_executor_kind(ExecutorKind::SingleThreaded)
+            .add_system(check_collisions);
+        group.bench_with_input(BenchmarkId::from_parameter(pipes), &pipes, |b, _| {
+            b.iter(|| schedule.run(&mut world));
+        });
+    }
+    group.finish();
+}
+
+fn spawning_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .init_resource::<FlappyConfig>();
+    app
+}
+
+fn spawn_pairs(pairs: usize) -> impl FnMut(Commands, Res<AssetServer>, Res<FlappyConfig>) {
+    move |mut commands, asset_server, config| {
+        for _ in 0..pairs {
+            spawn_pipe_couple(&mut commands, &asset_server, &config);
+        }
+    }
+}
+
+fn bench_spawning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("spawn_pipe_couple");
+    for pipes in PIPE_COUNTS {
+        group.bench_with_input(BenchmarkId::from_parameter(pipes), &pipes, |b, &pipes| {
+            b.iter_batched(
+                || {
+                    let mut app = spawning_app();
+                    let mut schedule = Schedule::new();
+                    schedule
+                        .set_executor_kind(ExecutorKind::SingleThreaded)
+                        .add_system(spawn_pairs(pipes / 2));
+                    app.update();
+                    (app, schedule)
+                },
+                |(mut app, mut schedule)| schedule.run(&mut app.world),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_collisions, bench_spawning);
+criterion_main!(benches);
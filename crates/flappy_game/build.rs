@@ -0,0 +1,28 @@
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|text| text.trim().to_string())
+}
+
+/// Compiles the commit hash in as `FLAPPY_GIT_HASH`, for `build_info`. Set the
+/// variable yourself to build from a source tarball without git.
+fn main() {
+    println!("cargo:rerun-if-env-changed=FLAPPY_GIT_HASH");
+    let hash = std::env::var("FLAPPY_GIT_HASH")
+        .ok()
+        .or_else(|| git(&["rev-parse", "--short", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=FLAPPY_GIT_HASH={hash}");
+
+    // pick up new commits and checkouts
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        println!("cargo:rerun-if-changed={git_dir}/HEAD");
+        println!("cargo:rerun-if-changed={git_dir}/refs");
+    }
+}
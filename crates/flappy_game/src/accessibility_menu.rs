@@ -0,0 +1,166 @@
+//! The accessibility screen, reached from the settings screen: a switch for
+//! each of the settings that open the game up to more players. Like the
+//! settings screen, changes apply and save straight away.
+
+use bevy::prelude::*;
+
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, Haptics, Localized, Settings};
+
+/// Switches one of the accessibility settings on and off.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessibilityToggle {
+    /// `Settings::single_switch`.
+    SingleSwitch,
+    /// `Settings::high_contrast`.
+    HighContrast,
+    /// `Settings::reduced_motion`.
+    ReducedMotion,
+    /// `Settings::photosensitive`, labelled by the flashes it turns off.
+    Photosensitive,
+    /// `Settings::audio_cues`.
+    AudioCues,
+    /// `Settings::haptics`.
+    Haptics,
+}
+
+impl AccessibilityToggle {
+    pub const ALL: [AccessibilityToggle; 6] = [
+        AccessibilityToggle::SingleSwitch,
+        AccessibilityToggle::HighContrast,
+        AccessibilityToggle::ReducedMotion,
+        AccessibilityToggle::Photosensitive,
+        AccessibilityToggle::AudioCues,
+        AccessibilityToggle::Haptics,
+    ];
+
+    pub fn enabled(self, settings: &Settings) -> bool {
+        match self {
+            AccessibilityToggle::SingleSwitch => settings.single_switch,
+            AccessibilityToggle::HighContrast => settings.high_contrast,
+            AccessibilityToggle::ReducedMotion => settings.reduced_motion,
+            AccessibilityToggle::Photosensitive => settings.photosensitive,
+            AccessibilityToggle::AudioCues => settings.audio_cues,
+            AccessibilityToggle::Haptics => settings.haptics == Haptics::On,
+        }
+    }
+
+    pub fn toggle(self, settings: &mut Settings) {
+        match self {
+            AccessibilityToggle::SingleSwitch => settings.single_switch = !settings.single_switch,
+            AccessibilityToggle::HighContrast => settings.high_contrast = !settings.high_contrast,
+            AccessibilityToggle::ReducedMotion => {
+                settings.reduced_motion = !settings.reduced_motion
+            }
+            AccessibilityToggle::Photosensitive => {
+                settings.photosensitive = !settings.photosensitive
+            }
+            AccessibilityToggle::AudioCues => settings.audio_cues = !settings.audio_cues,
+            AccessibilityToggle::Haptics => {
+                settings.haptics = if settings.haptics == Haptics::On {
+                    Haptics::Off
+                } else {
+                    Haptics::On
+                }
+            }
+        }
+    }
+
+    fn label(self, enabled: bool) -> &'static str {
+        match (self, enabled) {
+            (AccessibilityToggle::SingleSwitch, true) => "accessibility.single_switch_on",
+            (AccessibilityToggle::SingleSwitch, false) => "accessibility.single_switch_off",
+            (AccessibilityToggle::HighContrast, true) => "accessibility.high_contrast_on",
+            (AccessibilityToggle::HighContrast, false) => "accessibility.high_contrast_off",
+            (AccessibilityToggle::ReducedMotion, true) => "accessibility.reduced_motion_on",
+            (AccessibilityToggle::ReducedMotion, false) => "accessibility.reduced_motion_off",
+            (AccessibilityToggle::Photosensitive, true) => "accessibility.photosensitive_on",
+            (AccessibilityToggle::Photosensitive, false) => "accessibility.photosensitive_off",
+            (AccessibilityToggle::AudioCues, true) => "accessibility.audio_cues_on",
+            (AccessibilityToggle::AudioCues, false) => "accessibility.audio_cues_off",
+            (AccessibilityToggle::Haptics, true) => "accessibility.haptics_on",
+            (AccessibilityToggle::Haptics, false) => "accessibility.haptics_off",
+        }
+    }
+}
+
+#[derive(Component)]
+struct AccessibilityBackButton;
+
+pub(crate) fn add_accessibility_menu(app: &mut App) {
+    app.add_system(spawn_accessibility_menu.in_schedule(OnEnter(AppState::Accessibility)))
+        .add_systems(
+            (toggle_accessibility, leave_accessibility).in_set(OnUpdate(AppState::Accessibility)),
+        );
+}
+
+fn spawn_accessibility_menu(mut commands: Commands, settings: Res<Settings>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Accessibility),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("accessibility.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            for (order, toggle) in (0..).zip(AccessibilityToggle::ALL) {
+                spawn_text_button(
+                    parent,
+                    Localized::new(toggle.label(toggle.enabled(&settings))),
+                    order,
+                    toggle,
+                );
+            }
+            spawn_text_button(
+                parent,
+                Localized::new("menu.back"),
+                20,
+                AccessibilityBackButton,
+            );
+        });
+}
+
+fn toggle_accessibility(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &AccessibilityToggle, &Children), Changed<Interaction>>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, toggle, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        toggle.toggle(&mut settings);
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = toggle.label(toggle.enabled(&settings));
+        }
+    }
+}
+
+/// Back to the settings screen. The quit key does the same, see `quit`.
+fn leave_accessibility(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<AccessibilityBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Settings);
+    }
+}
@@ -0,0 +1,69 @@
+//! JNI calls into the Android activity. Build an APK with `cargo apk build -p
+//! flappy_game`, which packages the `cdylib` with the settings under
+//! `package.metadata.android` in `Cargo.toml`.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use jni::objects::{JObject, JString};
+use jni::{JNIEnv, JavaVM};
+
+/// Runs `f` with the JNI environment and the game's activity.
+pub(crate) fn with_activity<T>(
+    f: impl FnOnce(&mut JNIEnv, &JObject) -> jni::errors::Result<T>,
+) -> jni::errors::Result<T> {
+    let context = ndk_context::android_context();
+    // SAFETY: both pointers are kept valid by the activity for the life of the app
+    let vm = unsafe { JavaVM::from_raw(context.vm().cast()) }?;
+    let activity = unsafe { JObject::from_raw(context.context().cast()) };
+    let mut env = vm.attach_current_thread()?;
+    f(&mut env, &activity)
+}
+
+/// The app's private storage, where `dirs` finds nothing on Android.
+pub(crate) fn files_dir() -> Option<PathBuf> {
+    let path = with_activity(|env, activity| {
+        let dir = env
+            .call_method(activity, "getFilesDir", "()Ljava/io/File;", &[])?
+            .l()?;
+        let path = env
+            .call_method(&dir, "getAbsolutePath", "()Ljava/lang/String;", &[])?
+            .l()?;
+        let path: String = env.get_string(&JString::from(path))?.into();
+        Ok(PathBuf::from(path))
+    });
+    path.map_err(|error| warn!(%error, "couldn't find the files dir"))
+        .ok()
+}
+
+/// Puts `text` on the clipboard through the `ClipboardManager`.
+pub(crate) fn set_clipboard(text: &str) -> jni::errors::Result<()> {
+    with_activity(|env, activity| {
+        let service = env.new_string("clipboard")?;
+        let manager = env
+            .call_method(
+                activity,
+                "getSystemService",
+                "(Ljava/lang/String;)Ljava/lang/Object;",
+                &[(&service).into()],
+            )?
+            .l()?;
+        let label = env.new_string("Flappy Bevy")?;
+        let text = env.new_string(text)?;
+        let clip = env
+            .call_static_method(
+                "android/content/ClipData",
+                "newPlainText",
+                "(Ljava/lang/CharSequence;Ljava/lang/CharSequence;)Landroid/content/ClipData;",
+                &[(&label).into(), (&text).into()],
+            )?
+            .l()?;
+        env.call_method(
+            &manager,
+            "setPrimaryClip",
+            "(Landroid/content/ClipData;)V",
+            &[(&clip).into()],
+        )?;
+        Ok(())
+    })
+}
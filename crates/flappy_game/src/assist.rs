@@ -0,0 +1,86 @@
+//! Assist mode: wider gaps and slower pipes on top of whichever difficulty is
+//! chosen, for players who want a gentler game without changing presets.
+//! Toggled from the settings screen, tuned with the `assist` console command.
+//! Assisted runs are unranked, see `RunRecord`: they don't raise the best, and
+//! are marked on the live board and in challenge codes.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConsoleCommands, Pace, Settings};
+
+/// How much assist mode eases the game, in `Settings::assist`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Assist {
+    pub enabled: bool,
+    /// Percent added to both ends of the gap size range.
+    pub wider_gaps: f32,
+    /// Percent taken off the pipe speed.
+    pub slower_pipes: f32,
+}
+
+impl Default for Assist {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            wider_gaps: 25.0,
+            slower_pipes: 20.0,
+        }
+    }
+}
+
+impl Assist {
+    /// `pace` eased, when enabled.
+    pub fn apply(&self, pace: Pace) -> Pace {
+        if !self.enabled {
+            return pace;
+        }
+        let wider = 1.0 + self.wider_gaps.max(0.0) / 100.0;
+        let slower = 1.0 - self.slower_pipes.clamp(0.0, 90.0) / 100.0;
+        let mut gaps = pace.gaps;
+        gaps.min_size *= wider;
+        gaps.max_size *= wider;
+        Pace {
+            pipe_speed: pace.pipe_speed * slower,
+            gaps,
+            ..pace
+        }
+    }
+}
+
+pub(crate) fn add_assist(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "assist",
+        "assist on|off|<wider gaps %> <slower pipes %>: ease the game",
+        set_assist,
+    );
+}
+
+fn set_assist(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let mut assist = world.resource::<Settings>().assist;
+    match args {
+        ["on"] => assist.enabled = true,
+        ["off"] => assist.enabled = false,
+        [wider, slower] => {
+            let percent = |value: &str| {
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|percent| (0.0..=90.0).contains(percent))
+                    .ok_or_else(|| format!("'{value}' isn't a percentage from 0 to 90"))
+            };
+            assist.wider_gaps = percent(wider)?;
+            assist.slower_pipes = percent(slower)?;
+            assist.enabled = true;
+        }
+        _ => return Err("expected 'assist on', 'assist off' or two percentages".to_string()),
+    }
+    world.resource_mut::<Settings>().assist = assist;
+    Ok(format!(
+        "assist {}: gaps {}% wider, pipes {}% slower",
+        if assist.enabled { "on" } else { "off" },
+        assist.wider_gaps,
+        assist.slower_pipes
+    ))
+}
@@ -0,0 +1,210 @@
+//! Audio channels and their volumes, played through `bevy_kira_audio`. Music
+//! goes on the `MusicChannel` and everything else on the `SfxChannel`, whose
+//! volumes follow `Settings::volume` so the two can be balanced. Both channels
+//! pause along with the run.
+//!
+//! `InputBindings::mute` silences everything, with a speaker icon in the
+//! corner while muted. The rest does nothing without kira's `AudioPlugin`.
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy_kira_audio::prelude::{
+    AudioApp, AudioControl, AudioPlugin, AudioSource, Frame, StaticSoundData, StaticSoundSettings,
+};
+use bevy_kira_audio::AudioChannel as KiraChannel;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, DevConsole, FlappyConfig, RunState, SafeArea, Settings};
+
+/// Rate of the sounds synthesised in game.
+pub(crate) const SAMPLE_RATE: u32 = 44_100;
+
+/// Crossed-out speaker shown while muted, `#` for opaque pixels.
+#[rustfmt::skip]
+const MUTE_ICON: [&str; 8] = [
+    "...#....",
+    "..##....",
+    "####.#.#",
+    "####..#.",
+    "####.#.#",
+    "..##....",
+    "...#....",
+    "........",
+];
+
+/// What a sound is, for picking its volume.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioChannel {
+    Music,
+    Sfx,
+}
+
+/// Volume levels from 0 to 1, part of `Settings`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Volume {
+    /// Applies on top of the other two.
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    /// Silences everything without losing the levels.
+    pub muted: bool,
+}
+
+impl Default for Volume {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.8,
+            sfx: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl Volume {
+    /// Multiplier for sounds played on `channel`.
+    pub fn gain(&self, channel: AudioChannel) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        let level = match channel {
+            AudioChannel::Music => self.music,
+            AudioChannel::Sfx => self.sfx,
+        };
+        (self.master * level).clamp(0.0, 1.0)
+    }
+}
+
+/// Kira channel for `AudioChannel::Music`.
+#[derive(Resource)]
+pub struct MusicChannel;
+
+/// Kira channel for `AudioChannel::Sfx`.
+#[derive(Resource)]
+pub struct SfxChannel;
+
+/// A sound from mono samples at `SAMPLE_RATE`, for sounds synthesised in game.
+pub(crate) fn synthesize(samples: impl IntoIterator<Item = f32>) -> AudioSource {
+    let frames: Vec<_> = samples.into_iter().map(Frame::from_mono).collect();
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: SAMPLE_RATE,
+            frames: Arc::from(frames),
+            settings: StaticSoundSettings::default(),
+        },
+    }
+}
+
+/// The speaker icon, visible while muted.
+#[derive(Component)]
+pub struct MuteIndicator;
+
+pub(crate) fn add_audio_channels(app: &mut App) {
+    app.add_startup_system(spawn_mute_indicator)
+        .add_system(toggle_mute)
+        .add_system(show_mute_indicator.after(toggle_mute));
+    if app.is_plugin_added::<AudioPlugin>() {
+        app.add_audio_channel::<MusicChannel>()
+            .add_audio_channel::<SfxChannel>()
+            .add_system(apply_channel_volumes)
+            .add_system(pause_channels.in_schedule(OnExit(RunState::Running)))
+            .add_system(resume_channels.in_schedule(OnEnter(RunState::Running)));
+    }
+}
+
+fn spawn_mute_indicator(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let size = MUTE_ICON.len() as u32;
+    let data = MUTE_ICON
+        .iter()
+        .flat_map(|row| row.chars())
+        .flat_map(|pixel| match pixel {
+            '#' => [255, 255, 255, 255],
+            _ => [0, 0, 0, 0],
+        })
+        .collect();
+    let image = Image::new(
+        Extent3d {
+            width: size,
+            height: size,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    commands.spawn((
+        ImageBundle {
+            image: images.add(image).into(),
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(2.0),
+                    bottom: Val::Px(2.0),
+                    ..default()
+                },
+                size: Size::new(Val::Px(size as f32), Val::Px(size as f32)),
+                ..default()
+            },
+            z_index: ZIndex::Global(100),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        MuteIndicator,
+        SafeArea::default(),
+    ));
+}
+
+fn toggle_mute(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    app_state: Res<State<AppState>>,
+    mut settings: ResMut<Settings>,
+) {
+    // the key may be part of a challenge code being typed
+    let typing = console.open || app_state.0 == AppState::Challenge;
+    if !typing && keyboard_input.just_pressed(config.bindings.mute) {
+        settings.volume.muted = !settings.volume.muted;
+        info!(muted = settings.volume.muted, "toggled mute");
+    }
+}
+
+fn show_mute_indicator(
+    settings: Res<Settings>,
+    mut query: Query<&mut Visibility, With<MuteIndicator>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for mut visibility in query.iter_mut() {
+        *visibility = if settings.volume.muted {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn apply_channel_volumes(
+    settings: Res<Settings>,
+    music: Res<KiraChannel<MusicChannel>>,
+    sfx: Res<KiraChannel<SfxChannel>>,
+) {
+    if settings.is_changed() {
+        music.set_volume(f64::from(settings.volume.gain(AudioChannel::Music)));
+        sfx.set_volume(f64::from(settings.volume.gain(AudioChannel::Sfx)));
+    }
+}
+
+fn pause_channels(music: Res<KiraChannel<MusicChannel>>, sfx: Res<KiraChannel<SfxChannel>>) {
+    music.pause();
+    sfx.pause();
+}
+
+fn resume_channels(music: Res<KiraChannel<MusicChannel>>, sfx: Res<KiraChannel<SfxChannel>>) {
+    music.resume();
+    sfx.resume();
+}
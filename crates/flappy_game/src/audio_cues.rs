@@ -0,0 +1,194 @@
+//! Audio-cue assist, making the game partly playable by ear: a steady tone
+//! whose pitch follows the bird's height relative to the centre of the next
+//! gap, and clicks that speed up as that gap draws near. Enabled with
+//! `Settings::audio_cues`, from the accessibility screen or the `cues`
+//! console command.
+//!
+//! The cues are worked out in `GapCue` even without audio, and are only heard
+//! when the host app adds kira's `AudioPlugin`. Both sounds are synthesised,
+//! so there are no assets to load.
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::{AudioControl, AudioInstance, AudioPlugin, AudioSource, AudioTween};
+use bevy_kira_audio::AudioChannel as KiraChannel;
+
+use crate::audio::{synthesize, SfxChannel, SAMPLE_RATE};
+use crate::{AppState, Bird, ConsoleCommands, FlappyConfig, PipePair, Settings};
+
+/// Pitch of the tone with the bird level with the gap centre.
+const TONE_BASE_HZ: f32 = 440.0;
+/// Octaves the tone rises per playfield height the bird is above the gap.
+const TONE_OCTAVES: f32 = 2.0;
+const TONE_VOLUME: f32 = 0.15;
+/// How quickly the tone follows the bird, to smooth out steps between frames.
+const TONE_GLIDE: Duration = Duration::from_millis(50);
+/// World units the gap moves between clicks.
+const CLICK_SPACING: f32 = 32.0;
+/// Clicks start once the gap is this close.
+const CLICK_RANGE: f32 = 128.0;
+const CLICK_SECONDS: f32 = 0.02;
+const CLICK_HZ: f32 = 2000.0;
+
+/// Where the next gap is, relative to the bird.
+#[derive(Resource, Default, Debug)]
+pub struct GapCue {
+    /// Bird height above the centre of the next gap, in playfield heights.
+    /// `None` with the cues off or no gap ahead.
+    pub offset: Option<f32>,
+    /// Horizontal distance from the bird to the next gap.
+    pub distance: f32,
+    gap: Option<Entity>,
+    clicks: u32,
+}
+
+impl GapCue {
+    /// Frequency of the tone, in hertz.
+    pub fn pitch(&self) -> Option<f32> {
+        self.offset
+            .map(|offset| TONE_BASE_HZ * 2f32.powf(offset * TONE_OCTAVES))
+    }
+}
+
+/// Sent for each click as the next gap approaches.
+pub struct GapClickEvent;
+
+pub(crate) fn add_audio_cues(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "cues",
+        "cues on|off: toggle the audio-cue assist",
+        toggle_audio_cues,
+    );
+    app.init_resource::<GapCue>()
+        .add_event::<GapClickEvent>()
+        .add_system(reset_gap_cue.in_schedule(OnExit(AppState::Game)))
+        .add_system(update_gap_cue.in_set(OnUpdate(AppState::Game)));
+    if app.is_plugin_added::<AudioPlugin>() {
+        app.add_startup_system(start_tone).add_system(play_gap_cues);
+    }
+}
+
+fn toggle_audio_cues(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let audio_cues = match args.first() {
+        Some(&"on") => true,
+        Some(&"off") => false,
+        _ => return Err("expected 'cues on' or 'cues off'".to_string()),
+    };
+    world.resource_mut::<Settings>().audio_cues = audio_cues;
+    Ok(String::new())
+}
+
+fn reset_gap_cue(mut cue: ResMut<GapCue>) {
+    *cue = GapCue::default();
+}
+
+fn update_gap_cue(
+    settings: Res<Settings>,
+    config: Res<FlappyConfig>,
+    mut cue: ResMut<GapCue>,
+    mut clicks: EventWriter<GapClickEvent>,
+    bird_query: Query<&Transform, With<Bird>>,
+    pair_query: Query<(Entity, &Transform, &PipePair)>,
+) {
+    let Some(bird) = bird_query.iter().next().filter(|_| settings.audio_cues) else {
+        *cue = GapCue::default();
+        return;
+    };
+    // the nearest pair the bird hasn't got past yet
+    let next = pair_query
+        .iter()
+        .filter(|(_, transform, pair)| pair.active && transform.translation.x >= bird.translation.x)
+        .min_by(|(_, a, _), (_, b, _)| a.translation.x.total_cmp(&b.translation.x));
+    let Some((entity, transform, _)) = next else {
+        cue.offset = None;
+        return;
+    };
+
+    if cue.gap != Some(entity) {
+        cue.gap = Some(entity);
+        cue.clicks = 0;
+    }
+    cue.offset = Some((bird.translation.y - transform.translation.y) / config.height());
+    cue.distance = transform.translation.x - bird.translation.x;
+
+    let due = if cue.distance < CLICK_RANGE {
+        ((CLICK_RANGE - cue.distance) / CLICK_SPACING) as u32 + 1
+    } else {
+        0
+    };
+    if cue.clicks < due {
+        cue.clicks = due;
+        clicks.send(GapClickEvent);
+    }
+}
+
+/// The looping tone, and the click played as gaps approach.
+#[derive(Resource)]
+struct CueSounds {
+    tone: Handle<AudioInstance>,
+    click: Handle<AudioSource>,
+}
+
+/// One second of sine wave at `TONE_BASE_HZ`, a whole number of cycles so it
+/// loops without a seam. Its pitch is then set through the playback rate.
+fn tone_samples() -> impl Iterator<Item = f32> {
+    (0..SAMPLE_RATE).map(|sample| {
+        let t = sample as f32 / SAMPLE_RATE as f32;
+        (t * TONE_BASE_HZ * TAU).sin()
+    })
+}
+
+/// A short, quickly decaying beep.
+fn click_samples() -> impl Iterator<Item = f32> {
+    let samples = (CLICK_SECONDS * SAMPLE_RATE as f32) as u32;
+    (0..samples).map(|sample| {
+        let t = sample as f32 / SAMPLE_RATE as f32;
+        let envelope = (1.0 - t / CLICK_SECONDS).powi(2);
+        (t * CLICK_HZ * TAU).sin() * envelope * 0.5
+    })
+}
+
+/// The tone plays for the whole session, silent whenever there's no cue.
+fn start_tone(
+    mut commands: Commands,
+    mut sources: ResMut<Assets<AudioSource>>,
+    sfx: Res<KiraChannel<SfxChannel>>,
+) {
+    let tone = sfx
+        .play(sources.add(synthesize(tone_samples())))
+        .looped()
+        .with_volume(0.0)
+        .handle();
+    commands.insert_resource(CueSounds {
+        tone,
+        click: sources.add(synthesize(click_samples())),
+    });
+}
+
+fn play_gap_cues(
+    cue: Res<GapCue>,
+    sounds: Res<CueSounds>,
+    sfx: Res<KiraChannel<SfxChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut clicks: EventReader<GapClickEvent>,
+) {
+    if cue.is_changed() {
+        if let Some(tone) = instances.get_mut(&sounds.tone) {
+            let tween = AudioTween::linear(TONE_GLIDE);
+            match cue.pitch() {
+                Some(pitch) => {
+                    tone.set_playback_rate(f64::from(pitch / TONE_BASE_HZ), tween.clone());
+                    tone.set_volume(f64::from(TONE_VOLUME), tween);
+                }
+                None => {
+                    tone.set_volume(0.0, tween);
+                }
+            }
+        }
+    }
+    if clicks.iter().count() > 0 {
+        sfx.play(sounds.click.clone());
+    }
+}
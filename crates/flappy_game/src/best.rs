@@ -0,0 +1,82 @@
+//! The player's best score, kept in `Profile::best`, or `Profile::co_op_best`
+//! for `CoOp` runs. The best to beat is noted in `RunRecord` as each run
+//! starts, so the HUD and results can tell when it has been passed even
+//! though the profile is raised along with the score.
+//!
+//! Runs eased with `Settings::assist`, or played with the physics changed from
+//! the physics panel, are unranked: they neither raise the best nor announce
+//! a new one. So are runs cheated in from the console, whether by turning on
+//! `Cheats::invincible` or setting the score, and stress tests.
+
+use bevy::prelude::*;
+
+use crate::physics_menu::StartingPhysics;
+use crate::{AppState, Cheats, CoOp, FlappyConfig, Profile, Score, Settings};
+
+/// The best score from before the current run, for its kind of run.
+#[derive(Resource, Default, Debug)]
+pub struct RunRecord {
+    pub to_beat: u32,
+    /// The run counts toward the best.
+    pub ranked: bool,
+}
+
+impl RunRecord {
+    /// Whether `score` is a new best. A first run has nothing to beat, and an
+    /// unranked one can't beat anything.
+    pub fn beaten_by(&self, score: u32) -> bool {
+        self.ranked && self.to_beat > 0 && score > self.to_beat
+    }
+}
+
+pub(crate) fn add_best(app: &mut App) {
+    app.init_resource::<RunRecord>()
+        .add_system(note_best_to_beat.in_schedule(OnEnter(AppState::Game)))
+        .add_system(raise_best.in_set(OnUpdate(AppState::Game)));
+}
+
+fn note_best_to_beat(
+    config: Res<FlappyConfig>,
+    starting: Res<StartingPhysics>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    co_op: Res<CoOp>,
+    cheats: Res<Cheats>,
+    mut record: ResMut<RunRecord>,
+) {
+    record.to_beat = if co_op.0 {
+        profile.co_op_best
+    } else {
+        profile.best
+    };
+    record.ranked = !settings.assist.enabled
+        && !starting.modified(&config.physics)
+        && !cheats.invincible
+        && config.stress_test.is_none();
+}
+
+/// Saves the best as soon as it's passed, so quitting mid-run keeps it.
+fn raise_best(
+    score: Res<Score>,
+    cheats: Res<Cheats>,
+    mut record: ResMut<RunRecord>,
+    co_op: Res<CoOp>,
+    mut profile: ResMut<Profile>,
+) {
+    // turning invincible partway through still counts
+    if cheats.invincible && record.ranked {
+        record.ranked = false;
+    }
+    if !score.is_changed() || !record.ranked {
+        return;
+    }
+    if co_op.0 {
+        if score.0 > profile.co_op_best {
+            profile.co_op_best = score.0;
+        }
+    } else if score.0 > profile.best {
+        profile.best = score.0;
+        // played for here, whatever the save said before
+        profile.unverified = false;
+    }
+}
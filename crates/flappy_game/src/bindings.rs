@@ -0,0 +1,401 @@
+//! Rebinding `InputBindings` without leaving dead inputs behind. A key can
+//! only do one thing at a time: binding an action to a key already used by
+//! another action live at the same time is refused with a `BindingConflict`
+//! naming the clash, unless asked to swap the two or clear the other one.
+//! Actions on different screens, like flapping and restarting, may share keys.
+//!
+//! Keys are rebound on the controls screen, see `controls_menu`, or with the
+//! console's `bind` command. Either way the bindings are saved to
+//! `FlappyConfig::bindings_path`, and loaded from there on the next start.
+
+use std::fmt;
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::persist::{load_ron, save_ron, Versioned};
+use crate::{ConsoleCommands, FlappyConfig, InputBindings};
+
+/// Keys `bind` knows by name, matched case-insensitively against their
+/// `Debug` names.
+const NAMED_KEYS: &[KeyCode] = &[
+    KeyCode::A,
+    KeyCode::B,
+    KeyCode::C,
+    KeyCode::D,
+    KeyCode::E,
+    KeyCode::F,
+    KeyCode::G,
+    KeyCode::H,
+    KeyCode::I,
+    KeyCode::J,
+    KeyCode::K,
+    KeyCode::L,
+    KeyCode::M,
+    KeyCode::N,
+    KeyCode::O,
+    KeyCode::P,
+    KeyCode::Q,
+    KeyCode::R,
+    KeyCode::S,
+    KeyCode::T,
+    KeyCode::U,
+    KeyCode::V,
+    KeyCode::W,
+    KeyCode::X,
+    KeyCode::Y,
+    KeyCode::Z,
+    KeyCode::Key0,
+    KeyCode::Key1,
+    KeyCode::Key2,
+    KeyCode::Key3,
+    KeyCode::Key4,
+    KeyCode::Key5,
+    KeyCode::Key6,
+    KeyCode::Key7,
+    KeyCode::Key8,
+    KeyCode::Key9,
+    KeyCode::F1,
+    KeyCode::F2,
+    KeyCode::F3,
+    KeyCode::F4,
+    KeyCode::F5,
+    KeyCode::F6,
+    KeyCode::F7,
+    KeyCode::F8,
+    KeyCode::F9,
+    KeyCode::F10,
+    KeyCode::F11,
+    KeyCode::F12,
+    KeyCode::Space,
+    KeyCode::Return,
+    KeyCode::Escape,
+    KeyCode::Tab,
+    KeyCode::Back,
+    KeyCode::Up,
+    KeyCode::Down,
+    KeyCode::Left,
+    KeyCode::Right,
+    KeyCode::Grave,
+    KeyCode::LShift,
+    KeyCode::RShift,
+    KeyCode::LControl,
+    KeyCode::RControl,
+    KeyCode::LAlt,
+    KeyCode::RAlt,
+];
+
+/// Something a key can be bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Flap,
+    FlapTwo,
+    Restart,
+    QuickRetry,
+    Pause,
+    Quit,
+    Mute,
+    DebugOverlay,
+    Console,
+    StatsOverlay,
+    Heatmap,
+}
+
+/// Where an action's key does anything.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scope {
+    Run,
+    GameOver,
+    Anywhere,
+}
+
+impl Scope {
+    fn overlaps(self, other: Scope) -> bool {
+        self == Scope::Anywhere || other == Scope::Anywhere || self == other
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 11] = [
+        Action::Flap,
+        Action::FlapTwo,
+        Action::Restart,
+        Action::QuickRetry,
+        Action::Pause,
+        Action::Quit,
+        Action::Mute,
+        Action::DebugOverlay,
+        Action::Console,
+        Action::StatsOverlay,
+        Action::Heatmap,
+    ];
+
+    /// The name `bind` knows the action by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Flap => "flap",
+            Action::FlapTwo => "flap_two",
+            Action::Restart => "restart",
+            Action::QuickRetry => "quick_retry",
+            Action::Pause => "pause",
+            Action::Quit => "quit",
+            Action::Mute => "mute",
+            Action::DebugOverlay => "debug_overlay",
+            Action::Console => "console",
+            Action::StatsOverlay => "stats_overlay",
+            Action::Heatmap => "heatmap",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.name() == name)
+    }
+
+    fn scope(self) -> Scope {
+        match self {
+            // quick retry is only read during the death cam
+            Action::Flap | Action::FlapTwo | Action::QuickRetry | Action::Pause => Scope::Run,
+            Action::Restart => Scope::GameOver,
+            _ => Scope::Anywhere,
+        }
+    }
+}
+
+/// How to settle a rebind that clashes with other actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolve {
+    /// The other actions take the key being replaced.
+    Swap,
+    /// The other actions lose the key. Only for actions with other keys to
+    /// fall back on.
+    Clear,
+}
+
+/// A rebind refused because `key` already does something else while `action`
+/// is live.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindingConflict {
+    pub action: Action,
+    pub key: KeyCode,
+    pub clashes: Vec<Action>,
+}
+
+impl fmt::Display for BindingConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let clashes: Vec<_> = self.clashes.iter().map(|action| action.name()).collect();
+        write!(
+            f,
+            "{:?} is already {}, add 'swap' or 'clear' to bind it to {}",
+            self.key,
+            clashes.join(" and "),
+            self.action.name()
+        )
+    }
+}
+
+impl InputBindings {
+    /// Reads the bindings saved at `path`. A missing file is the defaults.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        load_ron(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(self, path)
+    }
+
+    /// The keys bound to `action`, the main one first.
+    pub fn keys(&self, action: Action) -> Vec<KeyCode> {
+        match action {
+            Action::Flap => self.flap.clone(),
+            Action::FlapTwo => self.flap_two.clone(),
+            Action::Restart => self.restart.clone(),
+            Action::QuickRetry => vec![self.quick_retry],
+            Action::Pause => vec![self.pause],
+            Action::Quit => vec![self.quit],
+            Action::Mute => vec![self.mute],
+            Action::DebugOverlay => vec![self.debug_overlay],
+            Action::Console => vec![self.console],
+            Action::StatsOverlay => vec![self.stats_overlay],
+            Action::Heatmap => vec![self.heatmap],
+        }
+    }
+
+    fn key_mut(&mut self, action: Action) -> Option<&mut KeyCode> {
+        match action {
+            Action::Flap => self.flap.first_mut(),
+            Action::FlapTwo => self.flap_two.first_mut(),
+            Action::Restart => self.restart.first_mut(),
+            Action::QuickRetry => Some(&mut self.quick_retry),
+            Action::Pause => Some(&mut self.pause),
+            Action::Quit => Some(&mut self.quit),
+            Action::Mute => Some(&mut self.mute),
+            Action::DebugOverlay => Some(&mut self.debug_overlay),
+            Action::Console => Some(&mut self.console),
+            Action::StatsOverlay => Some(&mut self.stats_overlay),
+            Action::Heatmap => Some(&mut self.heatmap),
+        }
+    }
+
+    /// Actions other than `action` that `key` already does something for
+    /// while `action` is live.
+    pub fn conflicts(&self, action: Action, key: KeyCode) -> Vec<Action> {
+        Action::ALL
+            .into_iter()
+            .filter(|other| *other != action && other.scope().overlaps(action.scope()))
+            .filter(|other| self.keys(*other).contains(&key))
+            .collect()
+    }
+
+    /// Makes `key` the main key of `action`. Refused if it clashes with other
+    /// actions, unless `resolve` says how to settle it.
+    pub fn rebind(
+        &mut self,
+        action: Action,
+        key: KeyCode,
+        resolve: Option<Resolve>,
+    ) -> Result<(), BindingConflict> {
+        let clashes = self.conflicts(action, key);
+        let conflict = || BindingConflict {
+            action,
+            key,
+            clashes: clashes.clone(),
+        };
+        let old = self.keys(action).first().copied();
+        match resolve {
+            _ if clashes.is_empty() => {}
+            None => return Err(conflict()),
+            // nothing to swap in, or a lone key to take away
+            Some(Resolve::Swap) if old.is_none() => return Err(conflict()),
+            Some(Resolve::Clear) if clashes.iter().any(|other| self.keys(*other).len() < 2) => {
+                return Err(conflict())
+            }
+            Some(Resolve::Swap) => {
+                for other in &clashes {
+                    self.replace_key(*other, key, old);
+                }
+            }
+            Some(Resolve::Clear) => {
+                for other in &clashes {
+                    self.replace_key(*other, key, None);
+                }
+            }
+        }
+        match self.key_mut(action) {
+            Some(main) => *main = key,
+            None if action == Action::Flap => self.flap.push(key),
+            None if action == Action::FlapTwo => self.flap_two.push(key),
+            None => self.restart.push(key),
+        }
+        Ok(())
+    }
+
+    /// Swaps `key` for `with` in `action`'s keys, or drops it without.
+    fn replace_key(&mut self, action: Action, key: KeyCode, with: Option<KeyCode>) {
+        let keys = match action {
+            Action::Flap => &mut self.flap,
+            Action::FlapTwo => &mut self.flap_two,
+            Action::Restart => &mut self.restart,
+            _ => {
+                if let (Some(single), Some(with)) = (self.key_mut(action), with) {
+                    *single = with;
+                }
+                return;
+            }
+        };
+        match with {
+            Some(with) if !keys.contains(&with) => {
+                keys.iter_mut()
+                    .filter(|bound| **bound == key)
+                    .for_each(|bound| *bound = with);
+            }
+            _ => keys.retain(|bound| *bound != key),
+        }
+    }
+}
+
+impl Versioned for InputBindings {
+    const VERSION: u32 = 1;
+}
+
+/// The name a key is shown and typed as.
+pub(crate) fn key_name(key: KeyCode) -> String {
+    format!("{key:?}")
+}
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    NAMED_KEYS
+        .iter()
+        .copied()
+        .find(|key| key_name(*key).eq_ignore_ascii_case(name))
+}
+
+pub(crate) fn add_bindings(app: &mut App) {
+    app.add_startup_system(load_bindings)
+        .add_system(save_bindings.in_base_set(CoreSet::Last));
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "bind",
+        "bind <action> <key> [swap|clear]: rebind a key",
+        bind,
+    );
+}
+
+fn bind(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let (Some(action), Some(key)) = (args.first(), args.get(1)) else {
+        return Err("expected 'bind <action> <key> [swap|clear]'".to_string());
+    };
+    let action = Action::from_name(action).ok_or_else(|| {
+        let names: Vec<_> = Action::ALL.iter().map(|action| action.name()).collect();
+        format!(
+            "unknown action '{action}', expected one of {}",
+            names.join(", ")
+        )
+    })?;
+    let key = parse_key(key).ok_or_else(|| format!("unknown key '{key}'"))?;
+    let resolve = match args.get(2) {
+        None => None,
+        Some(&"swap") => Some(Resolve::Swap),
+        Some(&"clear") => Some(Resolve::Clear),
+        Some(other) => return Err(format!("expected 'swap' or 'clear', not '{other}'")),
+    };
+    let mut config = world.resource_mut::<FlappyConfig>();
+    config
+        .bindings
+        .rebind(action, key, resolve)
+        .map_err(|conflict| conflict.to_string())?;
+    info!(action = action.name(), ?key, "rebound");
+    Ok(format!("{} is now {key:?}", action.name()))
+}
+
+/// Swaps in the bindings saved by an earlier session. Without a saved file
+/// the host's stay.
+fn load_bindings(mut config: ResMut<FlappyConfig>) {
+    let Some(path) = config.bindings_path.clone() else {
+        return;
+    };
+    if !path.exists() {
+        return;
+    }
+    match InputBindings::load(&path) {
+        Ok(bindings) => config.bindings = bindings,
+        Err(error) => warn!(%error, path = %path.display(), "failed to load the bindings"),
+    }
+}
+
+/// Saves the bindings whenever they're rebound.
+fn save_bindings(config: Res<FlappyConfig>, mut saved: Local<Option<InputBindings>>) {
+    if !config.is_changed() || saved.as_ref() == Some(&config.bindings) {
+        return;
+    }
+    // the first bindings seen are the ones loaded
+    let first = saved.is_none();
+    *saved = Some(config.bindings.clone());
+    let Some(path) = &config.bindings_path else {
+        return;
+    };
+    if first {
+        return;
+    }
+    if let Err(error) = config.bindings.save(path) {
+        warn!(%error, path = %path.display(), "failed to save the bindings");
+    }
+}
@@ -0,0 +1,205 @@
+//! Backdrops that change as a run goes on: every `BIOME_POINTS` the scene
+//! moves to the next `Biome`, crossfading both the skyline behind the pipes
+//! and the sky's tint so long runs don't look the same throughout.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::{AppState, FlappyConfig, MotionPrefs, Score, Sky};
+
+/// Points from one biome to the next.
+pub const BIOME_POINTS: u32 = 50;
+/// Seconds each crossfade takes.
+const BIOME_FADE: f32 = 2.0;
+/// Between the sky and the clouds.
+const BIOME_Z: f32 = -0.045;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Biome {
+    #[default]
+    City,
+    Forest,
+    Desert,
+    Space,
+}
+
+impl Biome {
+    pub const ALL: [Biome; 4] = [Biome::City, Biome::Forest, Biome::Desert, Biome::Space];
+
+    /// The biome a run is in at `score`, going round again after the last.
+    pub fn for_score(score: u32) -> Self {
+        Self::ALL[(score / BIOME_POINTS) as usize % Self::ALL.len()]
+    }
+
+    /// Multiplied with the theme's sky, see `Sky::tint`.
+    pub fn sky_tint(self) -> Color {
+        match self {
+            Biome::City => Color::WHITE,
+            Biome::Forest => Color::rgb(0.85, 1.0, 0.85),
+            Biome::Desert => Color::rgb(1.0, 0.9, 0.7),
+            Biome::Space => Color::rgb(0.15, 0.15, 0.3),
+        }
+    }
+
+    fn skyline_color(self) -> Color {
+        match self {
+            Biome::City => Color::rgba(0.3, 0.35, 0.45, 0.6),
+            Biome::Forest => Color::rgba(0.2, 0.45, 0.25, 0.6),
+            Biome::Desert => Color::rgba(0.85, 0.7, 0.45, 0.6),
+            Biome::Space => Color::rgba(1.0, 1.0, 1.0, 0.9),
+        }
+    }
+
+    /// Range of widths and heights of the shapes making up the skyline.
+    fn shape_sizes(self) -> (Vec2, Vec2) {
+        match self {
+            // buildings
+            Biome::City => (Vec2::new(12.0, 20.0), Vec2::new(24.0, 60.0)),
+            // trees
+            Biome::Forest => (Vec2::new(5.0, 24.0), Vec2::new(10.0, 48.0)),
+            // dunes
+            Biome::Desert => (Vec2::new(40.0, 6.0), Vec2::new(80.0, 18.0)),
+            // stars
+            Biome::Space => (Vec2::splat(1.0), Vec2::splat(2.0)),
+        }
+    }
+}
+
+/// The biome being shown, and the one being faded out of.
+#[derive(Resource, Debug)]
+pub struct Biomes {
+    pub current: Biome,
+    pub previous: Option<Biome>,
+    /// Runs from a change of biome to the end of its crossfade.
+    pub fade: Timer,
+}
+
+impl Default for Biomes {
+    fn default() -> Self {
+        Self {
+            current: Biome::default(),
+            previous: None,
+            fade: Timer::from_seconds(BIOME_FADE, TimerMode::Once),
+        }
+    }
+}
+
+impl Biomes {
+    /// How much of `biome` is showing, from 0 to 1.
+    pub fn weight(&self, biome: Biome) -> f32 {
+        let t = self.fade.percent();
+        if biome == self.current {
+            if self.previous.is_some() {
+                t
+            } else {
+                1.0
+            }
+        } else if Some(biome) == self.previous {
+            1.0 - t
+        } else {
+            0.0
+        }
+    }
+}
+
+/// One shape of a biome's skyline, at `alpha` when the biome is fully shown.
+#[derive(Component, Debug)]
+pub struct BiomeLayer {
+    pub biome: Biome,
+    alpha: f32,
+}
+
+pub(crate) fn add_biomes(app: &mut App) {
+    app.init_resource::<Biomes>()
+        .add_startup_system(spawn_skylines)
+        .add_system(reset_biomes.in_schedule(OnEnter(AppState::Game)))
+        .add_system(follow_score.in_set(OnUpdate(AppState::Game)))
+        .add_system(fade_biomes.after(follow_score));
+}
+
+/// Lays out every biome's skyline once, from a fixed seed so each looks the
+/// same every time. All but the current one start out invisible.
+fn spawn_skylines(mut commands: Commands, config: Res<FlappyConfig>, biomes: Res<Biomes>) {
+    let bottom = -config.height() / 2.0;
+    for (index, biome) in (0..).zip(Biome::ALL) {
+        let mut rng = StdRng::seed_from_u64(index);
+        let (min, max) = biome.shape_sizes();
+        let color = biome.skyline_color();
+        let mut x = -config.width() / 2.0;
+        while x < config.width() / 2.0 {
+            let size = Vec2::new(rng.gen_range(min.x..=max.x), rng.gen_range(min.y..=max.y));
+            let y = match biome {
+                Biome::Space => rng.gen_range(bottom..-bottom),
+                _ => bottom + size.y / 2.0,
+            };
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: color.with_a(color.a() * biomes.weight(biome)),
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_xyz(x + size.x / 2.0, y, BIOME_Z),
+                    ..default()
+                },
+                BiomeLayer {
+                    biome,
+                    alpha: color.a(),
+                },
+            ));
+            x += size.x + rng.gen_range(0.0..size.x);
+        }
+    }
+}
+
+fn reset_biomes(mut biomes: ResMut<Biomes>) {
+    if biomes.current != Biome::default() || biomes.previous.is_some() {
+        *biomes = Biomes::default();
+    }
+}
+
+fn follow_score(score: Res<Score>, mut biomes: ResMut<Biomes>) {
+    let biome = Biome::for_score(score.0);
+    if biome == biomes.current {
+        return;
+    }
+    info!(?biome, "entering biome");
+    biomes.previous = Some(biomes.current);
+    biomes.current = biome;
+    biomes.fade.reset();
+}
+
+/// Crossfades the skylines and sky tint, in real time. Skips straight to the
+/// new biome without `MotionPrefs::sky_transitions`.
+fn fade_biomes(
+    time: Res<Time>,
+    motion: Res<MotionPrefs>,
+    mut biomes: ResMut<Biomes>,
+    mut sky: ResMut<Sky>,
+    mut query: Query<(&mut Sprite, &BiomeLayer)>,
+) {
+    if biomes.previous.is_some() {
+        let step = if motion.sky_transitions {
+            time.delta()
+        } else {
+            biomes.fade.duration()
+        };
+        biomes.fade.tick(step);
+    } else if !biomes.is_changed() {
+        return;
+    }
+
+    for (mut sprite, layer) in query.iter_mut() {
+        sprite.color.set_a(layer.alpha * biomes.weight(layer.biome));
+    }
+    let tint = Biome::ALL
+        .iter()
+        .map(|biome| Vec4::from(biome.sky_tint().as_rgba_f32()) * biomes.weight(*biome))
+        .sum::<Vec4>();
+    sky.tint = Color::rgba(tint.x, tint.y, tint.z, tint.w);
+
+    if biomes.fade.finished() {
+        biomes.previous = None;
+    }
+}
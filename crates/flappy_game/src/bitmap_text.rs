@@ -0,0 +1,272 @@
+//! Pixel text for the UI, drawn from the 3x5 `BitmapFont` in
+//! `assets/fonts/pixel-3x5.txt` so it matches the nearest-neighbour art. A
+//! `BitmapText` is rendered to a texture whenever it changes, and shown as a
+//! UI image on UI nodes or as a sprite on anything else.
+//!
+//! Text is drawn upper case. Accented letters are the plain letter with a mark
+//! from the font, and anything else missing from it comes out as `?`.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::ui::UiSystem;
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+/// Rows above each glyph for accents, and below for the cedilla.
+const ABOVE: usize = 2;
+const BELOW: usize = 1;
+const CELL_HEIGHT: usize = ABOVE + GLYPH_HEIGHT + BELOW;
+/// Pixels between characters, and between lines.
+const SPACING: usize = 1;
+/// Marks drawn under the letter rather than over it.
+const MARKS_BELOW: &[&str] = &["cedilla"];
+
+/// Lit pixels, row by row.
+type Bitmap = Vec<Vec<bool>>;
+
+/// The letter and mark making up an accented capital.
+fn decompose(c: char) -> Option<(char, &'static str)> {
+    let parts = match c {
+        'Á' => ('A', "acute"),
+        'É' => ('E', "acute"),
+        'Í' => ('I', "acute"),
+        'Ó' => ('O', "acute"),
+        'Ú' => ('U', "acute"),
+        'À' => ('A', "grave"),
+        'È' => ('E', "grave"),
+        'Ì' => ('I', "grave"),
+        'Ò' => ('O', "grave"),
+        'Ù' => ('U', "grave"),
+        'Â' => ('A', "circumflex"),
+        'Ê' => ('E', "circumflex"),
+        'Î' => ('I', "circumflex"),
+        'Ô' => ('O', "circumflex"),
+        'Û' => ('U', "circumflex"),
+        'Ä' => ('A', "diaeresis"),
+        'Ë' => ('E', "diaeresis"),
+        'Ï' => ('I', "diaeresis"),
+        'Ö' => ('O', "diaeresis"),
+        'Ü' => ('U', "diaeresis"),
+        'Ã' => ('A', "tilde"),
+        'Ñ' => ('N', "tilde"),
+        'Õ' => ('O', "tilde"),
+        'Ç' => ('C', "cedilla"),
+        _ => return None,
+    };
+    Some(parts)
+}
+
+/// Glyphs and accent marks, parsed from the font file.
+#[derive(Resource, Debug)]
+pub struct BitmapFont {
+    glyphs: HashMap<char, Bitmap>,
+    marks: HashMap<String, Bitmap>,
+}
+
+impl Default for BitmapFont {
+    fn default() -> Self {
+        Self::parse(include_str!("../assets/fonts/pixel-3x5.txt")).expect("bundled pixel font")
+    }
+}
+
+impl BitmapFont {
+    /// Reads a font of `[name]` headers each followed by its rows, with `# `
+    /// starting a comment. Single character names are glyphs, longer ones are
+    /// marks.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut entries: Vec<(String, Bitmap)> = Vec::new();
+        for line in source.lines() {
+            // rows never have spaces, so can't be mistaken for comments
+            if line.starts_with("# ") {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                entries.push((name.to_string(), Vec::new()));
+                continue;
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let Some((name, rows)) = entries.last_mut() else {
+                return Err(format!("row {line:?} before any glyph"));
+            };
+            if line.chars().count() != GLYPH_WIDTH || line.chars().any(|c| c != '#' && c != '.') {
+                return Err(format!("bad row {line:?} in [{name}]"));
+            }
+            rows.push(line.chars().map(|c| c == '#').collect());
+        }
+
+        let mut font = Self {
+            glyphs: HashMap::new(),
+            marks: HashMap::new(),
+        };
+        for (name, rows) in entries {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if rows.len() == GLYPH_HEIGHT => {
+                    font.glyphs.insert(c, rows);
+                }
+                (Some(_), Some(_)) if rows.len() <= ABOVE.max(BELOW) => {
+                    font.marks.insert(name, rows);
+                }
+                _ => return Err(format!("[{name}] has {} rows", rows.len())),
+            }
+        }
+        Ok(font)
+    }
+
+    /// Whether `c` can be drawn, either directly or as a marked letter.
+    pub fn supports(&self, c: char) -> bool {
+        self.glyphs.contains_key(&c)
+            || decompose(c).is_some_and(|(base, mark)| {
+                self.glyphs.contains_key(&base) && self.marks.contains_key(mark)
+            })
+    }
+
+    /// Lights the pixels of `c` in a cell whose top left is at `x`, `y`.
+    fn draw(&self, c: char, pixels: &mut Bitmap, x: usize, y: usize) {
+        let (base, mark) = match decompose(c) {
+            Some((base, mark)) if self.supports(c) => (base, Some(mark)),
+            _ if self.glyphs.contains_key(&c) => (c, None),
+            _ => ('?', None),
+        };
+        let mut blit = |bitmap: &Bitmap, top: usize| {
+            for (row, lit) in bitmap.iter().enumerate() {
+                for (column, lit) in lit.iter().enumerate() {
+                    if *lit {
+                        pixels[top + row][x + column] = true;
+                    }
+                }
+            }
+        };
+        if let Some(glyph) = self.glyphs.get(&base) {
+            blit(glyph, y + ABOVE);
+        }
+        if let Some(name) = mark {
+            let mark = &self.marks[name];
+            let top = if MARKS_BELOW.contains(&name) {
+                y + ABOVE + GLYPH_HEIGHT
+            } else {
+                y + ABOVE - mark.len()
+            };
+            blit(mark, top);
+        }
+    }
+
+    /// `text` in white on transparent, one texel per font pixel.
+    pub fn render(&self, text: &str) -> Image {
+        let text = text.to_uppercase();
+        let lines: Vec<Vec<char>> = text.lines().map(|line| line.chars().collect()).collect();
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let width = columns * (GLYPH_WIDTH + SPACING) - SPACING;
+        let height = lines.len().max(1) * (CELL_HEIGHT + SPACING) - SPACING;
+
+        let mut pixels = vec![vec![false; width]; height];
+        for (row, line) in lines.iter().enumerate() {
+            for (column, c) in line.iter().enumerate() {
+                let x = column * (GLYPH_WIDTH + SPACING);
+                let y = row * (CELL_HEIGHT + SPACING);
+                self.draw(*c, &mut pixels, x, y);
+            }
+        }
+
+        let data = pixels
+            .iter()
+            .flatten()
+            .flat_map(|lit| {
+                if *lit {
+                    [255, 255, 255, 255]
+                } else {
+                    [0, 0, 0, 0]
+                }
+            })
+            .collect();
+        Image::new(
+            Extent3d {
+                width: width as u32,
+                height: height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+}
+
+/// Text drawn in the `BitmapFont`, tinted by the node's `BackgroundColor` or
+/// the sprite's colour.
+#[derive(Component, Clone, Debug, Default)]
+pub struct BitmapText {
+    pub value: String,
+    /// Screen pixels per font pixel.
+    pub scale: f32,
+}
+
+/// A UI node showing `value`.
+pub(crate) fn bitmap_text(
+    value: impl Into<String>,
+    scale: f32,
+    color: Color,
+) -> (ImageBundle, BitmapText) {
+    (
+        ImageBundle {
+            background_color: color.into(),
+            ..default()
+        },
+        BitmapText {
+            value: value.into(),
+            scale,
+        },
+    )
+}
+
+/// A sprite showing `value`, centred on its transform.
+pub(crate) fn bitmap_text_2d(
+    value: impl Into<String>,
+    scale: f32,
+    color: Color,
+) -> (SpriteBundle, BitmapText) {
+    (
+        SpriteBundle {
+            sprite: Sprite { color, ..default() },
+            ..default()
+        },
+        BitmapText {
+            value: value.into(),
+            scale,
+        },
+    )
+}
+
+pub(crate) fn add_bitmap_text(app: &mut App) {
+    app.init_resource::<BitmapFont>().add_system(
+        render_bitmap_text
+            .before(UiSystem::Flex)
+            .in_base_set(CoreSet::PostUpdate),
+    );
+}
+
+fn render_bitmap_text(
+    font: Res<BitmapFont>,
+    mut images: ResMut<Assets<Image>>,
+    mut ui_query: Query<(&BitmapText, &mut UiImage, &mut Style), Changed<BitmapText>>,
+    mut sprite_query: Query<(&BitmapText, &mut Handle<Image>, &mut Sprite), Changed<BitmapText>>,
+) {
+    for (text, mut image, mut style) in ui_query.iter_mut() {
+        let rendered = font.render(&text.value);
+        let size = rendered.size() * text.scale;
+        style.size = Size::new(Val::Px(size.x), Val::Px(size.y));
+        image.texture = images.add(rendered);
+    }
+    for (text, mut texture, mut sprite) in sprite_query.iter_mut() {
+        let rendered = font.render(&text.value);
+        sprite.custom_size = Some(rendered.size() * text.scale);
+        *texture = images.add(rendered);
+    }
+}
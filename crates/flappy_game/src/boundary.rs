@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use flappy_core::collision::boundaries;
+
+use crate::{AppState, Bird, Collider, DespawnOnExit, FlappyConfig, Hitbox, Velocity};
+
+/// Marks the floor and ceiling colliders. Touching one ends the run with
+/// `DeathCause::OutOfBounds` rather than `DeathCause::Pipe`.
+#[derive(Component, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Boundary {
+    Floor,
+    Ceiling,
+}
+
+/// A boundary that holds the bird back instead of killing it.
+#[derive(Component)]
+pub struct Blocking;
+
+pub(crate) fn boundaries_setup(mut commands: Commands, config: Res<FlappyConfig>) {
+    let (floor, ceiling) = boundaries(config.playfield);
+    for (boundary, aabb) in [(Boundary::Floor, floor), (Boundary::Ceiling, ceiling)] {
+        // boundaries never move, so their global transform can be set up front
+        // rather than waiting a frame for propagation to place them
+        let transform = Transform::from_translation(aabb.center.extend(0.0));
+        let mut entity = commands.spawn((
+            TransformBundle {
+                local: transform,
+                global: transform.into(),
+            },
+            Collider { size: aabb.size },
+            boundary,
+            DespawnOnExit(AppState::Game),
+        ));
+        if boundary == Boundary::Ceiling && !config.rules.lethal_ceiling {
+            entity.insert(Blocking);
+        }
+    }
+}
+
+/// Pushes the bird back out of any `Blocking` boundary and cancels its velocity
+/// towards it.
+pub(crate) fn block_bird(
+    boundary_query: Query<(&GlobalTransform, &Collider, &Boundary), With<Blocking>>,
+    mut bird_query: Query<(&mut Transform, &mut Velocity, &Hitbox), With<Bird>>,
+) {
+    for (mut transform, mut velocity, hitbox) in bird_query.iter_mut() {
+        for (boundary_transform, collider, boundary) in boundary_query.iter() {
+            let edge = collider.size.y / 2.0;
+            let center = boundary_transform.translation().y;
+            match boundary {
+                Boundary::Floor => {
+                    let bottom = center + edge + hitbox.size.y / 2.0;
+                    if transform.translation.y < bottom {
+                        transform.translation.y = bottom;
+                        velocity.0.y = velocity.0.y.max(0.0);
+                    }
+                }
+                Boundary::Ceiling => {
+                    let top = center - edge - hitbox.size.y / 2.0;
+                    if transform.translation.y > top {
+                        transform.translation.y = top;
+                        velocity.0.y = velocity.0.y.min(0.0);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Hover and pressed feedback for UI buttons. Give a button a `ButtonSkin` and
+//! its tint, and optionally its image, follow its `Interaction`. The button
+//! with `MenuFocus` looks hovered.
+
+use bevy::prelude::*;
+
+use crate::locale::localized_text;
+use crate::{Focusable, Localized, MenuFocus};
+
+/// How a button looks in one interaction state.
+#[derive(Clone, Debug)]
+pub struct ButtonLook {
+    /// Background colour, which also tints the button's image.
+    pub color: Color,
+    /// Replaces the `UiImage` when set. Leave unset to keep the current one.
+    pub image: Option<Handle<Image>>,
+}
+
+impl From<Color> for ButtonLook {
+    fn from(color: Color) -> Self {
+        Self { color, image: None }
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct ButtonSkin {
+    pub normal: ButtonLook,
+    pub hovered: ButtonLook,
+    pub pressed: ButtonLook,
+}
+
+impl ButtonSkin {
+    /// Untinted image buttons, darkened on hover and more so when pressed.
+    pub fn image() -> Self {
+        Self {
+            normal: Color::WHITE.into(),
+            hovered: Color::rgb(0.85, 0.85, 0.85).into(),
+            pressed: Color::rgb(0.65, 0.65, 0.65).into(),
+        }
+    }
+
+    /// Translucent dark panels, which brighten on hover.
+    pub fn panel() -> Self {
+        Self {
+            normal: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+            hovered: Color::rgba(0.2, 0.2, 0.2, 0.7).into(),
+            pressed: Color::rgba(0.0, 0.0, 0.0, 0.9).into(),
+        }
+    }
+
+    pub fn look(&self, interaction: Interaction) -> &ButtonLook {
+        match interaction {
+            Interaction::Clicked => &self.pressed,
+            Interaction::Hovered => &self.hovered,
+            Interaction::None => &self.normal,
+        }
+    }
+}
+
+/// Spawns a text button on a `ButtonSkin::panel`, as used in menus and
+/// dialogs, with `bundle` added to identify it.
+pub(crate) fn spawn_text_button(
+    parent: &mut ChildBuilder,
+    label: Localized,
+    focus_order: i32,
+    bundle: impl Bundle,
+) -> Entity {
+    let skin = ButtonSkin::panel();
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(56.0), Val::Px(12.0)),
+                    margin: UiRect::all(Val::Px(2.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: skin.normal.color.into(),
+                ..default()
+            },
+            skin,
+            Focusable { order: focus_order },
+            bundle,
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(label, 1.0, Color::WHITE));
+        })
+        .id()
+}
+
+pub(crate) fn add_button_skins(app: &mut App) {
+    app.add_system(apply_button_skins);
+}
+
+fn apply_button_skins(
+    focus: Res<MenuFocus>,
+    mut query: Query<(
+        Entity,
+        &Interaction,
+        &ButtonSkin,
+        &mut BackgroundColor,
+        &mut UiImage,
+    )>,
+) {
+    for (entity, interaction, skin, mut color, mut image) in query.iter_mut() {
+        let interaction = match interaction {
+            Interaction::None if focus.focused == Some(entity) => Interaction::Hovered,
+            interaction => *interaction,
+        };
+        let look = skin.look(interaction);
+        if color.0 != look.color {
+            color.0 = look.color;
+        }
+        if let Some(handle) = &look.image {
+            if image.texture != *handle {
+                image.texture = handle.clone();
+            }
+        }
+    }
+}
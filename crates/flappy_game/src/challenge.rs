@@ -0,0 +1,490 @@
+//! Challenge codes, like `FB-7K2Q-HARD`, naming a run's seed and difficulty.
+//! The game over screen shows the code for the run just played, and entering
+//! it from the main menu plays the same pipes, so players can compare scores.
+//! Codes for runs with `Settings::assist` on are tagged `-A`, those with the
+//! `AirDash` `-D`, those played with the physics changed from the physics
+//! panel `-P` and the step of each slider, and those for `CoOp` runs end in
+//! `-2P`. Each is played the same way again, so scores are only compared with
+//! runs played alike.
+//!
+//! A code's modifiers last until the player is back at the main menu, and
+//! aren't saved: the player's own are put back then, see `ChallengeRun`.
+//!
+//! The seed is written in Crockford's base 32, which leaves out letters easily
+//! mistaken for digits and reads them as those digits instead.
+
+use std::fmt;
+use std::str::FromStr;
+
+use bevy::prelude::*;
+use bevy::window::ReceivedCharacter;
+use flappy_core::physics::Physics;
+
+use crate::bitmap_text::bitmap_text;
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::persist::HoldSaves;
+use crate::physics_menu::{StartingPhysics, MAX_PHYSICS_STEP};
+use crate::results::ResultsCues;
+use crate::seed::reseed;
+use crate::{
+    AirDash, AppState, BitmapText, CoOp, DespawnOnExit, DevConsole, Difficulty, FlappyConfig,
+    Localized, MenuFocus, PhysicsSlider, Profile, ResultsPanelEvent, RunSeed, Settings,
+    DASH_UNLOCK_SCORE,
+};
+
+const PREFIX: &str = "FB";
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+/// Characters of seed in a code, enough for any seed below `SEED_LIMIT`.
+const SEED_DIGITS: usize = 4;
+/// Follows the difficulty in the code of an assisted run.
+const ASSIST_TAG: &str = "A";
+/// Follows the assist tag in the code of a run with the dash.
+const DASH_TAG: &str = "D";
+/// Starts the tag of a run with the physics changed, followed by the step of
+/// each `PhysicsSlider`.
+const PHYSICS_TAG: char = 'P';
+/// Ends the code of a `CoOp` run.
+const CO_OP_TAG: &str = "2P";
+/// Longest code that can be typed in, the length of one with every tag on
+/// `Normal`.
+const MAX_INPUT: usize = 26;
+
+/// A run to play again: its seed and difficulty, and whether it was played
+/// with assist mode, the dash, changed physics or in `CoOp`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChallengeCode {
+    pub seed: u32,
+    pub difficulty: Difficulty,
+    pub assisted: bool,
+    pub dash: bool,
+    /// The step of each of `PhysicsSlider::ALL`, when the physics were
+    /// changed.
+    pub physics: Option<[u32; 3]>,
+    pub co_op: bool,
+}
+
+impl ChallengeCode {
+    /// The steps to note for `physics`, unless they're the `starting` ones.
+    fn physics_steps(physics: &Physics, starting: &StartingPhysics) -> Option<[u32; 3]> {
+        starting
+            .modified(physics)
+            .then(|| PhysicsSlider::ALL.map(|slider| slider.step(physics)))
+    }
+
+    /// The physics the code's run was played with.
+    fn physics(&self, starting: &StartingPhysics) -> Physics {
+        let mut physics = starting.0;
+        if let Some(steps) = self.physics {
+            for (slider, step) in PhysicsSlider::ALL.into_iter().zip(steps) {
+                slider.set_step(&mut physics, step);
+            }
+        }
+        physics
+    }
+}
+
+/// The digit `c` stands for in `ALPHABET`, reading letters easily mistaken
+/// for digits as those digits.
+fn digit(c: char) -> Result<u32, String> {
+    let c = match c {
+        'O' => '0',
+        'I' | 'L' => '1',
+        c => c,
+    };
+    ALPHABET
+        .iter()
+        .position(|a| *a as char == c)
+        .map(|digit| digit as u32)
+        .ok_or_else(|| format!("'{c}' isn't used in codes"))
+}
+
+impl fmt::Display for ChallengeCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seed: String = (0..SEED_DIGITS)
+            .rev()
+            .map(|digit| ALPHABET[(self.seed >> (digit * 5)) as usize & 31] as char)
+            .collect();
+        write!(f, "{PREFIX}-{seed}-{}", self.difficulty.tag())?;
+        if self.assisted {
+            write!(f, "-{ASSIST_TAG}")?;
+        }
+        if self.dash {
+            write!(f, "-{DASH_TAG}")?;
+        }
+        if let Some(steps) = self.physics {
+            write!(f, "-{PHYSICS_TAG}")?;
+            for step in steps {
+                write!(f, "{}", ALPHABET[step as usize & 31] as char)?;
+            }
+        }
+        if self.co_op {
+            write!(f, "-{CO_OP_TAG}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ChallengeCode {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let code = code.trim().to_ascii_uppercase();
+        let parts: Vec<&str> = code.split('-').collect();
+        let expected = || {
+            format!(
+                "expected {PREFIX}-XXXX-DIFFICULTY[-{ASSIST_TAG}][-{DASH_TAG}][-{PHYSICS_TAG}XXX]\
+                 [-{CO_OP_TAG}]"
+            )
+        };
+        let [prefix, seed, difficulty, tags @ ..] = parts.as_slice() else {
+            return Err(expected());
+        };
+        // the tags are optional, but always in this order
+        let mut tags = tags.iter().peekable();
+        let assisted = tags.next_if_eq(&&ASSIST_TAG).is_some();
+        let dash = tags.next_if_eq(&&DASH_TAG).is_some();
+        let physics = tags
+            .next_if(|tag| tag.len() == 4 && tag.starts_with(PHYSICS_TAG))
+            .map(|tag| {
+                let mut steps = [0; 3];
+                for (step, c) in steps.iter_mut().zip(tag.chars().skip(1)) {
+                    *step = Some(digit(c)?)
+                        .filter(|step| *step <= MAX_PHYSICS_STEP)
+                        .ok_or_else(|| format!("'{c}' is past the end of a physics slider"))?;
+                }
+                Ok::<_, String>(steps)
+            })
+            .transpose()?;
+        let co_op = tags.next_if_eq(&&CO_OP_TAG).is_some();
+        if tags.next().is_some() {
+            return Err(expected());
+        }
+        if *prefix != PREFIX {
+            return Err(format!("codes start with {PREFIX}-"));
+        }
+        if seed.chars().count() != SEED_DIGITS {
+            return Err(format!("the seed should be {SEED_DIGITS} characters"));
+        }
+        let seed = seed
+            .chars()
+            .try_fold(0, |seed, c| Ok::<_, String>(seed << 5 | digit(c)?))?;
+        let difficulty = Difficulty::from_tag(difficulty)
+            .ok_or_else(|| format!("unknown difficulty '{difficulty}'"))?;
+        Ok(Self {
+            seed,
+            difficulty,
+            assisted,
+            dash,
+            physics,
+            co_op,
+        })
+    }
+}
+
+/// The modifiers a challenge code changes, as the player had them before it.
+#[derive(Clone, Debug)]
+struct OwnModifiers {
+    difficulty: Difficulty,
+    assisted: bool,
+    dash: bool,
+    physics: Physics,
+}
+
+/// Whether a challenge is being played, holding on to the player's own
+/// modifiers until they're back at the main menu. `Settings` aren't saved
+/// meanwhile, so quitting mid-challenge keeps them too.
+#[derive(Resource, Default, Debug)]
+pub struct ChallengeRun {
+    own: Option<OwnModifiers>,
+    /// The code's seed, played again by every restart.
+    seed: u32,
+}
+
+impl ChallengeRun {
+    pub fn active(&self) -> bool {
+        self.own.is_some()
+    }
+}
+
+/// What's been typed on the challenge screen.
+#[derive(Resource, Default, Debug)]
+pub struct ChallengeInput {
+    pub text: String,
+}
+
+/// Shows the `ChallengeInput`.
+#[derive(Component)]
+struct ChallengeEntry;
+
+/// Explains the entry, or why the last code was turned down.
+#[derive(Component)]
+struct ChallengeHint;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChallengeButton {
+    Play,
+    Back,
+}
+
+pub(crate) fn add_challenges(app: &mut App) {
+    app.init_resource::<ChallengeInput>()
+        .init_resource::<ChallengeRun>()
+        .add_system(end_challenge.in_schedule(OnEnter(AppState::MainMenu)))
+        .add_system(
+            replay_challenge_seed
+                .before(reseed)
+                .in_schedule(OnEnter(AppState::Game)),
+        )
+        .add_system(spawn_challenge_screen.in_schedule(OnEnter(AppState::Challenge)))
+        .add_systems(
+            (
+                type_challenge_code,
+                show_challenge_input.after(type_challenge_code),
+                answer_challenge_screen.after(type_challenge_code),
+            )
+                .in_set(OnUpdate(AppState::Challenge)),
+        )
+        .add_system(
+            spawn_challenge_code
+                .after(ResultsCues)
+                .in_set(OnUpdate(AppState::GameOver)),
+        );
+}
+
+fn spawn_challenge_screen(mut commands: Commands, mut input: ResMut<ChallengeInput>) {
+    input.text.clear();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Challenge),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("challenge.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            let (mut entry, text) = bitmap_text("", 2.0, Color::rgb(1.0, 0.8, 0.2));
+            entry.style.margin = UiRect::all(Val::Px(4.0));
+            parent.spawn((entry, text, ChallengeEntry));
+            let (hint, text, key) =
+                localized_text(Localized::new("challenge.hint"), 1.0, Color::WHITE);
+            parent.spawn((hint, text, key, ChallengeHint));
+            spawn_text_button(
+                parent,
+                Localized::new("menu.play"),
+                0,
+                ChallengeButton::Play,
+            );
+            spawn_text_button(
+                parent,
+                Localized::new("menu.back"),
+                1,
+                ChallengeButton::Back,
+            );
+        });
+}
+
+fn type_challenge_code(
+    keyboard_input: Res<Input<KeyCode>>,
+    console: Res<DevConsole>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut input: ResMut<ChallengeInput>,
+) {
+    if console.open {
+        characters.clear();
+        return;
+    }
+    for event in characters.iter() {
+        let c = event.char.to_ascii_uppercase();
+        if (c.is_ascii_alphanumeric() || c == '-') && input.text.len() < MAX_INPUT {
+            input.text.push(c);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        input.text.pop();
+    }
+}
+
+fn show_challenge_input(
+    input: Res<ChallengeInput>,
+    mut query: Query<&mut BitmapText, With<ChallengeEntry>>,
+) {
+    if !input.is_changed() {
+        return;
+    }
+    for mut text in query.iter_mut() {
+        // a cursor, and something to see before anything is typed
+        text.value = format!("{}_", input.text);
+    }
+}
+
+/// Plays the entered code on Play, or Return while no button has focus.
+#[allow(clippy::too_many_arguments)]
+fn answer_challenge_screen(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    focus: Res<MenuFocus>,
+    input: Res<ChallengeInput>,
+    starting: Res<StartingPhysics>,
+    profile: Res<Profile>,
+    mut run: ResMut<ChallengeRun>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut config: ResMut<FlappyConfig>,
+    mut settings: ResMut<Settings>,
+    mut seed: ResMut<RunSeed>,
+    mut co_op: ResMut<CoOp>,
+    query: Query<(&Interaction, &ChallengeButton), Changed<Interaction>>,
+    mut hint_query: Query<&mut Localized, With<ChallengeHint>>,
+) {
+    let mut play = focus.focused.is_none() && keyboard_input.just_pressed(KeyCode::Return);
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            ChallengeButton::Play => play = true,
+            ChallengeButton::Back => {
+                next_state.set(AppState::MainMenu);
+                return;
+            }
+        }
+    }
+    if !play {
+        return;
+    }
+    let code = match input.text.parse::<ChallengeCode>() {
+        Ok(code) if code.dash && profile.best < DASH_UNLOCK_SCORE => {
+            info!(%code, "the challenge needs the dash unlocked");
+            Err("challenge.dash_locked")
+        }
+        Ok(code) => Ok(code),
+        Err(error) => {
+            info!(%error, code = %input.text, "bad challenge code");
+            Err("challenge.invalid")
+        }
+    };
+    let code = match code {
+        Ok(code) => code,
+        Err(key) => {
+            for mut hint in hint_query.iter_mut() {
+                hint.key = key;
+            }
+            return;
+        }
+    };
+    info!(%code, "playing challenge");
+    if run.own.is_none() {
+        run.own = Some(OwnModifiers {
+            difficulty: settings.difficulty,
+            assisted: settings.assist.enabled,
+            dash: settings.air_dash,
+            physics: config.physics,
+        });
+        commands.init_resource::<HoldSaves<Settings>>();
+    }
+    settings.difficulty = code.difficulty;
+    settings.assist.enabled = code.assisted;
+    settings.air_dash = code.dash;
+    let physics = code.physics(&starting);
+    if config.physics != physics {
+        config.physics = physics;
+    }
+    run.seed = code.seed;
+    seed.queued = Some(code.seed);
+    co_op.0 = code.co_op;
+    next_state.set(AppState::Game);
+}
+
+/// Queues the challenge's seed again for each restart, as each run uses up
+/// the one queued.
+fn replay_challenge_seed(run: Res<ChallengeRun>, mut seed: ResMut<RunSeed>) {
+    if run.active() {
+        seed.queued = Some(run.seed);
+    }
+}
+
+/// Puts the player's own modifiers back once they leave a challenge, and
+/// goes back to random seeds.
+fn end_challenge(
+    mut commands: Commands,
+    mut run: ResMut<ChallengeRun>,
+    mut config: ResMut<FlappyConfig>,
+    mut settings: ResMut<Settings>,
+    mut seed: ResMut<RunSeed>,
+) {
+    let Some(own) = run.own.take() else {
+        return;
+    };
+    info!("challenge over");
+    commands.remove_resource::<HoldSaves<Settings>>();
+    settings.difficulty = own.difficulty;
+    settings.assist.enabled = own.assisted;
+    settings.air_dash = own.dash;
+    if config.physics != own.physics {
+        config.physics = own.physics;
+    }
+    seed.queued = None;
+}
+
+/// The code for the run just played, under the game over buttons, brought in
+/// with the panel.
+#[allow(clippy::too_many_arguments)]
+fn spawn_challenge_code(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    starting: Res<StartingPhysics>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    seed: Res<RunSeed>,
+    co_op: Res<CoOp>,
+    mut panels: EventReader<ResultsPanelEvent>,
+) {
+    if panels.iter().last().is_none() {
+        return;
+    }
+    let code = ChallengeCode {
+        seed: seed.seed,
+        difficulty: settings.difficulty,
+        assisted: settings.assist.enabled,
+        dash: AirDash::available(&profile, &settings),
+        physics: ChallengeCode::physics_steps(&config.physics, &starting),
+        co_op: co_op.0,
+    };
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px((15.0 + 42.0) + config.screen().y / 2.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Auto),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("challenge.code").with_args([code.to_string()]),
+                1.0,
+                Color::WHITE,
+            ));
+        });
+}
@@ -0,0 +1,26 @@
+use bevy::prelude::*;
+
+use crate::AppState;
+
+/// Despawns the entity (and its children) when the app leaves the given state.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DespawnOnExit<S: States = AppState>(pub S);
+
+/// Registers the cleanup for every variant of `S` with its `OnExit` schedule.
+pub(crate) fn add_state_cleanup<S: States>(app: &mut App) {
+    for state in S::variants() {
+        app.add_system(despawn_on_exit(state.clone()).in_schedule(OnExit(state)));
+    }
+}
+
+fn despawn_on_exit<S: States>(
+    state: S,
+) -> impl FnMut(Commands, Query<(Entity, &DespawnOnExit<S>)>) {
+    move |mut commands, query| {
+        for (entity, despawn) in query.iter() {
+            if despawn.0 == state {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Rolling recording of the last few seconds of a run, which can be saved as a
+//! GIF from the game over screen.
+//!
+//! Frames are drawn on the CPU from the sprites' own image data rather than
+//! read back from the GPU, so UI, rotation and anchors other than the centre
+//! don't show up in clips.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+
+use crate::config::user_data_dir;
+use crate::locale::localized_text;
+use crate::results::ResultsCues;
+use crate::{
+    rgba_bytes, AppState, ButtonSkin, DespawnOnExit, FlappyConfig, Focusable, Localized,
+    ResultsPanelEvent, Sky,
+};
+
+/// Length of a clip.
+const CLIP_SECONDS: f32 = 3.0;
+/// Frames recorded per second.
+const CLIP_FPS: f32 = 15.0;
+/// World units per clip pixel.
+const CLIP_DOWNSCALE: f32 = 2.0;
+
+/// The most recent frames of the current run, oldest first.
+#[derive(Resource)]
+pub struct ClipRecorder {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA8 pixels, top row first.
+    pub frames: VecDeque<Vec<u8>>,
+    timer: Timer,
+    saving: Option<Task<Result<PathBuf, String>>>,
+}
+
+impl ClipRecorder {
+    fn new(playfield: Vec2) -> Self {
+        Self {
+            width: (playfield.x / CLIP_DOWNSCALE) as u32,
+            height: (playfield.y / CLIP_DOWNSCALE) as u32,
+            frames: VecDeque::new(),
+            timer: Timer::from_seconds(1.0 / CLIP_FPS, TimerMode::Repeating),
+            saving: None,
+        }
+    }
+
+    fn capacity() -> usize {
+        (CLIP_SECONDS * CLIP_FPS) as usize
+    }
+}
+
+#[derive(Component)]
+pub(crate) struct SaveClipButton;
+
+pub(crate) fn add_clip_recorder(app: &mut App, playfield: Vec2) {
+    app.insert_resource(ClipRecorder::new(playfield))
+        .add_system(clear_clip.in_schedule(OnEnter(AppState::Game)))
+        .add_system(record_clip_frame.in_set(OnUpdate(AppState::Game)))
+        .add_systems(
+            (
+                spawn_save_clip_button.after(ResultsCues),
+                save_clip,
+                finish_saving_clip.after(save_clip),
+            )
+                .in_set(OnUpdate(AppState::GameOver)),
+        );
+}
+
+/// Encodes RGBA8 frames as a looping GIF, showing each for `delay`
+/// hundredths of a second.
+pub fn encode_gif<'a>(
+    width: u32,
+    height: u32,
+    frames: impl IntoIterator<Item = &'a [u8]>,
+    delay: u16,
+) -> Result<Vec<u8>, gif::EncodingError> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut bytes, width as u16, height as u16, &[])?;
+        encoder.set_repeat(gif::Repeat::Infinite)?;
+        for pixels in frames {
+            let mut pixels = pixels.to_vec();
+            let mut frame =
+                gif::Frame::from_rgba_speed(width as u16, height as u16, &mut pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame)?;
+        }
+    }
+    Ok(bytes)
+}
+
+fn clear_clip(mut recorder: ResMut<ClipRecorder>) {
+    recorder.frames.clear();
+    recorder.timer.reset();
+}
+
+fn record_clip_frame(
+    time: Res<Time>,
+    sky: Res<Sky>,
+    images: Res<Assets<Image>>,
+    mut recorder: ResMut<ClipRecorder>,
+    sprite_query: Query<(
+        &GlobalTransform,
+        &Sprite,
+        &Handle<Image>,
+        &ComputedVisibility,
+    )>,
+) {
+    if !recorder.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let (width, height) = (recorder.width, recorder.height);
+    let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        let row = rgba_bytes(sky.color_at((y as f32 + 0.5) / height as f32));
+        pixels.extend(row.repeat(width as usize));
+    }
+
+    let mut sprites: Vec<_> = sprite_query
+        .iter()
+        .filter(|(.., visibility)| visibility.is_visible_in_hierarchy())
+        .filter_map(|(transform, sprite, texture, _)| {
+            Some((transform.translation(), sprite, images.get(texture)?))
+        })
+        .collect();
+    sprites.sort_by(|(a, ..), (b, ..)| a.z.total_cmp(&b.z));
+    for (translation, sprite, image) in sprites {
+        draw_sprite(&mut pixels, width, height, translation, sprite, image);
+    }
+
+    recorder.frames.push_back(pixels);
+    let overflow = recorder
+        .frames
+        .len()
+        .saturating_sub(ClipRecorder::capacity());
+    recorder.frames.drain(..overflow);
+}
+
+/// Alpha blends `image` onto the frame with nearest-neighbour sampling. The
+/// frame is centred on the world origin, like the game's camera.
+fn draw_sprite(
+    pixels: &mut [u8],
+    width: u32,
+    height: u32,
+    translation: Vec3,
+    sprite: &Sprite,
+    image: &Image,
+) {
+    if !matches!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+    ) {
+        return;
+    }
+    let texture_size = image.size();
+    let size = sprite.custom_size.unwrap_or(texture_size);
+    // sprite corners in frame pixels
+    let frame_center = Vec2::new(width as f32, height as f32) / 2.0;
+    let center = Vec2::new(translation.x, -translation.y) / CLIP_DOWNSCALE + frame_center;
+    let min = (center - size / CLIP_DOWNSCALE / 2.0).max(Vec2::ZERO);
+    let max = (center + size / CLIP_DOWNSCALE / 2.0).min(frame_center * 2.0);
+
+    for y in min.y as u32..max.y.ceil() as u32 {
+        for x in min.x as u32..max.x.ceil() as u32 {
+            let frame_point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let mut uv = (frame_point - center) * CLIP_DOWNSCALE / size + 0.5;
+            if sprite.flip_x {
+                uv.x = 1.0 - uv.x;
+            }
+            if sprite.flip_y {
+                uv.y = 1.0 - uv.y;
+            }
+            if !(0.0..1.0).contains(&uv.x) || !(0.0..1.0).contains(&uv.y) {
+                continue;
+            }
+            let texel = (uv * texture_size).as_uvec2();
+            let src = ((texel.y * texture_size.x as u32 + texel.x) * 4) as usize;
+            let dst = ((y * width + x) * 4) as usize;
+            let Some(src) = image.data.get(src..src + 4) else {
+                continue;
+            };
+            let alpha = src[3] as f32 / 255.0 * sprite.color.a();
+            for (dst, src) in pixels[dst..dst + 3].iter_mut().zip(src) {
+                *dst = (*src as f32 * alpha + *dst as f32 * (1.0 - alpha)) as u8;
+            }
+        }
+    }
+}
+
+/// Brought in with the panel.
+fn spawn_save_clip_button(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    mut panels: EventReader<ResultsPanelEvent>,
+) {
+    if panels.iter().last().is_none() {
+        return;
+    }
+    let skin = ButtonSkin::panel();
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px((15.0 + 12.0) + config.screen().y / 2.0),
+                        left: Val::Px(config.screen().x / 2.0 - 20.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(40.0), Val::Px(12.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: skin.normal.color.into(),
+                ..default()
+            },
+            skin,
+            Focusable { order: 1 },
+            SaveClipButton,
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("clip.save"),
+                1.0,
+                Color::WHITE,
+            ));
+        });
+}
+
+fn set_button_label(
+    button_query: &Query<&Children, With<SaveClipButton>>,
+    label_query: &mut Query<&mut Localized>,
+    key: &'static str,
+) {
+    for children in button_query.iter() {
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = key;
+        }
+    }
+}
+
+/// Starts encoding the recorded frames in the background when the button is
+/// clicked.
+fn save_clip(
+    mut recorder: ResMut<ClipRecorder>,
+    query: Query<&Interaction, (Changed<Interaction>, With<SaveClipButton>)>,
+    button_query: Query<&Children, With<SaveClipButton>>,
+    mut label_query: Query<&mut Localized>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if !clicked || recorder.saving.is_some() || recorder.frames.is_empty() {
+        return;
+    }
+
+    let (width, height) = (recorder.width, recorder.height);
+    let frames: Vec<_> = recorder.frames.iter().cloned().collect();
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let delay = (100.0 / CLIP_FPS).round() as u16;
+        let bytes = encode_gif(width, height, frames.iter().map(Vec::as_slice), delay)
+            .map_err(|error| error.to_string())?;
+        let dir = user_data_dir()
+            .ok_or("no user data directory")?
+            .join("clips");
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default();
+        let path = dir.join(format!("flappy_{timestamp}.gif"));
+        std::fs::create_dir_all(&dir)
+            .and_then(|()| std::fs::write(&path, bytes))
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+        Ok(path)
+    });
+    recorder.saving = Some(task);
+    set_button_label(&button_query, &mut label_query, "clip.saving");
+}
+
+fn finish_saving_clip(
+    mut recorder: ResMut<ClipRecorder>,
+    button_query: Query<&Children, With<SaveClipButton>>,
+    mut label_query: Query<&mut Localized>,
+) {
+    let Some(task) = recorder.saving.as_mut() else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    recorder.saving = None;
+
+    let label = match result {
+        Ok(path) => {
+            info!(path = %path.display(), "saved clip");
+            "clip.saved"
+        }
+        Err(error) => {
+            warn!(%error, "failed to save clip");
+            "clip.failed"
+        }
+    };
+    set_button_label(&button_query, &mut label_query, label);
+}
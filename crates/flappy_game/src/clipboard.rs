@@ -0,0 +1,42 @@
+//! The system clipboard: arboard on desktop, the `ClipboardManager` on
+//! Android and `UIPasteboard` on iOS. Kept as a non-send resource, as some
+//! platforms need it on the main thread.
+
+use bevy::prelude::*;
+
+/// Access to the system clipboard.
+#[derive(Default)]
+pub struct Clipboard {
+    /// Opened on first use. On X11 the text is only served while this lives,
+    /// so it's kept for the life of the app.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Replaces the clipboard's contents with `text`.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        let clipboard = match &mut self.inner {
+            Some(clipboard) => clipboard,
+            inner => inner.insert(arboard::Clipboard::new().map_err(|error| error.to_string())?),
+        };
+        clipboard.set_text(text).map_err(|error| error.to_string())
+    }
+
+    /// Replaces the clipboard's contents with `text`.
+    #[cfg(target_os = "android")]
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        crate::android::set_clipboard(text).map_err(|error| error.to_string())
+    }
+
+    /// Replaces the clipboard's contents with `text`.
+    #[cfg(target_os = "ios")]
+    pub fn set_text(&mut self, text: &str) -> Result<(), String> {
+        crate::ios::set_clipboard(text)
+    }
+}
+
+pub(crate) fn add_clipboard(app: &mut App) {
+    app.init_non_send_resource::<Clipboard>();
+}
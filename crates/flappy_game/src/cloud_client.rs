@@ -0,0 +1,152 @@
+//! Cloud sync over HTTP, enabled with the `cloud_sync` feature. Syncs once on
+//! startup, then whenever the profile or settings stop matching the last
+//! synced copy, at most every `SYNC_INTERVAL`. The requests block, so they
+//! run on the IO task pool, away from the frame. Nothing syncs while a
+//! challenge holds the settings, see `HoldSaves`, so its modifiers are never
+//! taken for the player's own.
+
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, Task};
+use futures_lite::future;
+
+use crate::cloud_sync::unix_time;
+use crate::persist::{parse_versioned, to_versioned_string, HoldSaves};
+use crate::{CloudSave, CloudSyncConfig, Profile, Settings};
+
+/// Shortest wait between syncs, in seconds.
+const SYNC_INTERVAL: f32 = 10.0;
+
+#[derive(Resource)]
+struct CloudClient {
+    config: CloudSyncConfig,
+    /// When the local settings last changed, for choosing between copies.
+    settings_modified: u64,
+    /// The copy both sides agreed on at the last sync.
+    synced: Option<CloudSave>,
+    /// A sync in flight, with the local copy it started from.
+    syncing: Option<(CloudSave, Task<Result<CloudSave, String>>)>,
+    cooldown: f32,
+}
+
+pub(crate) fn add_cloud_client(app: &mut App, config: CloudSyncConfig, settings_modified: u64) {
+    app.insert_resource(CloudClient {
+        config,
+        settings_modified,
+        synced: None,
+        syncing: None,
+        cooldown: 0.0,
+    })
+    .add_system(track_settings_changes)
+    .add_system(start_sync.after(track_settings_changes))
+    .add_system(finish_sync.after(start_sync));
+}
+
+/// Dates settings changed here, but not those that came from a sync or a
+/// challenge.
+fn track_settings_changes(
+    settings: Res<Settings>,
+    hold: Option<Res<HoldSaves<Settings>>>,
+    mut client: ResMut<CloudClient>,
+) {
+    if !settings.is_changed() || settings.is_added() || hold.is_some() {
+        return;
+    }
+    let synced = client.synced.as_ref().map(|save| &save.settings);
+    if synced != Some(&*settings) {
+        client.settings_modified = unix_time(SystemTime::now());
+    }
+}
+
+fn start_sync(
+    time: Res<Time>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    hold: Option<Res<HoldSaves<Settings>>>,
+    mut client: ResMut<CloudClient>,
+) {
+    client.cooldown -= time.delta_seconds();
+    if client.syncing.is_some() || client.cooldown > 0.0 || hold.is_some() {
+        return;
+    }
+    let local = CloudSave {
+        profile: profile.clone(),
+        settings: settings.clone(),
+        settings_modified: client.settings_modified,
+    };
+    if client.synced.as_ref() == Some(&local) {
+        return;
+    }
+    client.cooldown = SYNC_INTERVAL;
+    let config = client.config.clone();
+    let sent = local.clone();
+    let task = IoTaskPool::get().spawn(async move { sync(&config, &sent) });
+    client.syncing = Some((local, task));
+}
+
+/// Fetches the remote copy, merges it with `local`, and puts the result back
+/// if that changed anything there.
+fn sync(config: &CloudSyncConfig, local: &CloudSave) -> Result<CloudSave, String> {
+    let authorization = format!("Bearer {}", config.token);
+    let remote = match ureq::get(&config.url)
+        .set("Authorization", &authorization)
+        .call()
+    {
+        Ok(response) => {
+            let text = response.into_string().map_err(|error| error.to_string())?;
+            Some(parse_versioned::<CloudSave>(&text)?)
+        }
+        // nothing saved yet
+        Err(ureq::Error::Status(404, _)) => None,
+        Err(error) => return Err(error.to_string()),
+    };
+    let merged = match &remote {
+        Some(remote) => local.merged(remote),
+        None => local.clone(),
+    };
+    if remote.as_ref() != Some(&merged) {
+        let text = to_versioned_string(&merged)?;
+        ureq::put(&config.url)
+            .set("Authorization", &authorization)
+            .set("Content-Type", "application/ron")
+            .send_string(&text)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(merged)
+}
+
+fn finish_sync(
+    mut client: ResMut<CloudClient>,
+    mut profile: ResMut<Profile>,
+    mut settings: ResMut<Settings>,
+) {
+    let Some((_, task)) = client.syncing.as_mut() else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    let Some((sent, _)) = client.syncing.take() else {
+        return;
+    };
+    let merged = match result {
+        Ok(merged) => merged,
+        Err(error) => {
+            warn!(%error, url = %client.config.url, "cloud sync failed");
+            return;
+        }
+    };
+    info!(best = merged.profile.best, "cloud sync done");
+    // anything changed here meanwhile is merged on the next sync instead
+    if *profile == sent.profile && *profile != merged.profile {
+        *profile = merged.profile.clone();
+    }
+    if *settings == sent.settings && client.settings_modified == sent.settings_modified {
+        if *settings != merged.settings {
+            *settings = merged.settings.clone();
+        }
+        client.settings_modified = merged.settings_modified;
+    }
+    client.synced = Some(merged);
+}
@@ -0,0 +1,130 @@
+//! Syncing the profile and settings with a backend of the player's own, set
+//! in `FlappyConfig::cloud_sync`. With the `cloud_sync` feature the game
+//! fetches the saved copy on startup and whenever its own changes, merges the
+//! two, and puts the result back, see `cloud_client`.
+//!
+//! The backend only needs to store one file: `GET` returns the last body
+//! `PUT`, or 404 before the first, and both carry the token as a bearer
+//! `Authorization` header. Bodies are a `CloudSave` in RON, versioned and
+//! checksummed like the save files, see `persist`, so a copy edited on the
+//! backend is refused rather than merged.
+//!
+//! The token is kept off the command line, where other users can see it: it
+//! comes from `CLOUD_TOKEN_VAR`, or a `cloud_token` file in the user data dir.
+
+#[cfg(feature = "cloud_sync")]
+use std::path::Path;
+#[cfg(feature = "cloud_sync")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::user_data_dir;
+use crate::persist::Versioned;
+use crate::{FlappyConfig, Profile, Settings};
+
+/// Environment variable holding the token, if the player keeps it there.
+pub const CLOUD_TOKEN_VAR: &str = "FLAPPY_CLOUD_TOKEN";
+
+/// Where to sync to.
+#[derive(Clone, Debug)]
+pub struct CloudSyncConfig {
+    pub url: String,
+    pub token: String,
+}
+
+impl CloudSyncConfig {
+    /// Syncs to `url` with the player's saved token: `CLOUD_TOKEN_VAR` when
+    /// set, or else the `cloud_token` file in the user data dir. Empty if
+    /// neither is, and the backend will refuse it.
+    pub fn with_saved_token(url: String) -> Self {
+        let token = std::env::var(CLOUD_TOKEN_VAR)
+            .ok()
+            .and_then(|token| trimmed_token(&token))
+            .or_else(|| {
+                let path = user_data_dir()?.join("cloud_token");
+                trimmed_token(&std::fs::read_to_string(path).ok()?)
+            })
+            .unwrap_or_default();
+        Self { url, token }
+    }
+}
+
+fn trimmed_token(text: &str) -> Option<String> {
+    let token = text.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Everything synced, as stored on the backend.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloudSave {
+    pub profile: Profile,
+    pub settings: Settings,
+    /// Seconds since the Unix epoch when `settings` were last changed.
+    pub settings_modified: u64,
+}
+
+impl CloudSave {
+    /// Combines two copies: the best of both profiles, and the newer settings,
+    /// keeping these on a tie.
+    pub fn merged(&self, other: &CloudSave) -> CloudSave {
+        let newer = if other.settings_modified > self.settings_modified {
+            other
+        } else {
+            self
+        };
+        CloudSave {
+            profile: self.profile.merged(&other.profile),
+            settings: newer.settings.clone(),
+            settings_modified: newer.settings_modified,
+        }
+    }
+}
+
+impl Versioned for CloudSave {
+    const VERSION: u32 = 1;
+    const CHECKSUM_SINCE: Option<u32> = Some(1);
+
+    fn mark_unverified(self) -> Self {
+        CloudSave {
+            profile: self.profile.mark_unverified(),
+            ..self
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(feature = "cloud_sync")]
+pub(crate) fn unix_time(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// When the settings file at `path` was last written, or the epoch if it never
+/// was, so any synced copy wins over settings nobody has touched.
+#[cfg(feature = "cloud_sync")]
+pub(crate) fn settings_modified(path: Option<&Path>) -> u64 {
+    path.and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok())
+        .map_or(0, unix_time)
+}
+
+pub(crate) fn add_cloud_sync(app: &mut App, config: &FlappyConfig) {
+    let Some(cloud) = &config.cloud_sync else {
+        return;
+    };
+    #[cfg(feature = "cloud_sync")]
+    crate::cloud_client::add_cloud_client(
+        app,
+        cloud.clone(),
+        settings_modified(config.settings_path.as_deref()),
+    );
+    #[cfg(not(feature = "cloud_sync"))]
+    {
+        let _ = app;
+        warn!(url = %cloud.url, "cloud sync needs the cloud_sync feature");
+    }
+}
@@ -0,0 +1,148 @@
+//! Clouds drifting across the sky, behind everything but the sky itself. They
+//! go by slower than the pipes and wrap round to the right as they leave. How
+//! many there are, and their colour, come from the `Theme`.
+
+use bevy::prelude::*;
+use flappy_core::physics::displacement;
+use rand::Rng;
+
+use crate::scroll::update_scroll_speed;
+use crate::{
+    AppState, FlappyConfig, GameSystem, GameTick, MotionPrefs, PhysicsClock, ScrollSpeed, Theme,
+};
+
+/// Between the sky and the pipes.
+const CLOUD_Z: f32 = -0.04;
+/// Cloud speed as a fraction of the pipes'.
+const CLOUD_SPEED: f32 = 0.3;
+/// Range of cloud widths and heights, in world units.
+const CLOUD_WIDTH: (f32, f32) = (20.0, 44.0);
+const CLOUD_HEIGHT: (f32, f32) = (8.0, 16.0);
+
+#[derive(Component, Debug)]
+pub struct Cloud {
+    /// Multiplies `CLOUD_SPEED`, so clouds drift apart over time.
+    pub speed: f32,
+}
+
+pub(crate) fn add_clouds(app: &mut App) {
+    app.add_system(match_cloud_count)
+        .add_system(tint_clouds.after(match_cloud_count))
+        // in step with the pipes during a run, and by the frame elsewhere
+        .add_system(
+            drift_clouds
+                .after(update_scroll_speed)
+                .in_set(GameSystem::Movement)
+                .in_schedule(GameTick),
+        )
+        .add_system(
+            drift_idle_clouds.run_if(|state: Res<State<AppState>>| state.0 != AppState::Game),
+        );
+}
+
+/// Spawns or removes clouds until there are `Theme::clouds` of them.
+fn match_cloud_count(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    theme: Res<Theme>,
+    query: Query<Entity, With<Cloud>>,
+) {
+    if !theme.is_changed() {
+        return;
+    }
+    let count = query.iter().len();
+    for entity in query.iter().skip(theme.clouds) {
+        commands.entity(entity).despawn();
+    }
+    let mut rng = rand::thread_rng();
+    for _ in count..theme.clouds {
+        let x = rng.gen_range(-0.5..0.5) * config.width();
+        spawn_cloud(&mut commands, &config, &mut rng, x, theme.cloud_color);
+    }
+}
+
+fn spawn_cloud(
+    commands: &mut Commands,
+    config: &FlappyConfig,
+    rng: &mut impl Rng,
+    x: f32,
+    color: Color,
+) {
+    let size = Vec2::new(
+        rng.gen_range(CLOUD_WIDTH.0..CLOUD_WIDTH.1),
+        rng.gen_range(CLOUD_HEIGHT.0..CLOUD_HEIGHT.1),
+    );
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color,
+                custom_size: Some(size),
+                ..default()
+            },
+            transform: Transform::from_xyz(x, cloud_height(config, rng), CLOUD_Z),
+            ..default()
+        },
+        Cloud {
+            speed: rng.gen_range(0.7..1.3),
+        },
+    ));
+}
+
+/// Somewhere in the upper two thirds of the sky.
+fn cloud_height(config: &FlappyConfig, rng: &mut impl Rng) -> f32 {
+    rng.gen_range(-1.0 / 6.0..0.5) * config.height()
+}
+
+fn tint_clouds(theme: Res<Theme>, mut query: Query<&mut Sprite, With<Cloud>>) {
+    if !theme.is_changed() {
+        return;
+    }
+    for mut sprite in query.iter_mut() {
+        sprite.color = theme.cloud_color;
+    }
+}
+
+fn drift_clouds(
+    clock: Res<PhysicsClock>,
+    config: Res<FlappyConfig>,
+    scroll: Res<ScrollSpeed>,
+    motion: Res<MotionPrefs>,
+    mut query: Query<(&mut Transform, &Sprite, &Cloud)>,
+) {
+    drift(clock.step, scroll.0, &config, &motion, &mut query);
+}
+
+fn drift_idle_clouds(
+    time: Res<Time>,
+    config: Res<FlappyConfig>,
+    motion: Res<MotionPrefs>,
+    mut query: Query<(&mut Transform, &Sprite, &Cloud)>,
+) {
+    let speed = config.physics.pipe_speed;
+    drift(time.delta_seconds(), speed, &config, &motion, &mut query);
+}
+
+/// Drifts the clouds left by `dt` seconds behind a level scrolling at
+/// `speed`, and wraps each back round to the right at a new height once it's
+/// gone. They hold still when parallax is off.
+fn drift(
+    dt: f32,
+    speed: f32,
+    config: &FlappyConfig,
+    motion: &MotionPrefs,
+    query: &mut Query<(&mut Transform, &Sprite, &Cloud)>,
+) {
+    if !motion.parallax {
+        return;
+    }
+    let drift = displacement(speed * CLOUD_SPEED, dt);
+    let mut rng = rand::thread_rng();
+    for (mut transform, sprite, cloud) in query.iter_mut() {
+        transform.translation.x -= drift * cloud.speed;
+        let half = sprite.custom_size.unwrap_or_default().x / 2.0;
+        if transform.translation.x < -config.width() / 2.0 - half {
+            transform.translation.x = config.width() / 2.0 + half;
+            transform.translation.y = cloud_height(config, &mut rng);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Two birds on one screen, through the same pipes. The second bird flaps
+//! with `InputBindings::flap_two` and is drawn from `AssetPaths::bird_two`.
+//! The score is shared: each gap scores once, for whichever bird gets to it
+//! first, and the run ends as soon as either bird dies.
+
+use bevy::prelude::*;
+
+use crate::{AssetPaths, InputBindings};
+
+/// Height between the two birds as they line up at the start of a run.
+const START_SPACING: f32 = 40.0;
+
+/// Whether runs are played by two. Picked on the main menu and kept through
+/// restarts.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoOp(pub bool);
+
+impl CoOp {
+    /// The players in each run, first to last.
+    pub fn players(self) -> impl Iterator<Item = Player> {
+        let count = if self.0 { 2 } else { 1 };
+        (0..count).map(Player)
+    }
+
+    /// Where `player`'s bird starts, above or below the middle.
+    pub fn start_height(self, player: Player) -> f32 {
+        match (self.0, player) {
+            (false, _) => 0.0,
+            (true, Player::ONE) => START_SPACING / 2.0,
+            (true, _) => -START_SPACING / 2.0,
+        }
+    }
+}
+
+/// Whose bird this is.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Player(pub u8);
+
+impl Player {
+    pub const ONE: Player = Player(0);
+    pub const TWO: Player = Player(1);
+
+    /// The keys that flap this player's bird.
+    pub fn flap_keys(self, bindings: &InputBindings) -> &[KeyCode] {
+        if self == Player::ONE {
+            &bindings.flap
+        } else {
+            &bindings.flap_two
+        }
+    }
+
+    pub fn texture(self, assets: &AssetPaths) -> &str {
+        if self == Player::ONE {
+            &assets.bird
+        } else {
+            &assets.bird_two
+        }
+    }
+}
+
+pub(crate) fn add_co_op(app: &mut App) {
+    app.init_resource::<CoOp>();
+}
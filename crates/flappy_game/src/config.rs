@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use flappy_core::collision::GroundParams;
+use flappy_core::physics::Physics;
+use flappy_core::rules::Rules;
+use flappy_core::sim::SimConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::{AppState, CloudSyncConfig, Curve, LiveBoardConfig, StickNavigation, StressTest};
+
+/// Options for embedding the game, inserted as a resource by `FlappyGamePlugin`.
+#[derive(Resource, Clone, Debug)]
+pub struct FlappyConfig {
+    /// Size of the playfield in world units, centred on the origin.
+    pub playfield: Vec2,
+    pub assets: AssetPaths,
+    pub bindings: InputBindings,
+    /// Spawn a `Camera2dBundle` on startup. Disable when the host app owns the camera.
+    pub spawn_camera: bool,
+    pub physics_backend: PhysicsBackend,
+    /// Read every frame, so changes apply to the run in progress.
+    pub physics: Physics,
+    /// Steps per second of the `GameTick`, with bodies drawn between steps,
+    /// see `interpolation`. Steps once per frame, by the frame's time, when
+    /// `None`.
+    pub physics_rate: Option<f32>,
+    /// Hills along the bottom of the playfield, `GroundParams::for_height`
+    /// of the playfield when `None`. `GroundParams::FLAT` keeps the ground
+    /// level.
+    pub ground: Option<GroundParams>,
+    pub rules: Rules,
+    /// Play turned on its side, see `vertical`. The host should make the
+    /// window the `screen` size, the playfield's height wide and its width
+    /// tall.
+    pub vertical: bool,
+    /// State entered on startup. `AppState::Game` skips the main menu.
+    pub initial_state: AppState,
+    /// Where the player's `Profile` is saved. Kept in memory only when `None`.
+    pub profile_path: Option<PathBuf>,
+    /// Where `Settings` are saved. Kept in memory only when `None`.
+    pub settings_path: Option<PathBuf>,
+    /// Where `DeathStats` are saved. Kept in memory only when `None`.
+    pub stats_path: Option<PathBuf>,
+    /// Where rebound `InputBindings` are saved. Once saved, they're used in
+    /// place of `bindings`. Kept in memory only when `None`.
+    pub bindings_path: Option<PathBuf>,
+    /// Seconds the game over screen waits for input before going back to the
+    /// main menu. Never times out when `None`.
+    pub idle_timeout: Option<f32>,
+    /// Seconds the game freezes after a death before the game over screen.
+    pub hit_stop: f32,
+    /// Seconds of slow-motion zoom after the hit stop.
+    pub death_slow_mo: f32,
+    /// Peak opacity of full-screen flashes, like the one on hit stop.
+    pub flash_intensity: f32,
+    /// Seconds taken to crossfade between pieces of music.
+    pub music_crossfade: f32,
+    /// Show friends' scores from a live board server while playing. Only
+    /// connects with the `live_board` feature.
+    pub live_board: Option<LiveBoardConfig>,
+    /// Keep the profile and settings in step with a copy on a backend, keeping
+    /// the higher scores and the newer settings. Only syncs with the
+    /// `cloud_sync` feature.
+    pub cloud_sync: Option<CloudSyncConfig>,
+    /// Where to post anonymous telemetry, offered to the player on the
+    /// settings screen. Only posts with the `telemetry` feature, and only once
+    /// the player opts in with `Settings::telemetry`.
+    pub telemetry_url: Option<String>,
+    /// Swamp the run with pipes and print frame time statistics, see
+    /// `stress`. Makes the bird invincible and exits when done.
+    pub stress_test: Option<StressTest>,
+}
+
+impl Default for FlappyConfig {
+    fn default() -> Self {
+        Self {
+            playfield: Vec2::new(1280.0 / 3.0, 720.0 / 3.0),
+            assets: AssetPaths::default(),
+            bindings: InputBindings::default(),
+            spawn_camera: true,
+            physics_backend: PhysicsBackend::default(),
+            physics: Physics::default(),
+            physics_rate: None,
+            ground: None,
+            rules: Rules::default(),
+            vertical: false,
+            initial_state: AppState::MainMenu,
+            profile_path: user_data_dir().map(|dir| dir.join("profile.ron")),
+            settings_path: user_data_dir().map(|dir| dir.join("settings.ron")),
+            stats_path: user_data_dir().map(|dir| dir.join("stats.ron")),
+            bindings_path: user_data_dir().map(|dir| dir.join("bindings.ron")),
+            idle_timeout: Some(30.0),
+            hit_stop: 0.1,
+            death_slow_mo: 0.5,
+            flash_intensity: 0.8,
+            music_crossfade: 1.0,
+            live_board: None,
+            cloud_sync: None,
+            telemetry_url: None,
+            stress_test: None,
+        }
+    }
+}
+
+impl FlappyConfig {
+    pub fn width(&self) -> f32 {
+        self.playfield.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.playfield.y
+    }
+
+    /// The hills along the bottom, see `ground`.
+    pub fn ground(&self) -> GroundParams {
+        self.ground
+            .unwrap_or_else(|| GroundParams::for_height(self.height()))
+    }
+
+    /// Size of the screen the playfield fills, turned on its side when
+    /// `vertical`. UI laid out across the window goes by this.
+    pub fn screen(&self) -> Vec2 {
+        if self.vertical {
+            Vec2::new(self.playfield.y, self.playfield.x)
+        } else {
+            self.playfield
+        }
+    }
+
+    /// The headless equivalent of a run following `curve`, starting from its
+    /// first key. The simulation keeps that speed and those gap sizes, and
+    /// only paces pairs closer together, see `Curve::pacing`.
+    pub fn sim_config(&self, curve: &Curve) -> SimConfig {
+        let start = curve.at(0);
+        let mut physics = self.physics;
+        physics.pipe_speed *= start.speed;
+        SimConfig {
+            playfield: self.playfield,
+            physics,
+            gaps: start.gap_params(self.height()),
+            pacing: curve.pacing(),
+            ground: self.ground(),
+            rules: self.rules,
+        }
+    }
+}
+
+/// Per-user directory for logs and saves, e.g. `~/.local/share/flappy_bevy`.
+#[cfg(not(target_os = "android"))]
+pub fn user_data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("flappy_bevy"))
+}
+
+/// Per-user directory for logs and saves: the app's private storage.
+#[cfg(target_os = "android")]
+pub fn user_data_dir() -> Option<PathBuf> {
+    crate::android::files_dir()
+}
+
+/// How bodies are moved and collisions detected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PhysicsBackend {
+    /// Hand-rolled AABB checks and velocity integration.
+    #[default]
+    Builtin,
+    /// Rapier rigid bodies and colliders. Requires the `rapier` feature.
+    #[cfg(feature = "rapier")]
+    Rapier,
+}
+
+/// Paths passed to the `AssetServer`, relative to the host app's asset folder.
+#[derive(Clone, Debug)]
+pub struct AssetPaths {
+    pub bird: String,
+    /// The second player's bird in `CoOp`.
+    pub bird_two: String,
+    pub pipe_top: String,
+    pub pipe_bottom: String,
+    pub restart_button: String,
+    /// Drawn in place of the OS cursor when `Settings::custom_cursor` is on.
+    pub cursor: String,
+    pub font: String,
+    /// Music for the menus. The game ships without music, so these are
+    /// `None`, and silent, unless a host app has its own.
+    pub menu_music: Option<MusicAsset>,
+    /// Music during runs.
+    pub game_music: Option<MusicAsset>,
+    /// Played once on game over.
+    pub game_over_sting: Option<String>,
+    /// The `DifficultyCurves`, a `.curve.ron` file.
+    pub difficulty_curves: String,
+}
+
+/// A piece of music that loops, possibly after an intro.
+#[derive(Clone, Debug)]
+pub struct MusicAsset {
+    pub path: String,
+    /// Seconds into the track each loop starts from, so an intro before this
+    /// point only plays the first time.
+    pub loop_from: f64,
+}
+
+impl MusicAsset {
+    pub fn new(path: &str, loop_from: f64) -> Self {
+        Self {
+            path: path.to_string(),
+            loop_from,
+        }
+    }
+}
+
+impl Default for AssetPaths {
+    fn default() -> Self {
+        Self {
+            bird: "bird.png".to_string(),
+            bird_two: "bird_two.png".to_string(),
+            pipe_top: "pipe_top.png".to_string(),
+            pipe_bottom: "pipe_bottom.png".to_string(),
+            restart_button: "gameover_ok.png".to_string(),
+            cursor: "cursor.png".to_string(),
+            font: "fonts/Hack-Regular.ttf".to_string(),
+            menu_music: None,
+            game_music: None,
+            game_over_sting: None,
+            difficulty_curves: "difficulty.curve.ron".to_string(),
+        }
+    }
+}
+
+/// Saved to `FlappyConfig::bindings_path` once rebound.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub flap: Vec<KeyCode>,
+    /// Flaps the second player's bird in `CoOp`.
+    pub flap_two: Vec<KeyCode>,
+    /// Restart from the game over screen, as the restart button does. Return
+    /// is left to the focused button while one has `MenuFocus`.
+    pub restart: Vec<KeyCode>,
+    /// Held through a death, starts the next run straight away instead of
+    /// showing the game over screen.
+    pub quick_retry: KeyCode,
+    /// Toggles the collider and sensor overlay.
+    pub debug_overlay: KeyCode,
+    /// Opens and closes the developer console.
+    pub console: KeyCode,
+    /// Toggles the frame time and entity count overlay.
+    pub stats_overlay: KeyCode,
+    /// Toggles the heatmap of where runs ended.
+    pub heatmap: KeyCode,
+    /// Pauses the run, and resumes it after a countdown.
+    pub pause: KeyCode,
+    /// Asks to quit, as closing the window does.
+    pub quit: KeyCode,
+    /// Mutes and unmutes all audio.
+    pub mute: KeyCode,
+    /// Deadzones and sensitivity of the left stick in menus.
+    pub stick: StickNavigation,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            flap: vec![KeyCode::Space],
+            flap_two: vec![KeyCode::Up],
+            restart: vec![KeyCode::Space, KeyCode::Return, KeyCode::R],
+            quick_retry: KeyCode::R,
+            debug_overlay: KeyCode::F3,
+            console: KeyCode::Grave,
+            stats_overlay: KeyCode::F2,
+            heatmap: KeyCode::F4,
+            pause: KeyCode::P,
+            quit: KeyCode::Escape,
+            mute: KeyCode::M,
+            stick: StickNavigation::default(),
+        }
+    }
+}
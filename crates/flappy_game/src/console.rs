@@ -0,0 +1,297 @@
+//! Developer console, toggled with `InputBindings::console`.
+//!
+//! Each line typed is split on whitespace and looked up by its first word in
+//! `ConsoleCommands`. Modules add their own commands with
+//! `ConsoleCommands::register`.
+
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::ReceivedCharacter;
+use flappy_core::pipes::{Gap, GapParams};
+
+use crate::{spawn_pipe_pair, AppState, FlappyConfig, RunRecord, Score};
+
+/// Lines of output kept on screen.
+const MAX_LOG_LINES: usize = 8;
+
+/// Runs a command with the words after its name, returning a message to print.
+pub type ConsoleCommandFn = fn(&mut World, &[&str]) -> Result<String, String>;
+
+struct ConsoleCommand {
+    help: &'static str,
+    run: ConsoleCommandFn,
+}
+
+#[derive(Resource, Default)]
+pub struct ConsoleCommands {
+    commands: HashMap<&'static str, ConsoleCommand>,
+}
+
+impl ConsoleCommands {
+    /// Adds a command, replacing any existing one with the same name.
+    pub fn register(&mut self, name: &'static str, help: &'static str, run: ConsoleCommandFn) {
+        self.commands.insert(name, ConsoleCommand { help, run });
+    }
+
+    /// One `name: help` line per command, sorted by name.
+    pub fn help(&self) -> Vec<String> {
+        let mut lines: Vec<_> = self
+            .commands
+            .iter()
+            .map(|(name, command)| format!("{name}: {}", command.help))
+            .collect();
+        lines.sort();
+        lines
+    }
+}
+
+/// Input and output of the console.
+#[derive(Resource, Default, Debug)]
+pub struct DevConsole {
+    pub open: bool,
+    pub input: String,
+    pub log: Vec<String>,
+    queued: Vec<String>,
+}
+
+impl DevConsole {
+    /// Queues a line to run at the end of the frame, as if typed and entered.
+    pub fn submit(&mut self, line: impl Into<String>) {
+        self.queued.push(line.into());
+    }
+
+    fn print(&mut self, line: String) {
+        self.log.push(line);
+        let overflow = self.log.len().saturating_sub(MAX_LOG_LINES);
+        self.log.drain(..overflow);
+    }
+}
+
+/// Switches set from the console.
+#[derive(Resource, Default, Debug)]
+pub struct Cheats {
+    /// Deaths are ignored.
+    pub invincible: bool,
+}
+
+#[derive(Component)]
+pub(crate) struct ConsoleRoot;
+
+#[derive(Component)]
+pub(crate) struct ConsoleText;
+
+pub(crate) fn add_console(app: &mut App) {
+    let mut commands = ConsoleCommands::default();
+    commands.register("help", "list commands", help);
+    commands.register(
+        "set",
+        "set gravity|jump|pipe_speed <value>: change physics",
+        set_physics,
+    );
+    commands.register(
+        "spawn",
+        "spawn pipe <y>: spawn a pipe pair with its gap at y",
+        spawn,
+    );
+    commands.register("score", "score <n>: set the score", set_score);
+    commands.register("god", "god on|off: ignore deaths", god);
+
+    // sent by the window plugin; registered here too for headless apps
+    app.add_event::<ReceivedCharacter>()
+        .insert_resource(commands)
+        .init_resource::<DevConsole>()
+        .init_resource::<Cheats>()
+        .add_startup_system(spawn_console)
+        .add_system(toggle_console)
+        .add_system(type_into_console.after(toggle_console))
+        .add_system(run_console_commands.after(type_into_console))
+        .add_system(update_console_ui.after(run_console_commands));
+}
+
+fn spawn_console(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(0.0),
+                        left: Val::Px(0.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Auto),
+                    padding: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            ConsoleRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load(config.assets.font.as_str()),
+                        font_size: 8.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                ConsoleText,
+            ));
+        });
+}
+
+fn toggle_console(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    mut console: ResMut<DevConsole>,
+) {
+    if keyboard_input.just_pressed(config.bindings.console) {
+        console.open = !console.open;
+    }
+}
+
+fn type_into_console(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut console: ResMut<DevConsole>,
+) {
+    // the toggle key arrives as a character too, whichever it's bound to and
+    // whatever the keyboard layout makes of it
+    if !console.open || keyboard_input.just_pressed(config.bindings.console) {
+        characters.clear();
+        return;
+    }
+    for event in characters.iter() {
+        if !event.char.is_control() {
+            console.input.push(event.char);
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+    if keyboard_input.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        console.submit(line);
+    }
+}
+
+fn run_console_commands(world: &mut World) {
+    let lines = std::mem::take(&mut world.resource_mut::<DevConsole>().queued);
+    for line in lines {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let Some((&name, args)) = words.split_first() else {
+            continue;
+        };
+        let run = world
+            .resource::<ConsoleCommands>()
+            .commands
+            .get(name)
+            .map(|command| command.run);
+        let output = match run {
+            Some(run) => run(world, args),
+            None => Err(format!("unknown command '{name}', try 'help'")),
+        };
+
+        let mut console = world.resource_mut::<DevConsole>();
+        console.print(format!("> {line}"));
+        match output {
+            Ok(message) if message.is_empty() => {}
+            Ok(message) => console.print(message),
+            Err(error) => console.print(format!("error: {error}")),
+        }
+    }
+}
+
+fn update_console_ui(
+    console: Res<DevConsole>,
+    mut root_query: Query<&mut Style, With<ConsoleRoot>>,
+    mut text_query: Query<&mut Text, With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    for mut style in root_query.iter_mut() {
+        style.display = if console.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    for mut text in text_query.iter_mut() {
+        let mut contents = console.log.join("\n");
+        contents.push_str(&format!("\n> {}_", console.input));
+        text.sections[0].value = contents;
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&&str>) -> Result<T, String> {
+    let arg = arg.ok_or("missing argument")?;
+    arg.parse().map_err(|_| format!("invalid value '{arg}'"))
+}
+
+fn help(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    Ok(world.resource::<ConsoleCommands>().help().join("\n"))
+}
+
+fn set_physics(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let value: f32 = parse(args.get(1))?;
+    let mut config = world.resource_mut::<FlappyConfig>();
+    let physics = &mut config.physics;
+    match args.first() {
+        Some(&"gravity") => physics.gravity = value,
+        Some(&"jump") => physics.jump_velocity = value,
+        Some(&"pipe_speed") => physics.pipe_speed = value,
+        _ => return Err("expected gravity, jump or pipe_speed".to_string()),
+    }
+    Ok(String::new())
+}
+
+fn spawn(world: &mut World, args: &[&str]) -> Result<String, String> {
+    if args.first() != Some(&"pipe") {
+        return Err("expected 'spawn pipe <y>'".to_string());
+    }
+    let center: f32 = parse(args.get(1))?;
+    if world.resource::<State<AppState>>().0 != AppState::Game {
+        return Err("pipes can only be spawned during a run".to_string());
+    }
+
+    let config = world.resource::<FlappyConfig>();
+    let params = GapParams::for_height(config.height());
+    let gap = Gap {
+        center,
+        size: (params.min_size + params.max_size) / 2.0,
+    };
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+    spawn_pipe_pair(&mut commands, world.resource(), config, gap);
+    queue.apply(world);
+    Ok(String::new())
+}
+
+fn set_score(world: &mut World, args: &[&str]) -> Result<String, String> {
+    world.resource_mut::<Score>().0 = parse(args.first())?;
+    // a score that wasn't played for can't be a best
+    world.resource_mut::<RunRecord>().ranked = false;
+    Ok(String::new())
+}
+
+fn god(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let invincible = match args.first() {
+        Some(&"on") => true,
+        Some(&"off") => false,
+        _ => return Err("expected 'god on' or 'god off'".to_string()),
+    };
+    world.resource_mut::<Cheats>().invincible = invincible;
+    Ok(String::new())
+}
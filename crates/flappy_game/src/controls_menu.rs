@@ -0,0 +1,354 @@
+//! The controls screen, reached from the settings screen, for rebinding keys
+//! an `Action` at a time. Picking an action waits for the next key press. A
+//! key already in use while the action is live changes nothing yet: the
+//! actions it clashes with are highlighted, and the player is asked whether
+//! to swap keys with them or clear it from them, as `InputBindings::rebind`
+//! settles it. The quit key cancels the wait, and leaves the screen as usual.
+//! Below the actions, the left stick's deadzones step through
+//! `StickNavigation::DEADZONES` a click at a time.
+
+use bevy::prelude::*;
+
+use crate::bindings::key_name;
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{
+    Action, AppState, BindingConflict, ButtonSkin, DespawnOnExit, FlappyConfig, InputBindings,
+    Localized, Resolve, StickNavigation,
+};
+
+/// Behind the action waiting for a key.
+const LISTENING_COLOR: Color = Color::rgba(0.2, 0.4, 0.8, 0.8);
+/// Behind the actions a picked key clashes with.
+const CLASH_COLOR: Color = Color::rgba(0.8, 0.15, 0.15, 0.8);
+
+/// Waits for a new main key for its action when clicked.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RebindButton(pub Action);
+
+/// Answers the prompt shown for a `BindingConflict`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictButton {
+    Swap,
+    Clear,
+    Cancel,
+}
+
+/// Steps the left stick's deadzone along one axis when clicked.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadzoneButton {
+    X,
+    Y,
+}
+
+impl DeadzoneButton {
+    fn label(self, stick: StickNavigation) -> Localized {
+        let (key, deadzone) = match self {
+            DeadzoneButton::X => ("controls.deadzone_x", stick.deadzone.x),
+            DeadzoneButton::Y => ("controls.deadzone_y", stick.deadzone.y),
+        };
+        Localized::new(key).with_args([format!("{:.0}%", deadzone * 100.0)])
+    }
+}
+
+/// Where the controls screen is at.
+#[derive(Resource, Default, Debug)]
+pub struct Rebinding {
+    /// Waiting for a key to bind to this.
+    pub listening: Option<Action>,
+    /// The key picked clashes with other actions, waiting on a
+    /// `ConflictButton`.
+    pub conflict: Option<BindingConflict>,
+    /// The last answer to the conflict couldn't be carried out.
+    pub refused: bool,
+}
+
+/// Explains what the screen is waiting for.
+#[derive(Component)]
+struct ControlsHint;
+
+/// Holds the `ConflictButton`s while there's a conflict.
+#[derive(Component)]
+struct ConflictPrompt;
+
+#[derive(Component)]
+struct ControlsBackButton;
+
+pub(crate) fn add_controls_menu(app: &mut App) {
+    app.init_resource::<Rebinding>()
+        .add_systems(
+            (reset_rebinding, spawn_controls_menu).in_schedule(OnEnter(AppState::Controls)),
+        )
+        .add_systems(
+            (
+                read_rebind_key,
+                start_rebind.after(read_rebind_key),
+                settle_conflict,
+                step_deadzone,
+                show_controls
+                    .after(start_rebind)
+                    .after(settle_conflict)
+                    .after(step_deadzone),
+                leave_controls,
+            )
+                .in_set(OnUpdate(AppState::Controls)),
+        );
+}
+
+fn reset_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::default();
+}
+
+fn action_label(action: Action, bindings: &InputBindings) -> Localized {
+    let key = bindings
+        .keys(action)
+        .first()
+        .map_or_else(|| "-".to_string(), |key| key_name(*key));
+    Localized::new(action_key(action)).with_args([key])
+}
+
+fn action_key(action: Action) -> &'static str {
+    match action {
+        Action::Flap => "action.flap",
+        Action::FlapTwo => "action.flap_two",
+        Action::Restart => "action.restart",
+        Action::QuickRetry => "action.quick_retry",
+        Action::Pause => "action.pause",
+        Action::Quit => "action.quit",
+        Action::Mute => "action.mute",
+        Action::DebugOverlay => "action.debug_overlay",
+        Action::Console => "action.console",
+        Action::StatsOverlay => "action.stats_overlay",
+        Action::Heatmap => "action.heatmap",
+    }
+}
+
+fn spawn_controls_menu(mut commands: Commands, config: Res<FlappyConfig>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Controls),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("controls.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            let (hint, text, key) =
+                localized_text(Localized::new("controls.hint"), 1.0, Color::GRAY);
+            parent.spawn((hint, text, key, ControlsHint));
+            // two columns, so every action fits on screen
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(120.0), Val::Auto),
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|grid| {
+                    for (order, action) in (0..).zip(Action::ALL) {
+                        spawn_text_button(
+                            grid,
+                            action_label(action, &config.bindings),
+                            order,
+                            RebindButton(action),
+                        );
+                    }
+                });
+            parent.spawn(NodeBundle::default()).with_children(|row| {
+                for (order, button) in (25..).zip([DeadzoneButton::X, DeadzoneButton::Y]) {
+                    spawn_text_button(row, button.label(config.bindings.stick), order, button);
+                }
+            });
+            parent.spawn((NodeBundle::default(), ConflictPrompt));
+            spawn_text_button(parent, Localized::new("menu.back"), 30, ControlsBackButton);
+        });
+}
+
+/// Binds the first key pressed while listening, or holds on to the conflict
+/// it causes.
+fn read_rebind_key(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut config: ResMut<FlappyConfig>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(action) = rebinding.listening else {
+        return;
+    };
+    let Some(key) = keyboard_input.get_just_pressed().next().copied() else {
+        return;
+    };
+    rebinding.listening = None;
+    if key == config.bindings.quit {
+        return;
+    }
+    match config.bindings.rebind(action, key, None) {
+        Ok(()) => info!(action = action.name(), ?key, "rebound"),
+        Err(conflict) => rebinding.conflict = Some(conflict),
+    }
+}
+
+/// Listens for a key for the clicked action. Only read on later frames, so
+/// the Return that clicked it isn't taken as the key.
+fn start_rebind(
+    mut rebinding: ResMut<Rebinding>,
+    query: Query<(&Interaction, &RebindButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction == Interaction::Clicked && rebinding.conflict.is_none() {
+            rebinding.listening = Some(button.0);
+        }
+    }
+}
+
+fn settle_conflict(
+    mut config: ResMut<FlappyConfig>,
+    mut rebinding: ResMut<Rebinding>,
+    query: Query<(&Interaction, &ConflictButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let Some(conflict) = rebinding.conflict.clone() else {
+            return;
+        };
+        let resolve = match button {
+            ConflictButton::Swap => Resolve::Swap,
+            ConflictButton::Clear => Resolve::Clear,
+            ConflictButton::Cancel => {
+                rebinding.conflict = None;
+                rebinding.refused = false;
+                return;
+            }
+        };
+        let rebound = config
+            .bindings
+            .rebind(conflict.action, conflict.key, Some(resolve));
+        rebinding.refused = rebound.is_err();
+        if rebound.is_ok() {
+            info!(action = conflict.action.name(), key = ?conflict.key, "rebound");
+            rebinding.conflict = None;
+        }
+    }
+}
+
+fn step_deadzone(
+    mut config: ResMut<FlappyConfig>,
+    query: Query<(&Interaction, &DeadzoneButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let deadzone = &mut config.bindings.stick.deadzone;
+        let axis = match button {
+            DeadzoneButton::X => &mut deadzone.x,
+            DeadzoneButton::Y => &mut deadzone.y,
+        };
+        *axis = StickNavigation::next_deadzone(*axis);
+    }
+}
+
+/// Relabels the actions with their keys and the deadzones with theirs, highlights the one listening and
+/// any a conflict is with, and puts up or takes down the conflict prompt.
+#[allow(clippy::too_many_arguments)]
+fn show_controls(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    rebinding: Res<Rebinding>,
+    mut button_query: Query<(&RebindButton, &mut ButtonSkin, &Children)>,
+    deadzone_query: Query<(&DeadzoneButton, &Children)>,
+    mut label_query: Query<&mut Localized, Without<ControlsHint>>,
+    mut hint_query: Query<&mut Localized, With<ControlsHint>>,
+    prompt_query: Query<(Entity, Option<&Children>), With<ConflictPrompt>>,
+) {
+    if !config.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+    for (button, mut skin, children) in button_query.iter_mut() {
+        let color = if rebinding.listening == Some(button.0) {
+            LISTENING_COLOR
+        } else if rebinding
+            .conflict
+            .as_ref()
+            .is_some_and(|conflict| conflict.clashes.contains(&button.0))
+        {
+            CLASH_COLOR
+        } else {
+            ButtonSkin::panel().normal.color
+        };
+        if skin.normal.color != color {
+            skin.normal.color = color;
+        }
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            *label = action_label(button.0, &config.bindings);
+        }
+    }
+    for (button, children) in deadzone_query.iter() {
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            *label = button.label(config.bindings.stick);
+        }
+    }
+
+    let hint = match &rebinding.conflict {
+        Some(_) if rebinding.refused => Localized::new("controls.cant_clear"),
+        Some(conflict) => Localized::new("controls.conflict").with_args([key_name(conflict.key)]),
+        None if rebinding.listening.is_some() => Localized::new("controls.listening"),
+        None => Localized::new("controls.hint"),
+    };
+    for mut text in hint_query.iter_mut() {
+        *text = hint.clone();
+    }
+
+    for (prompt, buttons) in prompt_query.iter() {
+        let shown = buttons.is_some_and(|buttons| !buttons.is_empty());
+        if shown == rebinding.conflict.is_some() {
+            continue;
+        }
+        let mut prompt = commands.entity(prompt);
+        if shown {
+            prompt.despawn_descendants();
+            continue;
+        }
+        prompt.with_children(|parent| {
+            for (order, (button, key)) in (20..).zip([
+                (ConflictButton::Swap, "controls.swap"),
+                (ConflictButton::Clear, "controls.clear"),
+                (ConflictButton::Cancel, "controls.cancel"),
+            ]) {
+                spawn_text_button(parent, Localized::new(key), order, button);
+            }
+        });
+    }
+}
+
+/// Back to the settings screen. The quit key does the same, see `quit`.
+fn leave_controls(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<ControlsBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Settings);
+    }
+}
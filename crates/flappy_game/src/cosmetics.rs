@@ -0,0 +1,191 @@
+//! Cosmetics earned through achievements: accessories, a trail and a theme
+//! that stay locked on the customization screen until the player gets there.
+//! A toast announces each one the first time it unlocks.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::locale::localized_text;
+use crate::{Localized, Profile};
+
+/// Seconds each unlock toast stays up.
+const TOAST_SECONDS: f32 = 3.0;
+
+/// Something the player has done, as kept in their `Profile`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement {
+    /// Scored at least this in one run.
+    Best(u32),
+    /// Picked up at least this many coins over every run.
+    Coins(u32),
+}
+
+impl Achievement {
+    pub fn reached(self, profile: &Profile) -> bool {
+        match self {
+            Achievement::Best(score) => profile.best >= score,
+            Achievement::Coins(coins) => profile.coins >= coins,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cosmetic {
+    Hat,
+    Scarf,
+    GoldTrail,
+    NightTheme,
+}
+
+impl Cosmetic {
+    pub const ALL: [Cosmetic; 4] = [
+        Cosmetic::Hat,
+        Cosmetic::Scarf,
+        Cosmetic::GoldTrail,
+        Cosmetic::NightTheme,
+    ];
+
+    /// What unlocks this.
+    pub fn achievement(self) -> Achievement {
+        match self {
+            Cosmetic::Hat => Achievement::Best(10),
+            Cosmetic::Scarf => Achievement::Coins(20),
+            Cosmetic::GoldTrail => Achievement::Best(25),
+            Cosmetic::NightTheme => Achievement::Best(50),
+        }
+    }
+
+    pub fn unlocked(self, profile: &Profile) -> bool {
+        self.achievement().reached(profile)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Cosmetic::Hat => "cosmetic.hat",
+            Cosmetic::Scarf => "cosmetic.scarf",
+            Cosmetic::GoldTrail => "cosmetic.gold_trail",
+            Cosmetic::NightTheme => "cosmetic.night_theme",
+        }
+    }
+
+    /// What to do to unlock this, for the customization screen.
+    pub fn requirement(self) -> Localized {
+        match self.achievement() {
+            Achievement::Best(score) => {
+                Localized::new("cosmetic.needs_best").with_args([score.to_string()])
+            }
+            Achievement::Coins(coins) => {
+                Localized::new("cosmetic.needs_coins").with_args([coins.to_string()])
+            }
+        }
+    }
+}
+
+/// Column along the top of the screen that toasts are stacked in.
+#[derive(Component)]
+struct ToastStack;
+
+/// Announces `cosmetic` until its timer runs out.
+#[derive(Component, Debug)]
+pub struct UnlockToast {
+    pub cosmetic: Cosmetic,
+    pub timer: Timer,
+}
+
+pub(crate) fn add_cosmetics(app: &mut App) {
+    app.add_startup_system(spawn_toast_stack)
+        .add_system(announce_unlocks)
+        .add_system(expire_toasts);
+}
+
+fn spawn_toast_stack(mut commands: Commands) {
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px(4.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        },
+        ToastStack,
+    ));
+}
+
+/// Toasts each cosmetic the first time its achievement is reached, and marks
+/// it announced in `Profile::unlocked`.
+fn announce_unlocks(
+    mut commands: Commands,
+    mut profile: ResMut<Profile>,
+    stack_query: Query<Entity, With<ToastStack>>,
+) {
+    if !profile.is_changed() {
+        return;
+    }
+    let new: Vec<_> = Cosmetic::ALL
+        .into_iter()
+        .filter(|cosmetic| cosmetic.unlocked(&profile) && !profile.unlocked.contains(cosmetic))
+        .collect();
+    if new.is_empty() {
+        return;
+    }
+    let Ok(stack) = stack_query.get_single() else {
+        return;
+    };
+    for cosmetic in new {
+        info!(?cosmetic, "unlocked");
+        profile.unlocked.push(cosmetic);
+        commands.entity(stack).with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(2.0)),
+                            margin: UiRect::bottom(Val::Px(2.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgba(0.0, 0.0, 0.0, 0.7).into(),
+                        ..default()
+                    },
+                    UnlockToast {
+                        cosmetic,
+                        timer: Timer::from_seconds(TOAST_SECONDS, TimerMode::Once),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(localized_text(
+                        Localized::new("cosmetic.unlocked"),
+                        1.0,
+                        Color::rgb(1.0, 0.85, 0.2),
+                    ));
+                    parent.spawn(localized_text(
+                        Localized::new(cosmetic.label()),
+                        1.0,
+                        Color::WHITE,
+                    ));
+                });
+        });
+    }
+}
+
+/// In real time, so toasts clear even while the game is slowed or paused.
+fn expire_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut UnlockToast)>,
+) {
+    for (entity, mut toast) in query.iter_mut() {
+        if toast.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Credits and attributions, reached from the main menu. The list scrolls
+//! with the mouse wheel or the arrow keys when it doesn't fit the window.
+
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+use crate::bitmap_text::bitmap_text;
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{build_info, AppState, DespawnOnExit, Localized};
+
+/// Kept in step with the `bevy` dependency in Cargo.toml.
+const BEVY_VERSION: &str = "0.10.1";
+/// Pixels scrolled per line of mouse wheel, or per frame an arrow is held.
+const SCROLL_STEP: f32 = 8.0;
+
+/// Heading keys, each followed by its lines, which are names and addresses
+/// and so left untranslated. The game and engine, which need formatting, go
+/// either side.
+const CREDITS: &[(&str, &[&str])] = &[
+    ("credits.art", &["Flappy Bird - Dong Nguyen"]),
+    (
+        "credits.font",
+        &["Hack - Source Foundry", "MIT - sourcefoundry.org/hack"],
+    ),
+    (
+        "credits.libraries",
+        &[
+            "gif, rand, dirs, ron, serde, tracing",
+            "bevy_prototype_debug_lines",
+            "bevy_rapier2d, bevy-inspector-egui",
+            "bevy_kira_audio, arboard",
+            "tungstenite, ureq",
+        ],
+    ),
+];
+
+#[derive(Component)]
+struct CreditsBackButton;
+
+/// The scrolling list, offset upwards by `scrolled` pixels.
+#[derive(Component, Default)]
+struct CreditsList {
+    scrolled: f32,
+}
+
+pub(crate) fn add_credits(app: &mut App) {
+    app.add_system(spawn_credits.in_schedule(OnEnter(AppState::Credits)))
+        .add_systems((scroll_credits, leave_credits).in_set(OnUpdate(AppState::Credits)));
+}
+
+fn spawn_credits(mut commands: Commands) {
+    let engine = format!("Bevy {BEVY_VERSION} - bevyengine.org");
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Credits),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("credits.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            // the viewport clips the list as it scrolls
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(80.0), Val::Percent(60.0)),
+                        margin: UiRect::all(Val::Px(4.0)),
+                        overflow: Overflow::Hidden,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent
+                        .spawn((
+                            NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    align_items: AlignItems::Center,
+                                    flex_shrink: 0.0,
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            CreditsList::default(),
+                        ))
+                        .with_children(|parent| {
+                            let build = build_info();
+                            let about: &[&str] = &[&build, "github.com/calwe/flappy_bevy"];
+                            let engine_lines: &[&str] = &[&engine, "MIT / Apache-2.0"];
+                            let sections = [("credits.game", about)]
+                                .into_iter()
+                                .chain(CREDITS.iter().copied())
+                                .chain([("credits.engine", engine_lines)]);
+                            for (heading, lines) in sections {
+                                let (mut image, text, key) = localized_text(
+                                    Localized::new(heading),
+                                    1.0,
+                                    Color::rgb(1.0, 0.8, 0.2),
+                                );
+                                image.style.margin = UiRect::top(Val::Px(4.0));
+                                parent.spawn((image, text, key));
+                                for line in lines {
+                                    parent.spawn(bitmap_text(*line, 1.0, Color::WHITE));
+                                }
+                            }
+                        });
+                });
+            spawn_text_button(parent, Localized::new("menu.back"), 0, CreditsBackButton);
+        });
+}
+
+fn scroll_credits(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut wheel: EventReader<MouseWheel>,
+    mut list_query: Query<(&mut CreditsList, &mut Style, &Node, &Parent)>,
+    node_query: Query<&Node>,
+) {
+    let mut delta: f32 = wheel
+        .iter()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_STEP,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if keyboard_input.pressed(KeyCode::Up) {
+        delta += SCROLL_STEP;
+    }
+    if keyboard_input.pressed(KeyCode::Down) {
+        delta -= SCROLL_STEP;
+    }
+    if delta == 0.0 {
+        return;
+    }
+
+    for (mut list, mut style, node, parent) in list_query.iter_mut() {
+        let Ok(viewport) = node_query.get(parent.get()) else {
+            continue;
+        };
+        let max = (node.size().y - viewport.size().y).max(0.0);
+        list.scrolled = (list.scrolled - delta).clamp(0.0, max);
+        style.position.top = Val::Px(-list.scrolled);
+    }
+}
+
+/// Back to the main menu. The quit key does the same, see `quit`.
+fn leave_credits(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<CreditsBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,245 @@
+//! Difficulty curves: how the pipe speed, the time between pairs and the gap
+//! sizes change with the score, one curve per `Difficulty`. They're data, read
+//! from `AssetPaths::difficulty_curves`, so balancing is done by editing that
+//! file. The copy bundled with the game applies until it loads, and changes
+//! are picked up whenever the asset server reloads it.
+
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use flappy_core::pipes::{GapParams, Pacing};
+use serde::Deserialize;
+
+use crate::{Difficulty, FlappyConfig, Score, Settings};
+
+/// The curve's values from `score` on.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct CurveKey {
+    pub score: u32,
+    /// Multiplies `Physics::pipe_speed`.
+    pub speed: f32,
+    /// Seconds from one pipe pair to the next.
+    pub interval: f32,
+    pub min_gap: f32,
+    pub max_gap: f32,
+}
+
+impl CurveKey {
+    /// The ranges gaps are drawn from on a playfield of the given height. Only
+    /// the sizes come from the curve, so the same seed gives the same heights
+    /// at any difficulty.
+    pub fn gap_params(&self, height: f32) -> GapParams {
+        GapParams {
+            min_size: self.min_gap,
+            max_size: self.max_gap,
+            ..GapParams::for_height(height)
+        }
+    }
+}
+
+/// Keys in order of score, interpolated linearly between.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Curve {
+    pub keys: Vec<CurveKey>,
+}
+
+impl Curve {
+    /// The values at `score`, held past either end.
+    pub fn at(&self, score: u32) -> CurveKey {
+        let next = self.keys.partition_point(|key| key.score <= score);
+        let (Some(from), Some(to)) = (self.keys.get(next.wrapping_sub(1)), self.keys.get(next))
+        else {
+            let key = self.keys[next.min(self.keys.len() - 1)];
+            return CurveKey { score, ..key };
+        };
+        let t = (score - from.score) as f32 / (to.score - from.score) as f32;
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        CurveKey {
+            score,
+            speed: lerp(from.speed, to.speed),
+            interval: lerp(from.interval, to.interval),
+            min_gap: lerp(from.min_gap, to.min_gap),
+            max_gap: lerp(from.max_gap, to.max_gap),
+        }
+    }
+
+    /// The nearest `Pacing` for the headless simulation, which counts pairs
+    /// rather than points: the interval falls from the first key's to the
+    /// shortest over as many pairs as the curve takes points.
+    pub fn pacing(&self) -> Pacing {
+        let start = self.keys[0];
+        let shortest = self
+            .keys
+            .iter()
+            .min_by(|a, b| a.interval.total_cmp(&b.interval))
+            .unwrap_or(&start);
+        Pacing {
+            start: start.interval,
+            step: (start.interval - shortest.interval) / shortest.score.max(1) as f32,
+            floor: shortest.interval,
+        }
+    }
+}
+
+/// Every difficulty's curve.
+#[derive(Resource, TypeUuid, Clone, Debug, PartialEq, Deserialize)]
+#[uuid = "8b3c1f52-6a0d-4e97-b2c4-5d1e9a7f3b60"]
+#[serde(transparent)]
+pub struct DifficultyCurves {
+    pub curves: HashMap<Difficulty, Curve>,
+}
+
+impl Default for DifficultyCurves {
+    fn default() -> Self {
+        Self::parse(include_str!("../assets/difficulty.curve.ron"))
+            .expect("bundled difficulty curves")
+    }
+}
+
+impl DifficultyCurves {
+    /// Reads curves written in RON as a map from difficulty to keys, checking
+    /// every difficulty has a usable one.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let curves: Self = ron::from_str(source).map_err(|error| error.to_string())?;
+        for difficulty in Difficulty::ALL {
+            let curve = curves
+                .curves
+                .get(&difficulty)
+                .ok_or_else(|| format!("no curve for {difficulty:?}"))?;
+            if curve.keys.is_empty() {
+                return Err(format!("the {difficulty:?} curve has no keys"));
+            }
+            if curve
+                .keys
+                .windows(2)
+                .any(|keys| keys[0].score >= keys[1].score)
+            {
+                return Err(format!("the {difficulty:?} keys aren't in order of score"));
+            }
+            for key in &curve.keys {
+                if key.speed <= 0.0 || key.interval <= 0.0 {
+                    return Err(format!(
+                        "{difficulty:?} at {}: speed and interval must be positive",
+                        key.score
+                    ));
+                }
+                if key.min_gap <= 0.0 || key.min_gap >= key.max_gap {
+                    return Err(format!(
+                        "{difficulty:?} at {}: gaps must be positive, min under max",
+                        key.score
+                    ));
+                }
+            }
+        }
+        Ok(curves)
+    }
+
+    pub fn get(&self, difficulty: Difficulty) -> &Curve {
+        &self.curves[&difficulty]
+    }
+}
+
+#[derive(Default)]
+struct CurveLoader;
+
+impl AssetLoader for CurveLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let curves = DifficultyCurves::parse(std::str::from_utf8(bytes)?)
+                .map_err(bevy::asset::Error::msg)?;
+            load_context.set_default_asset(LoadedAsset::new(curves));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["curve.ron"]
+    }
+}
+
+#[derive(Resource)]
+struct CurvesHandle(Handle<DifficultyCurves>);
+
+/// The current difficulty's curve at the current score, in world units, eased
+/// by `Settings::assist`. Updated at the start of every frame.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Pace {
+    pub pipe_speed: f32,
+    pub interval: f32,
+    pub gaps: GapParams,
+}
+
+pub(crate) fn add_difficulty_curves(app: &mut App) {
+    app.add_asset::<DifficultyCurves>()
+        .init_asset_loader::<CurveLoader>()
+        .init_resource::<DifficultyCurves>()
+        .insert_resource(Pace {
+            pipe_speed: 0.0,
+            interval: 0.0,
+            gaps: GapParams::for_height(0.0),
+        })
+        .add_startup_system(load_curves)
+        // ahead of everything reading the pace
+        .add_system(apply_loaded_curves.in_base_set(CoreSet::PreUpdate))
+        .add_system(
+            update_pace
+                .after(apply_loaded_curves)
+                .in_base_set(CoreSet::PreUpdate),
+        );
+}
+
+fn load_curves(mut commands: Commands, asset_server: Res<AssetServer>, config: Res<FlappyConfig>) {
+    let handle = asset_server.load(config.assets.difficulty_curves.as_str());
+    commands.insert_resource(CurvesHandle(handle));
+}
+
+fn apply_loaded_curves(
+    mut events: EventReader<AssetEvent<DifficultyCurves>>,
+    handle: Option<Res<CurvesHandle>>,
+    assets: Res<Assets<DifficultyCurves>>,
+    mut curves: ResMut<DifficultyCurves>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.iter() {
+        let (AssetEvent::Created { handle: changed } | AssetEvent::Modified { handle: changed }) =
+            event
+        else {
+            continue;
+        };
+        if *changed != handle.0 {
+            continue;
+        }
+        if let Some(loaded) = assets.get(changed) {
+            info!("difficulty curves loaded");
+            *curves = loaded.clone();
+        }
+    }
+}
+
+fn update_pace(
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    curves: Res<DifficultyCurves>,
+    score: Res<Score>,
+    mut pace: ResMut<Pace>,
+) {
+    let key = curves.get(settings.difficulty).at(score.0);
+    let new_pace = settings.assist.apply(Pace {
+        pipe_speed: config.physics.pipe_speed * key.speed,
+        interval: key.interval,
+        gaps: key.gap_params(config.height()),
+    });
+    if *pace != new_pace {
+        *pace = new_pace;
+    }
+}
@@ -0,0 +1,464 @@
+//! The bird customization screen, reached from the main menu: sliders for the
+//! bird's hue and saturation, and a choice of accessory drawn over it. The
+//! look is kept in `Profile::bird` and put on the bird each time it spawns.
+//! The trail and theme are picked here too, with some choices locked until
+//! their `Cosmetic` is unlocked.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{
+    AppState, Bird, BirdTrail, Cosmetic, DespawnOnExit, FlappyConfig, Localized, MenuFocus, Player,
+    Profile, Settings,
+};
+
+/// Steps from one end of a `LookSlider` to the other.
+const LOOK_STEPS: f32 = 12.0;
+/// Size of the bird shown on the customization screen, in screen pixels.
+const PREVIEW_SIZE: Vec2 = Vec2::new(34.0, 24.0);
+
+/// How the player dressed up the bird.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct BirdLook {
+    /// Degrees round the colour wheel, from 0 to 360.
+    pub hue: f32,
+    /// How strongly the hue tints the bird, from 0 for untouched to 1.
+    pub saturation: f32,
+    pub accessory: Accessory,
+}
+
+impl BirdLook {
+    /// The colour multiplied with the bird's texture. White at no saturation.
+    pub fn tint(&self) -> Color {
+        let saturation = self.saturation.clamp(0.0, 1.0);
+        Color::hsl(self.hue.rem_euclid(360.0), 1.0, 1.0 - saturation / 2.0)
+    }
+}
+
+/// Worn over the bird, as a child sprite.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Accessory {
+    #[default]
+    None,
+    Hat,
+    Scarf,
+}
+
+impl Accessory {
+    pub const ALL: [Accessory; 3] = [Accessory::None, Accessory::Hat, Accessory::Scarf];
+
+    /// The one after this, going round to the first.
+    pub fn next(self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|accessory| *accessory == self)
+            .unwrap_or(0)
+    }
+
+    /// What has to be unlocked to wear this.
+    pub fn cosmetic(self) -> Option<Cosmetic> {
+        match self {
+            Accessory::None => None,
+            Accessory::Hat => Some(Cosmetic::Hat),
+            Accessory::Scarf => Some(Cosmetic::Scarf),
+        }
+    }
+
+    pub fn unlocked(self, profile: &Profile) -> bool {
+        self.cosmetic()
+            .is_none_or(|cosmetic| cosmetic.unlocked(profile))
+    }
+
+    /// Size, offset from the bird's centre and colour of the overlay.
+    fn sprite(self) -> Option<(Vec2, Vec2, Color)> {
+        match self {
+            Accessory::None => None,
+            Accessory::Hat => Some((
+                Vec2::new(8.0, 5.0),
+                Vec2::new(2.0, 7.0),
+                Color::rgb(0.15, 0.15, 0.2),
+            )),
+            Accessory::Scarf => Some((
+                Vec2::new(11.0, 3.0),
+                Vec2::new(-1.0, -3.0),
+                Color::rgb(0.85, 0.15, 0.2),
+            )),
+        }
+    }
+}
+
+/// Sets the hue or saturation of `Profile::bird`. Works like a
+/// `PhysicsSlider`: click along it, or step with left and right while focused.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LookSlider {
+    Hue,
+    Saturation,
+}
+
+impl LookSlider {
+    pub const ALL: [LookSlider; 2] = [LookSlider::Hue, LookSlider::Saturation];
+
+    fn max(self) -> f32 {
+        match self {
+            LookSlider::Hue => 360.0,
+            LookSlider::Saturation => 1.0,
+        }
+    }
+
+    fn value_mut(self, look: &mut BirdLook) -> &mut f32 {
+        match self {
+            LookSlider::Hue => &mut look.hue,
+            LookSlider::Saturation => &mut look.saturation,
+        }
+    }
+
+    /// How far along the slider `look` is, from 0 to 1.
+    fn level(self, mut look: BirdLook) -> f32 {
+        (*self.value_mut(&mut look) / self.max()).clamp(0.0, 1.0)
+    }
+
+    fn label(self, look: BirdLook) -> Localized {
+        let level = self.level(look);
+        match self {
+            LookSlider::Hue => {
+                Localized::new("customize.hue").with_args([format!("{:.0}", level * 360.0)])
+            }
+            LookSlider::Saturation => {
+                Localized::new("customize.saturation").with_args([format!("{:.0}", level * 100.0)])
+            }
+        }
+    }
+}
+
+/// A choice on the customization screen that can be locked behind a
+/// `Cosmetic`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    Accessory,
+    Trail,
+    Theme,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 3] = [Slot::Accessory, Slot::Trail, Slot::Theme];
+
+    /// Label of each choice, and the cosmetic it needs if any.
+    fn choices(self) -> &'static [(&'static str, Option<Cosmetic>)] {
+        match self {
+            Slot::Accessory => &[
+                ("customize.accessory_none", None),
+                ("customize.accessory_hat", Some(Cosmetic::Hat)),
+                ("customize.accessory_scarf", Some(Cosmetic::Scarf)),
+            ],
+            Slot::Trail => &[
+                ("customize.trail_off", None),
+                ("customize.trail_white", None),
+                ("customize.trail_gold", Some(Cosmetic::GoldTrail)),
+            ],
+            Slot::Theme => &[
+                ("customize.theme_day", None),
+                ("customize.theme_night", Some(Cosmetic::NightTheme)),
+            ],
+        }
+    }
+
+    /// The choice in use.
+    fn current(self, profile: &Profile, settings: &Settings) -> usize {
+        match self {
+            Slot::Accessory => profile.bird.accessory.index(),
+            Slot::Trail => match settings.trail {
+                None => 0,
+                Some(trail) if trail == BirdTrail::gold() => 2,
+                Some(_) => 1,
+            },
+            Slot::Theme => usize::from(settings.night_theme),
+        }
+    }
+
+    fn apply(self, choice: usize, profile: &mut Profile, settings: &mut Settings) {
+        match self {
+            Slot::Accessory => profile.bird.accessory = Accessory::ALL[choice],
+            Slot::Trail => {
+                settings.trail = match choice {
+                    0 => None,
+                    1 => Some(BirdTrail::default()),
+                    _ => Some(BirdTrail::gold()),
+                }
+            }
+            Slot::Theme => settings.night_theme = choice == 1,
+        }
+    }
+}
+
+/// Steps through a `Slot`'s choices. Locked ones are shown, with what unlocks
+/// them underneath, but left unused until they're unlocked.
+#[derive(Component, Debug)]
+pub struct CosmeticButton {
+    pub slot: Slot,
+    /// Index into the slot's choices.
+    pub choice: usize,
+}
+
+impl CosmeticButton {
+    /// The cosmetic the shown choice still needs.
+    pub fn locked(&self, profile: &Profile) -> Option<Cosmetic> {
+        self.slot.choices()[self.choice]
+            .1
+            .filter(|cosmetic| !cosmetic.unlocked(profile))
+    }
+
+    fn label(&self, profile: &Profile) -> Localized {
+        match self.locked(profile) {
+            Some(_) => Localized::new("cosmetic.locked"),
+            None => Localized::new(self.slot.choices()[self.choice].0),
+        }
+    }
+}
+
+/// Says what unlocks the locked choice being shown, if any.
+#[derive(Component)]
+struct UnlockHint;
+
+/// The bird as it'll look, on the customization screen.
+#[derive(Component)]
+struct BirdPreview;
+
+#[derive(Component)]
+struct CustomizeBackButton;
+
+pub(crate) fn add_customize(app: &mut App) {
+    app.add_system(spawn_customize_menu.in_schedule(OnEnter(AppState::Customize)))
+        .add_systems(
+            (
+                adjust_look,
+                cycle_cosmetics,
+                show_look.after(adjust_look).after(cycle_cosmetics),
+                leave_customize,
+            )
+                .in_set(OnUpdate(AppState::Customize)),
+        )
+        .add_system(dress_bird);
+}
+
+fn spawn_customize_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+) {
+    let look = profile.bird;
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Customize),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("customize.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            parent.spawn((
+                ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(PREVIEW_SIZE.x), Val::Px(PREVIEW_SIZE.y)),
+                        margin: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    image: asset_server.load(config.assets.bird.as_str()).into(),
+                    background_color: look.tint().into(),
+                    ..default()
+                },
+                BirdPreview,
+            ));
+            for (order, slider) in (0..).zip(LookSlider::ALL) {
+                spawn_text_button(parent, slider.label(look), order, slider);
+            }
+            for (order, slot) in (2..).zip(Slot::ALL) {
+                let button = CosmeticButton {
+                    slot,
+                    choice: slot.current(&profile, &settings),
+                };
+                spawn_text_button(parent, button.label(&profile), order, button);
+            }
+            parent.spawn((
+                localized_text(Localized::new("customize.hint"), 1.0, Color::GRAY),
+                UnlockHint,
+            ));
+            spawn_text_button(parent, Localized::new("menu.back"), 5, CustomizeBackButton);
+        });
+}
+
+fn adjust_look(
+    keyboard_input: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    focus: Res<MenuFocus>,
+    mut profile: ResMut<Profile>,
+    query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &LookSlider,
+        &Node,
+        &GlobalTransform,
+    )>,
+) {
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let step = 1.0 / LOOK_STEPS;
+    for (entity, interaction, slider, node, transform) in query.iter() {
+        let level = slider.level(profile.bird);
+        let mut new_level = level;
+        if interaction.is_changed() && *interaction == Interaction::Clicked {
+            let left = transform.translation().x - node.size().x / 2.0;
+            new_level = match cursor.map(|cursor| (cursor.x - left) / node.size().x) {
+                Some(along) if (0.0..=1.0).contains(&along) => along,
+                // clicked from the keyboard
+                _ if level >= 1.0 => 0.0,
+                _ => level + step,
+            };
+        } else if focus.focused == Some(entity) {
+            if keyboard_input.just_pressed(KeyCode::Left) || focus.stick.x < 0 {
+                new_level -= step;
+            }
+            if keyboard_input.just_pressed(KeyCode::Right) || focus.stick.x > 0 {
+                new_level += step;
+            }
+        }
+        if new_level == level {
+            continue;
+        }
+        // whole steps, so the values stay round
+        let steps = (new_level * LOOK_STEPS).round().clamp(0.0, LOOK_STEPS);
+        *slider.value_mut(&mut profile.bird) = slider.max() * steps / LOOK_STEPS;
+    }
+}
+
+/// Moves each clicked button on to its next choice, and puts it to use if
+/// it's unlocked.
+fn cycle_cosmetics(
+    mut profile: ResMut<Profile>,
+    mut settings: ResMut<Settings>,
+    mut query: Query<(&Interaction, &mut CosmeticButton), Changed<Interaction>>,
+) {
+    for (interaction, mut button) in query.iter_mut() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        button.choice = (button.choice + 1) % button.slot.choices().len();
+        if button.locked(&profile).is_none() {
+            button
+                .slot
+                .apply(button.choice, &mut profile, &mut settings);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn show_look(
+    profile: Res<Profile>,
+    slider_query: Query<(&LookSlider, &Children)>,
+    button_query: Query<(Ref<CosmeticButton>, &Children)>,
+    mut label_query: Query<&mut Localized, Without<UnlockHint>>,
+    mut hint_query: Query<&mut Localized, With<UnlockHint>>,
+    mut preview_query: Query<&mut BackgroundColor, With<BirdPreview>>,
+) {
+    let buttons_changed = button_query.iter().any(|(button, _)| button.is_changed());
+    if !profile.is_changed() && !buttons_changed {
+        return;
+    }
+    let look = profile.bird;
+    for (slider, children) in slider_query.iter() {
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            *label = slider.label(look);
+        }
+    }
+    let mut hint = Localized::new("customize.hint");
+    for (button, children) in button_query.iter() {
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            *label = button.label(&profile);
+        }
+        if let Some(cosmetic) = button.locked(&profile) {
+            hint = cosmetic.requirement();
+        }
+    }
+    for mut label in hint_query.iter_mut() {
+        *label = hint.clone();
+    }
+    for mut color in preview_query.iter_mut() {
+        color.0 = look.tint();
+    }
+}
+
+/// Back to the main menu. The quit key does the same, see `quit`.
+fn leave_customize(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<CustomizeBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+/// Puts the player's look on their bird as it spawns, leaving off an
+/// accessory that isn't unlocked. A `CoOp` second player keeps their own
+/// sprite.
+fn dress_bird(
+    mut commands: Commands,
+    profile: Res<Profile>,
+    mut query: Query<(Entity, &Player, &mut Sprite), Added<Bird>>,
+) {
+    let look = profile.bird;
+    for (entity, player, mut sprite) in query.iter_mut() {
+        if *player != Player::ONE {
+            continue;
+        }
+        sprite.color = look.tint();
+        if !look.accessory.unlocked(&profile) {
+            continue;
+        }
+        let Some((size, offset, color)) = look.accessory.sprite() else {
+            continue;
+        };
+        commands.entity(entity).with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(size),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(offset.extend(0.1)),
+                    ..default()
+                },
+                look.accessory,
+            ));
+        });
+    }
+}
@@ -0,0 +1,166 @@
+//! The air-dash, an optional arcade ability: flapping twice in quick
+//! succession sends the bird forward for a moment, drawn as the level rushing
+//! past faster through the `ScrollSpeed`. Unlocked by reaching
+//! `DASH_UNLOCK_SCORE`, then switched on with `Settings::air_dash`. A meter in
+//! the corner fills as it recharges.
+
+use bevy::prelude::*;
+
+use crate::single_switch::auto_flap;
+use crate::{
+    jump, AppState, DespawnOnExit, FlapEvent, GameSystem, GameTick, PhysicsClock, Player, Profile,
+    SafeArea, Settings,
+};
+
+/// Best score that unlocks the dash.
+pub const DASH_UNLOCK_SCORE: u32 = 25;
+/// Longest gap between two flaps that counts as a double tap, in seconds.
+const DOUBLE_TAP: f32 = 0.25;
+/// Seconds each dash lasts.
+pub const DASH_SECONDS: f32 = 0.2;
+/// How many times faster the level goes by during a dash.
+const DASH_BOOST: f32 = 3.0;
+/// Seconds from one dash until the next can start.
+pub const DASH_COOLDOWN: f32 = 3.0;
+const METER_SIZE: Vec2 = Vec2::new(24.0, 3.0);
+
+/// The current run's dash.
+#[derive(Resource, Default, Debug)]
+pub struct AirDash {
+    /// Seconds left of the dash under way, zero when not dashing.
+    pub remaining: f32,
+    /// Seconds until another dash can start.
+    pub cooldown: f32,
+    /// Seconds since the last flap that didn't start a dash.
+    since_flap: Option<f32>,
+}
+
+impl AirDash {
+    /// Whether the player has the dash and has it switched on.
+    pub fn available(profile: &Profile, settings: &Settings) -> bool {
+        settings.air_dash && profile.best >= DASH_UNLOCK_SCORE
+    }
+
+    /// How recharged the dash is, from 0 just after dashing to 1 when ready.
+    pub fn charge(&self) -> f32 {
+        1.0 - self.cooldown / DASH_COOLDOWN
+    }
+
+    /// How many times faster than the pace the level scrolls by.
+    pub fn boost(&self) -> f32 {
+        if self.remaining > 0.0 {
+            DASH_BOOST
+        } else {
+            1.0
+        }
+    }
+}
+
+/// The bar filled by `AirDash::charge`.
+#[derive(Component)]
+pub struct DashMeter;
+
+pub(crate) fn add_air_dash(app: &mut App) {
+    app.init_resource::<AirDash>()
+        .add_systems((reset_dash, spawn_dash_meter).in_schedule(OnEnter(AppState::Game)))
+        .add_systems(
+            (
+                tick_dash,
+                start_dash.after(tick_dash).after(jump).after(auto_flap),
+            )
+                .in_set(GameSystem::Input)
+                .in_schedule(GameTick),
+        )
+        .add_system(show_dash_meter.in_set(OnUpdate(AppState::Game)));
+}
+
+fn reset_dash(mut dash: ResMut<AirDash>) {
+    *dash = AirDash::default();
+}
+
+fn spawn_dash_meter(mut commands: Commands, profile: Res<Profile>, settings: Res<Settings>) {
+    if !AirDash::available(&profile, &settings) {
+        return;
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        left: Val::Px(4.0),
+                        bottom: Val::Px(4.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(METER_SIZE.x), Val::Px(METER_SIZE.y)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.4).into(),
+                ..default()
+            },
+            SafeArea::default(),
+            DespawnOnExit(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.4, 0.8, 1.0).into(),
+                    ..default()
+                },
+                DashMeter,
+            ));
+        });
+}
+
+fn tick_dash(clock: Res<PhysicsClock>, mut dash: ResMut<AirDash>) {
+    let dt = clock.step;
+    if dt == 0.0 {
+        return;
+    }
+    dash.remaining = (dash.remaining - dt).max(0.0);
+    dash.cooldown = (dash.cooldown - dt).max(0.0);
+    if let Some(since_flap) = &mut dash.since_flap {
+        *since_flap += dt;
+    }
+}
+
+/// Dashes on the first player's double tap. A `CoOp` second player's flaps
+/// don't count towards it.
+fn start_dash(
+    profile: Res<Profile>,
+    settings: Res<Settings>,
+    mut flaps: EventReader<FlapEvent>,
+    mut dash: ResMut<AirDash>,
+    players: Query<&Player>,
+) {
+    let mut flapped = false;
+    for flap in flaps.iter() {
+        flapped |= players.get(flap.bird).ok() == Some(&Player::ONE);
+    }
+    if !flapped {
+        return;
+    }
+    let double_tap = dash.since_flap.is_some_and(|since| since <= DOUBLE_TAP);
+    if double_tap && dash.cooldown == 0.0 && AirDash::available(&profile, &settings) {
+        debug!("dashing");
+        dash.remaining = DASH_SECONDS;
+        dash.cooldown = DASH_COOLDOWN;
+        // a third tap starts a new double tap
+        dash.since_flap = None;
+    } else {
+        dash.since_flap = Some(0.0);
+    }
+}
+
+fn show_dash_meter(dash: Res<AirDash>, mut query: Query<&mut Style, With<DashMeter>>) {
+    if !dash.is_changed() {
+        return;
+    }
+    for mut style in query.iter_mut() {
+        style.size.width = Val::Percent(dash.charge() * 100.0);
+    }
+}
@@ -0,0 +1,96 @@
+//! Slow-motion zoom onto the point of impact, played between the hit stop and
+//! the game over screen for `FlappyConfig::death_slow_mo` seconds.
+//!
+//! Assumes 2D cameras rest at the centre of the playfield with a scale of one,
+//! which is where they are put back on leaving the run.
+//!
+//! Holding the quick retry key skips all this and the game over screen, and
+//! restarts the run as soon as the hit stop ends.
+
+use bevy::prelude::*;
+
+use crate::{AppState, DevConsole, FlappyConfig, GameOverEvent, MotionPrefs, Score, TimeScale};
+
+/// Time scale reached by the end of the zoom.
+const SLOW_MO_SCALE: f32 = 0.3;
+/// Magnification reached by the end of the zoom.
+const ZOOM: f32 = 2.0;
+
+/// Counts down in real time while zooming in after a death.
+#[derive(Resource, Default, Debug)]
+pub struct DeathCam {
+    pub timer: Option<Timer>,
+    /// World position zoomed in on.
+    pub focus: Vec2,
+}
+
+impl DeathCam {
+    pub(crate) fn start(&mut self, seconds: f32, focus: Vec2) {
+        self.timer = Some(Timer::from_seconds(seconds, TimerMode::Once));
+        self.focus = focus;
+    }
+}
+
+pub(crate) fn add_death_cam(app: &mut App) {
+    app.init_resource::<DeathCam>()
+        .add_system(reset_death_cam.in_schedule(OnExit(AppState::Game)));
+}
+
+fn reset_death_cam(
+    mut death_cam: ResMut<DeathCam>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    death_cam.timer = None;
+    for (mut transform, mut projection) in camera_query.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        projection.scale = 1.0;
+    }
+}
+
+/// Eases time down and the camera in, then moves on to the game over screen
+/// and sends the `GameOverEvent` that brings its panel in.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn update_death_cam(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    motion: Res<MotionPrefs>,
+    score: Res<Score>,
+    mut death_cam: ResMut<DeathCam>,
+    mut time_scale: ResMut<TimeScale>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut game_overs: EventWriter<GameOverEvent>,
+    mut camera_query: Query<(&mut Transform, &mut OrthographicProjection), With<Camera2d>>,
+) {
+    let focus = death_cam.focus;
+    let Some(timer) = death_cam.timer.as_mut() else {
+        return;
+    };
+    if !console.open && keyboard_input.pressed(config.bindings.quick_retry) {
+        info!("quick retry");
+        // the state fade on entering the new run covers the cut
+        next_state.set(AppState::Game);
+        return;
+    }
+    // real time, so the zoom keeps its length however slow the game gets
+    timer.tick(time.delta());
+    let t = timer.percent();
+    let eased = t * t * (3.0 - 2.0 * t);
+
+    time_scale.0 = 1.0 + (SLOW_MO_SCALE - 1.0) * eased;
+    // the slow motion alone is enough when camera motion is off
+    if motion.camera_motion {
+        for (mut transform, mut projection) in camera_query.iter_mut() {
+            let position = Vec2::ZERO.lerp(focus, eased);
+            transform.translation.x = position.x;
+            transform.translation.y = position.y;
+            projection.scale = 1.0 + (1.0 / ZOOM - 1.0) * eased;
+        }
+    }
+    if timer.just_finished() {
+        next_state.set(AppState::GameOver);
+        game_overs.send(GameOverEvent { score: score.0 });
+    }
+}
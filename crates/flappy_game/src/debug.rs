@@ -0,0 +1,83 @@
+//! Collider, sensor and velocity overlay, toggled with
+//! `InputBindings::debug_overlay`.
+
+use bevy::prelude::*;
+use bevy_prototype_debug_lines::DebugLines;
+use flappy_core::collision::Aabb;
+use flappy_core::physics::displacement;
+
+use crate::{Bird, Collider, FlappyConfig, GapSensor, Hitbox, Velocity};
+
+/// Seconds of travel the bird's velocity line covers.
+const VELOCITY_PREVIEW: f32 = 0.1;
+/// Draw lines in front of the sprites.
+const OVERLAY_Z: f32 = 10.0;
+
+#[derive(Resource, Default, Debug)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+pub(crate) fn toggle_debug_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    mut overlay: ResMut<DebugOverlay>,
+) {
+    if keyboard_input.just_pressed(config.bindings.debug_overlay) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+/// Draws the outline of every collider, gap sensor and bird hitbox, plus the
+/// bird's velocity. Does nothing without `DebugLinesPlugin`, as in headless apps.
+pub(crate) fn draw_debug_overlay(
+    overlay: Res<DebugOverlay>,
+    lines: Option<ResMut<DebugLines>>,
+    collider_query: Query<(&GlobalTransform, &Collider)>,
+    sensor_query: Query<(&GlobalTransform, &GapSensor)>,
+    bird_query: Query<(&GlobalTransform, &Hitbox, &Velocity), With<Bird>>,
+) {
+    let Some(mut lines) = lines else {
+        return;
+    };
+    if !overlay.enabled {
+        return;
+    }
+
+    for (transform, collider) in collider_query.iter() {
+        let aabb = Aabb::new(transform.translation().truncate(), collider.size);
+        draw_box(&mut lines, aabb, Color::RED);
+    }
+    for (transform, sensor) in sensor_query.iter() {
+        let aabb = Aabb::new(transform.translation().truncate(), sensor.size);
+        draw_box(&mut lines, aabb, Color::LIME_GREEN);
+    }
+    for (transform, hitbox, velocity) in bird_query.iter() {
+        let position = transform.translation().truncate();
+        draw_box(&mut lines, Aabb::new(position, hitbox.size), Color::YELLOW);
+        let travel = Vec2::new(
+            displacement(velocity.x, VELOCITY_PREVIEW),
+            displacement(velocity.y, VELOCITY_PREVIEW),
+        );
+        lines.line_colored(
+            position.extend(OVERLAY_Z),
+            (position + travel).extend(OVERLAY_Z),
+            0.0,
+            Color::CYAN,
+        );
+    }
+}
+
+fn draw_box(lines: &mut DebugLines, aabb: Aabb, color: Color) {
+    let (min, max) = (aabb.min(), aabb.max());
+    let corners = [
+        Vec2::new(min.x, min.y),
+        Vec2::new(max.x, min.y),
+        Vec2::new(max.x, max.y),
+        Vec2::new(min.x, max.y),
+    ];
+    for (i, start) in corners.iter().enumerate() {
+        let end = corners[(i + 1) % corners.len()];
+        lines.line_colored(start.extend(OVERLAY_Z), end.extend(OVERLAY_Z), 0.0, color);
+    }
+}
@@ -0,0 +1,77 @@
+//! Difficulty presets, each following its own `Curve` from the
+//! `DifficultyCurves`, and the `gaps` command for studying what they generate.
+
+use bevy::prelude::*;
+use flappy_core::analysis::analyse_gaps;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConsoleCommands, DifficultyCurves, FlappyConfig, Settings};
+
+/// Pairs the `gaps` command generates unless told otherwise.
+const ANALYSED_PAIRS: usize = 10_000;
+
+/// Which curve the game follows, part of `Settings`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Short upper case name, as used in challenge codes.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "EASY",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Hard => "HARD",
+        }
+    }
+
+    /// The difficulty named by `tag`, in any case.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|difficulty| difficulty.tag().eq_ignore_ascii_case(tag))
+    }
+}
+
+pub(crate) fn add_difficulty(app: &mut App) {
+    let mut commands = app.world.resource_mut::<ConsoleCommands>();
+    commands.register(
+        "difficulty",
+        "difficulty easy|normal|hard: set the difficulty curve",
+        set_difficulty,
+    );
+    commands.register(
+        "gaps",
+        "gaps [pairs]: study the gaps generated at the current difficulty",
+        report_gaps,
+    );
+}
+
+fn set_difficulty(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let difficulty = args
+        .first()
+        .and_then(|tag| Difficulty::from_tag(tag))
+        .ok_or("expected 'difficulty easy', 'normal' or 'hard'")?;
+    world.resource_mut::<Settings>().difficulty = difficulty;
+    Ok(String::new())
+}
+
+fn report_gaps(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let pairs = match args.first() {
+        Some(pairs) => pairs
+            .parse()
+            .map_err(|_| format!("invalid pair count '{pairs}'"))?,
+        None => ANALYSED_PAIRS,
+    };
+    let difficulty = world.resource::<Settings>().difficulty;
+    let curve = world.resource::<DifficultyCurves>().get(difficulty);
+    let config = world.resource::<FlappyConfig>().sim_config(curve);
+    let seed = rand::random();
+    Ok(analyse_gaps(&config, pairs, seed).to_string())
+}
@@ -0,0 +1,146 @@
+//! Numbers drawn in pixel-art digit sprites rather than a font, like the
+//! original game. One texture per digit is generated at startup from
+//! `GLYPHS`, outlined in black, and `Digits` composes them into a number.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+/// Size of a digit texture, with a pixel of outline all round the glyph.
+pub const DIGIT_SIZE: Vec2 = Vec2::new(GLYPH_WIDTH as f32 + 2.0, GLYPH_HEIGHT as f32 + 2.0);
+/// Distance between neighbouring digits, before scaling. Neighbours share a
+/// column of outline.
+const DIGIT_ADVANCE: f32 = DIGIT_SIZE.x - 1.0;
+
+/// Glyphs for 0 to 9, `#` for filled pixels.
+#[rustfmt::skip]
+const GLYPHS: [[&str; GLYPH_HEIGHT]; 10] = [
+    [".###.", "#...#", "#...#", "#...#", "#...#", "#...#", ".###."],
+    ["..#..", ".##..", "..#..", "..#..", "..#..", "..#..", ".###."],
+    [".###.", "#...#", "....#", "...#.", "..#..", ".#...", "#####"],
+    ["#####", "...#.", "..#..", "...#.", "....#", "#...#", ".###."],
+    ["...#.", "..##.", ".#.#.", "#..#.", "#####", "...#.", "...#."],
+    ["#####", "#....", "####.", "....#", "....#", "#...#", ".###."],
+    ["..##.", ".#...", "#....", "####.", "#...#", "#...#", ".###."],
+    ["#####", "....#", "...#.", "..#..", ".#...", ".#...", ".#..."],
+    [".###.", "#...#", "#...#", ".###.", "#...#", "#...#", ".###."],
+    [".###.", "#...#", "#...#", ".####", "....#", "...#.", ".##.."],
+];
+
+/// A texture for each digit, indexed by the digit.
+#[derive(Resource)]
+pub struct DigitFont {
+    pub digits: Vec<Handle<Image>>,
+}
+
+/// A number drawn in digit sprites: child `ImageBundle`s in a row on a UI
+/// node, or child sprites centred on anything else.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Digits {
+    pub value: u32,
+    /// Screen pixels per glyph pixel.
+    pub scale: f32,
+}
+
+impl Digits {
+    /// The digits of `value`, most significant first.
+    pub fn indices(&self) -> impl Iterator<Item = usize> {
+        self.value
+            .to_string()
+            .into_bytes()
+            .into_iter()
+            .map(|digit| (digit - b'0') as usize)
+    }
+}
+
+pub(crate) fn add_digits(app: &mut App) {
+    app.add_startup_system(create_digit_font)
+        .add_system(layout_digits.in_base_set(CoreSet::PostUpdate));
+}
+
+fn filled(glyph: &[&str; GLYPH_HEIGHT], x: i32, y: i32) -> bool {
+    if x < 0 || y < 0 || x >= GLYPH_WIDTH as i32 || y >= GLYPH_HEIGHT as i32 {
+        return false;
+    }
+    glyph[y as usize].as_bytes()[x as usize] == b'#'
+}
+
+fn digit_image(glyph: &[&str; GLYPH_HEIGHT]) -> Image {
+    let (width, height) = (DIGIT_SIZE.x as i32, DIGIT_SIZE.y as i32);
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            // glyph coordinates, inside the outline
+            let (gx, gy) = (x - 1, y - 1);
+            let outlined = (-1..=1).any(|dy| (-1..=1).any(|dx| filled(glyph, gx + dx, gy + dy)));
+            let pixel = if filled(glyph, gx, gy) {
+                [255, 255, 255, 255]
+            } else if outlined {
+                [0, 0, 0, 255]
+            } else {
+                [0, 0, 0, 0]
+            };
+            data.extend(pixel);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: width as u32,
+            height: height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn create_digit_font(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let digits = GLYPHS
+        .iter()
+        .map(|glyph| images.add(digit_image(glyph)))
+        .collect();
+    commands.insert_resource(DigitFont { digits });
+}
+
+fn layout_digits(
+    mut commands: Commands,
+    font: Res<DigitFont>,
+    query: Query<(Entity, &Digits, Option<&Node>), Changed<Digits>>,
+) {
+    for (entity, digits, node) in query.iter() {
+        let size = DIGIT_SIZE * digits.scale;
+        let count = digits.indices().count() as f32;
+        let mut entity = commands.entity(entity);
+        entity.despawn_descendants();
+        entity.with_children(|parent| {
+            for (i, digit) in digits.indices().enumerate() {
+                let texture = font.digits[digit].clone();
+                if node.is_some() {
+                    let overlap = if i == 0 { 0.0 } else { -digits.scale };
+                    parent.spawn(ImageBundle {
+                        image: texture.into(),
+                        style: Style {
+                            size: Size::new(Val::Px(size.x), Val::Px(size.y)),
+                            margin: UiRect::left(Val::Px(overlap)),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                } else {
+                    let x = (i as f32 - (count - 1.0) / 2.0) * DIGIT_ADVANCE * digits.scale;
+                    parent.spawn(SpriteBundle {
+                        texture,
+                        sprite: Sprite {
+                            custom_size: Some(size),
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(x, 0.0, 0.0),
+                        ..default()
+                    });
+                }
+            }
+        });
+    }
+}
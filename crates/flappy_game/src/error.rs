@@ -0,0 +1,137 @@
+//! Unrecoverable errors. Missing assets and the like send a `FatalError`, which
+//! ends the run and shows the message in the window. Panics can't be shown in
+//! the window, so `install_panic_hook` logs them and leaves a crash report in
+//! the user data directory instead.
+
+use std::backtrace::Backtrace;
+use std::panic;
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::config::user_data_dir;
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, FlappyConfig, Localized};
+
+/// Something went wrong that the game can't continue from.
+#[derive(Clone, Debug)]
+pub struct FatalError {
+    pub message: String,
+}
+
+/// The error shown while in `AppState::Error`.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ErrorScreen {
+    pub message: String,
+}
+
+pub(crate) fn add_error_screen(app: &mut App, check_assets: bool) {
+    app.add_event::<FatalError>()
+        .init_resource::<ErrorScreen>()
+        .add_system(enter_error_screen)
+        .add_system(spawn_error_screen.in_schedule(OnEnter(AppState::Error)));
+    // headless apps have no font loader, so the check would always fail
+    if check_assets {
+        app.add_system(check_asset_loads.before(enter_error_screen));
+    }
+}
+
+/// Sends a `FatalError` if the font failed to load. Textures get placeholders
+/// from `fallback` instead, but there's no stand-in for text.
+fn check_asset_loads(
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut reported: Local<bool>,
+    mut errors: EventWriter<FatalError>,
+) {
+    let path = &config.assets.font;
+    if !*reported && asset_server.get_load_state(path.as_str()) == LoadState::Failed {
+        *reported = true;
+        errors.send(FatalError {
+            message: format!("failed to load assets/{path}"),
+        });
+    }
+}
+
+fn enter_error_screen(
+    mut errors: EventReader<FatalError>,
+    mut screen: ResMut<ErrorScreen>,
+    app_state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Some(error) = errors.iter().next() else {
+        return;
+    };
+    error!(message = %error.message, "fatal error");
+    // later errors are usually fallout from the first
+    if app_state.0 == AppState::Error {
+        return;
+    }
+    screen.message = error.message.clone();
+    next_state.set(AppState::Error);
+    // the title is still readable if the font is what failed to load
+    if let Ok(mut window) = window_query.get_single_mut() {
+        window.title = format!("{} - {}", window.title, error.message);
+    }
+}
+
+fn spawn_error_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    screen: Res<ErrorScreen>,
+) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                z_index: ZIndex::Global(200),
+                ..default()
+            },
+            DespawnOnExit(AppState::Error),
+        ))
+        .with_children(|parent| {
+            let (mut title, text, key) =
+                localized_text(Localized::new("error.title"), 2.0, Color::WHITE);
+            title.style.margin = UiRect::bottom(Val::Px(4.0));
+            parent.spawn((title, text, key));
+            // the message is whatever went wrong, so may not be in the pixel font
+            parent.spawn(TextBundle::from_section(
+                screen.message.clone(),
+                TextStyle {
+                    font: asset_server.load(config.assets.font.as_str()),
+                    font_size: 8.0,
+                    color: Color::WHITE,
+                },
+            ));
+        });
+}
+
+/// Logs panics and writes them to `crash.txt` in the user data directory,
+/// before running the default hook.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        error!("{info}");
+        let report = format!("{info}\n\n{}\n", Backtrace::force_capture());
+        if let Some(dir) = user_data_dir() {
+            let path = dir.join("crash.txt");
+            let written =
+                std::fs::create_dir_all(&dir).and_then(|()| std::fs::write(&path, report));
+            if written.is_ok() {
+                eprintln!("crash report written to {}", path.display());
+            }
+        }
+        default_hook(info);
+    }));
+}
@@ -0,0 +1,71 @@
+//! Notable gameplay moments. Audio, effects, stats and the like should listen
+//! for these rather than reaching into the core systems.
+
+use bevy::prelude::*;
+use flappy_core::pipes::Gap;
+use serde::Serialize;
+
+/// The bird flapped.
+pub struct FlapEvent {
+    pub bird: Entity,
+}
+
+/// Points were earned by passing `pair`.
+pub struct ScoreEvent {
+    pub pair: Entity,
+    pub points: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    Pipe,
+    OutOfBounds,
+    Hazard,
+}
+
+/// A bird hit something. The run ends on the first one of these.
+pub struct DeathEvent {
+    pub bird: Entity,
+    pub position: Vec2,
+    /// Direction the bird was hit from, pointing away from the obstacle.
+    pub normal: Vec2,
+    pub cause: DeathCause,
+}
+
+/// The death animation after a `DeathEvent` has played out, and the game over
+/// screen is opening on the run's results.
+pub struct GameOverEvent {
+    pub score: u32,
+}
+
+/// The game over panel is coming in, sent from the `ResultsTimeline` for the
+/// rest of the screen to follow it.
+pub struct ResultsPanelEvent {
+    pub score: u32,
+}
+
+/// The run just ended beat the previous best, sent as the game over screen
+/// shows it.
+pub struct NewBestEvent {
+    pub score: u32,
+    pub previous: u32,
+}
+
+/// A pipe pair entered play, either freshly spawned or taken from the pool.
+pub struct PipeSpawnedEvent {
+    pub pair: Entity,
+    pub gap: Gap,
+    /// The pair's golden gap, if it has one, see `GoldenGap`.
+    pub golden: Option<Gap>,
+}
+
+pub(crate) fn add_events(app: &mut App) {
+    app.add_event::<FlapEvent>()
+        .add_event::<ScoreEvent>()
+        .add_event::<DeathEvent>()
+        .add_event::<GameOverEvent>()
+        .add_event::<ResultsPanelEvent>()
+        .add_event::<NewBestEvent>()
+        .add_event::<PipeSpawnedEvent>();
+}
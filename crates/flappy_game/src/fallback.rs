@@ -0,0 +1,69 @@
+//! Stand-ins for textures that failed to load. Each one is replaced by a solid
+//! rectangle of the sprite's usual size, so a missing file shows up as a
+//! coloured box and still collides, rather than as an invisible pipe.
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use flappy_core::{BIRD_SIZE, PIPE_SIZE};
+
+use crate::FlappyConfig;
+
+/// Size of the restart button image.
+const RESTART_BUTTON_SIZE: Vec2 = Vec2::new(40.0, 14.0);
+/// Size of the themed cursor image.
+const CURSOR_SIZE: Vec2 = Vec2::new(8.0, 8.0);
+
+pub(crate) fn add_fallback_textures(app: &mut App) {
+    app.add_system(substitute_failed_textures);
+}
+
+/// A `size` rectangle filled with `color`.
+pub fn placeholder_image(size: Vec2, color: Color) -> Image {
+    Image::new_fill(
+        Extent3d {
+            width: size.x as u32,
+            height: size.y as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        &rgba_bytes(color),
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// `color`'s sRGB channels as bytes, as stored in an `Rgba8UnormSrgb` image.
+pub fn rgba_bytes(color: Color) -> [u8; 4] {
+    color
+        .as_rgba_f32()
+        .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
+
+/// Stores a placeholder under the handle of each configured texture that
+/// failed to load, so every sprite already using it picks it up.
+fn substitute_failed_textures(
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut images: ResMut<Assets<Image>>,
+    mut substituted: Local<Vec<String>>,
+) {
+    let assets = &config.assets;
+    let textures = [
+        (&assets.bird, BIRD_SIZE, Color::YELLOW),
+        (&assets.bird_two, BIRD_SIZE, Color::BLUE),
+        (&assets.pipe_top, PIPE_SIZE, Color::FUCHSIA),
+        (&assets.pipe_bottom, PIPE_SIZE, Color::FUCHSIA),
+        (&assets.restart_button, RESTART_BUTTON_SIZE, Color::CYAN),
+        (&assets.cursor, CURSOR_SIZE, Color::WHITE),
+    ];
+    for (path, size, color) in textures {
+        if substituted.contains(path)
+            || asset_server.get_load_state(path.as_str()) != LoadState::Failed
+        {
+            continue;
+        }
+        warn!(%path, "failed to load texture, using a placeholder");
+        images.set_untracked(path.as_str(), placeholder_image(size, color));
+        substituted.push(path.clone());
+    }
+}
@@ -0,0 +1,175 @@
+//! The bird's height through the run, kept every frame in a `FlightLog` and
+//! drawn on the game over screen as a small graph, with the pipes the bird was
+//! passing at the time, so players can see where their rhythm broke down.
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use flappy_core::pipes::Gap;
+use flappy_core::PIPE_SIZE;
+
+use crate::results::ResultsCues;
+use crate::{
+    AppState, Bird, DespawnOnExit, FlappyConfig, GapSensor, PipePair, Player, ResultsPanelEvent,
+    ScreenSpace,
+};
+
+/// Size of the graph texture, in pixels.
+pub const GRAPH_SIZE: UVec2 = UVec2::new(96, 32);
+/// Gap from the top left corner of the playfield.
+const GRAPH_MARGIN: f32 = 4.0;
+const PATH_COLOR: [u8; 4] = [255, 255, 255, 255];
+const PIPE_COLOR: [u8; 4] = [80, 180, 60, 160];
+const BACKGROUND_COLOR: [u8; 4] = [0, 0, 0, 96];
+
+/// One frame of the run.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FlightSample {
+    /// Seconds since the run started.
+    pub time: f32,
+    pub height: f32,
+    /// The gap of the pair the bird was level with, if any.
+    pub gap: Option<Gap>,
+}
+
+/// The current or last run's flight, oldest first.
+#[derive(Resource, Default, Debug)]
+pub struct FlightLog {
+    pub samples: Vec<FlightSample>,
+}
+
+/// The graph of the last run on the game over screen.
+#[derive(Component)]
+pub struct FlightGraph;
+
+pub(crate) fn add_flight_log(app: &mut App) {
+    app.init_resource::<FlightLog>()
+        .add_system(clear_flight_log.in_schedule(OnEnter(AppState::Game)))
+        .add_system(log_flight.in_set(OnUpdate(AppState::Game)))
+        .add_system(
+            spawn_flight_graph
+                .after(ResultsCues)
+                .in_set(OnUpdate(AppState::GameOver)),
+        );
+}
+
+fn clear_flight_log(mut log: ResMut<FlightLog>) {
+    log.samples.clear();
+}
+
+fn log_flight(
+    time: Res<Time>,
+    mut log: ResMut<FlightLog>,
+    bird_query: Query<(&Transform, &Player), With<Bird>>,
+    pair_query: Query<(&Transform, &PipePair)>,
+    sensor_query: Query<&GapSensor>,
+) {
+    // in co-op, the first player's flight
+    let Some((bird, _)) = bird_query
+        .iter()
+        .find(|(_, player)| **player == Player::ONE)
+    else {
+        return;
+    };
+    let gap = pair_query
+        .iter()
+        .filter(|(transform, pair)| {
+            pair.active && (transform.translation.x - bird.translation.x).abs() < PIPE_SIZE.x / 2.0
+        })
+        .find_map(|(transform, pair)| {
+            let sensor = sensor_query.get(pair.sensor).ok()?;
+            Some(Gap {
+                center: transform.translation.y,
+                size: sensor.size.y,
+            })
+        });
+    let elapsed = log
+        .samples
+        .last()
+        .map_or(0.0, |sample| sample.time + time.delta_seconds());
+    log.samples.push(FlightSample {
+        time: elapsed,
+        height: bird.translation.y,
+        gap,
+    });
+}
+
+/// Plots `samples` over `size` pixels, time across and the playfield's
+/// `height` up, as RGBA rows from the top.
+pub fn plot_flight(samples: &[FlightSample], height: f32, size: UVec2) -> Vec<u8> {
+    let (width, rows) = (size.x as usize, size.y as usize);
+    let mut data = BACKGROUND_COLOR.repeat(width * rows);
+    let mut paint = |x: usize, y: usize, color: [u8; 4]| {
+        let i = ((rows - 1 - y) * width + x) * 4;
+        data[i..i + 4].copy_from_slice(&color);
+    };
+    let duration = samples.last().map_or(0.0, |sample| sample.time);
+    let row_of = |y: f32| {
+        let t = (y / height + 0.5).clamp(0.0, 1.0);
+        ((t * rows as f32) as usize).min(rows - 1)
+    };
+    let mut last_row = None;
+    for x in 0..width {
+        // the latest sample up to the end of this column
+        let until = duration * (x + 1) as f32 / width as f32;
+        let Some(sample) = samples
+            .iter()
+            .take_while(|sample| sample.time <= until)
+            .last()
+        else {
+            continue;
+        };
+        if let Some(gap) = sample.gap {
+            let (bottom, top) = (
+                row_of(gap.center - gap.size / 2.0),
+                row_of(gap.center + gap.size / 2.0),
+            );
+            for y in (0..bottom).chain(top + 1..rows) {
+                paint(x, y, PIPE_COLOR);
+            }
+        }
+        let row = row_of(sample.height);
+        let from = last_row.unwrap_or(row);
+        for y in from.min(row)..=from.max(row) {
+            paint(x, y, PATH_COLOR);
+        }
+        last_row = Some(row);
+    }
+    data
+}
+
+fn spawn_flight_graph(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<FlappyConfig>,
+    space: Res<ScreenSpace>,
+    log: Res<FlightLog>,
+    mut panels: EventReader<ResultsPanelEvent>,
+) {
+    if panels.iter().last().is_none() || log.samples.is_empty() {
+        return;
+    }
+    let image = Image::new(
+        Extent3d {
+            width: GRAPH_SIZE.x,
+            height: GRAPH_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        plot_flight(&log.samples, config.height(), GRAPH_SIZE),
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let corner = Vec2::new(-config.screen().x, config.screen().y) / 2.0;
+    let offset = Vec2::new(GRAPH_SIZE.x as f32, -(GRAPH_SIZE.y as f32)) / 2.0
+        + Vec2::new(GRAPH_MARGIN, -GRAPH_MARGIN);
+    commands
+        .spawn((
+            SpriteBundle {
+                texture: images.add(image),
+                transform: Transform::from_translation((corner + offset).extend(1.0)),
+                ..default()
+            },
+            FlightGraph,
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0);
+}
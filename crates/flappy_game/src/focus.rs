@@ -0,0 +1,232 @@
+//! Keyboard and gamepad navigation between `Focusable` buttons. Up and down
+//! (or the d-pad, or the left stick) move the focus, Enter (or A) clicks the
+//! focused button by setting its `Interaction` for a frame, as the mouse
+//! would. The stick only counts once pushed past the `StickNavigation`
+//! deadzones, so a drifting controller leaves the menus alone. The deadzones
+//! are set on the controls screen, and saved with the bindings.
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::ui::{UiPlugin, UiSystem};
+use serde::{Deserialize, Serialize};
+
+use crate::{DevConsole, FlappyConfig};
+
+/// How far past its deadzone, after `StickNavigation::sensitivity`, the stick
+/// has to go for a push.
+const STICK_PUSH: f32 = 0.5;
+
+/// A button that can be reached without the mouse. Lower `order`s come first.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Focusable {
+    pub order: i32,
+}
+
+/// The button the keyboard or gamepad is on, if any.
+#[derive(Resource, Default, Debug)]
+pub struct MenuFocus {
+    pub focused: Option<Entity>,
+    /// Direction the left stick was newly pushed in this frame, for sliders
+    /// to step with as they do with left and right.
+    pub stick: IVec2,
+}
+
+/// How the left stick moves through menus, kept with the `InputBindings`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StickNavigation {
+    /// Travel ignored along each axis, from 0 to 1, so a stick drifting at
+    /// rest doesn't scroll the menus.
+    pub deadzone: Vec2,
+    /// Scales the travel past the deadzone. Higher needs less of a push.
+    pub sensitivity: f32,
+}
+
+impl Default for StickNavigation {
+    fn default() -> Self {
+        Self {
+            deadzone: Vec2::splat(0.25),
+            sensitivity: 1.0,
+        }
+    }
+}
+
+impl StickNavigation {
+    /// The deadzones the controls screen steps through.
+    pub const DEADZONES: [f32; 5] = [0.1, 0.2, 0.25, 0.35, 0.5];
+
+    /// The deadzone after `deadzone` in `DEADZONES`, wrapping around. One
+    /// between steps goes to the next one up.
+    pub fn next_deadzone(deadzone: f32) -> f32 {
+        Self::DEADZONES
+            .into_iter()
+            .find(|step| *step > deadzone + f32::EPSILON)
+            .unwrap_or(Self::DEADZONES[0])
+    }
+
+    /// Which way `stick` pushes along each axis, as -1, 0 or 1.
+    pub fn direction(self, stick: Vec2) -> IVec2 {
+        let push = |value: f32, deadzone: f32| {
+            let travel = (value.abs() - deadzone) / (1.0 - deadzone);
+            if travel.max(0.0) * self.sensitivity >= STICK_PUSH {
+                value.signum() as i32
+            } else {
+                0
+            }
+        };
+        IVec2::new(
+            push(stick.x, self.deadzone.x),
+            push(stick.y, self.deadzone.y),
+        )
+    }
+}
+
+pub(crate) fn add_menu_focus(app: &mut App) {
+    app.init_resource::<MenuFocus>()
+        .add_system(
+            read_stick
+                .after(InputSystem)
+                .in_base_set(CoreSet::PreUpdate),
+        )
+        .add_system(move_focus);
+    // clicks must land after the UI's own focus system, which would otherwise
+    // clear them, and before the buttons' handlers read them in `Update`
+    if app.is_plugin_added::<UiPlugin>() {
+        app.add_system(
+            activate_focus
+                .after(UiSystem::Focus)
+                .in_base_set(CoreSet::PreUpdate),
+        );
+    } else {
+        app.add_system(
+            activate_focus
+                .after(InputSystem)
+                .in_base_set(CoreSet::PreUpdate),
+        );
+    }
+}
+
+fn pressed(
+    keys: &Input<KeyCode>,
+    buttons: &Input<GamepadButton>,
+    gamepads: &Gamepads,
+    key: KeyCode,
+    button: GamepadButtonType,
+) -> bool {
+    keys.just_pressed(key)
+        || gamepads
+            .iter()
+            .any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button)))
+}
+
+/// Turns the left stick of any gamepad into pushes, one per time it leaves
+/// the deadzone, as a key gives one press per time it goes down.
+fn read_stick(
+    config: Res<FlappyConfig>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut held: Local<IVec2>,
+    mut focus: ResMut<MenuFocus>,
+) {
+    let navigation = config.bindings.stick;
+    let direction = gamepads
+        .iter()
+        .map(|gamepad| {
+            let axis = |axis_type| {
+                axes.get(GamepadAxis::new(gamepad, axis_type))
+                    .unwrap_or(0.0)
+            };
+            navigation.direction(Vec2::new(
+                axis(GamepadAxisType::LeftStickX),
+                axis(GamepadAxisType::LeftStickY),
+            ))
+        })
+        .find(|direction| *direction != IVec2::ZERO)
+        .unwrap_or(IVec2::ZERO);
+    let fresh = |now: i32, before: i32| if now != before { now } else { 0 };
+    focus.stick = IVec2::new(fresh(direction.x, held.x), fresh(direction.y, held.y));
+    *held = direction;
+}
+
+fn move_focus(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    console: Res<DevConsole>,
+    mut focus: ResMut<MenuFocus>,
+    query: Query<(Entity, &Focusable)>,
+) {
+    // drop focus from buttons that have gone with their screen
+    if focus.focused.is_some_and(|entity| !query.contains(entity)) {
+        focus.focused = None;
+    }
+    if console.open {
+        return;
+    }
+
+    let up = pressed(
+        &keys,
+        &buttons,
+        &gamepads,
+        KeyCode::Up,
+        GamepadButtonType::DPadUp,
+    ) || focus.stick.y > 0;
+    let down = pressed(
+        &keys,
+        &buttons,
+        &gamepads,
+        KeyCode::Down,
+        GamepadButtonType::DPadDown,
+    ) || focus.stick.y < 0;
+    if !up && !down {
+        return;
+    }
+
+    let mut items: Vec<_> = query.iter().collect();
+    if items.is_empty() {
+        return;
+    }
+    items.sort_by_key(|(entity, focusable)| (focusable.order, *entity));
+    let current = focus
+        .focused
+        .and_then(|focused| items.iter().position(|(entity, _)| *entity == focused));
+    let next = match current {
+        None => 0,
+        Some(index) if up => (index + items.len() - 1) % items.len(),
+        Some(index) => (index + 1) % items.len(),
+    };
+    focus.focused = Some(items[next].0);
+}
+
+fn activate_focus(
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+    gamepads: Res<Gamepads>,
+    console: Res<DevConsole>,
+    focus: Res<MenuFocus>,
+    mut clicked: Local<Option<Entity>>,
+    mut query: Query<&mut Interaction, With<Focusable>>,
+) {
+    // release last frame's click
+    if let Some(mut interaction) = clicked.take().and_then(|entity| query.get_mut(entity).ok()) {
+        if *interaction == Interaction::Clicked {
+            *interaction = Interaction::None;
+        }
+    }
+    if console.open {
+        return;
+    }
+    let Some(mut interaction) = focus.focused.and_then(|entity| query.get_mut(entity).ok()) else {
+        return;
+    };
+    if pressed(
+        &keys,
+        &buttons,
+        &gamepads,
+        KeyCode::Return,
+        GamepadButtonType::South,
+    ) {
+        *interaction = Interaction::Clicked;
+        *clicked = focus.focused;
+    }
+}
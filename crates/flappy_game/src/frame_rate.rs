@@ -0,0 +1,117 @@
+//! Vsync and an optional frame rate cap, from `Settings::frame_rate`. Vsync
+//! is handed to the window as its present mode; the cap is kept by sleeping
+//! off whatever is left of each frame's share of a second once it's done.
+//! Both are lifted for a `FlappyConfig::stress_test`, so its frame times
+//! measure the load rather than the display or the cap.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::window::{PresentMode, PrimaryWindow};
+use serde::{Deserialize, Serialize};
+
+use crate::{FlappyConfig, Settings};
+
+/// How often frames are shown.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameRate {
+    /// Wait for the display's refresh before showing each frame, so frames
+    /// never tear.
+    pub vsync: bool,
+    /// Frames per second to stay at or under. Unlimited when `None`.
+    pub cap: Option<u32>,
+}
+
+impl Default for FrameRate {
+    fn default() -> Self {
+        Self {
+            vsync: true,
+            cap: None,
+        }
+    }
+}
+
+impl FrameRate {
+    /// The caps offered on the settings screen.
+    pub const CAPS: [Option<u32>; 5] = [None, Some(30), Some(60), Some(120), Some(144)];
+
+    /// The cap after this one in `CAPS`, going round to unlimited.
+    pub fn next_cap(self) -> Option<u32> {
+        let index = Self::CAPS.iter().position(|cap| *cap == self.cap);
+        Self::CAPS[index.map_or(0, |index| (index + 1) % Self::CAPS.len())]
+    }
+
+    pub fn present_mode(self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+
+    /// The frame rate to run at: the player's, unless frames are being timed.
+    fn for_config(config: &FlappyConfig, settings: &Settings) -> Self {
+        if config.stress_test.is_some() {
+            FrameRate {
+                vsync: false,
+                cap: None,
+            }
+        } else {
+            settings.frame_rate
+        }
+    }
+}
+
+/// When the last frame ended, to time the next against the cap.
+#[derive(Resource)]
+pub struct FrameLimiter {
+    last: Instant,
+    /// How long the last frame was held back for the cap.
+    pub waited: Duration,
+}
+
+pub(crate) fn add_frame_rate(app: &mut App) {
+    app.insert_resource(FrameLimiter {
+        last: Instant::now(),
+        waited: Duration::ZERO,
+    })
+    .add_system(apply_present_mode)
+    .add_system(limit_frame_rate.in_base_set(CoreSet::Last));
+}
+
+fn apply_present_mode(
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !config.is_changed() && !settings.is_changed() {
+        return;
+    }
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let present_mode = FrameRate::for_config(&config, &settings).present_mode();
+    if window.present_mode != present_mode {
+        info!(?present_mode, "switching present mode");
+        window.present_mode = present_mode;
+    }
+}
+
+/// Sleeps out the rest of the frame when it finished early for the cap.
+fn limit_frame_rate(
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    mut limiter: ResMut<FrameLimiter>,
+) {
+    limiter.waited = Duration::ZERO;
+    let cap = FrameRate::for_config(&config, &settings).cap;
+    if let Some(cap) = cap.filter(|cap| *cap > 0) {
+        let next = limiter.last + Duration::from_secs_f64(1.0 / cap as f64);
+        if let Some(left) = next.checked_duration_since(Instant::now()) {
+            std::thread::sleep(left);
+            limiter.waited = left;
+        }
+    }
+    limiter.last = Instant::now();
+}
@@ -0,0 +1,130 @@
+//! The ground along the bottom of the playfield, rising and falling in gentle
+//! hills as the level scrolls by, per `FlappyConfig::ground`. The strip is
+//! drawn in narrow columns, and the floor `Boundary` follows the hill under
+//! the bird so the run ends where the ground is drawn.
+
+use bevy::prelude::*;
+use flappy_core::collision::{boundaries, BOUNDARY_THICKNESS};
+use flappy_core::physics::displacement;
+
+use crate::boundary::block_bird;
+use crate::scroll::update_scroll_speed;
+use crate::{
+    AppState, Bird, Boundary, DespawnOnExit, FlappyConfig, GameSystem, GameTick, PhysicsClock,
+    ScrollSpeed,
+};
+
+/// Width of each column of the ground strip.
+const COLUMN_WIDTH: f32 = 8.0;
+const GROUND_COLOR: Color = Color::rgb(0.45, 0.65, 0.3);
+
+/// How far the level has scrolled past the bird this run.
+#[derive(Resource, Default, Debug)]
+pub struct GroundScroll {
+    pub distance: f32,
+}
+
+/// One column of the drawn ground, wrapped back to the right as it leaves.
+#[derive(Component)]
+pub struct GroundColumn {
+    /// Distance from the left of the strip at the start of the run.
+    offset: f32,
+}
+
+pub(crate) fn add_ground(app: &mut App) {
+    app.init_resource::<GroundScroll>()
+        .add_system(spawn_ground.in_schedule(OnEnter(AppState::Game)))
+        .add_systems(
+            (
+                scroll_ground.after(update_scroll_speed),
+                raise_floor.after(scroll_ground).before(block_bird),
+                shape_ground.after(scroll_ground),
+            )
+                .in_set(GameSystem::Movement)
+                .in_schedule(GameTick),
+        );
+}
+
+fn spawn_ground(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    mut scroll: ResMut<GroundScroll>,
+) {
+    scroll.distance = 0.0;
+    let (floor, _) = boundaries(config.playfield);
+    let columns = (config.width() / COLUMN_WIDTH).ceil() as usize + 2;
+    for column in 0..columns {
+        let offset = (column as f32 + 0.5) * COLUMN_WIDTH;
+        let x = strip_left(&config) + offset;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: GROUND_COLOR,
+                    custom_size: Some(Vec2::new(COLUMN_WIDTH, BOUNDARY_THICKNESS)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, floor.center.y, 0.8),
+                ..default()
+            },
+            GroundColumn { offset },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+}
+
+fn scroll_ground(
+    clock: Res<PhysicsClock>,
+    speed: Res<ScrollSpeed>,
+    mut scroll: ResMut<GroundScroll>,
+) {
+    if clock.step > 0.0 {
+        scroll.distance += displacement(speed.0, clock.step);
+    }
+}
+
+/// Moves the floor to the ground under the bird.
+fn raise_floor(
+    config: Res<FlappyConfig>,
+    scroll: Res<GroundScroll>,
+    mut query: Query<(&mut Transform, &Boundary)>,
+) {
+    if !scroll.is_changed() {
+        return;
+    }
+    let floor = config.ground().floor(config.playfield, scroll.distance);
+    for (mut transform, boundary) in query.iter_mut() {
+        if *boundary == Boundary::Floor {
+            transform.translation.y = floor.center.y;
+        }
+    }
+}
+
+/// Scrolls the columns and sets each to the height of the ground under it.
+fn shape_ground(
+    config: Res<FlappyConfig>,
+    scroll: Res<GroundScroll>,
+    bird_query: Query<&Transform, (With<Bird>, Without<GroundColumn>)>,
+    mut query: Query<(&mut Transform, &GroundColumn)>,
+) {
+    if !scroll.is_changed() {
+        return;
+    }
+    let bird_x = bird_query
+        .iter()
+        .next()
+        .map_or(-config.width() / 4.0, |transform| transform.translation.x);
+    let span = query.iter().len() as f32 * COLUMN_WIDTH;
+    for (mut transform, column) in query.iter_mut() {
+        let x = strip_left(&config) + (column.offset - scroll.distance).rem_euclid(span);
+        let floor = config
+            .ground()
+            .floor(config.playfield, scroll.distance + x - bird_x);
+        transform.translation.x = x;
+        transform.translation.y = floor.center.y;
+    }
+}
+
+/// Where the strip starts, a column past the left edge so none pop in.
+fn strip_left(config: &FlappyConfig) -> f32 {
+    -config.width() / 2.0 - COLUMN_WIDTH
+}
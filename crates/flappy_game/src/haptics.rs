@@ -0,0 +1,104 @@
+//! Haptic feedback: a short buzz when the bird dies and a tick for each point,
+//! unless `Settings::haptics` is off.
+//!
+//! Pulses are sent as `HapticEvent`s everywhere, but only felt on Android,
+//! through the activity's `Vibrator`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ConsoleCommands, DeathEvent, ScoreEvent, Settings};
+
+/// Whether to play haptic feedback, part of `Settings`. Gamepad rumble should
+/// follow this too.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Haptics {
+    #[default]
+    On,
+    Off,
+}
+
+/// A vibration to play.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HapticEvent {
+    /// The bird died.
+    Buzz,
+    /// A point was scored.
+    Tick,
+}
+
+impl HapticEvent {
+    pub fn duration(self) -> Duration {
+        match self {
+            HapticEvent::Buzz => Duration::from_millis(200),
+            HapticEvent::Tick => Duration::from_millis(15),
+        }
+    }
+}
+
+pub(crate) fn add_haptics(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "haptics",
+        "haptics on|off: toggle vibration",
+        toggle_haptics,
+    );
+    app.add_event::<HapticEvent>().add_system(send_haptics);
+    #[cfg(target_os = "android")]
+    app.add_system(vibrate.after(send_haptics));
+}
+
+fn toggle_haptics(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let haptics = match args.first() {
+        Some(&"on") => Haptics::On,
+        Some(&"off") => Haptics::Off,
+        _ => return Err("expected 'haptics on' or 'haptics off'".to_string()),
+    };
+    world.resource_mut::<Settings>().haptics = haptics;
+    Ok(String::new())
+}
+
+fn send_haptics(
+    settings: Res<Settings>,
+    mut deaths: EventReader<DeathEvent>,
+    mut scores: EventReader<ScoreEvent>,
+    mut haptics: EventWriter<HapticEvent>,
+) {
+    let died = deaths.iter().count() > 0;
+    let scored = scores.iter().count() > 0;
+    if settings.haptics == Haptics::Off {
+        return;
+    }
+    // the buzz covers a point scored on the same frame
+    if died {
+        haptics.send(HapticEvent::Buzz);
+    } else if scored {
+        haptics.send(HapticEvent::Tick);
+    }
+}
+
+#[cfg(target_os = "android")]
+fn vibrate(mut haptics: EventReader<HapticEvent>) {
+    use jni::objects::JValue;
+
+    for haptic in haptics.iter() {
+        let millis = haptic.duration().as_millis() as i64;
+        let vibrated = crate::android::with_activity(|env, activity| {
+            let service = env.new_string("vibrator")?;
+            let vibrator = env
+                .call_method(
+                    activity,
+                    "getSystemService",
+                    "(Ljava/lang/String;)Ljava/lang/Object;",
+                    &[JValue::Object(&service)],
+                )?
+                .l()?;
+            env.call_method(&vibrator, "vibrate", "(J)V", &[JValue::Long(millis)])?;
+            Ok(())
+        });
+        if let Err(error) = vibrated {
+            warn!(%error, "couldn't vibrate");
+        }
+    }
+}
@@ -0,0 +1,254 @@
+//! Hazards that now and then join the pipes: icicles dropping from the ceiling
+//! and balloons drifting in from the right. The `HazardScheduler` picks when,
+//! and each is telegraphed by a flashing warning `WARNING_SECONDS` before it
+//! appears. Touching one ends the run with `DeathCause::Hazard`, and getting
+//! past one narrowly counts as a near miss, see `RunStats`.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use flappy_core::collision::Aabb;
+use rand::Rng;
+
+use crate::bitmap_text::bitmap_text_2d;
+use crate::{
+    AppState, Bird, Clearance, Collider, ConsoleCommands, DespawnOffscreen, DespawnOnExit,
+    FlappyConfig, GameSystem, GameTick, HitStop, Hitbox, MotionPrefs, Pace, PhysicsClock, RunSeed,
+    RunStats, Scrolls, Velocity,
+};
+
+/// Seconds from a warning to its hazard.
+pub const WARNING_SECONDS: f32 = 1.0;
+/// Seconds into a run before the first hazard is due.
+const FIRST_HAZARD: f32 = 10.0;
+/// Range of seconds between one hazard and the next.
+const HAZARD_SPACING: (f32, f32) = (8.0, 16.0);
+/// Seconds each flash of a warning is shown or hidden for.
+const WARNING_FLASH: f32 = 0.125;
+/// Distance from the edge of the playfield to a warning.
+const WARNING_INSET: f32 = 8.0;
+/// Range of icicle falling speeds, in the units of `Velocity`.
+const ICICLE_FALL: (f32, f32) = (1.0, 2.0);
+/// Balloon speed as a fraction of the pipes', and rising speed.
+const BALLOON_DRIFT: Vec2 = Vec2::new(0.75, 0.2);
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hazard {
+    /// Drops from the ceiling as it scrolls along with the pipes.
+    Icicle,
+    /// Floats in from the right, a little slower than the pipes and rising.
+    Balloon,
+}
+
+impl Hazard {
+    pub const ALL: [Hazard; 2] = [Hazard::Icicle, Hazard::Balloon];
+
+    pub fn size(self) -> Vec2 {
+        match self {
+            Hazard::Icicle => Vec2::new(6.0, 16.0),
+            Hazard::Balloon => Vec2::new(12.0, 14.0),
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            Hazard::Icicle => Color::rgb(0.75, 0.9, 1.0),
+            Hazard::Balloon => Color::rgb(0.95, 0.3, 0.35),
+        }
+    }
+}
+
+/// When the next hazard is due.
+#[derive(Resource, Debug)]
+pub struct HazardScheduler {
+    /// Seconds until the next warning.
+    pub next: f32,
+    /// Hazard to send next instead of a random one.
+    pub queued: Option<Hazard>,
+}
+
+impl Default for HazardScheduler {
+    fn default() -> Self {
+        Self {
+            next: FIRST_HAZARD,
+            queued: None,
+        }
+    }
+}
+
+/// Flashes where `hazard` is about to appear.
+#[derive(Component, Debug)]
+pub struct HazardWarning {
+    pub hazard: Hazard,
+    pub timer: Timer,
+}
+
+pub(crate) fn add_hazards(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "hazard",
+        "hazard icicle|balloon: send a hazard now",
+        send_hazard,
+    );
+    app.init_resource::<HazardScheduler>()
+        .add_system(reset_hazards.in_schedule(OnEnter(AppState::Game)))
+        // in order, as both draw from the hazard stream
+        .add_systems(
+            (schedule_hazards, release_hazards)
+                .chain()
+                .in_set(GameSystem::Spawning)
+                .in_schedule(GameTick),
+        )
+        .add_system(
+            watch_hazard_clearance
+                .in_set(GameSystem::Scoring)
+                .in_schedule(GameTick),
+        );
+}
+
+fn send_hazard(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let hazard = match args.first() {
+        Some(&"icicle") => Hazard::Icicle,
+        Some(&"balloon") => Hazard::Balloon,
+        _ => return Err("expected 'hazard icicle' or 'hazard balloon'".to_string()),
+    };
+    let mut scheduler = world.resource_mut::<HazardScheduler>();
+    scheduler.queued = Some(hazard);
+    scheduler.next = 0.0;
+    Ok(String::new())
+}
+
+fn reset_hazards(mut scheduler: ResMut<HazardScheduler>) {
+    *scheduler = HazardScheduler::default();
+}
+
+/// Puts up a warning whenever a hazard is due.
+fn schedule_hazards(
+    mut commands: Commands,
+    clock: Res<PhysicsClock>,
+    config: Res<FlappyConfig>,
+    pace: Res<Pace>,
+    mut seed: ResMut<RunSeed>,
+    mut scheduler: ResMut<HazardScheduler>,
+) {
+    scheduler.next -= clock.step;
+    if scheduler.next > 0.0 {
+        return;
+    }
+    let rng = seed.hazards_rng();
+    scheduler.next = rng.gen_range(HAZARD_SPACING.0..HAZARD_SPACING.1);
+    let hazard = scheduler
+        .queued
+        .take()
+        .unwrap_or_else(|| Hazard::ALL[rng.gen_range(0..Hazard::ALL.len())]);
+    let edge = config.playfield / 2.0 - WARNING_INSET;
+    let (position, scrolls) = match hazard {
+        // scrolls with the icicle it warns of
+        Hazard::Icicle => (edge, Scrolls(1.0)),
+        Hazard::Balloon => {
+            let height = rng.gen_range(pace.gaps.min_center..pace.gaps.max_center);
+            (Vec2::new(edge.x, height), Scrolls(0.0))
+        }
+    };
+    info!(?hazard, "hazard warning");
+    let (mut warning, text) = bitmap_text_2d("!", 2.0, hazard.color());
+    warning.transform = Transform::from_translation(position.extend(1.0));
+    commands.spawn((
+        warning,
+        text,
+        HazardWarning {
+            hazard,
+            timer: Timer::from_seconds(WARNING_SECONDS, TimerMode::Once),
+        },
+        Velocity::default(),
+        scrolls,
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+/// Flashes warnings, and swaps each for its hazard once its time is up.
+fn release_hazards(
+    mut commands: Commands,
+    clock: Res<PhysicsClock>,
+    config: Res<FlappyConfig>,
+    motion: Res<MotionPrefs>,
+    mut seed: ResMut<RunSeed>,
+    mut query: Query<(Entity, &Transform, &mut HazardWarning, &mut Visibility)>,
+) {
+    for (entity, transform, mut warning, mut visibility) in query.iter_mut() {
+        let step = Duration::from_secs_f32(clock.step);
+        let elapsed = warning.timer.tick(step).elapsed_secs();
+        // steady for photosensitive players
+        let shown = !motion.flashes || ((elapsed / WARNING_FLASH) as u32).is_multiple_of(2);
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if !warning.timer.finished() {
+            continue;
+        }
+        commands.entity(entity).despawn_recursive();
+
+        let hazard = warning.hazard;
+        let mut position = transform.translation.truncate();
+        let (velocity, scrolls) = match hazard {
+            Hazard::Icicle => {
+                let fall = seed.hazards_rng().gen_range(ICICLE_FALL.0..ICICLE_FALL.1);
+                (Vec2::new(0.0, -fall), Scrolls(1.0))
+            }
+            Hazard::Balloon => {
+                position.x = config.width() / 2.0 + hazard.size().x;
+                (Vec2::new(0.0, BALLOON_DRIFT.y), Scrolls(BALLOON_DRIFT.x))
+            }
+        };
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: hazard.color(),
+                    custom_size: Some(hazard.size()),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.5)),
+                ..default()
+            },
+            hazard,
+            Collider {
+                size: hazard.size(),
+            },
+            Clearance::default(),
+            Velocity(velocity),
+            scrolls,
+            DespawnOffscreen {
+                margin: hazard.size().max_element() * 2.0,
+            },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+}
+
+/// Notes how close the birds come to each hazard until it's behind them all.
+fn watch_hazard_clearance(
+    hit_stop: Res<HitStop>,
+    mut stats: ResMut<RunStats>,
+    bird_query: Query<(&Transform, &Hitbox), With<Bird>>,
+    mut hazard_query: Query<(&Transform, &Collider, &mut Clearance), With<Hazard>>,
+) {
+    if hit_stop.timer.is_some() {
+        return;
+    }
+    let birds: Vec<_> = bird_query
+        .iter()
+        .map(|(transform, hitbox)| Aabb::new(transform.translation.truncate(), hitbox.size))
+        .collect();
+    for (transform, collider, mut clearance) in hazard_query.iter_mut() {
+        let hazard = Aabb::new(transform.translation.truncate(), collider.size);
+        let passed = birds.iter().all(|bird| hazard.max().x < bird.min().x);
+        if !passed {
+            for bird in &birds {
+                clearance.note(hazard.distance(bird));
+            }
+        } else if let Some(closest) = clearance.closest.take() {
+            stats.record_clearance(closest);
+        }
+    }
+}
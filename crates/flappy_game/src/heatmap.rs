@@ -0,0 +1,146 @@
+//! Where runs end. Every death is kept in `DeathStats`, saved to
+//! `FlappyConfig::stats_path`, and `InputBindings::heatmap` toggles an overlay
+//! shading the playfield by how many deaths fell in each cell, for tuning
+//! difficulty.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{add_persistent_resource, Versioned};
+use crate::{Cheats, DeathEvent, FlappyConfig, Score};
+
+/// Oldest deaths are dropped past this many, to keep the file small.
+const MAX_DEATHS: usize = 2000;
+/// Side of a heatmap cell, in world units.
+const CELL_SIZE: f32 = 8.0;
+/// Between the pipes and the debug overlay's lines.
+const HEATMAP_Z: f32 = 9.0;
+
+/// Where and on what score a run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeathRecord {
+    pub x: f32,
+    pub y: f32,
+    pub score: u32,
+}
+
+/// Every death kept, oldest first.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct DeathStats {
+    pub deaths: Vec<DeathRecord>,
+}
+
+impl DeathStats {
+    /// Deaths per `cell_size` square, keyed by the cell's index from the
+    /// origin.
+    pub fn heatmap(&self, cell_size: f32) -> HashMap<IVec2, u32> {
+        let mut cells = HashMap::new();
+        for death in &self.deaths {
+            let cell = (Vec2::new(death.x, death.y) / cell_size).floor().as_ivec2();
+            *cells.entry(cell).or_default() += 1;
+        }
+        cells
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct DeathHeatmap {
+    pub enabled: bool,
+}
+
+/// One shaded cell of the heatmap overlay.
+#[derive(Component, Debug)]
+pub struct HeatmapCell {
+    pub deaths: u32,
+}
+
+impl Versioned for DeathStats {
+    const VERSION: u32 = 2;
+    const CHECKSUM_SINCE: Option<u32> = Some(2);
+}
+
+pub(crate) fn add_death_stats(app: &mut App, config: &FlappyConfig) {
+    add_persistent_resource::<DeathStats>(app, config.stats_path.clone(), |config| {
+        config.stats_path.as_ref()
+    });
+    app.init_resource::<DeathHeatmap>()
+        .add_system(record_deaths)
+        .add_system(toggle_heatmap)
+        .add_system(draw_heatmap.after(record_deaths).after(toggle_heatmap));
+}
+
+fn record_deaths(
+    mut deaths: EventReader<DeathEvent>,
+    cheats: Res<Cheats>,
+    score: Res<Score>,
+    mut stats: ResMut<DeathStats>,
+) {
+    // a bird can hit several things on the frame it dies
+    let Some(position) = deaths.iter().next().map(|death| death.position) else {
+        return;
+    };
+    deaths.clear();
+    // an invincible bird "dies" on every tick it touches something
+    if cheats.invincible {
+        return;
+    }
+    stats.deaths.push(DeathRecord {
+        x: position.x,
+        y: position.y,
+        score: score.0,
+    });
+    if stats.deaths.len() > MAX_DEATHS {
+        let excess = stats.deaths.len() - MAX_DEATHS;
+        stats.deaths.drain(..excess);
+    }
+}
+
+fn toggle_heatmap(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    mut heatmap: ResMut<DeathHeatmap>,
+) {
+    if keyboard_input.just_pressed(config.bindings.heatmap) {
+        heatmap.enabled = !heatmap.enabled;
+    }
+}
+
+/// Rebuilds the overlay whenever it's toggled or a death is added. Cells go
+/// from a faint red to solid yellow at the busiest.
+fn draw_heatmap(
+    mut commands: Commands,
+    heatmap: Res<DeathHeatmap>,
+    stats: Res<DeathStats>,
+    query: Query<Entity, With<HeatmapCell>>,
+) {
+    if !heatmap.is_changed() && !stats.is_changed() {
+        return;
+    }
+    for cell in query.iter() {
+        commands.entity(cell).despawn();
+    }
+    if !heatmap.enabled {
+        return;
+    }
+    let cells = stats.heatmap(CELL_SIZE);
+    let busiest = cells.values().copied().max().unwrap_or(1) as f32;
+    for (cell, deaths) in cells {
+        let heat = deaths as f32 / busiest;
+        let centre = (cell.as_vec2() + 0.5) * CELL_SIZE;
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(1.0, heat, 0.0, 0.2 + 0.6 * heat),
+                    custom_size: Some(Vec2::splat(CELL_SIZE)),
+                    ..default()
+                },
+                transform: Transform::from_translation(centre.extend(HEATMAP_Z)),
+                ..default()
+            },
+            HeatmapCell { deaths },
+        ));
+    }
+}
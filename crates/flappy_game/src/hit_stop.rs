@@ -0,0 +1,132 @@
+//! Brief freeze and white flash on death, lasting `FlappyConfig::hit_stop`
+//! seconds, before the `DeathCam` takes over.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    AppState, Cheats, DeathCam, DeathEvent, DespawnOnExit, FlappyConfig, MotionPrefs, Score,
+};
+
+/// Multiplier for the time simulation systems advance by each frame. The
+/// `PhysicsClock` banks scaled time, so the `GameTick` slows with it; anything
+/// else that moves or counts down during a run should scale its delta by
+/// this.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl TimeScale {
+    pub fn delta(&self, time: &Time) -> Duration {
+        time.delta().mul_f32(self.0)
+    }
+
+    pub fn delta_seconds(&self, time: &Time) -> f32 {
+        time.delta_seconds() * self.0
+    }
+}
+
+/// Counts down in real time while the game is frozen after a death.
+#[derive(Resource, Default, Debug)]
+pub struct HitStop {
+    /// Set on the first death of the run and kept until it ends.
+    pub timer: Option<Timer>,
+    /// Where the bird died.
+    pub position: Vec2,
+}
+
+impl HitStop {
+    /// Whether the game is held still right now, between the first death and
+    /// the `DeathCam`.
+    pub fn frozen(&self) -> bool {
+        self.timer.as_ref().is_some_and(|timer| !timer.finished())
+    }
+}
+
+/// White overlay faded out over the hit stop.
+#[derive(Component)]
+pub struct HitFlash;
+
+pub(crate) fn add_hit_stop(app: &mut App) {
+    app.init_resource::<TimeScale>()
+        .init_resource::<HitStop>()
+        .add_system(reset_hit_stop.in_schedule(OnEnter(AppState::Game)))
+        .add_system(reset_hit_stop.in_schedule(OnExit(AppState::Game)));
+}
+
+fn reset_hit_stop(mut time_scale: ResMut<TimeScale>, mut hit_stop: ResMut<HitStop>) {
+    *time_scale = TimeScale::default();
+    hit_stop.timer = None;
+}
+
+/// Freezes the game on the first death of the run and flashes the screen.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_hit_stop(
+    mut commands: Commands,
+    cheats: Res<Cheats>,
+    config: Res<FlappyConfig>,
+    score: Res<Score>,
+    mut time_scale: ResMut<TimeScale>,
+    motion: Res<MotionPrefs>,
+    mut hit_stop: ResMut<HitStop>,
+    mut deaths: EventReader<DeathEvent>,
+) {
+    let Some(death) = deaths.iter().next() else {
+        return;
+    };
+    if cheats.invincible || hit_stop.timer.is_some() {
+        return;
+    }
+    info!(cause = ?death.cause, score = score.0, "run ended");
+    time_scale.0 = 0.0;
+    hit_stop.timer = Some(Timer::from_seconds(config.hit_stop, TimerMode::Once));
+    hit_stop.position = death.position;
+
+    if !motion.flashes {
+        return;
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            background_color: Color::WHITE.with_a(config.flash_intensity).into(),
+            z_index: ZIndex::Global(50),
+            ..default()
+        },
+        HitFlash,
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+/// Fades the flash out and starts the `DeathCam` once the freeze is up.
+pub(crate) fn finish_hit_stop(
+    time: Res<Time>,
+    config: Res<FlappyConfig>,
+    mut hit_stop: ResMut<HitStop>,
+    mut death_cam: ResMut<DeathCam>,
+    mut time_scale: ResMut<TimeScale>,
+    mut flash_query: Query<&mut BackgroundColor, With<HitFlash>>,
+) {
+    let position = hit_stop.position;
+    let Some(timer) = hit_stop.timer.as_mut() else {
+        return;
+    };
+    // deliberately unscaled, as the scale is zero
+    timer.tick(time.delta());
+    for mut color in flash_query.iter_mut() {
+        color.0.set_a(config.flash_intensity * timer.percent_left());
+    }
+    if timer.just_finished() {
+        time_scale.0 = 1.0;
+        death_cam.start(config.death_slow_mo, position);
+    }
+}
@@ -0,0 +1,190 @@
+//! Score shown during a run in sprite `Digits`, which pops on each point,
+//! plus a "+1" that floats up from the gap that was passed. A "BEST" marker
+//! appears under the score once it passes the player's previous best.
+
+use bevy::prelude::*;
+use flappy_core::physics::displacement;
+
+use crate::locale::localized_text;
+use crate::{
+    view_rotation, AppState, DespawnOnExit, Digits, Ease, FlappyConfig, Localized, RunRecord,
+    SafeArea, Score, ScoreEvent, Theme, TimeScale, Tween, TweenTarget,
+};
+
+/// Scale the score text jumps to before settling back.
+const POP_SCALE: f32 = 1.5;
+const POP_SECONDS: f32 = 0.25;
+/// Upward speed of the "+1", in the same units as `Velocity`.
+const POPUP_RISE: f32 = 0.3;
+const POPUP_SECONDS: f32 = 0.6;
+/// In front of the pipes.
+const POPUP_Z: f32 = 5.0;
+
+#[derive(Component)]
+pub(crate) struct ScoreText;
+
+/// Shown under the score once the run is a new best.
+#[derive(Component)]
+pub struct BestMarker;
+
+#[derive(Component)]
+pub struct ScorePopup {
+    age: Timer,
+}
+
+pub(crate) fn add_hud(app: &mut App) {
+    app.add_system(spawn_score_text.in_schedule(OnEnter(AppState::Game)))
+        .add_systems(
+            (
+                update_score_text,
+                show_best_marker,
+                pop_score,
+                animate_score_popups,
+            )
+                .in_set(OnUpdate(AppState::Game)),
+        );
+}
+
+fn spawn_score_text(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(8.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Auto),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DespawnOnExit(AppState::Game),
+            SafeArea::default(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle::default(),
+                Digits {
+                    value: 0,
+                    scale: theme.score_scale,
+                },
+                ScoreText,
+            ));
+            let (mut best, text, key) =
+                localized_text(Localized::new("hud.best"), 1.0, Color::rgb(1.0, 0.8, 0.2));
+            best.visibility = Visibility::Hidden;
+            parent.spawn((best, text, key, BestMarker));
+        });
+}
+
+fn update_score_text(score: Res<Score>, mut query: Query<&mut Digits, With<ScoreText>>) {
+    if !score.is_changed() {
+        return;
+    }
+    for mut digits in query.iter_mut() {
+        digits.value = score.0;
+    }
+}
+
+fn show_best_marker(
+    mut commands: Commands,
+    score: Res<Score>,
+    record: Res<RunRecord>,
+    mut query: Query<(Entity, &mut Visibility), With<BestMarker>>,
+) {
+    if !score.is_changed() {
+        return;
+    }
+    let beaten = record.beaten_by(score.0);
+    for (entity, mut visibility) in query.iter_mut() {
+        if beaten && *visibility == Visibility::Hidden {
+            commands.entity(entity).insert(Tween::new(
+                TweenTarget::Scale {
+                    from: Vec3::splat(POP_SCALE),
+                    to: Vec3::ONE,
+                },
+                POP_SECONDS,
+                Ease::QuadOut,
+            ));
+        }
+        *visibility = if beaten {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+/// Bumps the score text and floats the points up from the pair just passed.
+fn pop_score(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut scores: EventReader<ScoreEvent>,
+    text_query: Query<Entity, With<ScoreText>>,
+    pair_query: Query<&GlobalTransform>,
+) {
+    for event in scores.iter() {
+        for entity in text_query.iter() {
+            commands.entity(entity).insert(Tween::new(
+                TweenTarget::Scale {
+                    from: Vec3::splat(POP_SCALE),
+                    to: Vec3::ONE,
+                },
+                POP_SECONDS,
+                Ease::QuadOut,
+            ));
+        }
+
+        let Ok(pair) = pair_query.get(event.pair) else {
+            continue;
+        };
+        let mut translation = pair.translation();
+        translation.z = POPUP_Z;
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    format!("+{}", event.points),
+                    TextStyle {
+                        font: asset_server.load(config.assets.font.as_str()),
+                        font_size: 8.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_alignment(TextAlignment::Center),
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+            ScorePopup {
+                age: Timer::from_seconds(POPUP_SECONDS, TimerMode::Once),
+            },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+}
+
+fn animate_score_popups(
+    time: Res<Time>,
+    config: Res<FlappyConfig>,
+    time_scale: Res<TimeScale>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut ScorePopup, &mut Transform, &mut Text)>,
+) {
+    // up the screen, whichever way the camera is turned
+    let up = view_rotation(&config) * Vec3::Y;
+    for (entity, mut popup, mut transform, mut text) in query.iter_mut() {
+        if popup.age.tick(time_scale.delta(&time)).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += up * displacement(POPUP_RISE, time_scale.delta_seconds(&time));
+        let alpha = 1.0 - popup.age.percent();
+        for section in text.sections.iter_mut() {
+            section.style.color.set_a(alpha);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Returns to the main menu from a game over screen nobody is touching, so a
+//! kiosk or demo machine doesn't sit on one player's result forever.
+
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+
+use crate::{AppState, FlappyConfig};
+
+/// Real seconds since the screen opened or last saw any input.
+#[derive(Resource, Default)]
+struct IdleTime(f32);
+
+pub(crate) fn add_idle_timeout(app: &mut App) {
+    app.init_resource::<IdleTime>()
+        .add_system(reset_idle_time.in_schedule(OnEnter(AppState::GameOver)))
+        .add_system(time_out_idle_screen.in_set(OnUpdate(AppState::GameOver)));
+}
+
+fn reset_idle_time(mut idle: ResMut<IdleTime>) {
+    idle.0 = 0.0;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn time_out_idle_screen(
+    time: Res<Time>,
+    config: Res<FlappyConfig>,
+    keys: Res<Input<KeyCode>>,
+    mouse_buttons: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    touches: Res<Touches>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut idle: ResMut<IdleTime>,
+) {
+    let Some(timeout) = config.idle_timeout else {
+        return;
+    };
+    let touched = keys.get_pressed().next().is_some()
+        || mouse_buttons.get_pressed().next().is_some()
+        || gamepad_buttons.get_pressed().next().is_some()
+        || touches.iter().next().is_some()
+        || mouse_motion.iter().count() > 0;
+    if touched {
+        idle.0 = 0.0;
+        return;
+    }
+    idle.0 += time.delta_seconds();
+    if idle.0 >= timeout {
+        info!(seconds = timeout, "idle on the game over screen");
+        next_state.set(AppState::MainMenu);
+    }
+}
@@ -0,0 +1,16 @@
+//! World inspector for poking at entities while debugging, enabled with the
+//! `inspector` feature.
+
+use bevy::prelude::*;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+use crate::{Gravity, Hitbox, PipeTimer, Score, Velocity};
+
+pub(crate) fn add_inspector(app: &mut App) {
+    app.register_type::<Velocity>()
+        .register_type::<Gravity>()
+        .register_type::<Hitbox>()
+        .register_type::<PipeTimer>()
+        .register_type::<Score>()
+        .add_plugin(WorldInspectorPlugin::new());
+}
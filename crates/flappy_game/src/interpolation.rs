@@ -0,0 +1,151 @@
+//! Fixed-rate steps for the `GameTick`, see `FlappyConfig::physics_rate`.
+//! Time is banked in the `PhysicsClock` and spent a whole step at a time, so
+//! the simulation no longer depends on the frame rate. What's left over would
+//! make bodies stutter on displays whose refresh doesn't divide evenly into
+//! the physics rate, so each body is drawn that far between its last two
+//! steps.
+//!
+//! The `GameTick` still sees bodies where the simulation has them: their
+//! `Transform`s are put back before the update and only swapped for the
+//! drawn position after it. The `GlobalTransform`s the tick collides and
+//! scores with are propagated inside it from those simulated `Transform`s,
+//! so the drawn positions never reach the gameplay. A body moved by anything
+//! but the physics, like a recycled pipe, is drawn where it was put.
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use crate::{AppState, FlappyConfig, TimeScale, Velocity};
+
+/// Most steps taken in one frame. Time beyond that is dropped, so a long
+/// stall doesn't make for an even longer catch-up.
+const MAX_STEPS: u32 = 8;
+
+/// Physics time owed to the current frame.
+#[derive(Resource, Debug, Default)]
+pub struct PhysicsClock {
+    /// Seconds banked towards the next step.
+    accumulator: f32,
+    /// Steps to take this frame, each a run of the `GameTick`.
+    pub steps: u32,
+    /// Seconds each step covers. The whole frame without a fixed rate.
+    pub step: f32,
+    /// How far bodies are drawn from their previous step to their current
+    /// one, from 0 to 1.
+    pub alpha: f32,
+}
+
+impl PhysicsClock {
+    /// Seconds the physics advances this frame.
+    pub fn delta_seconds(&self) -> f32 {
+        self.steps as f32 * self.step
+    }
+}
+
+/// Where a body was after its last two steps, and where it was last drawn.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Interpolated {
+    pub previous: Vec3,
+    pub current: Vec3,
+    pub shown: Vec3,
+}
+
+impl Interpolated {
+    fn at(translation: Vec3) -> Self {
+        Self {
+            previous: translation,
+            current: translation,
+            shown: translation,
+        }
+    }
+
+    /// Starts over from `translation` if something other than the physics
+    /// moved the body there.
+    pub fn follow(&mut self, translation: Vec3) {
+        if translation != self.current {
+            *self = Self::at(translation);
+        }
+    }
+}
+
+pub(crate) fn add_interpolation(app: &mut App) {
+    app.init_resource::<PhysicsClock>()
+        .add_system(restore_simulated.in_base_set(CoreSet::PreUpdate))
+        .add_system(tick_physics_clock.in_set(OnUpdate(AppState::Game)))
+        .add_system(track_bodies)
+        .add_system(
+            interpolate_transforms
+                .before(TransformSystem::TransformPropagate)
+                .in_base_set(CoreSet::PostUpdate),
+        );
+}
+
+/// Banks this frame's time and works out the steps it pays for. Without a
+/// fixed rate, steps once by the whole frame.
+pub(crate) fn tick_physics_clock(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    config: Res<FlappyConfig>,
+    mut clock: ResMut<PhysicsClock>,
+) {
+    let dt = time_scale.delta_seconds(&time);
+    let Some(rate) = config.physics_rate.filter(|rate| *rate > 0.0) else {
+        *clock = PhysicsClock {
+            accumulator: 0.0,
+            steps: 1,
+            step: dt,
+            alpha: 1.0,
+        };
+        return;
+    };
+    let step = 1.0 / rate;
+    clock.accumulator += dt;
+    let steps = (clock.accumulator / step) as u32;
+    clock.accumulator -= steps as f32 * step;
+    if steps > MAX_STEPS {
+        clock.accumulator = 0.0;
+    }
+    clock.steps = steps.min(MAX_STEPS);
+    clock.step = step;
+    clock.alpha = (clock.accumulator / step).clamp(0.0, 1.0);
+}
+
+/// Interpolates bodies spawned while there's a fixed rate.
+#[allow(clippy::type_complexity)]
+fn track_bodies(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    query: Query<(Entity, &Transform), (Added<Velocity>, Without<Interpolated>)>,
+) {
+    if config.physics_rate.is_none() {
+        return;
+    }
+    for (entity, transform) in query.iter() {
+        commands
+            .entity(entity)
+            .insert(Interpolated::at(transform.translation));
+    }
+}
+
+/// Moves drawn bodies back to where the simulation has them.
+fn restore_simulated(mut query: Query<(&mut Transform, &Interpolated)>) {
+    for (mut transform, interpolated) in query.iter_mut() {
+        if transform.translation == interpolated.shown {
+            transform.translation = interpolated.current;
+        }
+    }
+}
+
+/// Draws each body `PhysicsClock::alpha` of the way into its current step.
+fn interpolate_transforms(
+    clock: Res<PhysicsClock>,
+    mut query: Query<(&mut Transform, &mut Interpolated)>,
+) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        interpolated.follow(transform.translation);
+        interpolated.shown = interpolated
+            .previous
+            .lerp(interpolated.current, clock.alpha);
+        transform.translation = interpolated.shown;
+    }
+}
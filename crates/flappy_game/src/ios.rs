@@ -0,0 +1,56 @@
+//! UIKit calls for iOS. Build the static library with `cargo build --target
+//! aarch64-apple-ios --lib` and link it from an Xcode project, as in Bevy's
+//! `mobile` example.
+
+use std::ffi::CString;
+
+use objc::runtime::Object;
+use objc::{class, msg_send, sel, sel_impl, Encode, Encoding};
+
+use crate::SafeAreaInsets;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct UiEdgeInsets {
+    top: f64,
+    left: f64,
+    bottom: f64,
+    right: f64,
+}
+
+unsafe impl Encode for UiEdgeInsets {
+    fn encode() -> Encoding {
+        unsafe { Encoding::from_str("{UIEdgeInsets=dddd}") }
+    }
+}
+
+/// The key window's safe area. UIKit points match Bevy's logical pixels.
+pub(crate) fn safe_area_insets() -> Option<SafeAreaInsets> {
+    // SAFETY: only called from the main thread, where UIKit lives
+    unsafe {
+        let app: *mut Object = msg_send![class!(UIApplication), sharedApplication];
+        let window: *mut Object = msg_send![app, keyWindow];
+        if window.is_null() {
+            return None;
+        }
+        let insets: UiEdgeInsets = msg_send![window, safeAreaInsets];
+        Some(SafeAreaInsets {
+            top: insets.top as f32,
+            bottom: insets.bottom as f32,
+            left: insets.left as f32,
+            right: insets.right as f32,
+        })
+    }
+}
+
+/// Puts `text` on the general `UIPasteboard`.
+pub(crate) fn set_clipboard(text: &str) -> Result<(), String> {
+    let text = CString::new(text).map_err(|error| error.to_string())?;
+    // SAFETY: `text` outlives the copy NSString makes of it
+    unsafe {
+        let string: *mut Object = msg_send![class!(NSString), stringWithUTF8String: text.as_ptr()];
+        let pasteboard: *mut Object = msg_send![class!(UIPasteboard), generalPasteboard];
+        let _: () = msg_send![pasteboard, setString: string];
+    }
+    Ok(())
+}
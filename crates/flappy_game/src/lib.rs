@@ -0,0 +1,685 @@
+use bevy::prelude::*;
+use bevy::render::RenderApp;
+use bevy_prototype_debug_lines::DebugLinesPlugin;
+use flappy_core::collision::Aabb;
+use flappy_core::physics::displacement;
+
+mod accessibility_menu;
+#[cfg(target_os = "android")]
+mod android;
+mod assist;
+mod audio;
+mod audio_cues;
+mod best;
+mod bindings;
+mod biomes;
+mod bitmap_text;
+mod boundary;
+mod buttons;
+mod challenge;
+mod cleanup;
+mod clip;
+mod clipboard;
+#[cfg(feature = "cloud_sync")]
+mod cloud_client;
+mod cloud_sync;
+mod clouds;
+mod co_op;
+mod config;
+mod console;
+mod controls_menu;
+mod cosmetics;
+mod credits;
+mod curve;
+mod customize;
+mod dash;
+mod death_cam;
+mod debug;
+mod difficulty;
+mod digits;
+mod error;
+mod events;
+mod fallback;
+mod flight;
+mod focus;
+mod frame_rate;
+mod ground;
+mod haptics;
+mod hazards;
+mod heatmap;
+mod hit_stop;
+mod hud;
+mod idle;
+#[cfg(feature = "inspector")]
+mod inspector;
+mod interpolation;
+#[cfg(target_os = "ios")]
+mod ios;
+mod lifecycle;
+mod live_board;
+#[cfg(feature = "live_board")]
+mod live_socket;
+mod locale;
+mod logging;
+mod masks;
+mod menu;
+mod motion;
+mod music;
+mod offscreen;
+mod outline;
+mod pause;
+mod persist;
+mod physics_menu;
+mod pickups;
+mod pipes;
+mod profile;
+mod quit;
+#[cfg(feature = "rapier")]
+mod rapier;
+mod replay;
+mod results;
+mod run_stats;
+mod safe_area;
+mod scroll;
+mod seed;
+mod settings;
+mod settings_menu;
+mod sfx;
+mod share;
+mod single_switch;
+mod sky;
+mod standalone;
+mod stats;
+mod stress;
+mod telemetry;
+#[cfg(feature = "telemetry")]
+mod telemetry_client;
+mod theme;
+mod tick;
+mod trail;
+mod tutorial;
+mod tween;
+mod version;
+mod vertical;
+mod window;
+
+pub use accessibility_menu::AccessibilityToggle;
+pub use assist::Assist;
+pub use audio::{AudioChannel, MusicChannel, MuteIndicator, SfxChannel, Volume};
+pub use audio_cues::{GapClickEvent, GapCue};
+pub use best::RunRecord;
+pub use bindings::{Action, BindingConflict, Resolve};
+pub use biomes::{Biome, BiomeLayer, Biomes, BIOME_POINTS};
+pub use bitmap_text::{BitmapFont, BitmapText};
+pub use boundary::{Blocking, Boundary};
+pub use buttons::{ButtonLook, ButtonSkin};
+pub use challenge::{ChallengeButton, ChallengeCode, ChallengeInput, ChallengeRun};
+pub use cleanup::DespawnOnExit;
+pub use clip::{encode_gif, ClipRecorder};
+pub use clipboard::Clipboard;
+pub use cloud_sync::{CloudSave, CloudSyncConfig, CLOUD_TOKEN_VAR};
+pub use clouds::Cloud;
+pub use co_op::{CoOp, Player};
+pub use config::{
+    user_data_dir, AssetPaths, FlappyConfig, InputBindings, MusicAsset, PhysicsBackend,
+};
+pub use console::{Cheats, ConsoleCommandFn, ConsoleCommands, DevConsole};
+pub use controls_menu::{ConflictButton, DeadzoneButton, RebindButton, Rebinding};
+pub use cosmetics::{Achievement, Cosmetic, UnlockToast};
+pub use curve::{Curve, CurveKey, DifficultyCurves, Pace};
+pub use customize::{Accessory, BirdLook, CosmeticButton, LookSlider, Slot};
+pub use dash::{AirDash, DashMeter, DASH_COOLDOWN, DASH_SECONDS, DASH_UNLOCK_SCORE};
+pub use death_cam::DeathCam;
+pub use debug::DebugOverlay;
+pub use difficulty::Difficulty;
+pub use digits::{DigitFont, Digits, DIGIT_SIZE};
+pub use error::{install_panic_hook, ErrorScreen, FatalError};
+pub use events::{
+    DeathCause, DeathEvent, FlapEvent, GameOverEvent, NewBestEvent, PipeSpawnedEvent,
+    ResultsPanelEvent, ScoreEvent,
+};
+pub use fallback::{placeholder_image, rgba_bytes};
+pub use flight::{plot_flight, FlightGraph, FlightLog, FlightSample, GRAPH_SIZE};
+pub use focus::{Focusable, MenuFocus, StickNavigation};
+pub use frame_rate::{FrameLimiter, FrameRate};
+pub use ground::{GroundColumn, GroundScroll};
+pub use haptics::{HapticEvent, Haptics};
+pub use hazards::{Hazard, HazardScheduler, HazardWarning, WARNING_SECONDS};
+pub use heatmap::{DeathHeatmap, DeathRecord, DeathStats, HeatmapCell};
+pub use hit_stop::{HitFlash, HitStop, TimeScale};
+pub use hud::{BestMarker, ScorePopup};
+pub use interpolation::{Interpolated, PhysicsClock};
+pub use lifecycle::AppLifecycle;
+pub use live_board::{LiveBoard, LiveBoardConfig, LiveBoardList, LiveMessage, LiveScore};
+pub use locale::{Language, Locale, Localized};
+pub use logging::{init_logging, log_dir, LogOptions};
+pub use masks::PixelMasks;
+pub use menu::MenuButton;
+pub use motion::MotionPrefs;
+pub use music::{Fade, Music, MusicTrack, PlayingTrack};
+pub use offscreen::DespawnOffscreen;
+pub use outline::Outline;
+pub use pause::Countdown;
+pub use physics_menu::{PhysicsResetButton, PhysicsSlider};
+pub use pickups::{spawn_pickup, Coins, Magnet, Pickup, PICKUP_SIZE};
+pub use pipes::{
+    spawn_pipe_couple, spawn_pipe_pair, GapSensor, GoldenGap, Pipe, PipePair, PipePool, PipeTimer,
+    Scored, GOLDEN_POINTS,
+};
+pub use profile::Profile;
+pub use quit::QuitButton;
+pub use replay::RunRecorder;
+pub use results::{MedalDisplay, NewBestRibbon, ResultsTimeline, PANEL_DELAY};
+pub use run_stats::{Clearance, RunStats, NEAR_MISS_MARGIN};
+pub use safe_area::{SafeArea, SafeAreaInsets};
+pub use scroll::{ScrollSpeed, Scrolls};
+pub use seed::{RunSeed, SEED_LIMIT};
+pub use settings::{CollisionMode, Settings};
+pub use settings_menu::{
+    AccessibilityButton, AssistButton, CollisionButton, ControlsButton, DashButton, FrameCapButton,
+    LanguageButton, TelemetryButton, VolumeSlider, VsyncButton,
+};
+pub use sfx::{stereo_pan, Chirp, GameSounds, SoundEffect, Variation};
+pub use share::{ShareButton, ShareEvent};
+pub use single_switch::AutoFlap;
+pub use sky::{Sky, SkyMaterial};
+pub use standalone::{
+    standalone_app, standalone_config, StandaloneOptions, PHYSICS_RATE, WINDOW_SCALE,
+};
+pub use stats::StatsOverlay;
+pub use stress::{FrameStats, StressPair, StressRecorder, StressTest};
+pub use telemetry::{Telemetry, TelemetryEvent};
+pub use theme::{PipeTint, Theme};
+pub use tick::GameTick;
+pub use trail::{Afterimage, BirdTrail};
+pub use tutorial::TutorialHint;
+pub use tween::{Ease, Tween, TweenTarget};
+pub use version::{build_info, GIT_HASH, VERSION};
+pub use vertical::{vertical_rotation, view_rotation, ScreenSpace};
+
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Settings,
+    /// The advanced physics panel, reached from `Settings`.
+    Physics,
+    /// Rebinding keys, reached from `Settings`.
+    Controls,
+    /// Switches for accessibility settings, reached from `Settings`.
+    Accessibility,
+    /// Dressing up the bird, reached from `MainMenu`.
+    Customize,
+    Credits,
+    /// Typing in a `ChallengeCode` to play.
+    Challenge,
+    Game,
+    GameOver,
+    /// Showing an `ErrorScreen`. There is no way out.
+    Error,
+}
+
+/// Where the run is at while in `AppState::Game`. Gameplay systems only
+/// update while `Running`; the rest are overlays over the frozen run. Put
+/// back to `Running` whenever the run ends.
+#[derive(States, Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum RunState {
+    #[default]
+    Running,
+    /// Paused until the player resumes.
+    Paused,
+    /// Counting down to resuming the run.
+    Countdown,
+    /// Asking whether to give up the run.
+    Quitting,
+}
+
+/// The stages of a `GameTick`, run in this order.
+#[derive(SystemSet, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GameSystem {
+    /// Flaps the birds pressed for since the last tick.
+    Input,
+    /// Brings on pipes, pickups and hazards as they come due.
+    Spawning,
+    /// Moves everything on by a step.
+    Movement,
+    /// Detects deaths and sends `DeathEvent`s.
+    Collisions,
+    /// Scores gaps and pickups, and clears away whatever has left the
+    /// playfield.
+    Scoring,
+}
+
+/// All of the gameplay, menus and state handling for Flappy Bevy.
+///
+/// The host app is expected to add `DefaultPlugins` (or an equivalent set),
+/// and `bevy_kira_audio::AudioPlugin` for sound, before this.
+#[derive(Default)]
+pub struct FlappyGamePlugin {
+    pub config: FlappyConfig,
+}
+
+impl Plugin for FlappyGamePlugin {
+    fn build(&self, app: &mut App) {
+        if self.config.spawn_camera {
+            app.add_startup_system(setup);
+        }
+
+        // the state schedules have to exist before the modules add to them
+        app.insert_resource(State(self.config.initial_state))
+            .add_state::<AppState>()
+            .add_state::<RunState>()
+            .configure_set(OnUpdate(AppState::Game).run_if(in_state(RunState::Running)));
+
+        events::add_events(app);
+        tick::add_game_tick(app);
+        profile::add_profile(app, &self.config);
+        settings::add_settings(app, &self.config);
+        bitmap_text::add_bitmap_text(app);
+        locale::add_locale(app);
+        console::add_console(app);
+        bindings::add_bindings(app);
+        co_op::add_co_op(app);
+        motion::add_motion_prefs(app);
+        audio::add_audio_channels(app);
+        audio_cues::add_audio_cues(app);
+        sfx::add_sound_effects(app);
+        music::add_music(app);
+        single_switch::add_single_switch(app);
+        haptics::add_haptics(app);
+        difficulty::add_difficulty(app);
+        curve::add_difficulty_curves(app);
+        assist::add_assist(app);
+        dash::add_air_dash(app);
+        scroll::add_scroll(app);
+        pickups::add_pickups(app);
+        hazards::add_hazards(app);
+        ground::add_ground(app);
+        vertical::add_vertical(app);
+        interpolation::add_interpolation(app);
+        lifecycle::add_lifecycle(app);
+        safe_area::add_safe_area(app);
+        trail::add_trail(app);
+        outline::add_outlines(app);
+        theme::add_themes(app);
+        clouds::add_clouds(app);
+        biomes::add_biomes(app);
+        customize::add_customize(app);
+        cosmetics::add_cosmetics(app);
+        hit_stop::add_hit_stop(app);
+        death_cam::add_death_cam(app);
+        tween::add_tweens(app);
+        seed::add_run_seed(app);
+        replay::add_replays(app);
+        best::add_best(app);
+        digits::add_digits(app);
+        hud::add_hud(app);
+        tutorial::add_tutorial(app);
+        results::add_results(app);
+        run_stats::add_run_stats(app);
+        flight::add_flight_log(app);
+        buttons::add_button_skins(app);
+        focus::add_menu_focus(app);
+        menu::add_main_menu(app);
+        credits::add_credits(app);
+        settings_menu::add_settings_menu(app);
+        physics_menu::add_physics_menu(app, &self.config);
+        controls_menu::add_controls_menu(app);
+        accessibility_menu::add_accessibility_menu(app);
+        version::add_version_overlay(app);
+        idle::add_idle_timeout(app);
+        pause::add_pause(app);
+        quit::add_quit_confirmation(app);
+        stats::add_stats_overlay(app);
+        stress::add_stress_test(app);
+        frame_rate::add_frame_rate(app);
+        heatmap::add_death_stats(app, &self.config);
+        clip::add_clip_recorder(app, self.config.playfield);
+        clipboard::add_clipboard(app);
+        share::add_share(app);
+        challenge::add_challenges(app);
+        live_board::add_live_board(app, &self.config);
+        cloud_sync::add_cloud_sync(app, &self.config);
+        telemetry::add_telemetry(app, &self.config);
+        // also registered by the render plugins; needed here for headless apps
+        app.add_asset::<Image>();
+        let rendering = app.get_sub_app(RenderApp).is_ok();
+        error::add_error_screen(app, rendering);
+        sky::add_sky(app, rendering);
+        if rendering {
+            fallback::add_fallback_textures(app);
+            window::add_window_dressing(app);
+            app.add_plugin(DebugLinesPlugin::default());
+            #[cfg(feature = "inspector")]
+            inspector::add_inspector(app);
+        }
+        app.insert_resource(self.config.clone())
+            .init_resource::<PixelMasks>()
+            .init_resource::<DebugOverlay>()
+            .init_resource::<Score>()
+            .init_resource::<PipePool>()
+            .add_system(masks::build_pixel_masks)
+            .add_system(debug::toggle_debug_overlay)
+            .add_system(debug::draw_debug_overlay.after(debug::toggle_debug_overlay))
+            .add_systems(
+                (game_setup, boundary::boundaries_setup, pipes::pipes_setup)
+                    .in_schedule(OnEnter(AppState::Game)),
+            )
+            .init_resource::<PendingFlaps>()
+            .add_systems(
+                (
+                    read_flaps.before(tick::run_game_ticks),
+                    hit_stop::finish_hit_stop.after(tick::run_game_ticks),
+                    death_cam::update_death_cam.after(hit_stop::finish_hit_stop),
+                )
+                    .in_set(OnUpdate(AppState::Game)),
+            )
+            .add_systems(
+                (jump, single_switch::auto_flap.after(jump))
+                    .in_set(GameSystem::Input)
+                    .in_schedule(GameTick),
+            )
+            .add_system(
+                pipes::spawn_pipes
+                    .in_set(GameSystem::Spawning)
+                    .in_schedule(GameTick),
+            )
+            .add_systems(
+                (
+                    hit_stop::start_hit_stop,
+                    pipes::score_gap_sensors.after(hit_stop::start_hit_stop),
+                    apply_score.after(pipes::score_gap_sensors),
+                    pipes::recycle_offscreen_pipes,
+                    offscreen::despawn_offscreen,
+                )
+                    .in_set(GameSystem::Scoring)
+                    .in_schedule(GameTick),
+            )
+            .add_system(restart_game.in_set(OnUpdate(AppState::GameOver)));
+
+        match self.config.physics_backend {
+            PhysicsBackend::Builtin => {
+                app.add_systems(
+                    (
+                        apply_gravity,
+                        apply_velocity
+                            .after(apply_gravity)
+                            .after(scroll::update_scroll_speed),
+                        boundary::block_bird.after(apply_velocity),
+                    )
+                        .in_set(GameSystem::Movement)
+                        .in_schedule(GameTick),
+                )
+                .add_system(
+                    check_collisions
+                        .in_set(GameSystem::Collisions)
+                        .in_schedule(GameTick),
+                );
+            }
+            #[cfg(feature = "rapier")]
+            PhysicsBackend::Rapier => rapier::add_rapier_backend(app),
+        }
+
+        cleanup::add_state_cleanup::<AppState>(app);
+        cleanup::add_state_cleanup::<RunState>(app);
+    }
+}
+
+/// Pipe pairs passed during the current run.
+#[derive(Resource, Default, Debug, Deref, DerefMut, Reflect)]
+#[reflect(Resource)]
+pub struct Score(pub u32);
+
+#[derive(Component)]
+pub struct Bird;
+
+#[derive(Component)]
+pub(crate) struct RestartButton;
+
+/// Box the bird collides and scores with, which can be smaller than its sprite.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Hitbox {
+    pub size: Vec2,
+}
+
+/// Box the bird dies on touching, centred on the entity's `GlobalTransform`.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Collider {
+    pub size: Vec2,
+}
+
+#[derive(Component, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct Velocity(pub Vec2);
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct Gravity(pub bool);
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+fn game_setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    co_op: Res<CoOp>,
+    mut score: ResMut<Score>,
+    mut pending: ResMut<PendingFlaps>,
+) {
+    score.0 = 0;
+    pending.0.clear();
+    info!(co_op = co_op.0, "run started");
+
+    // a bird for each player
+    for player in co_op.players() {
+        commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load(player.texture(&config.assets)),
+                transform: Transform {
+                    translation: Vec3::new(
+                        -(config.width() / 4.0),
+                        co_op.start_height(player),
+                        0.0,
+                    ),
+                    ..default()
+                },
+                ..default()
+            },
+            Bird,
+            player,
+            Hitbox {
+                size: config.rules.bird_hitbox(),
+            },
+            DespawnOnExit(AppState::Game),
+            Velocity(Vec2::new(0.0, 0.0)),
+            Gravity(false),
+        ));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn check_collisions(
+    mut deaths: EventWriter<DeathEvent>,
+    hit_stop: Res<HitStop>,
+    settings: Res<Settings>,
+    masks: Res<PixelMasks>,
+    collider_query: Query<
+        (
+            &GlobalTransform,
+            &Collider,
+            Option<&Boundary>,
+            Option<&Hazard>,
+            Option<&Handle<Image>>,
+        ),
+        Without<Blocking>,
+    >,
+    bird_query: Query<(Entity, &GlobalTransform, &Hitbox, Option<&Handle<Image>>), With<Bird>>,
+) {
+    // the run is over, the bird just carries on through the death cam
+    if hit_stop.timer.is_some() {
+        return;
+    }
+    let _span = info_span!("bird_vs_colliders", colliders = collider_query.iter().len()).entered();
+    for (bird, bird_transform, hitbox, bird_texture) in bird_query.iter() {
+        let bird_position = bird_transform.translation().truncate();
+        let bird_box = Aabb::new(bird_position, hitbox.size);
+        let hit = collider_query
+            .iter()
+            .map(|(transform, collider, boundary, hazard, texture)| {
+                let collider_box = Aabb::new(transform.translation().truncate(), collider.size);
+                (collider_box, boundary, hazard, texture)
+            })
+            .find(|(collider_box, _, _, texture)| {
+                collider_box.intersects(&bird_box)
+                    && (settings.collision == CollisionMode::Boxes
+                        || masks.overlap(
+                            bird_texture,
+                            bird_position,
+                            *texture,
+                            collider_box.center,
+                        ))
+            });
+        let Some((collider_box, boundary, hazard, _)) = hit else {
+            continue;
+        };
+        let cause = if boundary.is_some() {
+            DeathCause::OutOfBounds
+        } else if hazard.is_some() {
+            DeathCause::Hazard
+        } else {
+            DeathCause::Pipe
+        };
+        deaths.send(DeathEvent {
+            bird,
+            position: bird_position,
+            normal: bird_box.contact_normal(&collider_box),
+            cause,
+        });
+    }
+}
+
+fn apply_score(mut score: ResMut<Score>, mut scores: EventReader<ScoreEvent>) {
+    for event in scores.iter() {
+        score.0 += event.points;
+        info!(score = score.0, "scored");
+    }
+}
+
+fn apply_gravity(
+    clock: Res<PhysicsClock>,
+    config: Res<FlappyConfig>,
+    mut query: Query<(&mut Velocity, &Gravity, &Bird)>,
+) {
+    let physics = config.physics;
+
+    for (mut velocity, gravity, _) in query.iter_mut() {
+        if gravity.0 {
+            velocity.0.y = physics.fall(velocity.0.y, clock.step);
+        }
+    }
+}
+
+/// Moves bodies on by a `PhysicsClock` step, along with the level for those
+/// that `Scrolls`, keeping the last two steps of interpolated ones.
+fn apply_velocity(
+    clock: Res<PhysicsClock>,
+    scroll: Res<ScrollSpeed>,
+    mut query: Query<(
+        &Velocity,
+        &mut Transform,
+        Option<&Scrolls>,
+        Option<&mut Interpolated>,
+    )>,
+) {
+    let _span = info_span!("integrate_velocities", bodies = query.iter().len()).entered();
+    for (velocity, mut transform, scrolls, interpolated) in query.iter_mut() {
+        let velocity = velocity.0 + scrolls.map_or(Vec2::ZERO, |scrolls| scrolls.velocity(*scroll));
+        let previous = transform.translation;
+        transform.translation.x += displacement(velocity.x, clock.step);
+        transform.translation.y += displacement(velocity.y, clock.step);
+        if let Some(mut interpolated) = interpolated {
+            interpolated.follow(previous);
+            interpolated.previous = previous;
+            interpolated.current = transform.translation;
+        }
+    }
+}
+
+/// Players who pressed flap since the last `GameTick`, flapped by `jump` on
+/// the next one.
+#[derive(Resource, Default, Debug)]
+pub struct PendingFlaps(pub Vec<Player>);
+
+/// Notes this frame's flap presses for the next tick.
+fn read_flaps(
+    keyboard_input: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    co_op: Res<CoOp>,
+    mut pending: ResMut<PendingFlaps>,
+) {
+    if console.open {
+        return;
+    }
+    for player in co_op.players() {
+        let keys = player.flap_keys(&config.bindings);
+        // touches are the first player's, the second only has keys
+        let touched = player == Player::ONE && touches.any_just_pressed();
+        let pressed = keyboard_input.any_just_pressed(keys.iter().copied()) || touched;
+        if pressed && !pending.0.contains(&player) {
+            pending.0.push(player);
+        }
+    }
+}
+
+pub(crate) fn jump(
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    mut pending: ResMut<PendingFlaps>,
+    mut flaps: EventWriter<FlapEvent>,
+    mut query: Query<(Entity, &Player, &mut Velocity, &mut Gravity), With<Bird>>,
+) {
+    let jump_velocity = config.physics.jump_velocity;
+    for (bird, player, mut velocity, mut gravity) in query.iter_mut() {
+        if !pending.0.contains(player) {
+            continue;
+        }
+        // in single-switch mode only the first press flaps, see `auto_flap`
+        if settings.single_switch && *player == Player::ONE && gravity.0 {
+            continue;
+        }
+        gravity.0 = true;
+        velocity.0.y = jump_velocity;
+        flaps.send(FlapEvent { bird });
+    }
+    pending.0.clear();
+}
+
+/// Restarts on a click of the restart button or a press of a restart key.
+fn restart_game(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    focus: Res<MenuFocus>,
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    let keys = config
+        .bindings
+        .restart
+        .iter()
+        .copied()
+        .filter(|key| *key != KeyCode::Return || focus.focused.is_none());
+    let pressed = !console.open && keyboard_input.any_just_pressed(keys);
+    if clicked || pressed {
+        info!("restarting game");
+        next_state.set(AppState::Game);
+    }
+}
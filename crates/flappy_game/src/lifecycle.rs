@@ -0,0 +1,59 @@
+//! Going to and from the background on phones. Suspending pauses the run and
+//! writes out the settings and profile, in case the OS kills the app while
+//! it's away. Coming back leaves the run paused until the player taps.
+//!
+//! Bevy stops updating as soon as winit suspends the app, so on phones the
+//! window losing focus, which comes first, is taken as `Suspended`.
+
+use bevy::prelude::*;
+
+use crate::{AppState, Profile, RunState, Settings};
+
+/// The app went to or came back from the background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppLifecycle {
+    Suspended,
+    Resumed,
+}
+
+pub(crate) fn add_lifecycle(app: &mut App) {
+    app.add_event::<AppLifecycle>().add_system(suspend);
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    app.add_system(send_lifecycle_on_focus.before(suspend));
+}
+
+#[cfg(any(target_os = "android", target_os = "ios"))]
+fn send_lifecycle_on_focus(
+    mut focus: EventReader<bevy::window::WindowFocused>,
+    mut lifecycle: EventWriter<AppLifecycle>,
+) {
+    for focus in focus.iter() {
+        lifecycle.send(if focus.focused {
+            AppLifecycle::Resumed
+        } else {
+            AppLifecycle::Suspended
+        });
+    }
+}
+
+fn suspend(
+    mut lifecycle: EventReader<AppLifecycle>,
+    app_state: Res<State<AppState>>,
+    run_state: Res<State<RunState>>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut settings: ResMut<Settings>,
+    mut profile: ResMut<Profile>,
+) {
+    for event in lifecycle.iter() {
+        info!(?event, "app lifecycle");
+        if *event != AppLifecycle::Suspended {
+            continue;
+        }
+        if app_state.0 == AppState::Game && run_state.0 == RunState::Running {
+            next_run_state.set(RunState::Paused);
+        }
+        // both are saved on change, at the end of the frame
+        settings.set_changed();
+        profile.set_changed();
+    }
+}
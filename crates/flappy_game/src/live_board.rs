@@ -0,0 +1,175 @@
+//! The live board: friends' scores in a panel beside the playfield, updating
+//! as everyone plays. Shown when `FlappyConfig::live_board` is set, and fed
+//! over a WebSocket with the `live_board` feature, see `live_socket`.
+//!
+//! Messages are `LiveMessage`s as JSON text. The game sends its player's
+//! score as it changes, and as runs start and end, and the server answers with
+//! the whole board whenever anyone's changes.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bitmap_text::bitmap_text;
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, FlappyConfig, Localized, SafeArea};
+
+/// Rows shown in the panel, best first.
+const LIVE_ROWS: usize = 5;
+/// After the score of a row played with `Settings::assist`.
+const ASSISTED_MARK: &str = " (A)";
+/// After the score of a row played with the physics changed.
+const PHYSICS_MARK: &str = " (M)";
+/// After the score of a row played in `CoOp`.
+const CO_OP_MARK: &str = " (2P)";
+
+/// Where to find the live board server, and who to appear as.
+#[derive(Clone, Debug)]
+pub struct LiveBoardConfig {
+    /// A `ws://` address.
+    pub url: String,
+    pub player: String,
+}
+
+/// One player's entry on the board.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LiveScore {
+    pub player: String,
+    pub score: u32,
+    /// In a run, rather than on a menu or the game over screen.
+    pub playing: bool,
+    /// Playing with assist mode. Missing from older clients.
+    #[serde(default)]
+    pub assisted: bool,
+    /// Playing with the physics changed from the physics panel. Missing from
+    /// older clients.
+    #[serde(default)]
+    pub modified_physics: bool,
+    /// Playing `CoOp`. Missing from older clients.
+    #[serde(default)]
+    pub co_op: bool,
+}
+
+/// A message to or from the live board server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveMessage {
+    /// The player's own entry, from the game.
+    Score(LiveScore),
+    /// Everyone's entries, from the server.
+    Board { scores: Vec<LiveScore> },
+}
+
+/// The latest board from the server.
+#[derive(Resource, Default, Debug)]
+pub struct LiveBoard {
+    pub connected: bool,
+    pub scores: Vec<LiveScore>,
+}
+
+/// The panel's list of rows, rebuilt as the board changes.
+#[derive(Component)]
+pub struct LiveBoardList;
+
+pub(crate) fn add_live_board(app: &mut App, config: &FlappyConfig) {
+    app.init_resource::<LiveBoard>()
+        .add_system(spawn_live_board.in_schedule(OnEnter(AppState::Game)))
+        .add_system(show_live_board);
+    let Some(live) = &config.live_board else {
+        return;
+    };
+    #[cfg(feature = "live_board")]
+    crate::live_socket::add_live_socket(app, live.clone());
+    #[cfg(not(feature = "live_board"))]
+    warn!(url = %live.url, "the live board needs the live_board feature");
+}
+
+fn spawn_live_board(mut commands: Commands, config: Res<FlappyConfig>) {
+    if config.live_board.is_none() {
+        return;
+    }
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px(4.0),
+                        right: Val::Px(4.0),
+                        ..default()
+                    },
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    padding: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.4).into(),
+                ..default()
+            },
+            SafeArea::default(),
+            DespawnOnExit(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("live.title"),
+                1.0,
+                Color::rgb(1.0, 0.8, 0.2),
+            ));
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::FlexEnd,
+                        ..default()
+                    },
+                    ..default()
+                },
+                LiveBoardList,
+            ));
+        });
+}
+
+fn show_live_board(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    board: Res<LiveBoard>,
+    query: Query<(Entity, Ref<LiveBoardList>)>,
+) {
+    let player = config.live_board.as_ref().map(|live| live.player.as_str());
+    for (list, marker) in query.iter() {
+        if !board.is_changed() && !marker.is_added() {
+            continue;
+        }
+        let mut list = commands.entity(list);
+        list.despawn_descendants();
+        list.with_children(|parent| {
+            if !board.connected {
+                parent.spawn(localized_text(
+                    Localized::new("live.offline"),
+                    1.0,
+                    Color::GRAY,
+                ));
+                return;
+            }
+            let mut scores: Vec<_> = board.scores.iter().collect();
+            scores.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+            for entry in scores.into_iter().take(LIVE_ROWS) {
+                let color = if Some(entry.player.as_str()) == player {
+                    Color::rgb(1.0, 0.8, 0.2)
+                } else if entry.playing {
+                    Color::WHITE
+                } else {
+                    Color::GRAY
+                };
+                let assisted = if entry.assisted { ASSISTED_MARK } else { "" };
+                let physics = if entry.modified_physics {
+                    PHYSICS_MARK
+                } else {
+                    ""
+                };
+                let co_op = if entry.co_op { CO_OP_MARK } else { "" };
+                let text = format!("{} {}{assisted}{physics}{co_op}", entry.player, entry.score);
+                parent.spawn(bitmap_text(text, 1.0, color));
+            }
+        });
+    }
+}
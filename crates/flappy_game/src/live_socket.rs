@@ -0,0 +1,164 @@
+//! The live board's WebSocket, enabled with the `live_board` feature. A thread
+//! keeps the connection open, reconnecting whenever it drops, and passes
+//! `LiveMessage`s to and from the game over channels.
+
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::physics_menu::StartingPhysics;
+use crate::{
+    AppState, CoOp, FlappyConfig, LiveBoard, LiveBoardConfig, LiveMessage, LiveScore, Score,
+    Settings,
+};
+
+/// Wait between attempts to reach the server.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Longest a read waits before checking for scores to send.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// From the connection thread.
+enum Incoming {
+    Connected(bool),
+    Message(LiveMessage),
+}
+
+#[derive(Resource)]
+struct LiveSocket {
+    outgoing: Sender<LiveMessage>,
+    incoming: Receiver<Incoming>,
+}
+
+pub(crate) fn add_live_socket(app: &mut App, live: LiveBoardConfig) {
+    let (outgoing, to_send) = crossbeam_channel::unbounded();
+    let (received, incoming) = crossbeam_channel::unbounded();
+    let spawned = thread::Builder::new()
+        .name("live board".to_string())
+        .spawn(move || run(&live.url, &to_send, &received));
+    if let Err(error) = spawned {
+        warn!(%error, "couldn't start the live board");
+        return;
+    }
+    app.insert_resource(LiveSocket { outgoing, incoming })
+        .add_system(send_live_score)
+        .add_system(receive_live_board);
+}
+
+/// Connects and serves until the game goes away.
+fn run(url: &str, to_send: &Receiver<LiveMessage>, received: &Sender<Incoming>) {
+    // resent on reconnecting, so the server always has the latest
+    let mut latest = None;
+    loop {
+        match tungstenite::connect(url) {
+            Ok((socket, _)) => {
+                info!(url, "live board connected");
+                if received.send(Incoming::Connected(true)).is_err() {
+                    return;
+                }
+                let error = serve(socket, &mut latest, to_send, received);
+                warn!(%error, "live board disconnected");
+            }
+            Err(error) => warn!(%error, url, "couldn't reach the live board"),
+        }
+        if received.send(Incoming::Connected(false)).is_err() {
+            return;
+        }
+        thread::sleep(RECONNECT_DELAY);
+        latest = to_send.try_iter().last().or(latest);
+    }
+}
+
+/// Passes messages until the connection fails, returning why.
+fn serve(
+    mut socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    latest: &mut Option<LiveMessage>,
+    to_send: &Receiver<LiveMessage>,
+    received: &Sender<Incoming>,
+) -> String {
+    if let MaybeTlsStream::Plain(stream) = socket.get_mut() {
+        if let Err(error) = stream.set_read_timeout(Some(POLL_INTERVAL)) {
+            return error.to_string();
+        }
+    }
+    let mut pending: Vec<_> = latest.iter().cloned().collect();
+    loop {
+        loop {
+            match to_send.try_recv() {
+                Ok(message) => pending.push(message),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return "the game has gone".to_string(),
+            }
+        }
+        for message in pending.drain(..) {
+            let text = match serde_json::to_string(&message) {
+                Ok(text) => text,
+                Err(error) => return error.to_string(),
+            };
+            if let Err(error) = socket.write_message(Message::Text(text)) {
+                return error.to_string();
+            }
+            *latest = Some(message);
+        }
+        match socket.read_message() {
+            Ok(Message::Text(text)) => match serde_json::from_str(&text) {
+                Ok(message) => {
+                    if received.send(Incoming::Message(message)).is_err() {
+                        return "the game has gone".to_string();
+                    }
+                }
+                Err(error) => warn!(%error, "bad live board message"),
+            },
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(error))
+                if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(error) => return error.to_string(),
+        }
+    }
+}
+
+/// Sends the player's entry whenever the score changes, a run starts or ends,
+/// or assist mode, the physics or `CoOp` is switched.
+#[allow(clippy::too_many_arguments)]
+fn send_live_score(
+    config: Res<FlappyConfig>,
+    starting: Res<StartingPhysics>,
+    socket: Res<LiveSocket>,
+    settings: Res<Settings>,
+    co_op: Res<CoOp>,
+    score: Res<Score>,
+    app_state: Res<State<AppState>>,
+    mut last: Local<Option<LiveScore>>,
+) {
+    let Some(live) = &config.live_board else {
+        return;
+    };
+    let entry = LiveScore {
+        player: live.player.clone(),
+        score: score.0,
+        playing: app_state.0 == AppState::Game,
+        assisted: settings.assist.enabled,
+        modified_physics: starting.modified(&config.physics),
+        co_op: co_op.0,
+    };
+    if last.as_ref() == Some(&entry) {
+        return;
+    }
+    *last = Some(entry.clone());
+    socket.outgoing.send(LiveMessage::Score(entry)).ok();
+}
+
+fn receive_live_board(socket: Res<LiveSocket>, mut board: ResMut<LiveBoard>) {
+    for incoming in socket.incoming.try_iter() {
+        match incoming {
+            Incoming::Connected(connected) => board.connected = connected,
+            Incoming::Message(LiveMessage::Board { scores }) => board.scores = scores,
+            Incoming::Message(LiveMessage::Score(_)) => {}
+        }
+    }
+}
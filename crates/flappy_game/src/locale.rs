@@ -0,0 +1,150 @@
+//! Translated UI text. Each language is a file of `key = value` lines under
+//! `locale/`, compiled in. Text entities carry a `Localized` key and are
+//! rewritten whenever `Settings::language` changes.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bitmap_text::bitmap_text;
+use crate::{BitmapText, Settings};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::English,
+        Language::French,
+        Language::German,
+        Language::Spanish,
+    ];
+
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locale/en.lang"),
+            Language::French => include_str!("../locale/fr.lang"),
+            Language::German => include_str!("../locale/de.lang"),
+            Language::Spanish => include_str!("../locale/es.lang"),
+        }
+    }
+
+    /// The one after this in `ALL`, wrapping round.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|language| *language == self);
+        Self::ALL[index.map_or(0, |index| (index + 1) % Self::ALL.len())]
+    }
+}
+
+/// Strings for the current language, falling back to English.
+#[derive(Resource, Debug)]
+pub struct Locale {
+    pub language: Language,
+    strings: HashMap<&'static str, &'static str>,
+    fallback: HashMap<&'static str, &'static str>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::new(Language::default())
+    }
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            strings: parse(language.source()),
+            fallback: parse(Language::English.source()),
+        }
+    }
+
+    /// The text for `key`, or the key itself if no language has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .copied()
+            .unwrap_or(key)
+    }
+
+    /// The text for `key` with `{0}`, `{1}`... replaced by `args`.
+    pub fn format(&self, key: &str, args: &[String]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.get(key).to_string(), |text, (index, arg)| {
+                text.replace(&format!("{{{index}}}"), arg)
+            })
+    }
+
+    /// Keys in this language, for checking translations are complete.
+    pub fn keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.strings.keys().copied()
+    }
+}
+
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+/// Replaces the entity's `BitmapText` with the translation of `key`.
+#[derive(Component, Clone, Debug)]
+pub struct Localized {
+    pub key: &'static str,
+    pub args: Vec<String>,
+}
+
+impl Localized {
+    pub fn new(key: &'static str) -> Self {
+        Self { key, args: vec![] }
+    }
+
+    pub fn with_args(mut self, args: impl IntoIterator<Item = String>) -> Self {
+        self.args = args.into_iter().collect();
+        self
+    }
+}
+
+/// A `bitmap_text` node showing the translation of `key`.
+pub(crate) fn localized_text(
+    key: Localized,
+    scale: f32,
+    color: Color,
+) -> (ImageBundle, BitmapText, Localized) {
+    let (image, text) = bitmap_text("", scale, color);
+    (image, text, key)
+}
+
+pub(crate) fn add_locale(app: &mut App) {
+    app.init_resource::<Locale>()
+        .add_system(sync_language)
+        .add_system(localize_text.after(sync_language));
+}
+
+fn sync_language(settings: Res<Settings>, mut locale: ResMut<Locale>) {
+    if settings.is_changed() && settings.language != locale.language {
+        info!(language = ?settings.language, "switching language");
+        *locale = Locale::new(settings.language);
+    }
+}
+
+fn localize_text(locale: Res<Locale>, mut query: Query<(Ref<Localized>, &mut BitmapText)>) {
+    for (localized, mut text) in query.iter_mut() {
+        if locale.is_changed() || localized.is_changed() {
+            text.value = locale.format(localized.key, &localized.args);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Log output for the game binary: stdout as usual, plus optionally a daily
+//! rotating JSON log in the user data directory.
+//!
+//! This replaces Bevy's `LogPlugin`, which must be disabled when this is used.
+
+use std::path::PathBuf;
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use crate::config::user_data_dir;
+
+/// Rotated log files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+#[derive(Clone, Debug)]
+pub struct LogOptions {
+    /// Also write logs to `log_dir()`.
+    pub file: bool,
+    /// `EnvFilter` directives, overridden by `RUST_LOG`.
+    pub filter: String,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            file: false,
+            filter: "info,wgpu=error".to_string(),
+        }
+    }
+}
+
+pub fn log_dir() -> Option<PathBuf> {
+    user_data_dir().map(|dir| dir.join("logs"))
+}
+
+/// Installs the global subscriber. Keep the returned guard alive until exit so
+/// buffered file output is flushed.
+pub fn init_logging(options: &LogOptions) -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(options.filter.as_str()));
+
+    let mut file_error = None;
+    let (file_layer, guard) = match options.file.then(file_appender) {
+        Some(Ok(appender)) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            let layer = fmt::layer().json().with_writer(writer);
+            (Some(layer), Some(guard))
+        }
+        Some(Err(error)) => {
+            file_error = Some(error);
+            (None, None)
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .init();
+    if let Some(error) = file_error {
+        bevy::log::warn!(%error, "not logging to a file");
+    }
+    guard
+}
+
+fn file_appender() -> Result<RollingFileAppender, String> {
+    let dir = log_dir().ok_or("no user data directory")?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("flappy_bevy")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|error| format!("{}: {error}", dir.display()))
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use flappy_core::analysis::analyse_gaps;
+use flappy_game::{
+    init_logging, install_panic_hook, standalone_app, standalone_config, AppState, CloudSyncConfig,
+    Difficulty, DifficultyCurves, FlappyConfig, LiveBoardConfig, LogOptions, StandaloneOptions,
+    StressTest,
+};
+
+fn main() {
+    // `--analyse-gaps [pairs]` prints a study of the pipe gaps generated at the
+    // difficulty given with `--difficulty`, instead of starting the game
+    if std::env::args().any(|arg| arg == "--analyse-gaps") {
+        report_gaps();
+        return;
+    }
+
+    // `--log-file` swaps Bevy's logger for one that also writes to the user data
+    // dir. Bevy's trace output is unavailable in that mode.
+    let log_file = std::env::args().any(|arg| arg == "--log-file");
+    let _log_guard = log_file.then(|| {
+        init_logging(&LogOptions {
+            file: true,
+            ..default()
+        })
+    });
+    install_panic_hook();
+
+    // `--live-board ws://host:port` shows friends' scores from that server,
+    // under the name given with `--name`
+    let live_board = arg_value("--live-board").map(|url| LiveBoardConfig {
+        url,
+        player: arg_value("--name").unwrap_or_else(|| "player".to_string()),
+    });
+
+    // `--cloud-sync https://host/save` keeps the profile and settings in step
+    // with that backend, using the token saved for it, see `CloudSyncConfig`
+    let cloud_sync = arg_value("--cloud-sync").map(CloudSyncConfig::with_saved_token);
+
+    // `--telemetry https://host/runs` offers to send run summaries there, which
+    // the player still has to opt into from the settings screen
+    let telemetry_url = arg_value("--telemetry");
+
+    // `--stress-test [pairs]` goes straight into a run swamped with pipes, then
+    // prints frame time statistics and exits
+    let stress_test = std::env::args()
+        .any(|arg| arg == "--stress-test")
+        .then(|| StressTest {
+            pairs: arg_value("--stress-test")
+                .and_then(|pairs| pairs.parse().ok())
+                .unwrap_or(StressTest::default().pairs),
+            ..default()
+        });
+    let initial_state = if stress_test.is_some() {
+        AppState::Game
+    } else {
+        AppState::MainMenu
+    };
+
+    // `--vertical` turns the game on its side, in a window turned to match
+    let vertical = std::env::args().any(|arg| arg == "--vertical");
+    let config = FlappyConfig {
+        vertical,
+        live_board,
+        cloud_sync,
+        telemetry_url,
+        initial_state,
+        stress_test,
+        ..standalone_config()
+    };
+    let options = StandaloneOptions {
+        external_logging: log_file,
+        ..default()
+    };
+    standalone_app(config, options).run();
+}
+
+/// The argument following `flag`, if it was given.
+fn arg_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != flag);
+    args.next()?;
+    args.next()
+}
+
+fn report_gaps() {
+    let pairs = arg_value("--analyse-gaps")
+        .and_then(|pairs| pairs.parse().ok())
+        .unwrap_or(10_000);
+    let difficulty = match arg_value("--difficulty") {
+        Some(tag) => Difficulty::from_tag(&tag).unwrap_or_else(|| {
+            eprintln!("unknown difficulty '{tag}', expected easy, normal or hard");
+            std::process::exit(2);
+        }),
+        None => Difficulty::default(),
+    };
+    let config = standalone_config();
+    let curves = DifficultyCurves::default();
+    let report = analyse_gaps(
+        &config.sim_config(curves.get(difficulty)),
+        pairs,
+        rand::random(),
+    );
+    println!("{} gaps\n{report}", difficulty.tag());
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::utils::HashMap;
+use flappy_core::mask::PixelMask;
+
+/// Pixel masks for every loaded texture, used by `CollisionMode::PixelPerfect`.
+#[derive(Resource, Default)]
+pub struct PixelMasks {
+    masks: HashMap<Handle<Image>, PixelMask>,
+}
+
+impl PixelMasks {
+    pub fn get(&self, texture: &Handle<Image>) -> Option<&PixelMask> {
+        self.masks.get(texture)
+    }
+
+    /// Whether two sprites overlap pixel-for-pixel. Sprites without a mask are
+    /// treated as solid boxes, so this only ever refines an AABB hit.
+    pub fn overlap(
+        &self,
+        a: Option<&Handle<Image>>,
+        a_center: Vec2,
+        b: Option<&Handle<Image>>,
+        b_center: Vec2,
+    ) -> bool {
+        match (a.and_then(|a| self.get(a)), b.and_then(|b| self.get(b))) {
+            (Some(a), Some(b)) => a.overlaps(a_center, b, b_center),
+            _ => true,
+        }
+    }
+}
+
+/// Reads the alpha channel of 8-bit RGBA images. Other formats get no mask.
+fn mask_from_image(image: &Image) -> Option<PixelMask> {
+    if !matches!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb
+    ) {
+        return None;
+    }
+    let size = image.texture_descriptor.size;
+    let alpha = image.data.chunks_exact(4).map(|pixel| pixel[3]);
+    Some(PixelMask::from_alpha(size.width, size.height, alpha))
+}
+
+pub(crate) fn build_pixel_masks(
+    mut events: EventReader<AssetEvent<Image>>,
+    images: Res<Assets<Image>>,
+    mut masks: ResMut<PixelMasks>,
+) {
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                match images.get(handle).and_then(mask_from_image) {
+                    Some(mask) => masks.masks.insert(handle.clone_weak(), mask),
+                    None => masks.masks.remove(handle),
+                };
+            }
+            AssetEvent::Removed { handle } => {
+                masks.masks.remove(handle);
+            }
+        }
+    }
+}
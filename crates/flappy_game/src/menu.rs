@@ -0,0 +1,102 @@
+//! The main menu, where the game starts unless `FlappyConfig::initial_state`
+//! says otherwise.
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+
+use crate::bitmap_text::bitmap_text;
+use crate::buttons::spawn_text_button;
+use crate::{AppState, CoOp, DespawnOnExit, Localized};
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuButton {
+    Play,
+    /// Play with two birds, see `CoOp`.
+    CoOp,
+    Challenge,
+    Settings,
+    Customize,
+    Credits,
+    Quit,
+}
+
+pub(crate) fn add_main_menu(app: &mut App) {
+    app.add_system(spawn_main_menu.in_schedule(OnEnter(AppState::MainMenu)))
+        .add_system(choose_menu_button.in_set(OnUpdate(AppState::MainMenu)));
+}
+
+fn spawn_main_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            DespawnOnExit(AppState::MainMenu),
+        ))
+        .with_children(|parent| {
+            let (mut title, text) = bitmap_text("Flappy Bevy", 4.0, Color::WHITE);
+            title.style.margin = UiRect::bottom(Val::Px(8.0));
+            parent.spawn((title, text));
+            for (order, (button, label)) in [
+                (MenuButton::Play, "menu.play"),
+                (MenuButton::CoOp, "menu.co_op"),
+                (MenuButton::Challenge, "menu.challenge"),
+                (MenuButton::Settings, "menu.settings"),
+                (MenuButton::Customize, "menu.customize"),
+                (MenuButton::Credits, "menu.credits"),
+                (MenuButton::Quit, "menu.quit"),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                spawn_text_button(parent, Localized::new(label), order as i32, button);
+            }
+        });
+}
+
+fn choose_menu_button(
+    mut co_op: ResMut<CoOp>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exits: EventWriter<AppExit>,
+    query: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            MenuButton::Play => {
+                co_op.0 = false;
+                next_state.set(AppState::Game);
+            }
+            MenuButton::CoOp => {
+                co_op.0 = true;
+                next_state.set(AppState::Game);
+            }
+            MenuButton::Challenge => {
+                next_state.set(AppState::Challenge);
+            }
+            MenuButton::Settings => {
+                next_state.set(AppState::Settings);
+            }
+            MenuButton::Customize => {
+                next_state.set(AppState::Customize);
+            }
+            MenuButton::Credits => {
+                next_state.set(AppState::Credits);
+            }
+            MenuButton::Quit => {
+                info!("quitting");
+                exits.send(AppExit);
+            }
+        }
+    }
+}
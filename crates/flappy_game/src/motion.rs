@@ -0,0 +1,86 @@
+//! What kinds of motion effects may play, for motion-sensitive and
+//! photosensitive players. Effect systems check `MotionPrefs` before running,
+//! and the prefs follow `Settings::reduced_motion` and
+//! `Settings::photosensitive`.
+
+use bevy::prelude::*;
+
+use crate::Settings;
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MotionPrefs {
+    /// Camera shake and the death cam's zoom.
+    pub camera_motion: bool,
+    /// Full-screen flashes, like the one on hit stop.
+    pub flashes: bool,
+    /// Background layers scrolling at their own speeds.
+    pub parallax: bool,
+    /// Decorative particles such as sparkles and trail afterimages.
+    pub particles: bool,
+    /// Gradual changes to the sky's tint, such as from day to night. Tints
+    /// are applied at once when off.
+    pub sky_transitions: bool,
+}
+
+impl Default for MotionPrefs {
+    fn default() -> Self {
+        Self::full()
+    }
+}
+
+impl MotionPrefs {
+    pub fn full() -> Self {
+        Self {
+            camera_motion: true,
+            flashes: true,
+            parallax: true,
+            particles: true,
+            sky_transitions: true,
+        }
+    }
+
+    pub fn reduced() -> Self {
+        Self {
+            camera_motion: false,
+            flashes: false,
+            parallax: false,
+            particles: false,
+            sky_transitions: false,
+        }
+    }
+
+    /// Keeps camera motion and parallax, but nothing that flickers.
+    pub fn photosensitive() -> Self {
+        Self {
+            flashes: false,
+            particles: false,
+            sky_transitions: false,
+            ..Self::full()
+        }
+    }
+
+    pub fn for_settings(settings: &Settings) -> Self {
+        if settings.reduced_motion {
+            Self::reduced()
+        } else if settings.photosensitive {
+            Self::photosensitive()
+        } else {
+            Self::full()
+        }
+    }
+}
+
+pub(crate) fn add_motion_prefs(app: &mut App) {
+    app.init_resource::<MotionPrefs>()
+        .add_system(sync_motion_prefs.in_base_set(CoreSet::PreUpdate));
+}
+
+fn sync_motion_prefs(settings: Res<Settings>, mut prefs: ResMut<MotionPrefs>) {
+    if !settings.is_changed() {
+        return;
+    }
+    let selected = MotionPrefs::for_settings(&settings);
+    if *prefs != selected {
+        *prefs = selected;
+    }
+}
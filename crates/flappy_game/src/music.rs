@@ -0,0 +1,206 @@
+//! Music for each screen: a loop on the menus, another during runs, and a
+//! sting on game over. Changing screens crossfades between them over
+//! `FlappyConfig::music_crossfade` seconds rather than cutting.
+//!
+//! The fades are tracked in `Music` either way, but only heard with kira's
+//! `AudioPlugin`, and for tracks given a path in `AssetPaths`.
+
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::{AudioControl, AudioInstance, AudioPlugin, AudioTween};
+use bevy_kira_audio::AudioChannel as KiraChannel;
+
+use crate::audio::MusicChannel;
+use crate::{AppState, FlappyConfig, MusicAsset};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MusicTrack {
+    Menu,
+    Game,
+    GameOverSting,
+}
+
+impl MusicTrack {
+    /// What plays in `state`, if anything.
+    pub fn for_state(state: AppState) -> Option<MusicTrack> {
+        match state {
+            AppState::MainMenu
+            | AppState::Settings
+            | AppState::Physics
+            | AppState::Controls
+            | AppState::Accessibility
+            | AppState::Customize
+            | AppState::Credits
+            | AppState::Challenge => Some(MusicTrack::Menu),
+            AppState::Game => Some(MusicTrack::Game),
+            AppState::GameOver => Some(MusicTrack::GameOverSting),
+            AppState::Error => None,
+        }
+    }
+
+    /// The path to play, and where it loops from. The sting plays once.
+    /// `None` when there's no music for the track.
+    fn source(self, config: &FlappyConfig) -> Option<(&str, Option<f64>)> {
+        fn looped(music: &Option<MusicAsset>) -> Option<(&str, Option<f64>)> {
+            music
+                .as_ref()
+                .map(|music| (music.path.as_str(), Some(music.loop_from)))
+        }
+        let assets = &config.assets;
+        match self {
+            MusicTrack::Menu => looped(&assets.menu_music),
+            MusicTrack::Game => looped(&assets.game_music),
+            MusicTrack::GameOverSting => assets.game_over_sting.as_deref().map(|path| (path, None)),
+        }
+    }
+}
+
+/// Linear volume envelope between two levels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fade {
+    pub from: f32,
+    pub to: f32,
+    pub seconds: f32,
+    pub elapsed: f32,
+}
+
+impl Fade {
+    pub fn new(from: f32, to: f32, seconds: f32) -> Self {
+        Self {
+            from,
+            to,
+            seconds,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn level(&self) -> f32 {
+        if self.seconds <= 0.0 {
+            return self.to;
+        }
+        let t = (self.elapsed / self.seconds).min(1.0);
+        self.from + (self.to - self.from) * t
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.seconds
+    }
+}
+
+/// A track that is playing or fading out.
+#[derive(Debug)]
+pub struct PlayingTrack {
+    pub track: MusicTrack,
+    pub fade: Fade,
+    instance: Option<Handle<AudioInstance>>,
+}
+
+/// The tracks that can be heard, each with its own fade.
+#[derive(Resource, Default, Debug)]
+pub struct Music {
+    pub playing: Vec<PlayingTrack>,
+    /// The track last faded in.
+    pub current: Option<MusicTrack>,
+}
+
+impl Music {
+    /// Fades `track` in, from wherever it is if it's still fading out, and
+    /// everything else out.
+    pub fn crossfade_to(&mut self, track: Option<MusicTrack>, seconds: f32) {
+        self.current = track;
+        for playing in self.playing.iter_mut() {
+            let to = if Some(playing.track) == track {
+                1.0
+            } else {
+                0.0
+            };
+            playing.fade = Fade::new(playing.fade.level(), to, seconds);
+        }
+        if let Some(track) = track {
+            if !self.playing.iter().any(|playing| playing.track == track) {
+                self.playing.push(PlayingTrack {
+                    track,
+                    fade: Fade::new(0.0, 1.0, seconds),
+                    instance: None,
+                });
+            }
+        }
+    }
+
+    /// Current volume of `track` before the music channel's gain.
+    pub fn level(&self, track: MusicTrack) -> f32 {
+        self.playing
+            .iter()
+            .find(|playing| playing.track == track)
+            .map_or(0.0, |playing| playing.fade.level())
+    }
+}
+
+pub(crate) fn add_music(app: &mut App) {
+    app.init_resource::<Music>()
+        .add_system(choose_music)
+        .add_system(advance_fades.after(choose_music));
+    if app.is_plugin_added::<AudioPlugin>() {
+        app.add_system(play_music.after(advance_fades));
+    }
+}
+
+fn choose_music(config: Res<FlappyConfig>, state: Res<State<AppState>>, mut music: ResMut<Music>) {
+    let track = MusicTrack::for_state(state.0);
+    if track != music.current {
+        music.crossfade_to(track, config.music_crossfade);
+    }
+}
+
+/// Fades run in real time, so they carry on through hit stops and pauses.
+fn advance_fades(time: Res<Time>, mut music: ResMut<Music>) {
+    for playing in music.playing.iter_mut() {
+        playing.fade.elapsed += time.delta_seconds();
+    }
+    // faded out tracks are dropped, stopping them in `play_music`
+    music
+        .playing
+        .retain(|playing| !(playing.fade.finished() && playing.fade.to == 0.0));
+}
+
+fn play_music(
+    config: Res<FlappyConfig>,
+    asset_server: Res<AssetServer>,
+    channel: Res<KiraChannel<MusicChannel>>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut music: ResMut<Music>,
+    mut sounding: Local<Vec<Handle<AudioInstance>>>,
+) {
+    for playing in music.playing.iter_mut() {
+        if playing.instance.is_none() {
+            let Some((path, loop_from)) = playing.track.source(&config) else {
+                continue;
+            };
+            let mut command = channel.play(asset_server.load(path));
+            command.with_volume(f64::from(playing.fade.level()));
+            if let Some(loop_from) = loop_from {
+                command.loop_from(loop_from);
+            }
+            playing.instance = Some(command.handle());
+        }
+        let instance = playing.instance.as_ref();
+        if let Some(instance) = instance.and_then(|instance| instances.get_mut(instance)) {
+            instance.set_volume(f64::from(playing.fade.level()), AudioTween::default());
+        }
+    }
+
+    // stop whatever has faded out since last frame
+    let still_playing: Vec<_> = music
+        .playing
+        .iter()
+        .filter_map(|playing| playing.instance.clone())
+        .collect();
+    for instance in sounding
+        .iter()
+        .filter(|instance| !still_playing.contains(instance))
+    {
+        if let Some(instance) = instances.get_mut(instance) {
+            instance.stop(AudioTween::default());
+        }
+    }
+    *sounding = still_playing;
+}
@@ -0,0 +1,27 @@
+use bevy::prelude::*;
+use flappy_core::collision::offscreen;
+
+use crate::FlappyConfig;
+
+/// Despawns the entity (and its children) once it is more than `margin` world
+/// units outside the playfield.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct DespawnOffscreen {
+    pub margin: f32,
+}
+
+pub(crate) fn despawn_offscreen(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    query: Query<(Entity, &GlobalTransform, &DespawnOffscreen)>,
+) {
+    for (entity, transform, despawn) in query.iter() {
+        if offscreen(
+            transform.translation().truncate(),
+            config.playfield,
+            despawn.margin,
+        ) {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
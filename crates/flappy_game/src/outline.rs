@@ -0,0 +1,108 @@
+//! High-contrast outlines around the bird and pipes, enabled with
+//! `Settings::outlines`, so they stand out from the background without
+//! relying on colour.
+//!
+//! Each outline is a black silhouette of the sprite's own texture, drawn just
+//! behind it and scaled up to show a border.
+
+use bevy::prelude::*;
+
+use crate::{Bird, ConsoleCommands, Pipe, Settings};
+
+/// Border width in world units.
+const OUTLINE_WIDTH: f32 = 1.0;
+const OUTLINE_COLOR: Color = Color::BLACK;
+
+/// The silhouette drawn behind an outlined sprite.
+#[derive(Component)]
+pub struct Outline;
+
+/// On sprites that have an `Outline` child.
+#[derive(Component)]
+struct Outlined;
+
+pub(crate) fn add_outlines(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "outlines",
+        "outlines on|off: toggle high-contrast outlines",
+        toggle_outlines,
+    );
+    app.add_system(add_outlines_to_sprites)
+        .add_system(remove_outlines)
+        .add_system(fit_outlines);
+}
+
+fn toggle_outlines(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let outlines = match args.first() {
+        Some(&"on") => true,
+        Some(&"off") => false,
+        _ => return Err("expected 'outlines on' or 'outlines off'".to_string()),
+    };
+    world.resource_mut::<Settings>().outlines = outlines;
+    Ok(String::new())
+}
+
+#[allow(clippy::type_complexity)]
+fn add_outlines_to_sprites(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    query: Query<(Entity, &Handle<Image>), (Or<(With<Bird>, With<Pipe>)>, Without<Outlined>)>,
+) {
+    if !settings.outlines {
+        return;
+    }
+    for (entity, texture) in query.iter() {
+        commands
+            .entity(entity)
+            .insert(Outlined)
+            .with_children(|parent| {
+                parent.spawn((
+                    SpriteBundle {
+                        texture: texture.clone(),
+                        sprite: Sprite {
+                            color: OUTLINE_COLOR,
+                            ..default()
+                        },
+                        transform: Transform::from_xyz(0.0, 0.0, -0.001),
+                        ..default()
+                    },
+                    Outline,
+                ));
+            });
+    }
+}
+
+fn remove_outlines(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    outline_query: Query<Entity, With<Outline>>,
+    outlined_query: Query<Entity, With<Outlined>>,
+) {
+    if settings.outlines || !settings.is_changed() {
+        return;
+    }
+    for entity in outline_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in outlined_query.iter() {
+        commands.entity(entity).remove::<Outlined>();
+    }
+}
+
+/// Scales each silhouette so it sticks out by `OUTLINE_WIDTH` on every side,
+/// once its texture has loaded.
+fn fit_outlines(
+    images: Res<Assets<Image>>,
+    mut query: Query<(&Handle<Image>, &mut Transform), With<Outline>>,
+) {
+    for (texture, mut transform) in query.iter_mut() {
+        let Some(image) = images.get(texture) else {
+            continue;
+        };
+        let size = image.size();
+        let scale = ((size + 2.0 * OUTLINE_WIDTH) / size).extend(1.0);
+        if transform.scale != scale {
+            transform.scale = scale;
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Pausing mid-run. The pause key moves the run to `RunState::Paused`, which
+//! freezes every `OnUpdate(AppState::Game)` system. Resuming goes through
+//! `RunState::Countdown`, which counts down from three before the run carries
+//! on, so there's time to react to whatever was about to happen.
+
+use bevy::prelude::*;
+
+use crate::locale::localized_text;
+use crate::{
+    AppState, DespawnOnExit, DevConsole, Ease, FlappyConfig, Localized, RunState, Tween,
+    TweenTarget,
+};
+
+const COUNTDOWN_SECONDS: f32 = 3.0;
+
+/// Counts down in real time before the run carries on.
+#[derive(Resource, Debug)]
+pub struct Countdown {
+    pub timer: Timer,
+}
+
+impl Default for Countdown {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(COUNTDOWN_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl Countdown {
+    /// Whole seconds left, as shown on screen.
+    pub fn remaining(&self) -> u32 {
+        self.timer.remaining_secs().ceil() as u32
+    }
+}
+
+#[derive(Component)]
+struct CountdownText;
+
+pub(crate) fn add_pause(app: &mut App) {
+    app.init_resource::<Countdown>()
+        // not in `OnUpdate(AppState::Game)`, which is frozen while paused
+        .add_system(toggle_pause.run_if(in_state(AppState::Game)))
+        .add_system(end_run.in_schedule(OnExit(AppState::Game)))
+        .add_system(spawn_pause_screen.in_schedule(OnEnter(RunState::Paused)))
+        .add_system(start_countdown.in_schedule(OnEnter(RunState::Countdown)))
+        .add_system(run_countdown.in_set(OnUpdate(RunState::Countdown)));
+}
+
+fn toggle_pause(
+    keyboard_input: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    run_state: Res<State<RunState>>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+) {
+    if console.open {
+        return;
+    }
+    // phones have no pause key, so tapping the pause screen resumes too
+    let tapped = touches.any_just_pressed() && run_state.0 == RunState::Paused;
+    if !tapped && !keyboard_input.just_pressed(config.bindings.pause) {
+        return;
+    }
+    match run_state.0 {
+        RunState::Running => {
+            info!("paused");
+            next_run_state.set(RunState::Paused);
+        }
+        RunState::Paused => {
+            next_run_state.set(RunState::Countdown);
+        }
+        RunState::Countdown | RunState::Quitting => {}
+    }
+}
+
+/// Leaves whatever overlay the run was under along with the run.
+fn end_run(run_state: Res<State<RunState>>, mut next_run_state: ResMut<NextState<RunState>>) {
+    if run_state.0 != RunState::Running {
+        next_run_state.set(RunState::Running);
+    }
+}
+
+fn overlay() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: Color::rgba(0.0, 0.0, 0.0, 0.4).into(),
+        z_index: ZIndex::Global(100),
+        ..default()
+    }
+}
+
+fn spawn_pause_screen(mut commands: Commands) {
+    commands
+        .spawn((overlay(), DespawnOnExit(RunState::Paused)))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("pause.title"),
+                2.0,
+                Color::WHITE,
+            ));
+        });
+}
+
+fn start_countdown(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut countdown: ResMut<Countdown>,
+) {
+    *countdown = Countdown::default();
+    commands
+        .spawn((
+            NodeBundle {
+                background_color: Color::NONE.into(),
+                ..overlay()
+            },
+            DespawnOnExit(RunState::Countdown),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    countdown.remaining().to_string(),
+                    TextStyle {
+                        font: asset_server.load(config.assets.font.as_str()),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                CountdownText,
+            ));
+        });
+}
+
+/// Ticks in real time, as the run's own clock is frozen, and goes back to the
+/// run once it reaches zero.
+fn run_countdown(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut countdown: ResMut<Countdown>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut text_query: Query<(Entity, &mut Text), With<CountdownText>>,
+) {
+    let before = countdown.remaining();
+    if countdown.timer.tick(time.delta()).finished() {
+        info!("resumed");
+        next_run_state.set(RunState::Running);
+        return;
+    }
+    let remaining = countdown.remaining();
+    if remaining == before {
+        return;
+    }
+    for (entity, mut text) in text_query.iter_mut() {
+        text.sections[0].value = remaining.to_string();
+        commands.entity(entity).insert(Tween::new(
+            TweenTarget::Scale {
+                from: Vec3::splat(1.5),
+                to: Vec3::ONE,
+            },
+            0.25,
+            Ease::QuadOut,
+        ));
+    }
+}
@@ -0,0 +1,218 @@
+//! Saving resources to RON files in the user data directory. Each file notes
+//! the `Versioned::VERSION` of the format it was saved in, and files from
+//! older versions are migrated as they load rather than thrown away. Scores
+//! and stats also carry a checksum of what they hold, so a hand edited file is
+//! caught. Files that can't be read, or fail their checksum, are set aside
+//! next to themselves before the game starts afresh over them. Migrating
+//! skips the checksum, so what's migrated goes through
+//! `Versioned::mark_unverified` rather than being trusted.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::FlappyConfig;
+
+/// Mixed into every checksum, so editing a file means knowing the game's salt
+/// and not just running an FNV tool over it. Only meant to stop casual edits.
+const CHECKSUM_SALT: &str = "flappy_bevy";
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// A saved resource whose format can change between releases.
+pub(crate) trait Versioned: Serialize + DeserializeOwned {
+    /// The format saved now. Bump it when a change would lose what older
+    /// files hold, beyond the fields `#[serde(default)]` fills in, and teach
+    /// `migrate` the old format.
+    const VERSION: u32;
+    /// The version from which files carry a checksum, which files saved in
+    /// the current format must match. `None` for files players are free to
+    /// edit by hand. Older files are migrated unchecked, then passed through
+    /// `mark_unverified`.
+    const CHECKSUM_SINCE: Option<u32> = None;
+
+    /// Reads `text`, saved at `version`, which is older than `VERSION`. The
+    /// default reads the old value as the current one.
+    fn migrate(version: u32, text: &str) -> Result<Self, String> {
+        read_version(version, text)
+    }
+
+    /// Marks a value migrated without its checksum being checked, as anyone
+    /// could have written it. The default keeps it as it is.
+    fn mark_unverified(self) -> Self {
+        self
+    }
+}
+
+/// How files are laid out from version 1. Before that they held the value
+/// alone, and count as version 0.
+#[derive(Serialize, Deserialize)]
+struct Saved<T> {
+    version: u32,
+    data: T,
+    /// See `Versioned::CHECKSUM_SINCE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    checksum: Option<u64>,
+}
+
+/// Just the version of a file, whatever it holds.
+#[derive(Deserialize)]
+struct Header {
+    #[serde(default)]
+    version: u32,
+}
+
+/// Reads the value in `text`, laid out as files were at `version`, as a `T`.
+/// For `Versioned::migrate` to read old formats with.
+pub(crate) fn read_version<T: DeserializeOwned>(version: u32, text: &str) -> Result<T, String> {
+    let value = if version == 0 {
+        ron::from_str(text)
+    } else {
+        ron::from_str::<Saved<T>>(text).map(|saved| saved.data)
+    };
+    value.map_err(|error| error.to_string())
+}
+
+/// Reads `text`, migrating it if it was saved at an older version. Files from
+/// a newer version are refused rather than read wrong.
+pub(crate) fn parse_versioned<T: Versioned>(text: &str) -> Result<T, String> {
+    let version = ron::from_str::<Header>(text)
+        .map_err(|error| error.to_string())?
+        .version;
+    match version.cmp(&T::VERSION) {
+        Ordering::Equal if T::CHECKSUM_SINCE.is_some_and(|since| version >= since) => {
+            read_checked(text)
+        }
+        Ordering::Equal => read_version(version, text),
+        Ordering::Less => {
+            info!(from = version, to = T::VERSION, "migrating save");
+            let value = T::migrate(version, text)?;
+            Ok(if T::CHECKSUM_SINCE.is_some() {
+                value.mark_unverified()
+            } else {
+                value
+            })
+        }
+        Ordering::Greater => Err(format!(
+            "saved in format {version} by a newer version, this one reads up to {}",
+            T::VERSION
+        )),
+    }
+}
+
+/// Reads a file in the current format, refusing it when its checksum doesn't
+/// match what it holds.
+fn read_checked<T: Versioned>(text: &str) -> Result<T, String> {
+    let saved: Saved<T> = ron::from_str(text).map_err(|error| error.to_string())?;
+    if saved.checksum != Some(checksum(&saved.data)?) {
+        return Err("the checksum doesn't match, the file has been edited".to_string());
+    }
+    Ok(saved.data)
+}
+
+/// Salted FNV-1a of `value` written out as compact RON, which is the same
+/// however the file around it is laid out.
+fn checksum<T: Serialize>(value: &T) -> Result<u64, String> {
+    let text = ron::to_string(value).map_err(|error| error.to_string())?;
+    let hash = CHECKSUM_SALT
+        .bytes()
+        .chain(text.bytes())
+        .fold(FNV_OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+        });
+    Ok(hash)
+}
+
+/// Reads `path`. A missing file gives the default value.
+pub(crate) fn load_ron<T: Versioned + Default>(path: &Path) -> Result<T, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse_versioned(&text),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+/// `value` written out in the current format, as `parse_versioned` reads it.
+pub(crate) fn to_versioned_string<T: Versioned>(value: &T) -> Result<String, String> {
+    let saved = Saved {
+        version: T::VERSION,
+        data: value,
+        checksum: T::CHECKSUM_SINCE.map(|_| checksum(value)).transpose()?,
+    };
+    ron::ser::to_string_pretty(&saved, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())
+}
+
+/// Writes `value` to `path` in the current format.
+pub(crate) fn save_ron<T: Versioned>(value: &T, path: &Path) -> Result<(), String> {
+    let text = to_versioned_string(value)?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|error| error.to_string())
+}
+
+/// While inserted, changes to `T` aren't saved, so what's on disk stays as the
+/// player left it through a change meant to last a while only, like a
+/// challenge's modifiers.
+#[derive(Resource)]
+pub(crate) struct HoldSaves<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for HoldSaves<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Loads `T` from `path` into the app, and saves it there whenever it changes.
+/// Without a path it lives in memory only.
+pub(crate) fn add_persistent_resource<T>(
+    app: &mut App,
+    path: Option<PathBuf>,
+    path_of: fn(&FlappyConfig) -> Option<&PathBuf>,
+) where
+    T: Resource + Versioned + Default,
+{
+    let value = match &path {
+        Some(path) => load_ron(path).unwrap_or_else(|error| {
+            warn!(%error, path = %path.display(), "failed to load, starting afresh");
+            set_aside(path);
+            T::default()
+        }),
+        None => T::default(),
+    };
+    app.insert_resource(value)
+        .add_system(save_when_changed::<T>(path_of).in_base_set(CoreSet::Last));
+}
+
+/// Moves the unreadable file at `path` out of the way, so the next save
+/// doesn't overwrite it.
+fn set_aside(path: &Path) {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    match std::fs::rename(path, &backup) {
+        Ok(()) => info!(path = %Path::new(&backup).display(), "kept the unreadable file"),
+        Err(error) => warn!(%error, path = %path.display(), "failed to set the file aside"),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn save_when_changed<T: Resource + Versioned>(
+    path_of: fn(&FlappyConfig) -> Option<&PathBuf>,
+) -> impl FnMut(Res<FlappyConfig>, Res<T>, Option<Res<HoldSaves<T>>>) {
+    move |config, value, held| {
+        if !value.is_changed() || value.is_added() || held.is_some() {
+            return;
+        }
+        let Some(path) = path_of(&config) else {
+            return;
+        };
+        if let Err(error) = save_ron(&*value, path) {
+            warn!(%error, path = %path.display(), "failed to save");
+        }
+    }
+}
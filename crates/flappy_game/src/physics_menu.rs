@@ -0,0 +1,296 @@
+//! The advanced physics panel, reached from the settings screen: sliders for
+//! gravity, jump strength and pipe speed, for experimenting with how the game
+//! feels. They change `FlappyConfig::physics` live, like the console's `set`,
+//! and last until the game closes or they're reset. Runs played with the
+//! physics changed are unranked, see `RunRecord`.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use flappy_core::physics::Physics;
+
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, FlappyConfig, Localized, MenuFocus};
+
+/// Steps from one end of a `PhysicsSlider` to the other.
+const PHYSICS_STEPS: f32 = 20.0;
+/// The last of a `PhysicsSlider`'s steps.
+pub(crate) const MAX_PHYSICS_STEP: u32 = PHYSICS_STEPS as u32;
+
+/// Sets one of the `Physics` values within its `range`. Works like a
+/// `VolumeSlider`: click along it, or step with left and right while focused.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhysicsSlider {
+    Gravity,
+    Jump,
+    PipeSpeed,
+}
+
+impl PhysicsSlider {
+    pub const ALL: [PhysicsSlider; 3] = [
+        PhysicsSlider::Gravity,
+        PhysicsSlider::Jump,
+        PhysicsSlider::PipeSpeed,
+    ];
+
+    /// Lowest and highest values the slider reaches.
+    pub fn range(self) -> (f32, f32) {
+        match self {
+            PhysicsSlider::Gravity => (2.0, 12.0),
+            PhysicsSlider::Jump => (1.0, 3.0),
+            PhysicsSlider::PipeSpeed => (1.0, 3.0),
+        }
+    }
+
+    pub fn value(self, physics: &Physics) -> f32 {
+        match self {
+            PhysicsSlider::Gravity => physics.gravity,
+            PhysicsSlider::Jump => physics.jump_velocity,
+            PhysicsSlider::PipeSpeed => physics.pipe_speed,
+        }
+    }
+
+    pub fn value_mut(self, physics: &mut Physics) -> &mut f32 {
+        match self {
+            PhysicsSlider::Gravity => &mut physics.gravity,
+            PhysicsSlider::Jump => &mut physics.jump_velocity,
+            PhysicsSlider::PipeSpeed => &mut physics.pipe_speed,
+        }
+    }
+
+    /// How far along the range `physics` is, from 0 to 1.
+    fn level(self, physics: &Physics) -> f32 {
+        let (min, max) = self.range();
+        ((self.value(physics) - min) / (max - min)).clamp(0.0, 1.0)
+    }
+
+    /// The step of the slider nearest `physics`, counting up from the lowest
+    /// value.
+    pub fn step(self, physics: &Physics) -> u32 {
+        (self.level(physics) * PHYSICS_STEPS).round() as u32
+    }
+
+    /// Moves `physics` to `step` along the slider.
+    pub fn set_step(self, physics: &mut Physics, step: u32) {
+        let (min, max) = self.range();
+        let level = (step as f32 / PHYSICS_STEPS).min(1.0);
+        *self.value_mut(physics) = min + (max - min) * level;
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PhysicsSlider::Gravity => "physics.gravity",
+            PhysicsSlider::Jump => "physics.jump",
+            PhysicsSlider::PipeSpeed => "physics.pipe_speed",
+        }
+    }
+}
+
+/// Puts the physics back as the game started.
+#[derive(Component)]
+pub struct PhysicsResetButton;
+
+/// The physics from `FlappyGamePlugin::config`, for `PhysicsResetButton`.
+#[derive(Resource)]
+pub(crate) struct StartingPhysics(pub Physics);
+
+impl StartingPhysics {
+    /// Whether `physics` has been changed from these.
+    pub(crate) fn modified(&self, physics: &Physics) -> bool {
+        *physics != self.0
+    }
+}
+
+/// Bar along the bottom of a `PhysicsSlider` showing its level.
+#[derive(Component)]
+struct PhysicsFill;
+
+#[derive(Component)]
+struct PhysicsBackButton;
+
+pub(crate) fn add_physics_menu(app: &mut App, config: &FlappyConfig) {
+    app.insert_resource(StartingPhysics(config.physics))
+        .add_system(spawn_physics_menu.in_schedule(OnEnter(AppState::Physics)))
+        .add_systems(
+            (
+                adjust_physics,
+                reset_physics,
+                show_physics.after(adjust_physics).after(reset_physics),
+                leave_physics,
+            )
+                .in_set(OnUpdate(AppState::Physics)),
+        );
+}
+
+fn spawn_physics_menu(mut commands: Commands, config: Res<FlappyConfig>) {
+    let mut sliders = Vec::new();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Physics),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("physics.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            for (order, slider) in (0..).zip(PhysicsSlider::ALL) {
+                let value = slider.value(&config.physics);
+                sliders.push((
+                    spawn_text_button(
+                        parent,
+                        Localized::new(slider.label()).with_args([decimal(value)]),
+                        order,
+                        slider,
+                    ),
+                    slider.level(&config.physics),
+                ));
+            }
+            parent.spawn(localized_text(
+                Localized::new("physics.hint"),
+                1.0,
+                Color::GRAY,
+            ));
+            spawn_text_button(
+                parent,
+                Localized::new("physics.reset"),
+                3,
+                PhysicsResetButton,
+            );
+            spawn_text_button(parent, Localized::new("menu.back"), 4, PhysicsBackButton);
+        });
+    for (slider, level) in sliders {
+        commands.entity(slider).with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            left: Val::Px(0.0),
+                            bottom: Val::Px(0.0),
+                            ..default()
+                        },
+                        size: Size::new(Val::Percent(level * 100.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                },
+                PhysicsFill,
+            ));
+        });
+    }
+}
+
+fn decimal(value: f32) -> String {
+    format!("{value:.1}")
+}
+
+fn adjust_physics(
+    keyboard_input: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    focus: Res<MenuFocus>,
+    mut config: ResMut<FlappyConfig>,
+    query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &PhysicsSlider,
+        &Node,
+        &GlobalTransform,
+    )>,
+) {
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let step = 1.0 / PHYSICS_STEPS;
+    for (entity, interaction, slider, node, transform) in query.iter() {
+        let level = slider.level(&config.physics);
+        let mut new_level = level;
+        if interaction.is_changed() && *interaction == Interaction::Clicked {
+            let left = transform.translation().x - node.size().x / 2.0;
+            new_level = match cursor.map(|cursor| (cursor.x - left) / node.size().x) {
+                Some(along) if (0.0..=1.0).contains(&along) => along,
+                // clicked from the keyboard
+                _ if level >= 1.0 => 0.0,
+                _ => level + step,
+            };
+        } else if focus.focused == Some(entity) {
+            if keyboard_input.just_pressed(KeyCode::Left) || focus.stick.x < 0 {
+                new_level -= step;
+            }
+            if keyboard_input.just_pressed(KeyCode::Right) || focus.stick.x > 0 {
+                new_level += step;
+            }
+        }
+        if new_level == level {
+            continue;
+        }
+        // snapped to whole steps, so the labels stay round
+        let new_level = ((new_level / step).round() * step).clamp(0.0, 1.0);
+        let (min, max) = slider.range();
+        let value = min + (max - min) * new_level;
+        if value != slider.value(&config.physics) {
+            *slider.value_mut(&mut config.physics) = value;
+        }
+    }
+}
+
+fn reset_physics(
+    starting: Res<StartingPhysics>,
+    mut config: ResMut<FlappyConfig>,
+    query: Query<&Interaction, (Changed<Interaction>, With<PhysicsResetButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked && config.physics != starting.0 {
+        config.physics = starting.0;
+    }
+}
+
+fn show_physics(
+    config: Res<FlappyConfig>,
+    slider_query: Query<(&PhysicsSlider, &Children)>,
+    mut label_query: Query<&mut Localized>,
+    mut fill_query: Query<&mut Style, With<PhysicsFill>>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+    for (slider, children) in slider_query.iter() {
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.args = vec![decimal(slider.value(&config.physics))];
+        }
+        let mut fills = fill_query.iter_many_mut(children);
+        while let Some(mut style) = fills.fetch_next() {
+            style.size.width = Val::Percent(slider.level(&config.physics) * 100.0);
+        }
+    }
+}
+
+/// Back to the settings screen. The quit key does the same, see `quit`.
+fn leave_physics(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<PhysicsBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Settings);
+    }
+}
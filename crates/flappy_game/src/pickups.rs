@@ -0,0 +1,209 @@
+//! Coins and power-ups floating between the pipes. Coins are counted for the
+//! run in `Coins` and kept in `Profile::coins`. The magnet, while it lasts,
+//! steers nearby coins into the nearest bird.
+
+use bevy::prelude::*;
+use flappy_core::collision::Aabb;
+use flappy_core::physics::VELOCITY_SCALE;
+use rand::Rng;
+
+use crate::pipes::spawn_pipes;
+use crate::scroll::update_scroll_speed;
+use crate::{
+    apply_velocity, AppState, Bird, DespawnOffscreen, DespawnOnExit, FlappyConfig, GameSystem,
+    GameTick, Hitbox, Pace, PhysicsClock, PipeSpawnedEvent, Profile, RunSeed, RunStats,
+    ScrollSpeed, Scrolls, Velocity,
+};
+
+/// Size of every pickup's sprite and box.
+pub const PICKUP_SIZE: Vec2 = Vec2::new(8.0, 8.0);
+/// Chance of a coin between each pair of pipes and the next.
+const COIN_CHANCE: f64 = 0.5;
+/// Chance of a magnet in each gap.
+const MAGNET_CHANCE: f64 = 0.05;
+/// Seconds a magnet lasts.
+const MAGNET_SECONDS: f32 = 5.0;
+/// World units from the bird within which the magnet pulls coins.
+const MAGNET_RANGE: f32 = 96.0;
+/// Speed coins are pulled at, in the units of `Velocity`.
+const MAGNET_SPEED: f32 = 4.0;
+/// How quickly a pulled coin turns towards the bird, per second.
+const MAGNET_STEER: f32 = 10.0;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pickup {
+    Coin,
+    Magnet,
+}
+
+impl Pickup {
+    fn color(self) -> Color {
+        match self {
+            Pickup::Coin => Color::rgb(1.0, 0.8, 0.1),
+            Pickup::Magnet => Color::rgb(0.9, 0.2, 0.2),
+        }
+    }
+}
+
+/// Coins picked up during the current run.
+#[derive(Resource, Default, Debug, Deref, DerefMut)]
+pub struct Coins(pub u32);
+
+/// The magnet power-up, counting down while it pulls.
+#[derive(Resource, Default, Debug)]
+pub struct Magnet {
+    pub remaining: f32,
+}
+
+pub(crate) fn add_pickups(app: &mut App) {
+    app.init_resource::<Coins>()
+        .init_resource::<Magnet>()
+        .add_system(reset_pickups.in_schedule(OnEnter(AppState::Game)))
+        .add_system(
+            spawn_pickups
+                .after(spawn_pipes)
+                .in_set(GameSystem::Spawning)
+                .in_schedule(GameTick),
+        )
+        .add_system(
+            pull_coins
+                .after(update_scroll_speed)
+                .before(apply_velocity)
+                .in_set(GameSystem::Movement)
+                .in_schedule(GameTick),
+        )
+        .add_system(
+            collect_pickups
+                .in_set(GameSystem::Scoring)
+                .in_schedule(GameTick),
+        );
+}
+
+fn reset_pickups(mut coins: ResMut<Coins>, mut magnet: ResMut<Magnet>) {
+    coins.0 = 0;
+    magnet.remaining = 0.0;
+}
+
+/// Spawns a pickup of `kind` at `position`, scrolling with the level and
+/// drifting with `velocity` on top.
+pub fn spawn_pickup(
+    commands: &mut Commands,
+    config: &FlappyConfig,
+    kind: Pickup,
+    position: Vec2,
+    velocity: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(),
+                    custom_size: Some(PICKUP_SIZE),
+                    ..default()
+                },
+                transform: Transform::from_translation(position.extend(0.5)),
+                ..default()
+            },
+            kind,
+            Velocity(velocity),
+            Scrolls(1.0),
+            DespawnOffscreen {
+                margin: config.width(),
+            },
+            DespawnOnExit(AppState::Game),
+        ))
+        .id()
+}
+
+/// Sometimes puts a magnet in each new gap, and a coin halfway to the next.
+/// They come from their own stream of the `RunSeed`, so the seeded gaps stay
+/// the same.
+fn spawn_pickups(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    pace: Res<Pace>,
+    mut seed: ResMut<RunSeed>,
+    mut spawned: EventReader<PipeSpawnedEvent>,
+) {
+    let rng = seed.pickups_rng();
+    for event in spawned.iter() {
+        let x = config.width() / 2.0;
+        if rng.gen_bool(MAGNET_CHANCE) {
+            let position = Vec2::new(x, event.gap.center);
+            spawn_pickup(&mut commands, &config, Pickup::Magnet, position, Vec2::ZERO);
+        }
+        if rng.gen_bool(COIN_CHANCE) {
+            let spacing = pace.pipe_speed * VELOCITY_SCALE * pace.interval;
+            let height = rng.gen_range(pace.gaps.min_center..pace.gaps.max_center);
+            let position = Vec2::new(x + spacing / 2.0, height);
+            spawn_pickup(&mut commands, &config, Pickup::Coin, position, Vec2::ZERO);
+        }
+    }
+}
+
+/// Turns coins within `MAGNET_RANGE` towards the nearest bird while the magnet
+/// lasts.
+fn pull_coins(
+    clock: Res<PhysicsClock>,
+    scroll: Res<ScrollSpeed>,
+    mut magnet: ResMut<Magnet>,
+    bird_query: Query<&Transform, With<Bird>>,
+    mut coin_query: Query<(&Transform, &Pickup, &mut Velocity, &mut Scrolls), Without<Bird>>,
+) {
+    if magnet.remaining == 0.0 {
+        return;
+    }
+    let dt = clock.step;
+    magnet.remaining = (magnet.remaining - dt).max(0.0);
+    let steer = (MAGNET_STEER * dt).min(1.0);
+    for (transform, pickup, mut velocity, mut scrolls) in coin_query.iter_mut() {
+        let coin = transform.translation.truncate();
+        let nearest = bird_query
+            .iter()
+            .map(|bird| bird.translation.truncate() - coin)
+            .min_by(|a, b| a.length().total_cmp(&b.length()));
+        let Some(offset) = nearest else {
+            return;
+        };
+        if *pickup != Pickup::Coin || offset.length() > MAGNET_RANGE {
+            continue;
+        }
+        // pulled coins leave the level behind for the magnet to steer
+        velocity.0 += scrolls.velocity(*scroll);
+        scrolls.0 = 0.0;
+        let wanted = offset.normalize_or_zero() * MAGNET_SPEED;
+        let current = velocity.0;
+        velocity.0 = current + (wanted - current) * steer;
+    }
+}
+
+fn collect_pickups(
+    mut commands: Commands,
+    mut coins: ResMut<Coins>,
+    mut magnet: ResMut<Magnet>,
+    mut profile: ResMut<Profile>,
+    mut stats: ResMut<RunStats>,
+    bird_query: Query<(&Transform, &Hitbox), With<Bird>>,
+    pickup_query: Query<(Entity, &Transform, &Pickup)>,
+) {
+    for (bird, hitbox) in bird_query.iter() {
+        let bird_box = Aabb::new(bird.translation.truncate(), hitbox.size);
+        for (entity, transform, pickup) in pickup_query.iter() {
+            if !Aabb::new(transform.translation.truncate(), PICKUP_SIZE).intersects(&bird_box) {
+                continue;
+            }
+            commands.entity(entity).despawn();
+            match pickup {
+                Pickup::Coin => {
+                    coins.0 += 1;
+                    profile.coins += 1;
+                    stats.coins += 1;
+                }
+                Pickup::Magnet => {
+                    debug!("magnet picked up");
+                    magnet.remaining = MAGNET_SECONDS;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use flappy_core::collision::{offscreen, Aabb};
+use flappy_core::pipes::{Gap, GapParams, GoldenParams};
+use flappy_core::PIPE_SIZE;
+
+use crate::{
+    AppState, Bird, Clearance, Collider, DespawnOnExit, DifficultyCurves, FlappyConfig, HitStop,
+    Hitbox, Pace, PhysicsClock, PipeSpawnedEvent, RunSeed, RunStats, ScoreEvent, Scrolls, Settings,
+    Velocity,
+};
+
+/// Scored for getting through a `GoldenGap`.
+pub const GOLDEN_POINTS: u32 = 5;
+const GOLDEN_COLOR: Color = Color::rgba(1.0, 0.85, 0.2, 0.35);
+
+/// How far past the edge of the playfield a pair travels before it is recycled,
+/// as a fraction of the playfield width.
+const RECYCLE_MARGIN: f32 = 1.0 / 6.0;
+
+#[derive(Component)]
+pub struct Pipe;
+
+/// Parent of a top and bottom `Pipe`, positioned at the centre of their gap.
+///
+/// Pairs that leave the screen are parked in the `PipePool` and repositioned
+/// when the next pair is due, rather than being despawned.
+#[derive(Component)]
+pub struct PipePair {
+    pub top: Entity,
+    pub bottom: Entity,
+    pub sensor: Entity,
+    /// False while the pair is parked in the pool.
+    pub active: bool,
+}
+
+/// Invisible zone filling the gap of a `PipePair`. The bird scores `points`
+/// the first time it touches one.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GapSensor {
+    pub size: Vec2,
+    pub points: u32,
+}
+
+/// A smaller second gap in a `PipePair`, past a short divider from the main
+/// one, and worth `GOLDEN_POINTS`. Its divider and sensor are children of the
+/// pair, despawned when the pair is reused.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct GoldenGap {
+    pub gap: Gap,
+    pub divider: Entity,
+    pub sensor: Entity,
+}
+
+/// Set once the bird has touched a `GapSensor`.
+#[derive(Component)]
+pub struct Scored(pub bool);
+
+/// Counts down to the next pair, restarted with the `Pace`'s interval after
+/// each.
+#[derive(Component, Default, Deref, DerefMut, Reflect)]
+#[reflect(Component)]
+pub struct PipeTimer(pub Timer);
+
+/// Inactive pipe pairs waiting to be reused.
+#[derive(Resource, Default)]
+pub struct PipePool {
+    pub idle: Vec<Entity>,
+}
+
+pub(crate) fn pipes_setup(
+    mut commands: Commands,
+    mut pool: ResMut<PipePool>,
+    settings: Res<Settings>,
+    curves: Res<DifficultyCurves>,
+) {
+    // anything left in the pool was despawned with the previous scene
+    pool.idle.clear();
+
+    // the score may not have been reset for the `Pace` yet
+    let interval = curves.get(settings.difficulty).at(0).interval;
+    commands.spawn((
+        PipeTimer(Timer::from_seconds(interval, TimerMode::Repeating)),
+        DespawnOnExit(AppState::Game),
+    ));
+}
+
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+pub(crate) fn spawn_pipes(
+    clock: Res<PhysicsClock>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut pool: ResMut<PipePool>,
+    pace: Res<Pace>,
+    mut seed: ResMut<RunSeed>,
+    mut spawned: EventWriter<PipeSpawnedEvent>,
+    mut timer_query: Query<&mut PipeTimer>,
+    mut pair_query: Query<
+        (
+            &mut Transform,
+            &mut Scrolls,
+            &mut Visibility,
+            &mut PipePair,
+            Option<&GoldenGap>,
+        ),
+        Without<Pipe>,
+    >,
+    mut pipe_query: Query<&mut Transform, (With<Pipe>, Without<PipePair>)>,
+    mut sensor_query: Query<(&mut GapSensor, &mut Scored, &mut Clearance)>,
+) {
+    for mut timer in &mut timer_query {
+        timer.tick(Duration::from_secs_f32(clock.step));
+        if !timer.just_finished() {
+            continue;
+        }
+        timer.set_duration(Duration::from_secs_f32(pace.interval));
+
+        let gap = pace.gaps.sample(seed.rng());
+        let golden = GoldenParams::default().sample(gap, seed.extras_rng());
+        let _span = info_span!("spawn_pair", pooled = pool.idle.len()).entered();
+        let Some(entity) = pool.idle.pop() else {
+            let (pair, top, bottom) = spawn_pair_parts(&mut commands, &asset_server, &config, gap);
+            if let Some(golden) = golden {
+                let (top_y, bottom_y) = pipe_offsets(gap, Some(golden));
+                commands
+                    .entity(top)
+                    .insert(Transform::from_xyz(0.0, top_y, 0.0));
+                commands
+                    .entity(bottom)
+                    .insert(Transform::from_xyz(0.0, bottom_y, 0.0));
+                open_golden_gap(&mut commands, &asset_server, &config, pair, gap, golden);
+            }
+            spawned.send(PipeSpawnedEvent { pair, gap, golden });
+            continue;
+        };
+        let Ok((mut transform, mut scrolls, mut visibility, mut pair, old_golden)) =
+            pair_query.get_mut(entity)
+        else {
+            continue;
+        };
+        if let Some(old_golden) = old_golden {
+            commands.entity(old_golden.divider).despawn_recursive();
+            commands.entity(old_golden.sensor).despawn_recursive();
+            commands.entity(entity).remove::<GoldenGap>();
+        }
+
+        transform.translation.x = config.width() / 2.0;
+        transform.translation.y = gap.center;
+        *scrolls = Scrolls(1.0);
+        *visibility = Visibility::Inherited;
+        pair.active = true;
+
+        let (top, bottom) = pipe_offsets(gap, golden);
+        if let Ok(mut top_transform) = pipe_query.get_mut(pair.top) {
+            top_transform.translation.y = top;
+        }
+        if let Ok(mut bottom_transform) = pipe_query.get_mut(pair.bottom) {
+            bottom_transform.translation.y = bottom;
+        }
+        if let Ok((mut sensor, mut scored, mut clearance)) = sensor_query.get_mut(pair.sensor) {
+            sensor.size.y = gap.size;
+            scored.0 = false;
+            *clearance = Clearance::default();
+        }
+        if let Some(golden) = golden {
+            open_golden_gap(&mut commands, &asset_server, &config, entity, gap, golden);
+        }
+        spawned.send(PipeSpawnedEvent {
+            pair: entity,
+            gap,
+            golden,
+        });
+    }
+}
+
+/// Spawns a new pipe pair at the right edge of the playfield with a random gap.
+pub fn spawn_pipe_couple(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &FlappyConfig,
+) -> Entity {
+    let gap = GapParams::for_height(config.height()).sample(&mut rand::thread_rng());
+    spawn_pipe_pair(commands, asset_server, config, gap)
+}
+
+/// Spawns a new pipe pair at the right edge of the playfield around `gap`.
+pub fn spawn_pipe_pair(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &FlappyConfig,
+    gap: Gap,
+) -> Entity {
+    spawn_pair_parts(commands, asset_server, config, gap).0
+}
+
+/// `spawn_pipe_pair`, also returning the (pair, top, bottom) pipes.
+fn spawn_pair_parts(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &FlappyConfig,
+    gap: Gap,
+) -> (Entity, Entity, Entity) {
+    let (top_y, bottom_y) = pipe_offsets(gap, None);
+    let top = commands
+        .spawn((
+            SpriteBundle {
+                texture: asset_server.load(config.assets.pipe_top.as_str()),
+                transform: Transform::from_xyz(0.0, top_y, 0.0),
+                ..default()
+            },
+            Pipe,
+            Collider { size: PIPE_SIZE },
+        ))
+        .id();
+    let bottom = commands
+        .spawn((
+            SpriteBundle {
+                texture: asset_server.load(config.assets.pipe_bottom.as_str()),
+                transform: Transform::from_xyz(0.0, bottom_y, 0.0),
+                ..default()
+            },
+            Pipe,
+            Collider { size: PIPE_SIZE },
+        ))
+        .id();
+    let sensor = commands
+        .spawn((
+            TransformBundle::default(),
+            GapSensor {
+                size: gap.sensor(0.0).size,
+                points: 1,
+            },
+            Scored(false),
+            Clearance::default(),
+        ))
+        .id();
+
+    let pair = commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_xyz(
+                config.width() / 2.0,
+                gap.center,
+                0.0,
+            )),
+            PipePair {
+                top,
+                bottom,
+                sensor,
+                active: true,
+            },
+            DespawnOnExit(AppState::Game),
+            Velocity::default(),
+            Scrolls(1.0),
+        ))
+        .push_children(&[top, bottom, sensor])
+        .id();
+    (pair, top, bottom)
+}
+
+/// Offsets of the (top, bottom) pipes from the centre of `gap`, moved past
+/// `golden` when there is one.
+fn pipe_offsets(gap: Gap, golden: Option<Gap>) -> (f32, f32) {
+    let (top, bottom) = golden.map_or(gap, |golden| gap.span(golden)).pipe_centers();
+    (top - gap.center, bottom - gap.center)
+}
+
+/// Adds `golden`'s divider and sensor to `pair`, whose main gap is `gap`.
+fn open_golden_gap(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &FlappyConfig,
+    pair: Entity,
+    gap: Gap,
+    golden: Gap,
+) {
+    let divider = gap.between(golden);
+    let size = Vec2::new(PIPE_SIZE.x, divider.size);
+    let divider = commands
+        .spawn((
+            SpriteBundle {
+                texture: asset_server.load(config.assets.pipe_bottom.as_str()),
+                sprite: Sprite {
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, divider.center - gap.center, 0.0),
+                ..default()
+            },
+            Collider { size },
+        ))
+        .id();
+    let size = Vec2::new(PIPE_SIZE.x, golden.size);
+    let sensor = commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: GOLDEN_COLOR,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, golden.center - gap.center, -0.1),
+                ..default()
+            },
+            GapSensor {
+                size,
+                points: GOLDEN_POINTS,
+            },
+            Scored(false),
+            Clearance::default(),
+        ))
+        .id();
+    commands
+        .entity(pair)
+        .insert(GoldenGap {
+            gap: golden,
+            divider,
+            sensor,
+        })
+        .push_children(&[divider, sensor]);
+}
+
+/// Scores each gap once, for the first bird into it, and notes how close to
+/// the pipes birds come all the way through.
+pub(crate) fn score_gap_sensors(
+    hit_stop: Res<HitStop>,
+    mut scores: EventWriter<ScoreEvent>,
+    mut stats: ResMut<RunStats>,
+    bird_query: Query<(&GlobalTransform, &Hitbox), With<Bird>>,
+    pair_query: Query<&PipePair>,
+    mut sensor_query: Query<(
+        &Parent,
+        &GlobalTransform,
+        &GapSensor,
+        &mut Scored,
+        &mut Clearance,
+    )>,
+) {
+    // the run is over with the first death, even with a `CoOp` bird still up
+    if hit_stop.timer.is_some() {
+        return;
+    }
+    for (parent, transform, sensor, mut scored, mut clearance) in sensor_query.iter_mut() {
+        let active = pair_query.get(parent.get()).is_ok_and(|pair| pair.active);
+        let sensor_position = transform.translation().truncate();
+        let sensor_box = Aabb::new(sensor_position, sensor.size);
+        let mut inside = false;
+        for (bird_transform, hitbox) in bird_query.iter() {
+            let bird_position = bird_transform.translation().truncate();
+            if !active || !sensor_box.intersects(&Aabb::new(bird_position, hitbox.size)) {
+                continue;
+            }
+            inside = true;
+            let offset = bird_position.y - sensor_position.y;
+            let room = (sensor.size.y - hitbox.size.y) / 2.0;
+            clearance.note(room - offset.abs());
+            if !scored.0 {
+                stats.record_gap(offset, room);
+                scores.send(ScoreEvent {
+                    pair: parent.get(),
+                    points: sensor.points,
+                });
+                scored.0 = true;
+            }
+        }
+        if inside {
+            continue;
+        }
+        if let Some(closest) = clearance.closest.take() {
+            stats.record_clearance(closest);
+        }
+    }
+}
+
+pub(crate) fn recycle_offscreen_pipes(
+    config: Res<FlappyConfig>,
+    mut pool: ResMut<PipePool>,
+    mut query: Query<(
+        Entity,
+        &Transform,
+        &mut Scrolls,
+        &mut Visibility,
+        &mut PipePair,
+    )>,
+) {
+    for (entity, transform, mut scrolls, mut visibility, mut pair) in query.iter_mut() {
+        let margin = config.width() * RECYCLE_MARGIN;
+        if pair.active && offscreen(transform.translation.truncate(), config.playfield, margin) {
+            pair.active = false;
+            scrolls.0 = 0.0;
+            *visibility = Visibility::Hidden;
+            pool.idle.push(entity);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Per-player progress that outlives the session, kept as RON at
+//! `FlappyConfig::profile_path`.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{add_persistent_resource, load_ron, save_ron, Versioned};
+use crate::{BirdLook, Cosmetic, FlappyConfig};
+
+/// Progress saved between sessions. Missing fields take their defaults, so
+/// older files still load.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Profile {
+    /// The first-run hints have been seen through.
+    pub tutorial_done: bool,
+    /// Highest score over every ranked run, see `RunRecord`.
+    pub best: u32,
+    /// Highest score over every `CoOp` run, kept apart from `best` as two
+    /// birds score more easily than one.
+    pub co_op_best: u32,
+    /// Coins picked up over every run, see `Coins`.
+    pub coins: u32,
+    /// The bird's colours and accessory, see `customize`.
+    pub bird: BirdLook,
+    /// Cosmetics whose unlocking has been announced, see `UnlockToast`.
+    pub unlocked: Vec<Cosmetic>,
+    /// The bests and `coins` came from a save with no checksum, from before
+    /// checksums or an old cloud copy, so nothing vouches for them. Cleared
+    /// once a run beats that best.
+    pub unverified: bool,
+}
+
+impl Profile {
+    /// Reads the profile at `path`. A missing file is a fresh profile.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        load_ron(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        save_ron(self, path)
+    }
+
+    /// The best of this and `other`, for combining saves from two devices.
+    pub fn merged(&self, other: &Profile) -> Profile {
+        let best = self.best.max(other.best);
+        // the best stays unverified unless a vouched for copy holds it too
+        let vouched = |profile: &Profile| profile.best == best && !profile.unverified;
+        Profile {
+            tutorial_done: self.tutorial_done || other.tutorial_done,
+            best,
+            co_op_best: self.co_op_best.max(other.co_op_best),
+            coins: self.coins.max(other.coins),
+            // a look can't be better than another, so this device's stays
+            bird: self.bird,
+            unlocked: self.unlocked.iter().chain(&other.unlocked).fold(
+                Vec::new(),
+                |mut unlocked, cosmetic| {
+                    if !unlocked.contains(cosmetic) {
+                        unlocked.push(*cosmetic);
+                    }
+                    unlocked
+                },
+            ),
+            unverified: !(vouched(self) || vouched(other)),
+        }
+    }
+}
+
+impl Versioned for Profile {
+    const VERSION: u32 = 2;
+    const CHECKSUM_SINCE: Option<u32> = Some(2);
+
+    fn mark_unverified(self) -> Self {
+        Profile {
+            unverified: self.best > 0 || self.co_op_best > 0 || self.coins > 0,
+            ..self
+        }
+    }
+}
+
+pub(crate) fn add_profile(app: &mut App, config: &FlappyConfig) {
+    add_persistent_resource::<Profile>(app, config.profile_path.clone(), |config| {
+        config.profile_path.as_ref()
+    });
+}
@@ -0,0 +1,150 @@
+//! Confirmation before quitting mid-run. Closing the window or pressing the
+//! quit key during a run moves it to `RunState::Quitting`, which freezes the
+//! run and asks whether to give it up, and goes back to it through the resume
+//! countdown if not. Anywhere else the game just exits.
+//!
+//! Bevy closes windows on request by default, before the question can be
+//! asked. Host apps should set `WindowPlugin::close_when_requested` to `false`
+//! and leave closing to this module.
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::WindowCloseRequested;
+
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, DevConsole, FlappyConfig, Localized, RunState};
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuitButton {
+    Quit,
+    KeepPlaying,
+}
+
+pub(crate) fn add_quit_confirmation(app: &mut App) {
+    // headless apps have no `WindowPlugin` to register it
+    app.add_event::<WindowCloseRequested>()
+        .add_system(request_quit)
+        .add_system(spawn_quit_dialog.in_schedule(OnEnter(RunState::Quitting)))
+        .add_system(answer_quit_dialog.in_set(OnUpdate(RunState::Quitting)));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn request_quit(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    console: Res<DevConsole>,
+    mut close_requests: EventReader<WindowCloseRequested>,
+    app_state: Res<State<AppState>>,
+    run_state: Res<State<RunState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut exits: EventWriter<AppExit>,
+) {
+    let closing = close_requests.iter().count() > 0;
+    let key = !console.open && keyboard_input.just_pressed(config.bindings.quit);
+    if !closing && !key {
+        return;
+    }
+    match (app_state.0, run_state.0) {
+        (AppState::Game, RunState::Running | RunState::Paused | RunState::Countdown) => {
+            info!("asking to quit");
+            next_run_state.set(RunState::Quitting);
+        }
+        // a second request while asking is taken as the answer
+        (AppState::Game, RunState::Quitting) if closing => {
+            info!("quitting");
+            exits.send(AppExit);
+        }
+        (AppState::Game, RunState::Quitting) => {
+            next_run_state.set(RunState::Countdown);
+        }
+        (AppState::Physics | AppState::Controls | AppState::Accessibility, _) if !closing => {
+            next_state.set(AppState::Settings);
+        }
+        (AppState::Settings | AppState::Customize | AppState::Credits | AppState::Challenge, _)
+            if !closing =>
+        {
+            next_state.set(AppState::MainMenu);
+        }
+        (
+            AppState::MainMenu
+            | AppState::Settings
+            | AppState::Physics
+            | AppState::Controls
+            | AppState::Accessibility
+            | AppState::Customize
+            | AppState::Credits
+            | AppState::Challenge
+            | AppState::GameOver
+            | AppState::Error,
+            _,
+        ) => {
+            info!("quitting");
+            exits.send(AppExit);
+        }
+    }
+}
+
+fn spawn_quit_dialog(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                z_index: ZIndex::Global(100),
+                ..default()
+            },
+            DespawnOnExit(RunState::Quitting),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("quit.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            parent.spawn(localized_text(
+                Localized::new("quit.warning"),
+                1.0,
+                Color::WHITE,
+            ));
+            // keeping the run is the safe choice, so it comes first
+            for (order, (button, label)) in [
+                (QuitButton::KeepPlaying, "quit.keep_playing"),
+                (QuitButton::Quit, "quit.confirm"),
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                spawn_text_button(parent, Localized::new(label), order as i32, button);
+            }
+        });
+}
+
+fn answer_quit_dialog(
+    mut next_run_state: ResMut<NextState<RunState>>,
+    mut exits: EventWriter<AppExit>,
+    query: Query<(&Interaction, &QuitButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match button {
+            QuitButton::Quit => {
+                info!("quitting");
+                exits.send(AppExit);
+            }
+            QuitButton::KeepPlaying => {
+                next_run_state.set(RunState::Countdown);
+            }
+        }
+    }
+}
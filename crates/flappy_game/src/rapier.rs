@@ -0,0 +1,217 @@
+//! Rapier physics backend, selected with `PhysicsBackend::Rapier`.
+//!
+//! Gameplay code keeps reading and writing our own `Velocity` and `Gravity`;
+//! these systems mirror them into rapier bodies around the flapping systems
+//! and turn rapier contacts into `DeathEvent`s. Rapier's own systems are
+//! taken out of the frame and stepped in the `GameTick` too, by the
+//! `PhysicsClock` step, so it freezes and slows with the rest of the run and
+//! its contacts are read on the tick that made them.
+
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+use bevy_rapier2d::prelude as rapier;
+use flappy_core::physics::VELOCITY_SCALE;
+
+use crate::interpolation::tick_physics_clock;
+use crate::scroll::update_scroll_speed;
+use crate::tick::run_game_ticks;
+use crate::{
+    jump, AppState, Bird, Blocking, Boundary, Collider, DeathCause, DeathEvent, FlappyConfig,
+    GameSystem, GameTick, Gravity, Hazard, HitStop, Hitbox, PhysicsClock, Pickup, PipePair,
+    ScrollSpeed, Scrolls, Velocity,
+};
+
+type RapierPlugin = rapier::RapierPhysicsPlugin<rapier::NoUserData>;
+
+pub(crate) fn add_rapier_backend(app: &mut App) {
+    app.add_plugin(RapierPlugin::default().with_default_system_setup(false))
+        .edit_schedule(GameTick, |schedule| {
+            schedule.configure_sets(
+                (
+                    rapier::PhysicsSet::SyncBackend,
+                    rapier::PhysicsSet::SyncBackendFlush,
+                    rapier::PhysicsSet::StepSimulation,
+                    rapier::PhysicsSet::Writeback,
+                )
+                    .chain()
+                    .after(GameSystem::Movement)
+                    .before(TransformSystem::TransformPropagate),
+            );
+        });
+    for set in [
+        rapier::PhysicsSet::SyncBackend,
+        rapier::PhysicsSet::SyncBackendFlush,
+        rapier::PhysicsSet::StepSimulation,
+        rapier::PhysicsSet::Writeback,
+    ] {
+        app.add_systems(
+            RapierPlugin::get_systems(set.clone())
+                .in_base_set(set)
+                .in_schedule(GameTick),
+        );
+    }
+    app.add_system(sync_gravity)
+        .add_system(
+            sync_timestep
+                .after(tick_physics_clock)
+                .before(run_game_ticks)
+                .in_set(OnUpdate(AppState::Game)),
+        )
+        .add_systems(
+            (
+                add_bird_bodies,
+                add_pipe_bodies,
+                add_pickup_bodies,
+                add_hazard_bodies,
+                add_colliders,
+                pull_bird_velocity.before(jump).in_set(GameSystem::Input),
+                push_velocities
+                    .after(update_scroll_speed)
+                    .in_set(GameSystem::Movement),
+                detect_collisions.in_set(GameSystem::Collisions),
+            )
+                .in_schedule(GameTick),
+        );
+}
+
+fn sync_gravity(config: Res<FlappyConfig>, mut rapier_config: ResMut<rapier::RapierConfiguration>) {
+    if config.is_changed() {
+        let gravity = config.physics.gravity * VELOCITY_SCALE;
+        rapier_config.gravity = Vec2::new(0.0, -gravity);
+    }
+}
+
+/// Steps rapier by the `PhysicsClock` step each tick. Nothing moves on steps
+/// of no time, which rapier can't take.
+fn sync_timestep(clock: Res<PhysicsClock>, mut rapier_config: ResMut<rapier::RapierConfiguration>) {
+    rapier_config.timestep_mode = rapier::TimestepMode::Fixed {
+        dt: clock.step,
+        substeps: 1,
+    };
+    rapier_config.physics_pipeline_active = clock.step > 0.0;
+}
+
+fn add_bird_bodies(mut commands: Commands, query: Query<(Entity, &Hitbox), Added<Bird>>) {
+    for (entity, hitbox) in query.iter() {
+        commands.entity(entity).insert((
+            rapier::RigidBody::Dynamic,
+            rapier::Collider::cuboid(hitbox.size.x / 2.0, hitbox.size.y / 2.0),
+            rapier::LockedAxes::ROTATION_LOCKED | rapier::LockedAxes::TRANSLATION_LOCKED_X,
+            rapier::GravityScale(0.0),
+            rapier::Velocity::zero(),
+            rapier::ActiveEvents::COLLISION_EVENTS,
+        ));
+    }
+}
+
+fn add_pipe_bodies(mut commands: Commands, query: Query<Entity, Added<PipePair>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert((
+            rapier::RigidBody::KinematicVelocityBased,
+            rapier::Velocity::zero(),
+        ));
+    }
+}
+
+fn add_pickup_bodies(mut commands: Commands, query: Query<Entity, Added<Pickup>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert((
+            rapier::RigidBody::KinematicVelocityBased,
+            rapier::Velocity::zero(),
+        ));
+    }
+}
+
+fn add_hazard_bodies(mut commands: Commands, query: Query<Entity, Added<Hazard>>) {
+    for entity in query.iter() {
+        commands.entity(entity).insert((
+            rapier::RigidBody::KinematicVelocityBased,
+            rapier::Velocity::zero(),
+        ));
+    }
+}
+
+/// Gives every `Collider` a matching rapier shape. Colliders without a body of
+/// their own, like the boundaries, are treated as fixed by rapier.
+fn add_colliders(mut commands: Commands, query: Query<(Entity, &Collider), Added<Collider>>) {
+    for (entity, collider) in query.iter() {
+        commands.entity(entity).insert(rapier::Collider::cuboid(
+            collider.size.x / 2.0,
+            collider.size.y / 2.0,
+        ));
+    }
+}
+
+/// Copies the vertical velocity rapier integrated last tick back to the bird.
+fn pull_bird_velocity(mut query: Query<(&mut Velocity, &rapier::Velocity), With<Bird>>) {
+    for (mut velocity, body_velocity) in query.iter_mut() {
+        velocity.0 = body_velocity.linvel / VELOCITY_SCALE;
+    }
+}
+
+fn push_velocities(
+    scroll: Res<ScrollSpeed>,
+    mut query: Query<(&Velocity, Option<&Scrolls>, &mut rapier::Velocity)>,
+    mut gravity_query: Query<(&Gravity, &mut rapier::GravityScale)>,
+) {
+    for (velocity, scrolls, mut body_velocity) in query.iter_mut() {
+        let velocity = velocity.0 + scrolls.map_or(Vec2::ZERO, |scrolls| scrolls.velocity(*scroll));
+        body_velocity.linvel = velocity * VELOCITY_SCALE;
+    }
+    for (gravity, mut scale) in gravity_query.iter_mut() {
+        scale.0 = if gravity.0 { 1.0 } else { 0.0 };
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn detect_collisions(
+    mut collisions: EventReader<rapier::CollisionEvent>,
+    mut deaths: EventWriter<DeathEvent>,
+    hit_stop: Res<HitStop>,
+    context: Res<rapier::RapierContext>,
+    bird_query: Query<(Entity, &GlobalTransform), With<Bird>>,
+    boundary_query: Query<(), With<Boundary>>,
+    hazard_query: Query<(), With<Hazard>>,
+    blocking_query: Query<(), With<Blocking>>,
+) {
+    // the run is over, the bird just carries on through the death cam
+    if hit_stop.timer.is_some() {
+        collisions.clear();
+        return;
+    }
+    for event in collisions.iter() {
+        let rapier::CollisionEvent::Started(a, b, _) = *event else {
+            continue;
+        };
+        let Some((bird, transform)) = bird_query.iter().find(|(bird, _)| *bird == a || *bird == b)
+        else {
+            continue;
+        };
+        // rapier normals point from the first collider to the second
+        let normal = context
+            .contact_pair(a, b)
+            .and_then(|pair| pair.manifold(0).map(|manifold| manifold.normal()))
+            .map_or(
+                Vec2::ZERO,
+                |normal| if bird == a { -normal } else { normal },
+            );
+        let other = if bird == a { b } else { a };
+        if blocking_query.contains(other) {
+            // rapier already stops the bird against solid boundaries
+            continue;
+        }
+        let cause = if boundary_query.contains(other) {
+            DeathCause::OutOfBounds
+        } else if hazard_query.contains(other) {
+            DeathCause::Hazard
+        } else {
+            DeathCause::Pipe
+        };
+        deaths.send(DeathEvent {
+            bird,
+            position: transform.translation().truncate(),
+            normal,
+            cause,
+        });
+    }
+}
@@ -0,0 +1,159 @@
+//! Recording runs to share, and playing them back. Every run on a fixed
+//! `FlappyConfig::physics_rate` is recorded tick by tick into an `InputLog`,
+//! with a `run_hash` of everything besides the flaps that the run depends on.
+//! The `replay` console command prints the last run's log, and
+//! `replay <log>` plays one back, feeding its flaps in on the ticks they were
+//! made on. As the `GameTick` plays out the same for the same flaps, the
+//! replay meets the same pipes, pickups and hazards and dies in the same
+//! place, so a server can check a claimed score by replaying the log in a
+//! headless app.
+//!
+//! Co-op and single-switch runs aren't recorded, as they take input the log
+//! doesn't hold.
+
+use bevy::prelude::*;
+use flappy_core::replay::{config_hash, InputLog};
+use flappy_core::sim::hash_words;
+
+use crate::seed::reseed;
+use crate::{
+    jump, AirDash, AppState, Cheats, CoOp, ConsoleCommands, DifficultyCurves, FlappyConfig,
+    GameSystem, GameTick, PendingFlaps, Player, Profile, RunSeed, Settings, SEED_LIMIT,
+};
+
+/// The log of the current run, and any log being played back.
+#[derive(Resource, Default, Debug)]
+pub struct RunRecorder {
+    /// The run in progress, or the last one once it's over. `None` for runs
+    /// that can't be recorded.
+    pub log: Option<InputLog>,
+    /// Played back in place of the player's flaps this run.
+    pub playback: Option<InputLog>,
+    /// Played back by the next run.
+    pub queued: Option<InputLog>,
+}
+
+pub(crate) fn add_replays(app: &mut App) {
+    app.init_resource::<RunRecorder>()
+        .add_system(
+            start_recording
+                .after(reseed)
+                .in_schedule(OnEnter(AppState::Game)),
+        )
+        .add_system(
+            record_flaps
+                .before(jump)
+                .in_set(GameSystem::Input)
+                .in_schedule(GameTick),
+        );
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "replay",
+        "replay [log]: print the last run's log, or play one back",
+        replay,
+    );
+}
+
+/// Hash of everything a run started now depends on besides its seed and
+/// flaps, to check a log is played back the way it was recorded.
+fn run_hash(world: &World) -> u64 {
+    let config = world.resource::<FlappyConfig>();
+    let settings = world.resource::<Settings>();
+    let curve = world
+        .resource::<DifficultyCurves>()
+        .get(settings.difficulty);
+    let sim = config_hash(&config.sim_config(curve));
+    let assist = settings.assist;
+    let mut words = vec![
+        sim as u32,
+        (sim >> 32) as u32,
+        config.physics_rate.map_or(0, f32::to_bits),
+        config.physics_backend as u32,
+        settings.collision as u32,
+        assist.enabled as u32,
+        assist.wider_gaps.to_bits(),
+        assist.slower_pipes.to_bits(),
+        AirDash::available(world.resource::<Profile>(), settings) as u32,
+        world.resource::<Cheats>().invincible as u32,
+    ];
+    for key in &curve.keys {
+        words.extend([
+            key.score,
+            key.speed.to_bits(),
+            key.interval.to_bits(),
+            key.min_gap.to_bits(),
+            key.max_gap.to_bits(),
+        ]);
+    }
+    hash_words(&words)
+}
+
+fn start_recording(world: &mut World) {
+    let config = world.resource::<FlappyConfig>();
+    let recordable = config.physics_rate.is_some()
+        && !world.resource::<CoOp>().0
+        && !world.resource::<Settings>().single_switch;
+    let log = recordable.then(|| InputLog {
+        seed: world.resource::<RunSeed>().seed.into(),
+        config_hash: run_hash(world),
+        ..default()
+    });
+    let mut recorder = world.resource_mut::<RunRecorder>();
+    recorder.playback = recorder.queued.take().filter(|playback| {
+        let matches = log.as_ref().is_some_and(|log| {
+            (log.seed, log.config_hash) == (playback.seed, playback.config_hash)
+        });
+        if !matches {
+            warn!("not replaying a log recorded with other settings");
+        }
+        matches
+    });
+    recorder.log = log;
+}
+
+/// Records the flaps of each tick, first swapping in the log's own while one
+/// is played back.
+fn record_flaps(mut recorder: ResMut<RunRecorder>, mut pending: ResMut<PendingFlaps>) {
+    let RunRecorder { log, playback, .. } = &mut *recorder;
+    let Some(log) = log else {
+        return;
+    };
+    if let Some(playback) = playback {
+        pending.0.clear();
+        if playback.flaps.binary_search(&log.ticks).is_ok() {
+            pending.0.push(Player::ONE);
+        }
+    }
+    log.record(pending.0.contains(&Player::ONE));
+}
+
+fn replay(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let [log] = args else {
+        return world
+            .resource::<RunRecorder>()
+            .log
+            .as_ref()
+            .map(InputLog::to_string)
+            .ok_or_else(|| "the last run wasn't recorded".to_string());
+    };
+    let log: InputLog = log.parse()?;
+    let seed = u32::try_from(log.seed)
+        .ok()
+        .filter(|seed| *seed < SEED_LIMIT)
+        .ok_or_else(|| format!("invalid seed {}", log.seed))?;
+    if world.resource::<FlappyConfig>().physics_rate.is_none() {
+        return Err("replays need a fixed physics rate".to_string());
+    }
+    if log.config_hash != run_hash(world) {
+        return Err(format!(
+            "recorded with settings {:016x}, not these",
+            log.config_hash
+        ));
+    }
+    info!(seed, ticks = log.ticks, "replaying");
+    world.resource_mut::<RunSeed>().queued = Some(seed);
+    world.resource_mut::<RunRecorder>().queued = Some(log);
+    world
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::Game);
+    Ok(String::new())
+}
@@ -0,0 +1,465 @@
+//! The game over panel and medal reveal, run from a `ResultsTimeline` of cues.
+//! Nothing is scheduled until the `GameOverEvent` arrives, once the death
+//! animation has played out, so the banner slides in a beat after the cut to
+//! the screen and the medal follows it in, across from the run's score in
+//! sprite `Digits`. Runs that beat the previous best also get a "NEW BEST"
+//! ribbon over the banner, and a jingle. The `RunStats` breakdown slides in
+//! down the right a line at a time. The rest of the screen, the share and
+//! clip buttons, challenge code and flight graph, comes in with the panel on
+//! a `ResultsPanelEvent`.
+//!
+//! The panel's sprites and text are laid out under the `ScreenSpace` root,
+//! so they keep to the screen when it's turned for `FlappyConfig::vertical`.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use flappy_core::medal::Medal;
+use rand::Rng;
+
+use crate::bitmap_text::bitmap_text_2d;
+use crate::{
+    AppState, ButtonSkin, DespawnOnExit, Digits, Ease, FlappyConfig, Focusable, GameOverEvent,
+    Localized, MotionPrefs, NewBestEvent, RestartButton, ResultsPanelEvent, RunRecord, RunStats,
+    ScreenSpace, Tween, TweenTarget,
+};
+
+/// Seconds between the `GameOverEvent` and the panel sliding in.
+pub const PANEL_DELAY: f32 = 0.25;
+const BANNER_SLIDE_SECONDS: f32 = 0.4;
+/// Once the banner has landed.
+const RESTART_DELAY: f32 = 0.3;
+/// Seconds after the panel starts sliding in, once the banner has landed.
+const MEDAL_DELAY: f32 = 0.6;
+const MEDAL_REVEAL_SECONDS: f32 = 0.5;
+/// Left of the restart button.
+const MEDAL_POSITION: Vec3 = Vec3::new(-40.0, -15.0, 1.0);
+/// Right of the restart button, across from the medal.
+const SCORE_POSITION: Vec3 = Vec3::new(40.0, -15.0, 1.0);
+const SCORE_SCALE: f32 = 2.0;
+/// Diameter of the generated medal texture.
+const MEDAL_SIZE: u32 = 16;
+const SPARKLE_INTERVAL: f32 = 0.15;
+const SPARKLE_SECONDS: f32 = 0.4;
+/// Once the medal has landed.
+const RIBBON_DELAY: f32 = MEDAL_DELAY + MEDAL_REVEAL_SECONDS;
+const RIBBON_REVEAL_SECONDS: f32 = 0.4;
+/// Over the top of the game over banner.
+const RIBBON_POSITION: Vec3 = Vec3::new(0.0, 30.0, 2.0);
+const RIBBON_SIZE: Vec2 = Vec2::new(80.0, 10.0);
+/// Radians the ribbon ends up tilted by.
+const RIBBON_TILT: f32 = 0.15;
+/// Once the medal has landed, alongside the ribbon.
+const BREAKDOWN_DELAY: f32 = MEDAL_DELAY + MEDAL_REVEAL_SECONDS;
+/// Seconds between one breakdown line starting to slide in and the next.
+const BREAKDOWN_STAGGER: f32 = 0.1;
+const BREAKDOWN_SLIDE_SECONDS: f32 = 0.3;
+/// Where the first line lands, from the right edge and the centre.
+const BREAKDOWN_OFFSET: Vec2 = Vec2::new(-70.0, 20.0);
+/// A line of bitmap text and the pixel between lines.
+const BREAKDOWN_LINE_HEIGHT: f32 = 9.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ResultsCue {
+    /// The banner, restart button and final score.
+    Panel(u32),
+    RevealMedal(Medal),
+    /// Repeats every `SPARKLE_INTERVAL` until the screen closes.
+    Sparkle,
+    NewBest(u32),
+    Breakdown,
+}
+
+/// Pending cues for the game over screen, in real seconds since it opened.
+/// Stays empty when the screen is opened without a `GameOverEvent`.
+#[derive(Resource, Default, Debug)]
+pub struct ResultsTimeline {
+    pub elapsed: f32,
+    cues: Vec<(f32, ResultsCue)>,
+}
+
+impl ResultsTimeline {
+    fn schedule(&mut self, at: f32, cue: ResultsCue) {
+        self.cues.push((at, cue));
+    }
+}
+
+/// The medal shown for the run just ended.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct MedalDisplay(pub Medal);
+
+/// The "NEW BEST" ribbon, shown when the run beat the previous best.
+#[derive(Component)]
+pub struct NewBestRibbon;
+
+/// White medal texture, tinted per medal.
+#[derive(Resource)]
+struct MedalTexture(Handle<Image>);
+
+/// Plays the results cues. What answers a cue's event, like the share button
+/// on the panel's, runs after this to show up on the same frame.
+#[derive(SystemSet, Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub(crate) struct ResultsCues;
+
+pub(crate) fn add_results(app: &mut App) {
+    app.init_resource::<ResultsTimeline>()
+        .add_startup_system(create_medal_texture)
+        .add_system(reset_results.in_schedule(OnEnter(AppState::GameOver)))
+        .add_systems(
+            (
+                schedule_results,
+                run_results_timeline
+                    .after(schedule_results)
+                    .in_set(ResultsCues),
+            )
+                .in_set(OnUpdate(AppState::GameOver)),
+        );
+}
+
+fn medal_color(medal: Medal) -> Color {
+    match medal {
+        Medal::Bronze => Color::rgb(0.8, 0.5, 0.2),
+        Medal::Silver => Color::rgb(0.75, 0.75, 0.8),
+        Medal::Gold => Color::rgb(1.0, 0.8, 0.2),
+        Medal::Platinum => Color::rgb(0.9, 0.95, 1.0),
+    }
+}
+
+/// A disc with a darker star in the middle, so the spin is visible.
+fn create_medal_texture(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let radius = MEDAL_SIZE as f32 / 2.0;
+    let mut data = Vec::with_capacity((MEDAL_SIZE * MEDAL_SIZE * 4) as usize);
+    for y in 0..MEDAL_SIZE {
+        for x in 0..MEDAL_SIZE {
+            let offset = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - radius;
+            let distance = offset.length();
+            let angle = offset.y.atan2(offset.x);
+            let star_radius = radius * (0.35 + 0.2 * (5.0 * angle).cos());
+            let pixel = if distance > radius {
+                [0, 0, 0, 0]
+            } else if distance < star_radius {
+                [180, 180, 180, 255]
+            } else {
+                [255, 255, 255, 255]
+            };
+            data.extend(pixel);
+        }
+    }
+    let image = Image::new(
+        Extent3d {
+            width: MEDAL_SIZE,
+            height: MEDAL_SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    commands.insert_resource(MedalTexture(images.add(image)));
+}
+
+fn reset_results(mut timeline: ResMut<ResultsTimeline>) {
+    *timeline = ResultsTimeline::default();
+}
+
+/// Lines the panel up after the death animation, and the medal and ribbon up
+/// after the panel.
+fn schedule_results(
+    record: Res<RunRecord>,
+    motion: Res<MotionPrefs>,
+    mut timeline: ResMut<ResultsTimeline>,
+    mut game_overs: EventReader<GameOverEvent>,
+) {
+    let Some(game_over) = game_overs.iter().last() else {
+        return;
+    };
+    let score = game_over.score;
+    let panel = timeline.elapsed + PANEL_DELAY;
+    timeline.schedule(panel, ResultsCue::Panel(score));
+    timeline.schedule(panel + BREAKDOWN_DELAY, ResultsCue::Breakdown);
+    if record.beaten_by(score) {
+        timeline.schedule(panel + RIBBON_DELAY, ResultsCue::NewBest(score));
+    }
+    let Some(medal) = Medal::for_score(score) else {
+        return;
+    };
+    timeline.schedule(panel + MEDAL_DELAY, ResultsCue::RevealMedal(medal));
+    if medal >= Medal::Gold && motion.particles {
+        timeline.schedule(
+            panel + MEDAL_DELAY + MEDAL_REVEAL_SECONDS,
+            ResultsCue::Sparkle,
+        );
+    }
+}
+
+/// Plays the cues as they come due.
+#[allow(clippy::too_many_arguments)]
+fn run_results_timeline(
+    time: Res<Time>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    space: Res<ScreenSpace>,
+    texture: Res<MedalTexture>,
+    record: Res<RunRecord>,
+    stats: Res<RunStats>,
+    mut timeline: ResMut<ResultsTimeline>,
+    mut panels: EventWriter<ResultsPanelEvent>,
+    mut new_bests: EventWriter<NewBestEvent>,
+) {
+    timeline.elapsed += time.delta_seconds();
+    let now = timeline.elapsed;
+    let (due, pending): (Vec<_>, Vec<_>) = timeline.cues.drain(..).partition(|(at, _)| *at <= now);
+    timeline.cues = pending;
+
+    for (_, cue) in due {
+        match cue {
+            ResultsCue::Panel(score) => {
+                spawn_panel(&mut commands, &asset_server, &config, *space, score);
+                panels.send(ResultsPanelEvent { score });
+            }
+            ResultsCue::RevealMedal(medal) => spawn_medal(&mut commands, &texture, *space, medal),
+            ResultsCue::Sparkle => {
+                spawn_sparkle(&mut commands, *space);
+                timeline.schedule(now + SPARKLE_INTERVAL, ResultsCue::Sparkle);
+            }
+            ResultsCue::Breakdown => spawn_breakdown(&mut commands, &config, *space, &stats),
+            ResultsCue::NewBest(score) => {
+                spawn_ribbon(&mut commands, *space);
+                new_bests.send(NewBestEvent {
+                    score,
+                    previous: record.to_beat,
+                });
+            }
+        }
+    }
+}
+
+/// Slides the banner down from above the playfield, then pops the restart
+/// button in under it once it has landed.
+fn spawn_panel(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    config: &FlappyConfig,
+    space: ScreenSpace,
+    score: u32,
+) {
+    let screen = config.screen();
+    let banner_from = Vec3::new(0.0, screen.y / 2.0 + 20.0, 0.0);
+    let (mut banner, text) = bitmap_text_2d("", 3.0, Color::rgb(1.0, 0.6, 0.2));
+    banner.transform = Transform::from_translation(banner_from);
+    commands
+        .spawn((
+            banner,
+            text,
+            Localized::new("game_over"),
+            Tween::new(
+                TweenTarget::Translation {
+                    from: banner_from,
+                    to: Vec3::new(0.0, 15.0, 0.0),
+                },
+                BANNER_SLIDE_SECONDS,
+                Ease::BackOut,
+            ),
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0);
+
+    commands.spawn((
+        ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(40.0), Val::Px(14.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    top: Val::Px((15.0 - 7.0) + screen.y / 2.0),
+                    left: Val::Px(screen.x / 2.0 - 20.0),
+                    ..default()
+                },
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            image: asset_server
+                .load(config.assets.restart_button.as_str())
+                .into(),
+            transform: Transform::from_scale(Vec3::ZERO),
+            ..default()
+        },
+        Tween::new(
+            TweenTarget::Scale {
+                from: Vec3::ZERO,
+                to: Vec3::ONE,
+            },
+            0.6,
+            Ease::ElasticOut,
+        )
+        .with_delay(RESTART_DELAY),
+        ButtonSkin::image(),
+        Focusable { order: 0 },
+        RestartButton,
+        DespawnOnExit(AppState::GameOver),
+    ));
+
+    commands
+        .spawn((
+            SpatialBundle::from_transform(Transform::from_translation(SCORE_POSITION)),
+            Digits {
+                value: score,
+                scale: SCORE_SCALE,
+            },
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0);
+}
+
+/// Slides each line of the breakdown in from the right, one after another.
+fn spawn_breakdown(
+    commands: &mut Commands,
+    config: &FlappyConfig,
+    space: ScreenSpace,
+    stats: &RunStats,
+) {
+    let right = config.screen().x / 2.0;
+    for (index, (key, value)) in stats.breakdown().into_iter().enumerate() {
+        let y = BREAKDOWN_OFFSET.y - index as f32 * BREAKDOWN_LINE_HEIGHT;
+        let from = Vec3::new(right - BREAKDOWN_OFFSET.x, y, 1.0);
+        let (mut line, text) = bitmap_text_2d("", 1.0, Color::WHITE);
+        line.transform = Transform::from_translation(from);
+        commands
+            .spawn((
+                line,
+                text,
+                Localized::new(key).with_args([value]),
+                Tween::new(
+                    TweenTarget::Translation {
+                        from,
+                        to: Vec3::new(right + BREAKDOWN_OFFSET.x, y, 1.0),
+                    },
+                    BREAKDOWN_SLIDE_SECONDS,
+                    Ease::QuadOut,
+                )
+                .with_delay(index as f32 * BREAKDOWN_STAGGER),
+                DespawnOnExit(AppState::GameOver),
+            ))
+            .set_parent(space.0);
+    }
+}
+
+/// Scales the medal in on a parent while the sprite spins into place.
+fn spawn_medal(commands: &mut Commands, texture: &MedalTexture, space: ScreenSpace, medal: Medal) {
+    commands
+        .spawn((
+            SpatialBundle {
+                transform: Transform::from_translation(MEDAL_POSITION).with_scale(Vec3::ZERO),
+                ..default()
+            },
+            Tween::new(
+                TweenTarget::Scale {
+                    from: Vec3::ZERO,
+                    to: Vec3::ONE,
+                },
+                MEDAL_REVEAL_SECONDS,
+                Ease::BackOut,
+            ),
+            MedalDisplay(medal),
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0)
+        .with_children(|parent| {
+            parent.spawn((
+                SpriteBundle {
+                    texture: texture.0.clone(),
+                    sprite: Sprite {
+                        color: medal_color(medal),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Tween::new(
+                    TweenTarget::Rotation {
+                        from: -TAU,
+                        to: 0.0,
+                    },
+                    MEDAL_REVEAL_SECONDS,
+                    Ease::QuadOut,
+                ),
+            ));
+        });
+}
+
+/// Scales the ribbon in while it swings to a tilt.
+fn spawn_ribbon(commands: &mut Commands, space: ScreenSpace) {
+    commands
+        .spawn((
+            SpatialBundle {
+                transform: Transform::from_translation(RIBBON_POSITION).with_scale(Vec3::ZERO),
+                ..default()
+            },
+            Tween::new(
+                TweenTarget::Scale {
+                    from: Vec3::ZERO,
+                    to: Vec3::ONE,
+                },
+                RIBBON_REVEAL_SECONDS,
+                Ease::BackOut,
+            ),
+            NewBestRibbon,
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0)
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            color: Color::rgb(0.85, 0.15, 0.2),
+                            custom_size: Some(RIBBON_SIZE),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    Tween::new(
+                        TweenTarget::Rotation {
+                            from: RIBBON_TILT,
+                            to: -RIBBON_TILT,
+                        },
+                        RIBBON_REVEAL_SECONDS,
+                        Ease::ElasticOut,
+                    ),
+                ))
+                .with_children(|ribbon| {
+                    let (mut label, text) = bitmap_text_2d("", 1.0, Color::WHITE);
+                    label.transform = Transform::from_xyz(0.0, 0.0, 0.1);
+                    ribbon.spawn((label, text, Localized::new("results.new_best")));
+                });
+        });
+}
+
+fn spawn_sparkle(commands: &mut Commands, space: ScreenSpace) {
+    let mut rng = rand::thread_rng();
+    let radius = MEDAL_SIZE as f32 / 2.0;
+    let offset = Vec2::new(
+        rng.gen_range(-radius..radius),
+        rng.gen_range(-radius..radius),
+    );
+    commands
+        .spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(2.0)),
+                    ..default()
+                },
+                transform: Transform::from_translation(MEDAL_POSITION + offset.extend(1.0)),
+                ..default()
+            },
+            Tween::new(
+                TweenTarget::SpriteAlpha { from: 1.0, to: 0.0 },
+                SPARKLE_SECONDS,
+                Ease::QuadOut,
+            )
+            .despawn_when_done(),
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .set_parent(space.0);
+}
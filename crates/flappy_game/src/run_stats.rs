@@ -0,0 +1,118 @@
+//! What happened during a run, beyond the score, kept in `RunStats` for the
+//! breakdown on the game over screen. The gameplay systems feed it as they go:
+//! scoring notes each gap and how centred the bird came into it, pickups count
+//! coins, and the glide timer here runs between flaps. Gaps and hazards carry
+//! a `Clearance`, for how close the bird came to them on its way past.
+
+use bevy::prelude::*;
+
+use crate::{AppState, Bird, FlapEvent, GameSystem, GameTick, Gravity, HitStop, PhysicsClock};
+
+/// World units between the bird and a pipe or hazard, at the closest it came
+/// getting past, that count as a near miss.
+pub const NEAR_MISS_MARGIN: f32 = 3.0;
+
+/// The current or last run's stats, reset as each run starts.
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct RunStats {
+    /// Gaps passed, golden ones included.
+    pub pipes: u32,
+    pub coins: u32,
+    /// Gaps and hazards got past within `NEAR_MISS_MARGIN` of touching.
+    pub near_misses: u32,
+    /// Longest time between flaps, in seconds of game time.
+    pub longest_glide: f32,
+    /// Seconds since the last flap.
+    glide: f32,
+    /// How centred the bird was entering each gap, summed.
+    centering: f32,
+}
+
+impl RunStats {
+    /// Notes a gap entered `offset` from its centre, with `room` to either
+    /// side before the bird would touch a pipe.
+    pub fn record_gap(&mut self, offset: f32, room: f32) {
+        self.pipes += 1;
+        let centering = if room > 0.0 {
+            1.0 - (offset.abs() / room).min(1.0)
+        } else {
+            0.0
+        };
+        self.centering += centering;
+    }
+
+    /// Notes a gap or hazard got past with `closest` world units to spare at
+    /// the closest.
+    pub fn record_clearance(&mut self, closest: f32) {
+        if closest < NEAR_MISS_MARGIN {
+            self.near_misses += 1;
+        }
+    }
+
+    /// How centred the bird was entering gaps on average, from 0 at a pipe to
+    /// 1 dead centre. `None` before the first gap.
+    pub fn accuracy(&self) -> Option<f32> {
+        (self.pipes > 0).then(|| self.centering / self.pipes as f32)
+    }
+
+    /// The lines of the game over breakdown, as locale keys and their values.
+    pub fn breakdown(&self) -> Vec<(&'static str, String)> {
+        let mut lines = vec![
+            ("results.pipes", self.pipes.to_string()),
+            ("results.coins", self.coins.to_string()),
+            ("results.near_misses", self.near_misses.to_string()),
+            ("results.glide", format!("{:.1}", self.longest_glide)),
+        ];
+        if let Some(accuracy) = self.accuracy() {
+            lines.push(("results.accuracy", format!("{:.0}", accuracy * 100.0)));
+        }
+        lines
+    }
+}
+
+/// The least room the bird has had around the gap or hazard it's on, while
+/// getting past it. Taken for `RunStats::record_clearance` once it's past.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct Clearance {
+    pub closest: Option<f32>,
+}
+
+impl Clearance {
+    pub fn note(&mut self, room: f32) {
+        self.closest = Some(self.closest.map_or(room, |closest| closest.min(room)));
+    }
+}
+
+pub(crate) fn add_run_stats(app: &mut App) {
+    app.init_resource::<RunStats>()
+        .add_system(reset_run_stats.in_schedule(OnEnter(AppState::Game)))
+        .add_system(
+            time_glides
+                .in_set(GameSystem::Scoring)
+                .in_schedule(GameTick),
+        );
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+/// Times the bird between flaps, from its first one until the run ends.
+fn time_glides(
+    clock: Res<PhysicsClock>,
+    hit_stop: Res<HitStop>,
+    mut stats: ResMut<RunStats>,
+    mut flaps: EventReader<FlapEvent>,
+    bird_query: Query<&Gravity, With<Bird>>,
+) {
+    if flaps.iter().count() > 0 {
+        stats.glide = 0.0;
+        return;
+    }
+    let flying = bird_query.iter().any(|gravity| gravity.0);
+    if !flying || hit_stop.timer.is_some() {
+        return;
+    }
+    stats.glide += clock.step;
+    stats.longest_glide = stats.longest_glide.max(stats.glide);
+}
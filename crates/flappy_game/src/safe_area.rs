@@ -0,0 +1,66 @@
+//! Keeping UI clear of notches, rounded corners and home indicators. Nodes
+//! with a `SafeArea` are pushed in from the sides they're positioned from by
+//! the `SafeAreaInsets`, which are read from UIKit on iOS and zero elsewhere.
+
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+/// How far in from each edge of the window UI is safe from being covered, in
+/// logical pixels.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct SafeAreaInsets {
+    pub top: f32,
+    pub bottom: f32,
+    pub left: f32,
+    pub right: f32,
+}
+
+/// Moves an absolutely positioned node inside the safe area. Only sides set
+/// in `Val::Px` are moved.
+#[derive(Component, Default)]
+pub struct SafeArea {
+    /// The node's position with no insets, taken on first sight.
+    position: Option<UiRect>,
+}
+
+pub(crate) fn add_safe_area(app: &mut App) {
+    app.init_resource::<SafeAreaInsets>().add_system(
+        apply_safe_area
+            .before(UiSystem::Flex)
+            .in_base_set(CoreSet::PostUpdate),
+    );
+    #[cfg(target_os = "ios")]
+    app.add_system(read_insets.in_base_set(CoreSet::PreUpdate));
+}
+
+#[cfg(target_os = "ios")]
+fn read_insets(mut insets: ResMut<SafeAreaInsets>) {
+    // they change as the phone rotates
+    if let Some(latest) = crate::ios::safe_area_insets() {
+        if *insets != latest {
+            *insets = latest;
+        }
+    }
+}
+
+fn inset(val: Val, by: f32) -> Val {
+    match val {
+        Val::Px(px) => Val::Px(px + by),
+        other => other,
+    }
+}
+
+fn apply_safe_area(insets: Res<SafeAreaInsets>, mut query: Query<(&mut Style, &mut SafeArea)>) {
+    for (mut style, mut safe_area) in query.iter_mut() {
+        if safe_area.position.is_some() && !insets.is_changed() {
+            continue;
+        }
+        let base = *safe_area.position.get_or_insert(style.position);
+        style.position = UiRect {
+            left: inset(base.left, insets.left),
+            right: inset(base.right, insets.right),
+            top: inset(base.top, insets.top),
+            bottom: inset(base.bottom, insets.bottom),
+        };
+    }
+}
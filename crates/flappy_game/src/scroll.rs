@@ -0,0 +1,43 @@
+//! The level scrolling past the bird. Pipes, pickups, hazards and the ground
+//! all travel left at the one `ScrollSpeed`, the `Pace`'s pipe speed sped up
+//! while an `AirDash` is under way, so a dash or a change of pace carries
+//! everything along together.
+
+use bevy::prelude::*;
+
+use crate::{AirDash, GameSystem, GameTick, Pace};
+
+/// How fast the level goes by this tick, in the units of `Velocity`.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Deref)]
+pub struct ScrollSpeed(pub f32);
+
+/// Carried along with the level at this multiple of the `ScrollSpeed`, on top
+/// of any `Velocity` of its own.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Scrolls(pub f32);
+
+impl Scrolls {
+    /// The velocity the scrolling adds at `speed`.
+    pub fn velocity(self, speed: ScrollSpeed) -> Vec2 {
+        Vec2::new(-self.0 * speed.0, 0.0)
+    }
+}
+
+pub(crate) fn add_scroll(app: &mut App) {
+    app.init_resource::<ScrollSpeed>().add_system(
+        update_scroll_speed
+            .in_set(GameSystem::Movement)
+            .in_schedule(GameTick),
+    );
+}
+
+pub(crate) fn update_scroll_speed(
+    pace: Res<Pace>,
+    dash: Res<AirDash>,
+    mut speed: ResMut<ScrollSpeed>,
+) {
+    let new_speed = ScrollSpeed(pace.pipe_speed * dash.boost());
+    if *speed != new_speed {
+        *speed = new_speed;
+    }
+}
@@ -0,0 +1,88 @@
+//! The seed each run's pipe gaps are drawn from. A fresh one is picked as
+//! every run starts, unless one was queued from a challenge code, and shown
+//! when sharing a score so the run can be named.
+//!
+//! Everything random that the bird can run into or pick up comes from a
+//! stream of this seed, so a seed always deals out the same run. Scenery and
+//! effects, like clouds and sparkles, still use `thread_rng`.
+
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::AppState;
+
+/// Seeds are kept below this so they stay short enough to read out.
+pub const SEED_LIMIT: u32 = 1 << 20;
+/// Set in the seed of `RunSeed::extras_rng`, above any run's seed.
+const EXTRAS_STREAM: u64 = 1 << 32;
+/// Set in the seed of `RunSeed::pickups_rng`.
+const PICKUPS_STREAM: u64 = 2 << 32;
+/// Set in the seed of `RunSeed::hazards_rng`.
+const HAZARDS_STREAM: u64 = 3 << 32;
+
+/// The current run's seed, and the generator seeded with it.
+#[derive(Resource, Debug)]
+pub struct RunSeed {
+    pub seed: u32,
+    /// Played by the next run instead of a random seed.
+    pub queued: Option<u32>,
+    rng: StdRng,
+    extras: StdRng,
+    pickups: StdRng,
+    hazards: StdRng,
+}
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RunSeed {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            seed,
+            queued: None,
+            rng: StdRng::seed_from_u64(seed.into()),
+            extras: StdRng::seed_from_u64(u64::from(seed) | EXTRAS_STREAM),
+            pickups: StdRng::seed_from_u64(u64::from(seed) | PICKUPS_STREAM),
+            hazards: StdRng::seed_from_u64(u64::from(seed) | HAZARDS_STREAM),
+        }
+    }
+
+    pub fn random() -> Self {
+        Self::new(rand::thread_rng().gen_range(0..SEED_LIMIT))
+    }
+
+    /// The generator for this run's pipe gaps.
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    /// The generator for anything else about the run's pipes, like golden
+    /// gaps, kept apart so adding to it doesn't change the gaps a seed gives.
+    pub fn extras_rng(&mut self) -> &mut StdRng {
+        &mut self.extras
+    }
+
+    /// The generator for coins and magnets.
+    pub fn pickups_rng(&mut self) -> &mut StdRng {
+        &mut self.pickups
+    }
+
+    /// The generator for hazards, their timing and where they come from.
+    pub fn hazards_rng(&mut self) -> &mut StdRng {
+        &mut self.hazards
+    }
+}
+
+pub(crate) fn add_run_seed(app: &mut App) {
+    app.init_resource::<RunSeed>()
+        .add_system(reseed.in_schedule(OnEnter(AppState::Game)));
+}
+
+pub(crate) fn reseed(mut seed: ResMut<RunSeed>) {
+    *seed = seed.queued.map_or_else(RunSeed::random, RunSeed::new);
+    info!(seed = seed.seed, "new run");
+}
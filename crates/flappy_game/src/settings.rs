@@ -0,0 +1,70 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::persist::{add_persistent_resource, Versioned};
+use crate::{Assist, BirdTrail, Difficulty, FlappyConfig, FrameRate, Haptics, Language, Volume};
+
+/// Player preferences, as opposed to the embedding options in `FlappyConfig`.
+/// Saved to `FlappyConfig::settings_path` on every change.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    pub volume: Volume,
+    pub collision: CollisionMode,
+    pub difficulty: Difficulty,
+    /// Wider gaps and slower pipes at any difficulty.
+    pub assist: Assist,
+    /// Double tap to dash, once unlocked, see `AirDash`.
+    pub air_dash: bool,
+    /// Afterimages behind the bird, off when `None`.
+    pub trail: Option<BirdTrail>,
+    /// Replace the OS cursor with the themed one in `AssetPaths::cursor`.
+    pub custom_cursor: bool,
+    /// Black outlines around the bird and pipes, for players who struggle to
+    /// pick out the green pipes.
+    pub outlines: bool,
+    /// Use `Theme::high_contrast`.
+    pub high_contrast: bool,
+    /// Use `Theme::night`, once unlocked, unless `high_contrast` is on.
+    pub night_theme: bool,
+    /// Turn off camera motion, flashes, parallax and particles, see
+    /// `MotionPrefs`.
+    pub reduced_motion: bool,
+    /// Turn off flashes, sparkles and sky transitions, see `MotionPrefs`.
+    pub photosensitive: bool,
+    /// Play a tone and clicks locating the next gap, see `GapCue`.
+    pub audio_cues: bool,
+    /// Flap automatically, steered by holding or releasing a single key, see
+    /// `AutoFlap`.
+    pub single_switch: bool,
+    /// Vibrate on deaths and points, see `HapticEvent`.
+    pub haptics: Haptics,
+    /// Vsync and the frame rate cap, see `frame_rate`.
+    pub frame_rate: FrameRate,
+    /// Send anonymous run summaries to `FlappyConfig::telemetry_url`, see
+    /// `TelemetryEvent`. Off unless the player opts in.
+    pub telemetry: bool,
+}
+
+/// How the bird is tested against pipes once their boxes overlap.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionMode {
+    /// Bounding boxes only.
+    #[default]
+    Boxes,
+    /// Opaque sprite pixels, so the bird's transparent corners don't count.
+    /// Falls back to boxes for anything whose texture has no mask. Only the
+    /// builtin physics backend supports this.
+    PixelPerfect,
+}
+
+impl Versioned for Settings {
+    const VERSION: u32 = 1;
+}
+
+pub(crate) fn add_settings(app: &mut App, config: &FlappyConfig) {
+    add_persistent_resource::<Settings>(app, config.settings_path.clone(), |config| {
+        config.settings_path.as_ref()
+    });
+}
@@ -0,0 +1,546 @@
+//! The settings screen, reached from the main menu. Changes apply and save
+//! straight away.
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::buttons::spawn_text_button;
+use crate::locale::localized_text;
+use crate::{
+    AppState, CollisionMode, DespawnOnExit, FlappyConfig, Localized, MenuFocus, Profile, Settings,
+    Volume, DASH_UNLOCK_SCORE,
+};
+
+/// Fraction of the range a `VolumeSlider` moves per step.
+const VOLUME_STEP: f32 = 0.1;
+
+/// Cycles through `Language::ALL`. Labelled with the current language's own
+/// name, so it stays readable whichever language is picked.
+#[derive(Component)]
+pub struct LanguageButton;
+
+/// Sets one of the `Volume` levels. Click along it to pick a level, or step
+/// the level with left and right while it has focus. Activating it from the
+/// keyboard steps up, wrapping round to silence.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VolumeSlider {
+    Master,
+    Music,
+    Sfx,
+}
+
+impl VolumeSlider {
+    pub const ALL: [VolumeSlider; 3] =
+        [VolumeSlider::Master, VolumeSlider::Music, VolumeSlider::Sfx];
+
+    pub fn level(self, volume: &Volume) -> f32 {
+        match self {
+            VolumeSlider::Master => volume.master,
+            VolumeSlider::Music => volume.music,
+            VolumeSlider::Sfx => volume.sfx,
+        }
+    }
+
+    pub fn level_mut(self, volume: &mut Volume) -> &mut f32 {
+        match self {
+            VolumeSlider::Master => &mut volume.master,
+            VolumeSlider::Music => &mut volume.music,
+            VolumeSlider::Sfx => &mut volume.sfx,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            VolumeSlider::Master => "volume.master",
+            VolumeSlider::Music => "volume.music",
+            VolumeSlider::Sfx => "volume.sfx",
+        }
+    }
+}
+
+/// Switches `Settings::collision` between boxes and pixels.
+#[derive(Component)]
+pub struct CollisionButton;
+
+/// Opens the advanced physics panel, see `physics_menu`.
+#[derive(Component)]
+struct PhysicsButton;
+
+/// Opens the controls screen, see `controls_menu`.
+#[derive(Component)]
+pub struct ControlsButton;
+
+/// Opens the accessibility screen, see `accessibility_menu`.
+#[derive(Component)]
+pub struct AccessibilityButton;
+
+/// Switches `Settings::assist` on and off.
+#[derive(Component)]
+pub struct AssistButton;
+
+/// Switches `FrameRate::vsync` on and off.
+#[derive(Component)]
+pub struct VsyncButton;
+
+/// Cycles through `FrameRate::CAPS`.
+#[derive(Component)]
+pub struct FrameCapButton;
+
+/// Switches `Settings::air_dash` on and off. Only shown once the dash is
+/// unlocked.
+#[derive(Component)]
+pub struct DashButton;
+
+/// Opts in or out of `Settings::telemetry`. Only shown when the game has
+/// somewhere to send it.
+#[derive(Component)]
+pub struct TelemetryButton;
+
+/// Bar along the bottom of a `VolumeSlider` showing its level.
+#[derive(Component)]
+struct VolumeFill;
+
+#[derive(Component)]
+struct SettingsBackButton;
+
+pub(crate) fn add_settings_menu(app: &mut App) {
+    app.add_system(spawn_settings_menu.in_schedule(OnEnter(AppState::Settings)))
+        .add_systems(
+            (
+                cycle_language,
+                adjust_volume,
+                show_volume.after(adjust_volume),
+                toggle_assist,
+                toggle_collision,
+                toggle_vsync,
+                cycle_frame_cap,
+                toggle_dash,
+                toggle_telemetry,
+                open_physics,
+                open_controls,
+                open_accessibility,
+                leave_settings,
+            )
+                .in_set(OnUpdate(AppState::Settings)),
+        );
+}
+
+fn spawn_settings_menu(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+) {
+    let mut sliders = Vec::new();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                ..default()
+            },
+            DespawnOnExit(AppState::Settings),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("settings.title"),
+                2.0,
+                Color::WHITE,
+            ));
+            parent.spawn(localized_text(
+                Localized::new("settings.language"),
+                1.0,
+                Color::WHITE,
+            ));
+            spawn_text_button(parent, Localized::new("language.name"), 0, LanguageButton);
+            parent.spawn(localized_text(
+                Localized::new("settings.volume"),
+                1.0,
+                Color::WHITE,
+            ));
+            for (order, slider) in (1..).zip(VolumeSlider::ALL) {
+                let level = slider.level(&settings.volume);
+                sliders.push((
+                    spawn_text_button(
+                        parent,
+                        Localized::new(slider.label()).with_args([percent(level)]),
+                        order,
+                        slider,
+                    ),
+                    level,
+                ));
+            }
+            spawn_text_button(
+                parent,
+                Localized::new(assist_label(settings.assist.enabled)),
+                4,
+                AssistButton,
+            );
+            spawn_text_button(
+                parent,
+                Localized::new(collision_label(settings.collision)),
+                5,
+                CollisionButton,
+            );
+            // the screens further in side by side, to leave room
+            parent.spawn(NodeBundle::default()).with_children(|row| {
+                spawn_text_button(row, Localized::new("settings.physics"), 6, PhysicsButton);
+                spawn_text_button(row, Localized::new("settings.controls"), 7, ControlsButton);
+                spawn_text_button(
+                    row,
+                    Localized::new("settings.accessibility"),
+                    8,
+                    AccessibilityButton,
+                );
+            });
+            spawn_text_button(
+                parent,
+                Localized::new(vsync_label(settings.frame_rate.vsync)),
+                9,
+                VsyncButton,
+            );
+            spawn_text_button(
+                parent,
+                frame_cap_label(settings.frame_rate.cap),
+                10,
+                FrameCapButton,
+            );
+            if profile.best >= DASH_UNLOCK_SCORE {
+                spawn_text_button(
+                    parent,
+                    Localized::new(dash_label(settings.air_dash)),
+                    11,
+                    DashButton,
+                );
+            }
+            if config.telemetry_url.is_some() {
+                spawn_text_button(
+                    parent,
+                    Localized::new(telemetry_label(settings.telemetry)),
+                    12,
+                    TelemetryButton,
+                );
+                parent.spawn(localized_text(
+                    Localized::new("settings.telemetry_hint"),
+                    1.0,
+                    Color::GRAY,
+                ));
+            }
+            spawn_text_button(parent, Localized::new("menu.back"), 13, SettingsBackButton);
+        });
+    for (slider, level) in sliders {
+        commands.entity(slider).with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        position: UiRect {
+                            left: Val::Px(0.0),
+                            bottom: Val::Px(0.0),
+                            ..default()
+                        },
+                        size: Size::new(Val::Percent(level * 100.0), Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::WHITE.into(),
+                    ..default()
+                },
+                VolumeFill,
+            ));
+        });
+    }
+}
+
+fn percent(level: f32) -> String {
+    format!("{}", (level * 100.0).round())
+}
+
+fn cycle_language(
+    mut settings: ResMut<Settings>,
+    query: Query<&Interaction, (Changed<Interaction>, With<LanguageButton>)>,
+) {
+    for interaction in query.iter() {
+        if *interaction == Interaction::Clicked {
+            settings.language = settings.language.next();
+        }
+    }
+}
+
+fn adjust_volume(
+    keyboard_input: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    focus: Res<MenuFocus>,
+    mut settings: ResMut<Settings>,
+    query: Query<(
+        Entity,
+        Ref<Interaction>,
+        &VolumeSlider,
+        &Node,
+        &GlobalTransform,
+    )>,
+) {
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    for (entity, interaction, slider, node, transform) in query.iter() {
+        let level = slider.level(&settings.volume);
+        let mut new_level = level;
+        if interaction.is_changed() && *interaction == Interaction::Clicked {
+            let left = transform.translation().x - node.size().x / 2.0;
+            new_level = match cursor.map(|cursor| (cursor.x - left) / node.size().x) {
+                Some(along) if (0.0..=1.0).contains(&along) => along,
+                // clicked from the keyboard
+                _ if level >= 1.0 => 0.0,
+                _ => level + VOLUME_STEP,
+            };
+        } else if focus.focused == Some(entity) {
+            if keyboard_input.just_pressed(KeyCode::Left) || focus.stick.x < 0 {
+                new_level -= VOLUME_STEP;
+            }
+            if keyboard_input.just_pressed(KeyCode::Right) || focus.stick.x > 0 {
+                new_level += VOLUME_STEP;
+            }
+        }
+        // snapped to whole steps, so the labels stay round
+        let new_level = ((new_level / VOLUME_STEP).round() * VOLUME_STEP).clamp(0.0, 1.0);
+        if new_level != level {
+            *slider.level_mut(&mut settings.volume) = new_level;
+        }
+    }
+}
+
+fn show_volume(
+    settings: Res<Settings>,
+    slider_query: Query<(&VolumeSlider, &Children)>,
+    mut label_query: Query<&mut Localized>,
+    mut fill_query: Query<&mut Style, With<VolumeFill>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (slider, children) in slider_query.iter() {
+        let level = slider.level(&settings.volume);
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.args = vec![percent(level)];
+        }
+        let mut fills = fill_query.iter_many_mut(children);
+        while let Some(mut style) = fills.fetch_next() {
+            style.size.width = Val::Percent(level * 100.0);
+        }
+    }
+}
+
+fn assist_label(enabled: bool) -> &'static str {
+    if enabled {
+        "settings.assist_on"
+    } else {
+        "settings.assist_off"
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_assist(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<AssistButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.assist.enabled = !settings.assist.enabled;
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = assist_label(settings.assist.enabled);
+        }
+    }
+}
+
+fn collision_label(collision: CollisionMode) -> &'static str {
+    match collision {
+        CollisionMode::Boxes => "settings.collision_boxes",
+        CollisionMode::PixelPerfect => "settings.collision_pixels",
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_collision(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<CollisionButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.collision = match settings.collision {
+            CollisionMode::Boxes => CollisionMode::PixelPerfect,
+            CollisionMode::PixelPerfect => CollisionMode::Boxes,
+        };
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = collision_label(settings.collision);
+        }
+    }
+}
+
+fn vsync_label(enabled: bool) -> &'static str {
+    if enabled {
+        "settings.vsync_on"
+    } else {
+        "settings.vsync_off"
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_vsync(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<VsyncButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.frame_rate.vsync = !settings.frame_rate.vsync;
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = vsync_label(settings.frame_rate.vsync);
+        }
+    }
+}
+
+fn frame_cap_label(cap: Option<u32>) -> Localized {
+    match cap {
+        Some(cap) => Localized::new("settings.frame_cap").with_args([cap.to_string()]),
+        None => Localized::new("settings.frame_cap_off"),
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn cycle_frame_cap(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<FrameCapButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.frame_rate.cap = settings.frame_rate.next_cap();
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            *label = frame_cap_label(settings.frame_rate.cap);
+        }
+    }
+}
+
+fn dash_label(enabled: bool) -> &'static str {
+    if enabled {
+        "settings.dash_on"
+    } else {
+        "settings.dash_off"
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_dash(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<DashButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.air_dash = !settings.air_dash;
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = dash_label(settings.air_dash);
+        }
+    }
+}
+
+fn telemetry_label(enabled: bool) -> &'static str {
+    if enabled {
+        "settings.telemetry_on"
+    } else {
+        "settings.telemetry_off"
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn toggle_telemetry(
+    mut settings: ResMut<Settings>,
+    query: Query<(&Interaction, &Children), (Changed<Interaction>, With<TelemetryButton>)>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for (interaction, children) in query.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        settings.telemetry = !settings.telemetry;
+        let mut labels = label_query.iter_many_mut(children);
+        while let Some(mut label) = labels.fetch_next() {
+            label.key = telemetry_label(settings.telemetry);
+        }
+    }
+}
+
+fn open_physics(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<PhysicsButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Physics);
+    }
+}
+
+fn open_controls(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<ControlsButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Controls);
+    }
+}
+
+fn open_accessibility(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<AccessibilityButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::Accessibility);
+    }
+}
+
+/// Back to the main menu. The quit key does the same, see `quit`.
+fn leave_settings(
+    mut next_state: ResMut<NextState<AppState>>,
+    query: Query<&Interaction, (Changed<Interaction>, With<SettingsBackButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        next_state.set(AppState::MainMenu);
+    }
+}
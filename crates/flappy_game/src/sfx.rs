@@ -0,0 +1,265 @@
+//! Gameplay sound effects. Each `SoundEffect` rotates through a few variants
+//! and nudges the pitch and volume of every play, so long runs don't sound
+//! like a metronome.
+//!
+//! The sounds are synthesised from `Chirp`s, so there are no assets to load,
+//! and are only played with kira's `AudioPlugin`. The score sound starts in the
+//! same frame the gap is passed, straight after the `ScoreEvent` is sent.
+//!
+//! Pipes whoosh as they go past the bird, panning from right to left, and the
+//! score chime is panned towards its gap, for a little stereo spread. A new
+//! best gets a rising jingle on the game over screen.
+
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_kira_audio::prelude::{AudioControl, AudioInstance, AudioPlugin, AudioSource, AudioTween};
+use bevy_kira_audio::AudioChannel as KiraChannel;
+use flappy_core::physics::VELOCITY_SCALE;
+use rand::Rng;
+
+use crate::audio::{synthesize, SfxChannel, SAMPLE_RATE};
+use crate::tick::run_game_ticks;
+use crate::{
+    AppState, Bird, FlapEvent, FlappyConfig, NewBestEvent, PipePair, ScoreEvent, ScrollSpeed,
+};
+
+/// Furthest a sound is panned from the centre, where 0.5 is the full width.
+const MAX_PAN: f64 = 0.25;
+/// World units either side of the bird over which a pipe's whoosh pans.
+const WHOOSH_RANGE: f32 = 48.0;
+
+/// Kira panning for a sound `offset` world units right of the bird, 0.5
+/// being centred. Reaches `MAX_PAN` either side at the edges of a playfield
+/// `width` wide.
+pub fn stereo_pan(offset: f32, width: f32) -> f64 {
+    let side = (offset / (width / 2.0)).clamp(-1.0, 1.0);
+    0.5 + f64::from(side) * MAX_PAN
+}
+
+/// A tone sliding from one pitch to another while it fades out.
+#[derive(Clone, Copy, Debug)]
+pub struct Chirp {
+    pub from_hz: f32,
+    pub to_hz: f32,
+    pub seconds: f32,
+    pub volume: f32,
+}
+
+impl Chirp {
+    pub fn samples(self) -> impl Iterator<Item = f32> {
+        let count = (self.seconds * SAMPLE_RATE as f32) as u32;
+        let mut phase = 0.0;
+        (0..count).map(move |sample| {
+            let t = sample as f32 / count as f32;
+            let frequency = self.from_hz + (self.to_hz - self.from_hz) * t;
+            phase = (phase + frequency / SAMPLE_RATE as f32).fract();
+            let envelope = (1.0 - t).powi(2);
+            (phase * TAU).sin() * envelope * self.volume
+        })
+    }
+}
+
+/// Random nudges for one play of a `SoundEffect`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Variation {
+    /// Multiplies the speed, and so the pitch.
+    pub playback_rate: f64,
+    pub volume: f64,
+}
+
+/// Variants of one sound, played in turn.
+#[derive(Debug)]
+pub struct SoundEffect {
+    variants: Vec<Handle<AudioSource>>,
+    next: usize,
+    /// Largest change to the playback speed, and so the pitch, either way.
+    pub pitch_jitter: f32,
+    /// Largest change to the volume either way.
+    pub volume_jitter: f32,
+}
+
+impl SoundEffect {
+    pub fn new(variants: Vec<Handle<AudioSource>>) -> Self {
+        Self {
+            variants,
+            next: 0,
+            pitch_jitter: 0.08,
+            volume_jitter: 0.15,
+        }
+    }
+
+    /// The variant to play now, moving on to the next for the time after.
+    pub fn next_variant(&mut self) -> Option<Handle<AudioSource>> {
+        let variant = self.variants.get(self.next)?.clone();
+        self.next = (self.next + 1) % self.variants.len();
+        Some(variant)
+    }
+
+    /// Random nudges for one play.
+    pub fn variation(&self) -> Variation {
+        let mut rng = rand::thread_rng();
+        Variation {
+            playback_rate: 1.0 + f64::from(rng.gen_range(-self.pitch_jitter..=self.pitch_jitter)),
+            volume: 1.0 + f64::from(rng.gen_range(-self.volume_jitter..=self.volume_jitter)),
+        }
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct GameSounds {
+    pub flap: SoundEffect,
+    pub score: SoundEffect,
+    pub whoosh: SoundEffect,
+    pub new_best: SoundEffect,
+}
+
+impl FromWorld for GameSounds {
+    fn from_world(world: &mut World) -> Self {
+        let mut sources = world.resource_mut::<Assets<AudioSource>>();
+        let chirp = |from_hz, to_hz, seconds| Chirp {
+            from_hz,
+            to_hz,
+            seconds,
+            volume: 0.3,
+        };
+        // a major arpeggio, C5 E5 G5 C6
+        let jingle = [523.0, 659.0, 784.0, 1047.0]
+            .into_iter()
+            .flat_map(|hz| chirp(hz, hz, 0.12).samples());
+        let new_best = sources.add(synthesize(jingle));
+        let mut add = |from_hz, to_hz, seconds| {
+            sources.add(synthesize(chirp(from_hz, to_hz, seconds).samples()))
+        };
+        Self {
+            // quick upward whooshes
+            flap: SoundEffect::new(vec![
+                add(300.0, 600.0, 0.08),
+                add(320.0, 660.0, 0.07),
+                add(280.0, 560.0, 0.09),
+            ]),
+            // bright rising dings
+            score: SoundEffect::new(vec![add(880.0, 1320.0, 0.15), add(932.0, 1397.0, 0.15)]),
+            // low falling sweeps
+            whoosh: SoundEffect::new(vec![add(220.0, 110.0, 0.3), add(200.0, 100.0, 0.3)]),
+            // always the same tune
+            new_best: SoundEffect {
+                pitch_jitter: 0.0,
+                ..SoundEffect::new(vec![new_best])
+            },
+        }
+    }
+}
+
+pub(crate) fn add_sound_effects(app: &mut App) {
+    if app.is_plugin_added::<AudioPlugin>() {
+        app.init_resource::<GameSounds>()
+            .add_system(play_flap_sounds)
+            .add_system(play_score_sounds.after(run_game_ticks))
+            .add_system(play_new_best_jingle)
+            .add_system(play_whooshes.in_set(OnUpdate(AppState::Game)));
+    }
+}
+
+fn play(
+    sfx: &KiraChannel<SfxChannel>,
+    effect: &mut SoundEffect,
+    panning: f64,
+) -> Option<Handle<AudioInstance>> {
+    let variant = effect.next_variant()?;
+    let variation = effect.variation();
+    Some(
+        sfx.play(variant)
+            .with_playback_rate(variation.playback_rate)
+            .with_volume(variation.volume)
+            .with_panning(panning)
+            .handle(),
+    )
+}
+
+fn play_flap_sounds(
+    sfx: Res<KiraChannel<SfxChannel>>,
+    mut sounds: ResMut<GameSounds>,
+    mut flaps: EventReader<FlapEvent>,
+) {
+    for _ in flaps.iter() {
+        play(&sfx, &mut sounds.flap, 0.5);
+    }
+}
+
+fn play_score_sounds(
+    config: Res<FlappyConfig>,
+    sfx: Res<KiraChannel<SfxChannel>>,
+    mut sounds: ResMut<GameSounds>,
+    mut scores: EventReader<ScoreEvent>,
+    bird_query: Query<&GlobalTransform, With<Bird>>,
+    pair_query: Query<&GlobalTransform, With<PipePair>>,
+) {
+    let bird_x = bird_query
+        .iter()
+        .next()
+        .map_or(0.0, |transform| transform.translation().x);
+    for score in scores.iter() {
+        let offset = pair_query
+            .get(score.pair)
+            .map_or(0.0, |transform| transform.translation().x - bird_x);
+        play(&sfx, &mut sounds.score, stereo_pan(offset, config.width()));
+    }
+}
+
+fn play_new_best_jingle(
+    sfx: Res<KiraChannel<SfxChannel>>,
+    mut sounds: ResMut<GameSounds>,
+    mut new_bests: EventReader<NewBestEvent>,
+) {
+    if new_bests.iter().count() > 0 {
+        play(&sfx, &mut sounds.new_best, 0.5);
+    }
+}
+
+/// Starts a whoosh as each pipe comes within `WHOOSH_RANGE` of the bird,
+/// panned right, and sweeps it to the left for as long as the pipe takes to
+/// go past.
+#[allow(clippy::too_many_arguments)]
+fn play_whooshes(
+    config: Res<FlappyConfig>,
+    scroll: Res<ScrollSpeed>,
+    sfx: Res<KiraChannel<SfxChannel>>,
+    mut sounds: ResMut<GameSounds>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut last_offsets: Local<HashMap<Entity, f32>>,
+    mut pending_sweeps: Local<Vec<Handle<AudioInstance>>>,
+    bird_query: Query<&Transform, With<Bird>>,
+    pair_query: Query<(Entity, &Transform, &PipePair)>,
+) {
+    let Some(bird) = bird_query.iter().next() else {
+        return;
+    };
+    let speed = scroll.0 * VELOCITY_SCALE;
+    let pass = Duration::from_secs_f32(2.0 * WHOOSH_RANGE / speed.max(1.0));
+    let start = stereo_pan(WHOOSH_RANGE, config.width());
+    let end = stereo_pan(-WHOOSH_RANGE, config.width());
+
+    // instances only exist once kira has started them, usually a frame later
+    pending_sweeps.retain(|whoosh| match instances.get_mut(whoosh) {
+        Some(instance) => {
+            instance.set_panning(end, AudioTween::linear(pass));
+            false
+        }
+        None => true,
+    });
+
+    // forget pairs that have been despawned since
+    last_offsets.retain(|entity, _| pair_query.contains(*entity));
+    for (entity, transform, pair) in pair_query.iter() {
+        let offset = transform.translation.x - bird.translation.x;
+        let last = last_offsets.insert(entity, offset);
+        let arrived = pair.active && last.is_some_and(|last| last > WHOOSH_RANGE);
+        if !arrived || offset > WHOOSH_RANGE {
+            continue;
+        }
+        pending_sweeps.extend(play(&sfx, &mut sounds.whoosh, start));
+    }
+}
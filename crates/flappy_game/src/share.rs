@@ -0,0 +1,119 @@
+//! A Share button on the game over screen, which copies a line about the run,
+//! with its score and seed, to the clipboard for pasting wherever the player
+//! likes.
+
+use bevy::prelude::*;
+
+use crate::clipboard::Clipboard;
+use crate::locale::localized_text;
+use crate::results::ResultsCues;
+use crate::{
+    AppState, ButtonSkin, DespawnOnExit, FlappyConfig, Focusable, Locale, Localized,
+    ResultsPanelEvent, RunSeed, Score,
+};
+
+#[derive(Component)]
+pub struct ShareButton;
+
+/// Text to put on the clipboard, sent when the Share button is clicked.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShareEvent {
+    pub text: String,
+}
+
+pub(crate) fn add_share(app: &mut App) {
+    app.add_event::<ShareEvent>().add_systems(
+        (
+            spawn_share_button.after(ResultsCues),
+            share_score,
+            copy_shared_text.after(share_score),
+        )
+            .in_set(OnUpdate(AppState::GameOver)),
+    );
+}
+
+/// Sits under the save clip button, brought in with the panel.
+fn spawn_share_button(
+    mut commands: Commands,
+    config: Res<FlappyConfig>,
+    mut panels: EventReader<ResultsPanelEvent>,
+) {
+    if panels.iter().last().is_none() {
+        return;
+    }
+    let skin = ButtonSkin::panel();
+    commands
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        top: Val::Px((15.0 + 26.0) + config.screen().y / 2.0),
+                        left: Val::Px(config.screen().x / 2.0 - 20.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Px(40.0), Val::Px(12.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: skin.normal.color.into(),
+                ..default()
+            },
+            skin,
+            Focusable { order: 2 },
+            ShareButton,
+            DespawnOnExit(AppState::GameOver),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("share.button"),
+                1.0,
+                Color::WHITE,
+            ));
+        });
+}
+
+fn share_score(
+    locale: Res<Locale>,
+    score: Res<Score>,
+    seed: Res<RunSeed>,
+    mut shares: EventWriter<ShareEvent>,
+    query: Query<&Interaction, (Changed<Interaction>, With<ShareButton>)>,
+) {
+    let clicked = query
+        .iter()
+        .any(|interaction| *interaction == Interaction::Clicked);
+    if clicked {
+        let args = [score.0.to_string(), seed.seed.to_string()];
+        shares.send(ShareEvent {
+            text: locale.format("share.text", &args),
+        });
+    }
+}
+
+fn copy_shared_text(
+    mut clipboard: NonSendMut<Clipboard>,
+    mut shares: EventReader<ShareEvent>,
+    button_query: Query<&Children, With<ShareButton>>,
+    mut label_query: Query<&mut Localized>,
+) {
+    for share in shares.iter() {
+        let key = match clipboard.set_text(&share.text) {
+            Ok(()) => {
+                info!(text = %share.text, "copied to the clipboard");
+                "share.copied"
+            }
+            Err(error) => {
+                warn!(%error, "couldn't copy to the clipboard");
+                "share.failed"
+            }
+        };
+        for children in button_query.iter() {
+            let mut labels = label_query.iter_many_mut(children);
+            while let Some(mut label) = labels.fetch_next() {
+                label.key = key;
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Single-switch assist for players who can't press repeatedly. With
+//! `Settings::single_switch` on, the first flap starts the bird flapping by
+//! itself, about often enough to hold its height. Holding a flap key steers it
+//! up by flapping sooner, and letting go steers it down by flapping later.
+//! In `CoOp` the switch is the first player's; the second flaps as usual.
+
+use bevy::prelude::*;
+use flappy_core::physics::Physics;
+
+use crate::{
+    Bird, DevConsole, FlapEvent, FlappyConfig, Gravity, PhysicsClock, Player, Settings, Velocity,
+};
+
+/// Flap interval, as a multiple of the level one, while the switch is held.
+const HELD_RATE: f32 = 0.8;
+/// Flap interval, as a multiple of the level one, while the switch is up.
+const RELEASED_RATE: f32 = 1.25;
+
+/// Time since the last automatic flap.
+#[derive(Resource, Default, Debug)]
+pub struct AutoFlap {
+    pub elapsed: f32,
+}
+
+impl AutoFlap {
+    /// Seconds between flaps that keep the bird level, as each flap's climb is
+    /// undone by the fall before the next.
+    pub fn level_interval(physics: &Physics) -> f32 {
+        2.0 * physics.jump_velocity / physics.gravity
+    }
+}
+
+pub(crate) fn add_single_switch(app: &mut App) {
+    app.init_resource::<AutoFlap>();
+}
+
+/// Flaps on a timer biased by the switch, once the player has started the
+/// bird off.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn auto_flap(
+    clock: Res<PhysicsClock>,
+    keyboard_input: Res<Input<KeyCode>>,
+    touches: Res<Touches>,
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    console: Res<DevConsole>,
+    mut auto_flap: ResMut<AutoFlap>,
+    mut flaps: EventWriter<FlapEvent>,
+    mut query: Query<(Entity, &Player, &mut Velocity, &Gravity), With<Bird>>,
+) {
+    if !settings.single_switch {
+        return;
+    }
+    let held = !console.open
+        && (keyboard_input.any_pressed(config.bindings.flap.iter().copied())
+            || touches.iter().next().is_some());
+    let rate = if held { HELD_RATE } else { RELEASED_RATE };
+    let interval = AutoFlap::level_interval(&config.physics) * rate;
+
+    for (bird, player, mut velocity, gravity) in query.iter_mut() {
+        if *player != Player::ONE {
+            continue;
+        }
+        if !gravity.0 {
+            // still waiting for the first flap
+            auto_flap.elapsed = 0.0;
+            continue;
+        }
+        auto_flap.elapsed += clock.step;
+        if auto_flap.elapsed >= interval {
+            auto_flap.elapsed = 0.0;
+            velocity.0.y = config.physics.jump_velocity;
+            flaps.send(FlapEvent { bird });
+        }
+    }
+}
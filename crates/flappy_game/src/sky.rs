@@ -0,0 +1,115 @@
+//! Vertical gradient behind the playfield, drawn with `SkyMaterial` on a
+//! full-playfield quad. Change the `Sky` resource to recolour it.
+
+use bevy::asset::load_internal_asset;
+use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy::render::render_resource::{AsBindGroup, ShaderRef};
+use bevy::sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle};
+
+use crate::FlappyConfig;
+
+const SKY_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 0x5c1f_08d3_a2e4_77b1);
+
+/// Just in front of the far plane of a default `Camera2dBundle`.
+const SKY_Z: f32 = -0.05;
+
+/// Colours of the sky, read by the material whenever this changes.
+#[derive(Resource, Clone, Debug)]
+pub struct Sky {
+    pub top: Color,
+    pub bottom: Color,
+    /// Multiplied with the gradient, e.g. to darken it at night.
+    pub tint: Color,
+}
+
+impl Default for Sky {
+    fn default() -> Self {
+        Self {
+            top: Color::rgb(0.35, 0.65, 0.9),
+            bottom: Color::rgb(0.95, 0.8, 0.65),
+            tint: Color::WHITE,
+        }
+    }
+}
+
+impl Sky {
+    /// Colour `t` of the way down from the top, as the shader draws it.
+    pub fn color_at(&self, t: f32) -> Color {
+        let top = Vec4::from(self.top.as_linear_rgba_f32());
+        let bottom = Vec4::from(self.bottom.as_linear_rgba_f32());
+        let tint = Vec4::from(self.tint.as_linear_rgba_f32());
+        let [r, g, b, a] = (top.lerp(bottom, t.clamp(0.0, 1.0)) * tint).to_array();
+        Color::rgba_linear(r, g, b, a)
+    }
+}
+
+#[derive(AsBindGroup, TypeUuid, Clone, Debug)]
+#[uuid = "2f6a3f0e-8d1b-4c52-9a57-1b0e6c3d9f41"]
+pub struct SkyMaterial {
+    #[uniform(0)]
+    pub top: Color,
+    #[uniform(0)]
+    pub bottom: Color,
+    #[uniform(0)]
+    pub tint: Color,
+}
+
+impl From<&Sky> for SkyMaterial {
+    fn from(sky: &Sky) -> Self {
+        Self {
+            top: sky.top,
+            bottom: sky.bottom,
+            tint: sky.tint,
+        }
+    }
+}
+
+impl Material2d for SkyMaterial {
+    fn fragment_shader() -> ShaderRef {
+        SKY_SHADER_HANDLE.typed().into()
+    }
+}
+
+/// Only the `Sky` resource is added without a renderer.
+pub(crate) fn add_sky(app: &mut App, rendering: bool) {
+    app.init_resource::<Sky>();
+    if !rendering {
+        return;
+    }
+    load_internal_asset!(app, SKY_SHADER_HANDLE, "sky.wgsl", Shader::from_wgsl);
+    app.add_plugin(Material2dPlugin::<SkyMaterial>::default())
+        .add_startup_system(spawn_sky)
+        .add_system(update_sky_material);
+}
+
+fn spawn_sky(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<SkyMaterial>>,
+    config: Res<FlappyConfig>,
+    sky: Res<Sky>,
+) {
+    commands.spawn(MaterialMesh2dBundle {
+        mesh: meshes.add(shape::Quad::new(config.playfield).into()).into(),
+        material: materials.add(SkyMaterial::from(&*sky)),
+        transform: Transform::from_xyz(0.0, 0.0, SKY_Z),
+        ..default()
+    });
+}
+
+fn update_sky_material(
+    sky: Res<Sky>,
+    mut materials: ResMut<Assets<SkyMaterial>>,
+    query: Query<&Handle<SkyMaterial>>,
+) {
+    if !sky.is_changed() {
+        return;
+    }
+    for handle in query.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            *material = SkyMaterial::from(&*sky);
+        }
+    }
+}
@@ -0,0 +1,16 @@
+struct SkyMaterial {
+    top: vec4<f32>,
+    bottom: vec4<f32>,
+    tint: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> material: SkyMaterial;
+
+@fragment
+fn fragment(
+    #import bevy_sprite::mesh2d_vertex_output
+) -> @location(0) vec4<f32> {
+    // the quad's uvs run from 0 at the top to 1 at the bottom
+    return mix(material.top, material.bottom, uv.y) * material.tint;
+}
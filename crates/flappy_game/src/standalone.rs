@@ -0,0 +1,102 @@
+//! The game as an app of its own, rather than a plugin in someone else's:
+//! what the desktop binary and the `flappy_mobile` library both run. The
+//! window's scale factor is kept so the `FlappyConfig::screen` fills it,
+//! whether that's a window opened at `WINDOW_SCALE` or a phone's whole
+//! screen.
+
+use bevy::log::LogPlugin;
+use bevy::prelude::*;
+use bevy::window::{PrimaryWindow, WindowMode, WindowResolution};
+use bevy_kira_audio::AudioPlugin;
+
+use crate::{FlappyConfig, FlappyGamePlugin};
+
+/// Screen pixels per pixel of art in a desktop window.
+pub const WINDOW_SCALE: f32 = 3.0;
+/// Physics steps per second. Displays refreshing faster see bodies drawn
+/// between steps.
+pub const PHYSICS_RATE: f32 = 60.0;
+
+/// How the app is opened, besides the `FlappyConfig`.
+#[derive(Clone, Debug, Default)]
+pub struct StandaloneOptions {
+    /// Take the whole screen, as on phones, rather than opening a window.
+    pub fullscreen: bool,
+    /// Leave logging to a subscriber the host has already set up, see
+    /// `init_logging`.
+    pub external_logging: bool,
+}
+
+/// The config the game is played with on its own: a 16:9 playfield a third
+/// of 720p, on a fixed `PHYSICS_RATE`.
+pub fn standalone_config() -> FlappyConfig {
+    FlappyConfig {
+        playfield: Vec2::new(1280.0, 720.0) / WINDOW_SCALE,
+        physics_rate: Some(PHYSICS_RATE),
+        ..default()
+    }
+}
+
+/// An app that runs the game with `config`: Bevy's default plugins with kira
+/// in place of Bevy's audio and nearest filtering for the pixel art, and the
+/// `FlappyGamePlugin`.
+pub fn standalone_app(config: FlappyConfig, options: StandaloneOptions) -> App {
+    let screen = config.screen();
+    let mut window = Window {
+        title: "Flappy Bevy".to_string(),
+        ..default()
+    };
+    if options.fullscreen {
+        // on Android, with the activity's fullscreen theme, this hides the
+        // system bars
+        window.mode = WindowMode::BorderlessFullscreen;
+    } else {
+        window.resolution = WindowResolution::new(screen.x, screen.y)
+            .with_scale_factor_override(WINDOW_SCALE as f64);
+    }
+
+    // audio goes through `bevy_kira_audio` instead
+    let mut plugins = DefaultPlugins.build().disable::<bevy::audio::AudioPlugin>();
+    if options.external_logging {
+        plugins = plugins.disable::<LogPlugin>();
+    }
+
+    let mut app = App::new();
+    app.add_plugins(
+        plugins
+            .set(WindowPlugin {
+                primary_window: Some(window),
+                // closing mid-run asks first, see `RunState::Quitting`
+                close_when_requested: false,
+                ..default()
+            })
+            .set(ImagePlugin::default_nearest()),
+    )
+    .add_plugin(AudioPlugin)
+    .add_plugin(FlappyGamePlugin { config })
+    .add_system(fit_screen_to_window);
+    app
+}
+
+/// Scales the window so the screen fills as much of it as fits, as it opens
+/// and whenever it's resized.
+fn fit_screen_to_window(
+    config: Res<FlappyConfig>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let screen = config.screen();
+    let physical = Vec2::new(
+        window.resolution.physical_width() as f32,
+        window.resolution.physical_height() as f32,
+    );
+    if physical.min_element() <= 0.0 {
+        return;
+    }
+    let scale = (physical / screen).min_element() as f64;
+    if window.resolution.scale_factor_override() != Some(scale) {
+        window.resolution.set_scale_factor_override(Some(scale));
+    }
+}
@@ -0,0 +1,103 @@
+//! Frame time, FPS and entity counts in the corner of the screen, toggled with
+//! `InputBindings::stats_overlay`.
+
+use bevy::diagnostic::{Diagnostics, DiagnosticsPlugin, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+
+use crate::{FlappyConfig, PipePair, SafeArea};
+
+#[derive(Resource, Default, Debug)]
+pub struct StatsOverlay {
+    pub enabled: bool,
+}
+
+#[derive(Component)]
+pub(crate) struct StatsText;
+
+pub(crate) fn add_stats_overlay(app: &mut App) {
+    // `DefaultPlugins` brings the former but not `MinimalPlugins`
+    if !app.is_plugin_added::<DiagnosticsPlugin>() {
+        app.add_plugin(DiagnosticsPlugin);
+    }
+    if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+        app.add_plugin(FrameTimeDiagnosticsPlugin);
+    }
+    app.init_resource::<StatsOverlay>()
+        .add_startup_system(spawn_stats_text)
+        .add_system(toggle_stats_overlay)
+        .add_system(update_stats_text.after(toggle_stats_overlay));
+}
+
+fn spawn_stats_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+) {
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load(config.assets.font.as_str()),
+                font_size: 8.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            display: Display::None,
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(2.0),
+                right: Val::Px(2.0),
+                ..default()
+            },
+            ..default()
+        }),
+        StatsText,
+        SafeArea::default(),
+    ));
+}
+
+fn toggle_stats_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    config: Res<FlappyConfig>,
+    mut overlay: ResMut<StatsOverlay>,
+) {
+    if keyboard_input.just_pressed(config.bindings.stats_overlay) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+fn update_stats_text(
+    overlay: Res<StatsOverlay>,
+    diagnostics: Res<Diagnostics>,
+    entity_query: Query<Entity>,
+    pair_query: Query<&PipePair>,
+    mut text_query: Query<(&mut Text, &mut Style), With<StatsText>>,
+) {
+    for (mut text, mut style) in text_query.iter_mut() {
+        style.display = if overlay.enabled {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if !overlay.enabled {
+            continue;
+        }
+
+        let smoothed = |id| {
+            diagnostics
+                .get(id)
+                .and_then(|diagnostic| diagnostic.smoothed())
+                .unwrap_or_default()
+        };
+        let active_pairs = pair_query.iter().filter(|pair| pair.active).count();
+        text.sections[0].value = format!(
+            "{:.1} ms\n{:.0} fps\n{} entities\n{} pipe pairs ({} pooled)",
+            smoothed(FrameTimeDiagnosticsPlugin::FRAME_TIME),
+            smoothed(FrameTimeDiagnosticsPlugin::FPS),
+            entity_query.iter().count(),
+            active_pairs,
+            pair_query.iter().count() - active_pairs,
+        );
+    }
+}
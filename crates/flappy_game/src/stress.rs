@@ -0,0 +1,167 @@
+//! A hidden benchmark, `FlappyConfig::stress_test`: thousands of pipe pairs
+//! scrolling across the playfield at once, for checking that collision and
+//! rendering changes hold up under load. The pairs wrap round to the right
+//! rather than going back to the `PipePool`, and the bird is made invincible
+//! so the run never ends. Vsync and the frame rate cap are lifted meanwhile,
+//! see `frame_rate`. After enough frames the frame times are summed up in
+//! `FrameStats`, printed, and the app exits.
+
+use std::fmt;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use flappy_core::pipes::GapParams;
+
+use crate::{spawn_pipe_pair, AppState, Cheats, FlappyConfig};
+
+/// How big a stress test to run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StressTest {
+    /// Pipe pairs scrolling at once, each a top and a bottom pipe.
+    pub pairs: usize,
+    /// Frames to time before printing `FrameStats` and exiting.
+    pub frames: usize,
+}
+
+impl Default for StressTest {
+    fn default() -> Self {
+        Self {
+            pairs: 2000,
+            frames: 600,
+        }
+    }
+}
+
+/// Summary of a stress test's frame times, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameStats {
+    pub frames: usize,
+    pub mean: f32,
+    pub min: f32,
+    pub median: f32,
+    pub p95: f32,
+    pub p99: f32,
+    pub max: f32,
+}
+
+impl FrameStats {
+    /// Sums up `frame_times`, given in seconds. `None` when there are none.
+    pub fn from_frame_times(frame_times: &[f32]) -> Option<Self> {
+        if frame_times.is_empty() {
+            return None;
+        }
+        let mut ms: Vec<f32> = frame_times.iter().map(|time| time * 1000.0).collect();
+        ms.sort_by(f32::total_cmp);
+        let percentile = |p: f32| ms[((ms.len() - 1) as f32 * p).round() as usize];
+        Some(Self {
+            frames: ms.len(),
+            mean: ms.iter().sum::<f32>() / ms.len() as f32,
+            min: ms[0],
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: ms[ms.len() - 1],
+        })
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} frames, {:.0} fps on average",
+            self.frames,
+            1000.0 / self.mean
+        )?;
+        writeln!(f, "mean   {:>8.2} ms", self.mean)?;
+        writeln!(f, "min    {:>8.2} ms", self.min)?;
+        writeln!(f, "median {:>8.2} ms", self.median)?;
+        writeln!(f, "p95    {:>8.2} ms", self.p95)?;
+        writeln!(f, "p99    {:>8.2} ms", self.p99)?;
+        write!(f, "max    {:>8.2} ms", self.max)
+    }
+}
+
+/// Frame times of the stress test so far, in seconds.
+#[derive(Resource, Default, Debug)]
+pub struct StressRecorder {
+    pub frame_times: Vec<f32>,
+}
+
+/// A pair spawned for the stress test, wrapped round instead of pooled.
+#[derive(Component)]
+pub struct StressPair;
+
+pub(crate) fn add_stress_test(app: &mut App) {
+    app.init_resource::<StressRecorder>()
+        .add_system(spawn_stress_pairs.in_schedule(OnEnter(AppState::Game)))
+        .add_systems((wrap_stress_pairs, record_frame_times).in_set(OnUpdate(AppState::Game)));
+}
+
+/// Spreads the pairs evenly over the playfield, with the usual random gaps.
+fn spawn_stress_pairs(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    mut cheats: ResMut<Cheats>,
+    mut recorder: ResMut<StressRecorder>,
+) {
+    let Some(stress) = &config.stress_test else {
+        return;
+    };
+    info!(
+        pairs = stress.pairs,
+        frames = stress.frames,
+        "starting stress test"
+    );
+    cheats.invincible = true;
+    recorder.frame_times.clear();
+    let gaps = GapParams::for_height(config.height());
+    let mut rng = rand::thread_rng();
+    for index in 0..stress.pairs {
+        let gap = gaps.sample(&mut rng);
+        let x = (index as f32 / stress.pairs as f32 - 0.5) * config.width();
+        let pair = spawn_pipe_pair(&mut commands, &asset_server, &config, gap);
+        commands
+            .entity(pair)
+            .insert((Transform::from_xyz(x, gap.center, 0.0), StressPair));
+    }
+}
+
+/// Moves pairs leaving on the left back round to the right, well before
+/// `recycle_offscreen_pipes` would park them.
+fn wrap_stress_pairs(
+    config: Res<FlappyConfig>,
+    mut query: Query<&mut Transform, With<StressPair>>,
+) {
+    for mut transform in query.iter_mut() {
+        if transform.translation.x < -config.width() / 2.0 {
+            transform.translation.x += config.width();
+        }
+    }
+}
+
+/// Times each frame in real time, then prints the `FrameStats` and exits once
+/// `StressTest::frames` have gone by.
+fn record_frame_times(
+    time: Res<Time>,
+    config: Res<FlappyConfig>,
+    mut recorder: ResMut<StressRecorder>,
+    mut exits: EventWriter<AppExit>,
+) {
+    let Some(stress) = &config.stress_test else {
+        return;
+    };
+    if recorder.frame_times.len() >= stress.frames {
+        return;
+    }
+    recorder.frame_times.push(time.delta_seconds());
+    if recorder.frame_times.len() < stress.frames {
+        return;
+    }
+    if let Some(stats) = FrameStats::from_frame_times(&recorder.frame_times) {
+        info!(?stats, "stress test done");
+        println!("{} pipe pairs\n{stats}", stress.pairs);
+    }
+    exits.send(AppExit);
+}
@@ -0,0 +1,87 @@
+//! Anonymous gameplay telemetry, for tuning. Only collected when the player
+//! turns on `Settings::telemetry` from the settings screen, which offers it
+//! when `FlappyConfig::telemetry_url` is set, and only posted with the
+//! `telemetry` feature, see `telemetry_client`.
+//!
+//! Nothing identifies the player or device: each event is one run's length,
+//! score, death cause, difficulty and whether assist mode was on. Batches are
+//! posted as JSON, `{"events": [...]}`.
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{Cheats, DeathCause, DeathEvent, Difficulty, FlappyConfig, FlightLog, Score, Settings};
+
+/// Oldest events are dropped past this many waiting to be sent.
+const MAX_PENDING: usize = 100;
+
+/// Something worth knowing about how the game plays.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    RunEnded {
+        /// Seconds from the start of the run to the death.
+        seconds: f32,
+        score: u32,
+        cause: DeathCause,
+        difficulty: Difficulty,
+        /// Played with `Settings::assist`.
+        assisted: bool,
+    },
+}
+
+/// Events waiting for the next batch.
+#[derive(Resource, Default, Debug)]
+pub struct Telemetry {
+    pub pending: Vec<TelemetryEvent>,
+}
+
+pub(crate) fn add_telemetry(app: &mut App, config: &FlappyConfig) {
+    app.init_resource::<Telemetry>()
+        .add_system(record_runs)
+        .add_system(forget_when_disabled);
+    let Some(url) = &config.telemetry_url else {
+        return;
+    };
+    #[cfg(feature = "telemetry")]
+    crate::telemetry_client::add_telemetry_client(app, url.clone());
+    #[cfg(not(feature = "telemetry"))]
+    warn!(%url, "telemetry needs the telemetry feature");
+}
+
+fn record_runs(
+    config: Res<FlappyConfig>,
+    settings: Res<Settings>,
+    score: Res<Score>,
+    flight: Res<FlightLog>,
+    cheats: Res<Cheats>,
+    mut deaths: EventReader<DeathEvent>,
+    mut telemetry: ResMut<Telemetry>,
+) {
+    // a bird can hit several things on the frame it dies
+    let Some(cause) = deaths.iter().next().map(|death| death.cause) else {
+        return;
+    };
+    deaths.clear();
+    // an invincible bird "dies" on every tick it touches something
+    if !settings.telemetry || config.telemetry_url.is_none() || cheats.invincible {
+        return;
+    }
+    telemetry.pending.push(TelemetryEvent::RunEnded {
+        seconds: flight.samples.last().map_or(0.0, |sample| sample.time),
+        score: score.0,
+        cause,
+        difficulty: settings.difficulty,
+        assisted: settings.assist.enabled,
+    });
+    if telemetry.pending.len() > MAX_PENDING {
+        telemetry.pending.remove(0);
+    }
+}
+
+/// Drops anything unsent once the player opts out.
+fn forget_when_disabled(settings: Res<Settings>, mut telemetry: ResMut<Telemetry>) {
+    if settings.is_changed() && !settings.telemetry && !telemetry.pending.is_empty() {
+        telemetry.pending.clear();
+    }
+}
@@ -0,0 +1,78 @@
+//! Posting telemetry batches, enabled with the `telemetry` feature. A batch
+//! goes once `BATCH_SIZE` events are waiting, or the oldest has waited
+//! `BATCH_SECONDS`. Batches that fail to post are dropped rather than retried.
+
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use serde::Serialize;
+
+use crate::{Telemetry, TelemetryEvent};
+
+const BATCH_SIZE: usize = 10;
+const BATCH_SECONDS: f32 = 300.0;
+
+#[derive(Serialize)]
+struct Batch<'a> {
+    events: &'a [TelemetryEvent],
+}
+
+#[derive(Resource)]
+struct TelemetryClient {
+    url: String,
+    /// Seconds the oldest pending event has waited.
+    waited: f32,
+    posting: Option<Task<Result<usize, String>>>,
+}
+
+pub(crate) fn add_telemetry_client(app: &mut App, url: String) {
+    app.insert_resource(TelemetryClient {
+        url,
+        waited: 0.0,
+        posting: None,
+    })
+    .add_system(post_telemetry)
+    .add_system(finish_posting.after(post_telemetry));
+}
+
+fn post_telemetry(
+    time: Res<Time>,
+    mut telemetry: ResMut<Telemetry>,
+    mut client: ResMut<TelemetryClient>,
+) {
+    if telemetry.pending.is_empty() {
+        client.waited = 0.0;
+        return;
+    }
+    client.waited += time.delta_seconds();
+    let due = telemetry.pending.len() >= BATCH_SIZE || client.waited >= BATCH_SECONDS;
+    if !due || client.posting.is_some() {
+        return;
+    }
+    client.waited = 0.0;
+    let events = std::mem::take(&mut telemetry.pending);
+    let url = client.url.clone();
+    client.posting = Some(AsyncComputeTaskPool::get().spawn(async move {
+        let body =
+            serde_json::to_string(&Batch { events: &events }).map_err(|error| error.to_string())?;
+        ureq::post(&url)
+            .set("Content-Type", "application/json")
+            .send_string(&body)
+            .map_err(|error| error.to_string())?;
+        Ok(events.len())
+    }));
+}
+
+fn finish_posting(mut client: ResMut<TelemetryClient>) {
+    let Some(task) = client.posting.as_mut() else {
+        return;
+    };
+    let Some(result) = future::block_on(future::poll_once(task)) else {
+        return;
+    };
+    client.posting = None;
+    match result {
+        Ok(events) => debug!(events, "posted telemetry"),
+        Err(error) => warn!(%error, "failed to post telemetry"),
+    }
+}
@@ -0,0 +1,232 @@
+//! Visual themes, applied to the sky, clouds, pipes and score whenever `Theme`
+//! changes. The theme follows `Settings`, which choose between the classic
+//! look, a high-contrast one and, once unlocked, the night one.
+
+use bevy::prelude::*;
+use flappy_core::PIPE_SIZE;
+use rand::Rng;
+
+use crate::hud::ScoreText;
+use crate::{
+    placeholder_image, Cosmetic, Digits, Pipe, PipePair, PipeSpawnedEvent, Profile, Settings, Sky,
+};
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub sky_top: Color,
+    pub sky_bottom: Color,
+    /// Tints the pipe textures, or colours them when `solid_pipes` is set.
+    pub pipe_color: Color,
+    /// Shades one of which is picked for each pair, on top of `pipe_color`,
+    /// so the pipes aren't all alike. Empty leaves every pair `pipe_color`.
+    pub pipe_palette: Vec<Color>,
+    /// Draw pipes as flat rectangles of `pipe_color` instead of their textures.
+    pub solid_pipes: bool,
+    /// Screen pixels per pixel of the score's `Digits`.
+    pub score_scale: f32,
+    /// Clouds drifting across the sky at once, see `clouds`.
+    pub clouds: usize,
+    pub cloud_color: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+impl Theme {
+    pub fn classic() -> Self {
+        let sky = Sky::default();
+        Self {
+            sky_top: sky.top,
+            sky_bottom: sky.bottom,
+            pipe_color: Color::WHITE,
+            pipe_palette: vec![
+                Color::WHITE,
+                Color::rgb(0.88, 1.0, 0.88),
+                Color::rgb(1.0, 0.95, 0.85),
+                Color::rgb(0.9, 0.95, 1.0),
+            ],
+            solid_pipes: false,
+            score_scale: 2.0,
+            clouds: 6,
+            cloud_color: Color::rgba(1.0, 1.0, 1.0, 0.8),
+        }
+    }
+
+    /// Bright flat pipes on a dark, cloudless sky, with a bigger score.
+    pub fn high_contrast() -> Self {
+        Self {
+            sky_top: Color::rgb(0.05, 0.05, 0.1),
+            sky_bottom: Color::rgb(0.05, 0.05, 0.1),
+            pipe_color: Color::rgb(1.0, 0.9, 0.0),
+            // every pipe alike, for the clearest contrast
+            pipe_palette: Vec::new(),
+            solid_pipes: true,
+            score_scale: 3.0,
+            clouds: 0,
+            cloud_color: Color::NONE,
+        }
+    }
+
+    /// The colour of a pair that picked `pick`, from 0 to 1, from the palette.
+    pub fn pipe_tint(&self, pick: f32) -> Color {
+        if self.pipe_palette.is_empty() {
+            return self.pipe_color;
+        }
+        let index = (pick * self.pipe_palette.len() as f32) as usize;
+        let shade = self.pipe_palette[index.min(self.pipe_palette.len() - 1)];
+        let [r, g, b, a] = self.pipe_color.as_rgba_f32();
+        Color::rgba(r * shade.r(), g * shade.g(), b * shade.b(), a * shade.a())
+    }
+
+    /// A dark blue sky with dimmed pipes and a few grey clouds, unlocked as
+    /// `Cosmetic::NightTheme`.
+    pub fn night() -> Self {
+        Self {
+            sky_top: Color::rgb(0.03, 0.05, 0.15),
+            sky_bottom: Color::rgb(0.2, 0.15, 0.35),
+            pipe_color: Color::rgb(0.7, 0.75, 0.9),
+            clouds: 3,
+            cloud_color: Color::rgba(0.6, 0.6, 0.75, 0.5),
+            ..Self::classic()
+        }
+    }
+
+    /// The theme `settings` choose, skipping the night theme until `profile`
+    /// has unlocked it.
+    pub fn for_settings(settings: &Settings, profile: &Profile) -> Self {
+        if settings.high_contrast {
+            Self::high_contrast()
+        } else if settings.night_theme && Cosmetic::NightTheme.unlocked(profile) {
+            Self::night()
+        } else {
+            Self::classic()
+        }
+    }
+}
+
+/// White stand-in for pipe textures when `Theme::solid_pipes` is set.
+#[derive(Resource)]
+struct SolidPipeTexture(Handle<Image>);
+
+/// The texture a pipe was spawned with, to go back to after a solid theme.
+#[derive(Component)]
+struct ThemedTexture(Handle<Image>);
+
+/// A pair's pick from `Theme::pipe_palette`, from 0 to 1, kept across themes.
+/// Picked again each time the pair comes out of the pool.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PipeTint(pub f32);
+
+pub(crate) fn add_themes(app: &mut App) {
+    app.init_resource::<Theme>()
+        .add_startup_system(create_solid_pipe_texture)
+        .add_system(select_theme)
+        .add_system(apply_sky_theme.after(select_theme))
+        // new pairs' tints are inserted before the theme is applied to them
+        .add_systems(
+            (
+                tint_new_pairs,
+                apply_system_buffers,
+                apply_pipe_theme.after(select_theme),
+            )
+                .chain(),
+        )
+        .add_system(apply_score_theme.after(select_theme));
+}
+
+fn create_solid_pipe_texture(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    let texture = images.add(placeholder_image(PIPE_SIZE, Color::WHITE));
+    commands.insert_resource(SolidPipeTexture(texture));
+}
+
+fn select_theme(settings: Res<Settings>, profile: Res<Profile>, mut theme: ResMut<Theme>) {
+    if !settings.is_changed() && !profile.is_changed() {
+        return;
+    }
+    let selected = Theme::for_settings(&settings, &profile);
+    if *theme != selected {
+        *theme = selected;
+    }
+}
+
+fn apply_sky_theme(theme: Res<Theme>, mut sky: ResMut<Sky>) {
+    if theme.is_changed() {
+        sky.top = theme.sky_top;
+        sky.bottom = theme.sky_bottom;
+    }
+}
+
+/// Picks a tint for each new pair, away from the seeded generator so the gaps
+/// stay the same. Pooled pipes are recoloured here; new ones once they've
+/// been spawned, by `apply_pipe_theme`.
+fn tint_new_pairs(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut spawned: EventReader<PipeSpawnedEvent>,
+    pair_query: Query<&PipePair>,
+    mut sprite_query: Query<&mut Sprite, With<Pipe>>,
+) {
+    let mut rng = rand::thread_rng();
+    for event in spawned.iter() {
+        let tint = PipeTint(rng.gen());
+        commands.entity(event.pair).insert(tint);
+        let Ok(pair) = pair_query.get(event.pair) else {
+            continue;
+        };
+        let mut sprites = sprite_query.iter_many_mut([pair.top, pair.bottom]);
+        while let Some(mut sprite) = sprites.fetch_next() {
+            sprite.color = theme.pipe_tint(tint.0);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_pipe_theme(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    solid: Res<SolidPipeTexture>,
+    mut query: Query<
+        (
+            Entity,
+            &mut Handle<Image>,
+            &mut Sprite,
+            Option<&ThemedTexture>,
+            Option<&Parent>,
+        ),
+        With<Pipe>,
+    >,
+    tint_query: Query<&PipeTint>,
+) {
+    for (entity, mut texture, mut sprite, themed, parent) in query.iter_mut() {
+        let original = match themed {
+            Some(_) if !theme.is_changed() => continue,
+            Some(themed) => themed.0.clone(),
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(ThemedTexture(texture.clone()));
+                texture.clone()
+            }
+        };
+        *texture = if theme.solid_pipes {
+            solid.0.clone()
+        } else {
+            original
+        };
+        let tint = parent.and_then(|parent| tint_query.get(parent.get()).ok());
+        sprite.color = theme.pipe_tint(tint.map_or(0.0, |tint| tint.0));
+    }
+}
+
+/// The score also reads the theme when it's spawned.
+fn apply_score_theme(theme: Res<Theme>, mut query: Query<&mut Digits, With<ScoreText>>) {
+    if !theme.is_changed() {
+        return;
+    }
+    for mut digits in query.iter_mut() {
+        digits.scale = theme.score_scale;
+    }
+}
@@ -0,0 +1,63 @@
+//! The game tick: everything that decides how a run plays out, from flapping
+//! through spawning and moving to collisions and scoring, run in the
+//! `GameTick` schedule once per `PhysicsClock` step. Each system in it
+//! advances by `PhysicsClock::step`, so with a fixed `physics_rate` a run
+//! plays out the same whatever the frame rate. The stages run in the order
+//! of `GameSystem`, and transforms are propagated between the movement and
+//! the collisions, so the tick collides and scores with where everything
+//! has just moved to rather than where it was drawn last frame.
+//!
+//! What only shows the run, like effects, the HUD and sound, stays in the
+//! frame's update and reads the tick's events after it.
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy::transform::systems::{propagate_transforms, sync_simple_transforms};
+use bevy::transform::TransformSystem;
+
+use crate::interpolation::tick_physics_clock;
+use crate::{AppState, GameSystem, HitStop, PhysicsClock};
+
+/// Schedule run for each step of the `PhysicsClock` while a run is under way.
+#[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GameTick;
+
+pub(crate) fn add_game_tick(app: &mut App) {
+    let mut schedule = Schedule::new();
+    schedule.configure_sets(
+        (
+            GameSystem::Input,
+            GameSystem::Spawning,
+            GameSystem::Movement,
+            GameSystem::Collisions,
+            GameSystem::Scoring,
+        )
+            .chain(),
+    );
+    app.add_schedule(GameTick, schedule)
+        .add_systems(
+            (sync_simple_transforms, propagate_transforms)
+                .chain()
+                .in_set(TransformSystem::TransformPropagate)
+                .after(GameSystem::Movement)
+                .before(GameSystem::Collisions)
+                .in_schedule(GameTick),
+        )
+        .add_system(
+            run_game_ticks
+                .after(tick_physics_clock)
+                .in_set(OnUpdate(AppState::Game)),
+        );
+}
+
+/// Runs the `GameTick` for each step this frame pays for. The run freezes on
+/// its first death for the hit stop, then ticks on through the `DeathCam` at
+/// its slowed rate.
+pub(crate) fn run_game_ticks(world: &mut World) {
+    for _ in 0..world.resource::<PhysicsClock>().steps {
+        if world.resource::<HitStop>().frozen() {
+            break;
+        }
+        world.run_schedule(GameTick);
+    }
+}
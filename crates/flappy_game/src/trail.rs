@@ -0,0 +1,153 @@
+//! Fading afterimages left behind the bird, enabled with `Settings::trail`.
+//!
+//! Afterimages scroll left with the pipes, so they read as a trail even though
+//! the bird itself never moves horizontally.
+
+use bevy::prelude::*;
+use flappy_core::physics::displacement;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppState, Bird, ConsoleCommands, Cosmetic, DespawnOnExit, MotionPrefs, Profile, ScrollSpeed,
+    Settings, TimeScale,
+};
+
+/// Draw afterimages just behind the bird.
+const AFTERIMAGE_Z: f32 = -0.01;
+
+/// Look of the bird's trail. Different values can be handed out as unlockable
+/// cosmetics.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BirdTrail {
+    /// Tint of each afterimage when spawned. The alpha fades to zero.
+    pub color: Color,
+    /// Seconds between afterimages.
+    pub interval: f32,
+    /// Seconds each afterimage lasts.
+    pub lifetime: f32,
+}
+
+impl Default for BirdTrail {
+    fn default() -> Self {
+        Self {
+            color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+            interval: 0.05,
+            lifetime: 0.3,
+        }
+    }
+}
+
+impl BirdTrail {
+    /// Unlocked as `Cosmetic::GoldTrail`.
+    pub fn gold() -> Self {
+        Self {
+            color: Color::rgba(1.0, 0.8, 0.2, 0.6),
+            ..default()
+        }
+    }
+
+    /// What has to be unlocked to fly with this.
+    pub fn cosmetic(&self) -> Option<Cosmetic> {
+        (*self == Self::gold()).then_some(Cosmetic::GoldTrail)
+    }
+
+    pub fn unlocked(&self, profile: &Profile) -> bool {
+        self.cosmetic()
+            .is_none_or(|cosmetic| cosmetic.unlocked(profile))
+    }
+}
+
+#[derive(Component)]
+pub struct Afterimage {
+    age: Timer,
+    alpha: f32,
+}
+
+pub(crate) fn add_trail(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "trail",
+        "trail on|off: toggle the bird's trail",
+        toggle_trail,
+    );
+    app.add_systems((spawn_afterimages, fade_afterimages).in_set(OnUpdate(AppState::Game)));
+}
+
+fn toggle_trail(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let trail = match args.first() {
+        Some(&"on") => Some(BirdTrail::default()),
+        Some(&"off") => None,
+        _ => return Err("expected 'trail on' or 'trail off'".to_string()),
+    };
+    world.resource_mut::<Settings>().trail = trail;
+    Ok(String::new())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_afterimages(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    profile: Res<Profile>,
+    motion: Res<MotionPrefs>,
+    mut timer: Local<Timer>,
+    bird_query: Query<(&Transform, &Handle<Image>), With<Bird>>,
+) {
+    let trail = settings
+        .trail
+        .filter(|trail| motion.particles && trail.unlocked(&profile));
+    let Some(trail) = trail else {
+        return;
+    };
+    if timer.duration().as_secs_f32() != trail.interval {
+        *timer = Timer::from_seconds(trail.interval, TimerMode::Repeating);
+    }
+    if !timer.tick(time_scale.delta(&time)).just_finished() {
+        return;
+    }
+
+    for (transform, texture) in bird_query.iter() {
+        let mut translation = transform.translation;
+        translation.z += AFTERIMAGE_Z;
+        commands.spawn((
+            SpriteBundle {
+                texture: texture.clone(),
+                sprite: Sprite {
+                    color: trail.color,
+                    ..default()
+                },
+                transform: Transform {
+                    translation,
+                    ..*transform
+                },
+                ..default()
+            },
+            Afterimage {
+                age: Timer::from_seconds(trail.lifetime, TimerMode::Once),
+                alpha: trail.color.a(),
+            },
+            DespawnOnExit(AppState::Game),
+        ));
+    }
+}
+
+/// Scrolls afterimages with the pipes, fades them out and despawns them once
+/// their lifetime is up.
+fn fade_afterimages(
+    time: Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut commands: Commands,
+    scroll: Res<ScrollSpeed>,
+    mut query: Query<(Entity, &mut Afterimage, &mut Sprite, &mut Transform)>,
+) {
+    let dt = time_scale.delta_seconds(&time);
+    for (entity, mut afterimage, mut sprite, mut transform) in query.iter_mut() {
+        if afterimage.age.tick(time_scale.delta(&time)).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let remaining = 1.0 - afterimage.age.percent();
+        sprite.color.set_a(afterimage.alpha * remaining);
+        transform.translation.x -= displacement(scroll.0, dt);
+    }
+}
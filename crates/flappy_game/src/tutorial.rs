@@ -0,0 +1,75 @@
+//! Hints shown over the first run on a fresh profile, until the first pipe is
+//! passed.
+
+use bevy::prelude::*;
+
+use crate::locale::localized_text;
+use crate::{AppState, DespawnOnExit, FlappyConfig, Localized, Profile, ScoreEvent};
+
+/// One of the first-run hints.
+#[derive(Component)]
+pub struct TutorialHint;
+
+pub(crate) fn add_tutorial(app: &mut App) {
+    app.add_system(spawn_tutorial.in_schedule(OnEnter(AppState::Game)))
+        .add_system(finish_tutorial.in_set(OnUpdate(AppState::Game)));
+}
+
+fn spawn_tutorial(mut commands: Commands, config: Res<FlappyConfig>, profile: Res<Profile>) {
+    if profile.tutorial_done {
+        return;
+    }
+    let flap_key = config
+        .bindings
+        .flap
+        .first()
+        .map_or_else(String::new, |key| format!("{key:?}"));
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: UiRect {
+                        bottom: Val::Px(24.0),
+                        ..default()
+                    },
+                    size: Size::new(Val::Percent(100.0), Val::Auto),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+            TutorialHint,
+            DespawnOnExit(AppState::Game),
+        ))
+        .with_children(|parent| {
+            parent.spawn(localized_text(
+                Localized::new("tutorial.flap").with_args([flap_key]),
+                2.0,
+                Color::WHITE,
+            ));
+            parent.spawn(localized_text(
+                Localized::new("tutorial.pipes"),
+                1.0,
+                Color::WHITE,
+            ));
+        });
+}
+
+/// Clears the hints for good once a pipe is passed.
+fn finish_tutorial(
+    mut commands: Commands,
+    mut scores: EventReader<ScoreEvent>,
+    mut profile: ResMut<Profile>,
+    query: Query<Entity, With<TutorialHint>>,
+) {
+    if scores.iter().count() == 0 || profile.tutorial_done {
+        return;
+    }
+    info!("tutorial done");
+    profile.tutorial_done = true;
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
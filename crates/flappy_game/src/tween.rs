@@ -0,0 +1,214 @@
+//! Minimal tweening for UI and sprites. Add a `Tween` to an entity and it is
+//! animated in real time, so menus keep moving while the game is slowed or
+//! frozen. The component is removed once the tween finishes.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+
+use crate::AppState;
+
+/// Length of the fade from black when entering a state.
+const STATE_FADE_SECONDS: f32 = 0.25;
+
+/// Easing curves, mapping linear progress in `0..=1` to eased progress.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Ease {
+    #[default]
+    Linear,
+    /// Fast start, slow finish.
+    QuadOut,
+    /// Overshoots the end slightly before settling.
+    BackOut,
+    /// Springs past the end a few times before settling.
+    ElasticOut,
+}
+
+impl Ease {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Ease::BackOut => {
+                const OVERSHOOT: f32 = 1.70158;
+                let t = t - 1.0;
+                1.0 + (OVERSHOOT + 1.0) * t * t * t + OVERSHOOT * t * t
+            }
+            Ease::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    return t;
+                }
+                2f32.powf(-10.0 * t) * ((t * 10.0 - 0.75) * (2.0 * PI / 3.0)).sin() + 1.0
+            }
+        }
+    }
+}
+
+/// What a `Tween` animates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TweenTarget {
+    Translation {
+        from: Vec3,
+        to: Vec3,
+    },
+    Scale {
+        from: Vec3,
+        to: Vec3,
+    },
+    /// Rotation about the z axis, in radians.
+    Rotation {
+        from: f32,
+        to: f32,
+    },
+    /// Alpha of a UI node's `BackgroundColor`.
+    BackgroundAlpha {
+        from: f32,
+        to: f32,
+    },
+    /// Alpha of a `Sprite`'s colour.
+    SpriteAlpha {
+        from: f32,
+        to: f32,
+    },
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Tween {
+    pub target: TweenTarget,
+    pub ease: Ease,
+    /// Seconds from start to finish, not counting `delay`.
+    pub duration: f32,
+    /// Seconds to hold the `from` value before starting.
+    pub delay: f32,
+    /// Despawn the entity, rather than just this component, when finished.
+    pub despawn: bool,
+    elapsed: f32,
+}
+
+impl Tween {
+    pub fn new(target: TweenTarget, duration: f32, ease: Ease) -> Self {
+        Self {
+            target,
+            ease,
+            duration,
+            delay: 0.0,
+            despawn: false,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn despawn_when_done(mut self) -> Self {
+        self.despawn = true;
+        self
+    }
+
+    /// Eased progress, which can leave `0..=1` for overshooting curves.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        self.ease.apply((self.elapsed - self.delay) / self.duration)
+    }
+
+    pub fn finished(&self) -> bool {
+        self.elapsed >= self.delay + self.duration
+    }
+}
+
+/// Black overlay faded out when entering a state.
+#[derive(Component)]
+pub(crate) struct StateFade;
+
+pub(crate) fn add_tweens(app: &mut App) {
+    app.add_system(animate_tweens);
+    for state in [AppState::MainMenu, AppState::Game, AppState::GameOver] {
+        app.add_system(spawn_state_fade.in_schedule(OnEnter(state)));
+    }
+}
+
+fn spawn_state_fade(mut commands: Commands, fade_query: Query<Entity, With<StateFade>>) {
+    for entity in fade_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    commands.spawn((
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            background_color: Color::BLACK.into(),
+            // let clicks through to the buttons underneath
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(150),
+            ..default()
+        },
+        StateFade,
+        Tween::new(
+            TweenTarget::BackgroundAlpha { from: 1.0, to: 0.0 },
+            STATE_FADE_SECONDS,
+            Ease::Linear,
+        )
+        .despawn_when_done(),
+    ));
+}
+
+#[allow(clippy::type_complexity)]
+fn animate_tweens(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(
+        Entity,
+        &mut Tween,
+        Option<&mut Transform>,
+        Option<&mut BackgroundColor>,
+        Option<&mut Sprite>,
+    )>,
+) {
+    for (entity, mut tween, transform, background, sprite) in query.iter_mut() {
+        tween.elapsed += time.delta_seconds();
+        let t = tween.progress();
+        match tween.target {
+            TweenTarget::Translation { from, to } => {
+                if let Some(mut transform) = transform {
+                    transform.translation = from.lerp(to, t);
+                }
+            }
+            TweenTarget::Scale { from, to } => {
+                if let Some(mut transform) = transform {
+                    transform.scale = from.lerp(to, t);
+                }
+            }
+            TweenTarget::Rotation { from, to } => {
+                if let Some(mut transform) = transform {
+                    transform.rotation = Quat::from_rotation_z(from + (to - from) * t);
+                }
+            }
+            TweenTarget::BackgroundAlpha { from, to } => {
+                if let Some(mut background) = background {
+                    background.0.set_a(from + (to - from) * t);
+                }
+            }
+            TweenTarget::SpriteAlpha { from, to } => {
+                if let Some(mut sprite) = sprite {
+                    sprite.color.set_a(from + (to - from) * t);
+                }
+            }
+        }
+
+        if tween.finished() {
+            if tween.despawn {
+                commands.entity(entity).despawn_recursive();
+            } else {
+                commands.entity(entity).remove::<Tween>();
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Build identification, shown in a corner of the main menu and written to the
+//! log so bug reports can be tied to a build.
+
+use bevy::prelude::*;
+
+use crate::{AppState, DespawnOnExit, FlappyConfig};
+
+/// The crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Short hash of the commit built from, or "unknown". Set by `build.rs`.
+pub const GIT_HASH: &str = env!("FLAPPY_GIT_HASH");
+
+/// The version and commit as one string, e.g. "v0.1.0 (1a2b3c4)".
+pub fn build_info() -> String {
+    format!("v{VERSION} ({GIT_HASH})")
+}
+
+pub(crate) fn add_version_overlay(app: &mut App) {
+    info!(version = VERSION, commit = GIT_HASH, "flappy bevy");
+    app.add_system(spawn_version_text.in_schedule(OnEnter(AppState::MainMenu)));
+}
+
+fn spawn_version_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+) {
+    commands.spawn((
+        TextBundle::from_section(
+            build_info(),
+            TextStyle {
+                font: asset_server.load(config.assets.font.as_str()),
+                font_size: 6.0,
+                color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                right: Val::Px(2.0),
+                bottom: Val::Px(2.0),
+                ..default()
+            },
+            ..default()
+        }),
+        DespawnOnExit(AppState::MainMenu),
+    ));
+}
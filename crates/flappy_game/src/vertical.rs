@@ -0,0 +1,90 @@
+//! The vertical variant, `FlappyConfig::vertical`: the game turned on its
+//! side, with the pipes falling from the top and the bird flapping sideways
+//! against a pull to the right. The world runs exactly as usual, so velocity,
+//! collisions, spawning and the floor and ceiling all carry over; the camera
+//! is turned, making the playfield's long axis the screen's height.
+//!
+//! What's drawn for the player rather than the world is turned back to face
+//! them. Text in the world is turned upright where it's spawned, and the
+//! game over screen's sprites are laid out under the `ScreenSpace` root,
+//! which turns with the camera, across the `FlappyConfig::screen` rather than
+//! the playfield. UI nodes already follow the window.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{BitmapText, FlappyConfig};
+
+/// Root of what's laid out across the screen in the world, like the results
+/// panel's sprites. Turned with the camera, so its children keep to the
+/// screen's axes either way up.
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct ScreenSpace(pub Entity);
+
+pub(crate) fn add_vertical(app: &mut App) {
+    let root = app.world.spawn(SpatialBundle::default()).id();
+    app.insert_resource(ScreenSpace(root))
+        .add_system(turn_cameras)
+        .add_system(turn_screen_space)
+        .add_system(upright_text);
+}
+
+/// How the camera is turned so the world's right, where pipes spawn, is up.
+pub fn vertical_rotation() -> Quat {
+    Quat::from_rotation_z(-FRAC_PI_2)
+}
+
+/// How the camera is turned for `config`.
+pub fn view_rotation(config: &FlappyConfig) -> Quat {
+    if config.vertical {
+        vertical_rotation()
+    } else {
+        Quat::IDENTITY
+    }
+}
+
+/// Turns every 2D camera as it's spawned, including a host's own.
+fn turn_cameras(config: Res<FlappyConfig>, mut query: Query<&mut Transform, Added<Camera2d>>) {
+    if !config.vertical {
+        return;
+    }
+    for mut transform in query.iter_mut() {
+        transform.rotation = vertical_rotation();
+    }
+}
+
+fn turn_screen_space(
+    config: Res<FlappyConfig>,
+    space: Res<ScreenSpace>,
+    mut query: Query<&mut Transform>,
+) {
+    if !config.is_changed() {
+        return;
+    }
+    if let Ok(mut transform) = query.get_mut(space.0) {
+        transform.rotation = view_rotation(&config);
+    }
+}
+
+/// Turns text in the world with the camera, so it still reads across. Text
+/// under a parent is left to it, like `ScreenSpace`'s.
+#[allow(clippy::type_complexity)]
+fn upright_text(
+    config: Res<FlappyConfig>,
+    mut query: Query<
+        &mut Transform,
+        (
+            Or<(Added<BitmapText>, Added<Text>)>,
+            Without<Node>,
+            Without<Parent>,
+        ),
+    >,
+) {
+    if !config.vertical {
+        return;
+    }
+    for mut transform in query.iter_mut() {
+        transform.rotation = vertical_rotation();
+    }
+}
@@ -0,0 +1,171 @@
+//! Window dressing: the bird as the window and taskbar icon, and optionally a
+//! themed cursor drawn in place of the OS one while the window has focus.
+//!
+//! Bevy has no icon API yet, so the icon goes straight to winit.
+
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::render::render_resource::TextureFormat;
+use bevy::ui::FocusPolicy;
+use bevy::window::PrimaryWindow;
+use bevy::winit::WinitWindows;
+use winit::window::Icon;
+
+use crate::{ConsoleCommands, FlappyConfig, Settings};
+
+/// Nearest-neighbour magnification of the bird, so the OS has to do less
+/// blurry scaling of its own.
+const ICON_SCALE: u32 = 4;
+
+/// Follows the mouse while `Settings::custom_cursor` is on.
+#[derive(Component)]
+struct ThemedCursor;
+
+pub(crate) fn add_window_dressing(app: &mut App) {
+    app.world.resource_mut::<ConsoleCommands>().register(
+        "cursor",
+        "cursor on|off: toggle the themed mouse cursor",
+        toggle_cursor,
+    );
+    app.add_startup_system(spawn_themed_cursor)
+        .add_system(set_window_icon)
+        .add_system(update_themed_cursor);
+}
+
+fn toggle_cursor(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let custom_cursor = match args.first() {
+        Some(&"on") => true,
+        Some(&"off") => false,
+        _ => return Err("expected 'cursor on' or 'cursor off'".to_string()),
+    };
+    world.resource_mut::<Settings>().custom_cursor = custom_cursor;
+    Ok(String::new())
+}
+
+/// Centres `image` in a square, as taskbars show icons square, and scales it
+/// up by `ICON_SCALE`. Only 8-bit RGBA images are supported.
+fn icon_rgba(image: &Image) -> Option<(Vec<u8>, u32)> {
+    if !matches!(
+        image.texture_descriptor.format,
+        TextureFormat::Rgba8UnormSrgb | TextureFormat::Rgba8Unorm
+    ) {
+        return None;
+    }
+    let size = image.texture_descriptor.size;
+    let (width, height) = (size.width, size.height);
+    let side = width.max(height);
+    let (left, top) = ((side - width) / 2, (side - height) / 2);
+    let scaled = side * ICON_SCALE;
+    let mut rgba = vec![0; (scaled * scaled * 4) as usize];
+    for y in 0..scaled {
+        for x in 0..scaled {
+            let (source_x, source_y) = (x / ICON_SCALE, y / ICON_SCALE);
+            if source_x < left
+                || source_y < top
+                || source_x >= left + width
+                || source_y >= top + height
+            {
+                continue;
+            }
+            let from = (((source_y - top) * width + (source_x - left)) * 4) as usize;
+            let to = ((y * scaled + x) * 4) as usize;
+            rgba[to..to + 4].copy_from_slice(&image.data[from..from + 4]);
+        }
+    }
+    Some((rgba, scaled))
+}
+
+/// Waits for the bird texture, then hands it to the primary window.
+fn set_window_icon(
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+    images: Res<Assets<Image>>,
+    winit_windows: NonSend<WinitWindows>,
+    window_query: Query<Entity, With<PrimaryWindow>>,
+    mut done: Local<bool>,
+    mut handle: Local<Option<Handle<Image>>>,
+) {
+    if *done {
+        return;
+    }
+    let handle = handle.get_or_insert_with(|| asset_server.load(config.assets.bird.as_str()));
+    match asset_server.get_load_state(&*handle) {
+        LoadState::Loaded => {}
+        LoadState::Failed | LoadState::Unloaded => {
+            *done = true;
+            return;
+        }
+        LoadState::NotLoaded | LoadState::Loading => return,
+    }
+    let Some(window) = window_query
+        .get_single()
+        .ok()
+        .and_then(|entity| winit_windows.get_window(entity))
+    else {
+        return;
+    };
+    *done = true;
+    let Some((rgba, side)) = images.get(handle).and_then(icon_rgba) else {
+        warn!("bird texture isn't RGBA, leaving the default window icon");
+        return;
+    };
+    match Icon::from_rgba(rgba, side, side) {
+        Ok(icon) => window.set_window_icon(Some(icon)),
+        Err(error) => warn!(%error, "failed to set the window icon"),
+    }
+}
+
+fn spawn_themed_cursor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<FlappyConfig>,
+) {
+    commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            image: asset_server.load(config.assets.cursor.as_str()).into(),
+            focus_policy: FocusPolicy::Pass,
+            // above everything, even the error screen
+            z_index: ZIndex::Global(i32::MAX),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        ThemedCursor,
+    ));
+}
+
+/// Swaps the OS cursor for the themed one while it's over the focused window.
+fn update_themed_cursor(
+    settings: Res<Settings>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    mut cursor_query: Query<(&mut Style, &mut Visibility), With<ThemedCursor>>,
+) {
+    let Ok(mut window) = window_query.get_single_mut() else {
+        return;
+    };
+    let position = window
+        .cursor_position()
+        .filter(|_| settings.custom_cursor && window.focused);
+    if window.cursor.visible != position.is_none() {
+        window.cursor.visible = position.is_none();
+    }
+    for (mut style, mut visibility) in cursor_query.iter_mut() {
+        *visibility = if position.is_some() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if let Some(position) = position {
+            // window coordinates start at the bottom, UI ones at the top, and
+            // the cursor's hot spot is the top left of its image
+            style.position = UiRect {
+                left: Val::Px(position.x),
+                top: Val::Px(window.height() - position.y),
+                ..default()
+            };
+        }
+    }
+}
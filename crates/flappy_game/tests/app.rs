@@ -0,0 +1,3462 @@
+use std::time::Duration;
+
+use bevy::app::AppExit;
+use bevy::asset::HandleId;
+use bevy::ecs::event::ManualEventReader;
+use bevy::ecs::system::CommandQueue;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadInfo};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::touch::{TouchInput, TouchPhase};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use bevy::time::TimeUpdateStrategy;
+use bevy::transform::TransformPlugin;
+use bevy::window::{ReceivedCharacter, WindowCloseRequested};
+use bevy_kira_audio::AudioSource;
+use bevy_prototype_debug_lines::DebugLines;
+use flappy_core::collision::BOUNDARY_THICKNESS;
+use flappy_core::medal::Medal;
+use flappy_core::physics::displacement;
+use flappy_core::pipes::{Gap, GapParams};
+use flappy_core::{BIRD_SIZE, PIPE_SIZE};
+use flappy_game::{
+    build_info, encode_gif, placeholder_image, plot_flight, rgba_bytes, spawn_pickup,
+    spawn_pipe_pair, stereo_pan, vertical_rotation, AccessibilityButton, AccessibilityToggle,
+    Accessory, Action, Afterimage, AirDash, AppLifecycle, AppState, AssistButton, AudioChannel,
+    AutoFlap, BestMarker, Biome, BiomeLayer, Biomes, Bird, BirdTrail, BitmapFont, BitmapText,
+    Boundary, ButtonSkin, ChallengeCode, ChallengeInput, ChallengeRun, Cheats, Clearance,
+    ClipRecorder, Cloud, CloudSave, CloudSyncConfig, CoOp, Coins, Collider, CollisionButton,
+    CollisionMode, ConflictButton, Cosmetic, CosmeticButton, Countdown, DashMeter, DeadzoneButton,
+    DeathCam, DeathCause, DeathEvent, DeathRecord, DeathStats, DebugOverlay, DespawnOffscreen,
+    DespawnOnExit, DevConsole, Difficulty, DifficultyCurves, DigitFont, Digits, Ease, ErrorScreen,
+    FatalError, FlapEvent, FlappyConfig, FlappyGamePlugin, FlightGraph, FlightLog, FlightSample,
+    Focusable, FrameCapButton, FrameLimiter, FrameRate, FrameStats, GameOverEvent, GapClickEvent,
+    GapCue, GoldenGap, Gravity, GroundColumn, GroundScroll, HapticEvent, Haptics, Hazard,
+    HazardWarning, HeatmapCell, HitFlash, Hitbox, InputBindings, Interpolated, Language, LiveBoard,
+    LiveBoardConfig, LiveBoardList, LiveScore, Locale, Localized, LookSlider, Magnet, MedalDisplay,
+    MenuButton, MenuFocus, MotionPrefs, Music, MusicTrack, MuteIndicator, NewBestEvent,
+    NewBestRibbon, Outline, Pace, PendingFlaps, PhysicsClock, PhysicsResetButton, PhysicsSlider,
+    Pickup, Pipe, PipePair, PipePool, PipeSpawnedEvent, PipeTimer, PipeTint, Player, Profile,
+    QuitButton, RebindButton, Rebinding, Resolve, ResultsTimeline, RunRecord, RunRecorder, RunSeed,
+    RunState, RunStats, SafeArea, SafeAreaInsets, Score, ScoreEvent, ScorePopup, ScrollSpeed,
+    Scrolls, Settings, ShareButton, ShareEvent, Sky, Slot, SoundEffect, StatsOverlay,
+    StickNavigation, StressPair, StressRecorder, StressTest, Telemetry, TelemetryButton,
+    TelemetryEvent, Theme, TimeScale, TutorialHint, Tween, TweenTarget, UnlockToast, Velocity,
+    Volume, VolumeSlider, VsyncButton, BIOME_POINTS, CLOUD_TOKEN_VAR, DASH_COOLDOWN, DASH_SECONDS,
+    DASH_UNLOCK_SCORE, GIT_HASH, GOLDEN_POINTS, NEAR_MISS_MARGIN, PANEL_DELAY, SEED_LIMIT,
+    WARNING_SECONDS,
+};
+
+fn test_app() -> App {
+    test_app_with(|_| {})
+}
+
+/// A `test_app` with `configure` applied to its config first.
+fn test_app_with(configure: impl FnOnce(&mut FlappyConfig)) -> App {
+    let mut config = FlappyConfig {
+        spawn_camera: false,
+        initial_state: AppState::Game,
+        profile_path: None,
+        settings_path: None,
+        stats_path: None,
+        bindings_path: None,
+        // skip the death sequence, see `deaths_freeze_the_game_before_game_over`
+        hit_stop: 0.0,
+        death_slow_mo: 0.0,
+        ..default()
+    };
+    configure(&mut config);
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .add_plugin(AssetPlugin::default())
+        .add_plugin(InputPlugin)
+        .add_plugin(TransformPlugin)
+        .add_plugin(HierarchyPlugin)
+        .add_plugin(FlappyGamePlugin { config });
+    // enter the initial state and spawn the bird
+    app.update();
+    app
+}
+
+fn bird_position(app: &mut App) -> Vec3 {
+    app.world
+        .query_filtered::<&Transform, With<Bird>>()
+        .single(&app.world)
+        .translation
+}
+
+/// Each bird's player, position and whether it has started falling, first
+/// player first.
+fn players(app: &mut App) -> Vec<(Player, Vec3, bool)> {
+    let mut birds: Vec<_> = app
+        .world
+        .query_filtered::<(&Player, &Transform, &Gravity), With<Bird>>()
+        .iter(&app.world)
+        .map(|(player, transform, gravity)| (*player, transform.translation, gravity.0))
+        .collect();
+    birds.sort_by_key(|(player, _, _)| player.0);
+    birds
+}
+
+fn spawn_collider(app: &mut App, position: Vec3) -> Entity {
+    app.world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_translation(position)),
+            Collider {
+                size: Vec2::splat(10.0),
+            },
+            DespawnOnExit(AppState::Game),
+        ))
+        .id()
+}
+
+/// Spawns a pipe pair through the game's own spawning code, then moves it to `x`.
+fn spawn_pair(app: &mut App, x: f32) -> Entity {
+    let mut queue = CommandQueue::default();
+    let pair = {
+        let mut commands = Commands::new(&mut queue, &app.world);
+        let gap = Gap {
+            center: 0.0,
+            size: 100.0,
+        };
+        spawn_pipe_pair(
+            &mut commands,
+            app.world.resource::<AssetServer>(),
+            app.world.resource::<FlappyConfig>(),
+            gap,
+        )
+    };
+    queue.apply(&mut app.world);
+    app.world.get_mut::<Transform>(pair).unwrap().translation.x = x;
+    pair
+}
+
+fn add_image(app: &mut App, width: u32, height: u32, data: Vec<u8>) -> Handle<Image> {
+    let size = Extent3d {
+        width,
+        height,
+        depth_or_array_layers: 1,
+    };
+    let image = Image::new(
+        size,
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    app.world.resource_mut::<Assets<Image>>().add(image)
+}
+
+fn state(app: &App) -> AppState {
+    app.world.resource::<State<AppState>>().0
+}
+
+fn run_state(app: &App) -> RunState {
+    app.world.resource::<State<RunState>>().0
+}
+
+fn set_state(app: &mut App, state: AppState) {
+    app.world.resource_mut::<NextState<AppState>>().set(state);
+    app.update();
+}
+
+/// Opens the game over screen the way a death does, and waits out the
+/// `PANEL_DELAY` so its panel is in.
+fn game_over(app: &mut App) {
+    let score = app.world.resource::<Score>().0;
+    set_state(app, AppState::GameOver);
+    app.world.send_event(GameOverEvent { score });
+    app.update();
+    app.world.resource_mut::<ResultsTimeline>().elapsed += PANEL_DELAY;
+    app.update();
+}
+
+/// Runs a frame `seconds` after the last, however long the test really took.
+/// Later frames take no time at all.
+fn advance(app: &mut App, seconds: f32) {
+    // `ManualDuration` counts from the wall clock, not the last frame
+    let time = app.world.resource::<Time>();
+    let last = time.last_update().unwrap_or_else(|| time.startup());
+    app.insert_resource(TimeUpdateStrategy::ManualInstant(
+        last + Duration::from_secs_f32(seconds),
+    ));
+    app.update();
+}
+
+fn finish_countdown(app: &mut App) {
+    let mut countdown = app.world.resource_mut::<Countdown>();
+    let duration = countdown.timer.duration();
+    countdown.timer.set_elapsed(duration);
+    app.update();
+    app.update();
+}
+
+fn press(app: &mut App, key_code: KeyCode) {
+    for state in [ButtonState::Pressed, ButtonState::Released] {
+        app.world.send_event(KeyboardInput {
+            scan_code: 0,
+            key_code: Some(key_code),
+            state,
+        });
+        app.update();
+    }
+}
+
+fn tap(app: &mut App) {
+    for phase in [TouchPhase::Started, TouchPhase::Ended] {
+        app.world.send_event(TouchInput {
+            phase,
+            position: Vec2::ZERO,
+            force: None,
+            id: 0,
+        });
+        app.update();
+    }
+}
+
+#[test]
+fn bird_dies_when_overlapping_a_pipe() {
+    let mut app = test_app();
+    assert_eq!(state(&app), AppState::Game);
+
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    let mut deaths = ManualEventReader::<DeathEvent>::default();
+    let mut causes = Vec::new();
+    for _ in 0..3 {
+        app.update();
+        let events = app.world.resource::<Events<DeathEvent>>();
+        causes.extend(deaths.iter(events).map(|death| death.cause));
+    }
+
+    assert_eq!(state(&app), AppState::GameOver);
+    assert_eq!(causes, vec![DeathCause::Pipe]);
+}
+
+#[test]
+fn collisions_see_where_colliders_moved_this_tick() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+    let collider = spawn_collider(&mut app, bird + Vec3::X * 100.0);
+    app.update();
+    assert!(app.world.resource::<Events<DeathEvent>>().is_empty());
+
+    app.world
+        .get_mut::<Transform>(collider)
+        .unwrap()
+        .translation = bird;
+    app.update();
+    assert!(!app.world.resource::<Events<DeathEvent>>().is_empty());
+}
+
+#[test]
+fn bird_dies_when_touching_the_floor() {
+    let mut app = test_app();
+    let height = app.world.resource::<FlappyConfig>().height();
+    assert_eq!(app.world.query::<&Boundary>().iter(&app.world).count(), 2);
+
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    app.world.get_mut::<Transform>(bird).unwrap().translation.y = -height / 2.0;
+    let mut deaths = ManualEventReader::<DeathEvent>::default();
+    let mut causes = Vec::new();
+    for _ in 0..3 {
+        app.update();
+        let events = app.world.resource::<Events<DeathEvent>>();
+        causes.extend(deaths.iter(events).map(|death| (death.cause, death.normal)));
+    }
+
+    assert_eq!(state(&app), AppState::GameOver);
+    assert_eq!(causes, vec![(DeathCause::OutOfBounds, Vec2::Y)]);
+}
+
+#[test]
+fn floor_rises_with_the_ground() {
+    let mut app = test_app();
+    let config = app.world.resource::<FlappyConfig>().clone();
+    let bottom = -config.height() / 2.0;
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    let hitbox = app.world.get::<Hitbox>(bird).unwrap().size;
+    app.world.get_mut::<Transform>(bird).unwrap().translation.y = bottom + hitbox.y / 2.0 + 1.0;
+    app.update();
+    assert_eq!(state(&app), AppState::Game);
+
+    // over the top of a hill, measured before the ground goes with the run
+    app.world.resource_mut::<GroundScroll>().distance = config.ground().wavelength / 2.0;
+    let mut highest = bottom;
+    for _ in 0..3 {
+        app.update();
+        highest = app
+            .world
+            .query_filtered::<&Transform, With<GroundColumn>>()
+            .iter(&app.world)
+            .map(|transform| transform.translation.y + BOUNDARY_THICKNESS / 2.0)
+            .fold(highest, f32::max);
+    }
+    assert_eq!(state(&app), AppState::GameOver);
+    assert!(highest > bottom + 1.0, "{highest}");
+}
+
+#[test]
+fn vertical_mode_turns_the_camera_so_pipes_fall() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().vertical = true;
+    let camera = app.world.spawn(Camera2dBundle::default()).id();
+    app.update();
+
+    // the view is the inverse of the camera's transform
+    let view = app
+        .world
+        .get::<Transform>(camera)
+        .unwrap()
+        .rotation
+        .inverse();
+    let pipes_arrive = view * Vec3::X;
+    let flaps_towards = view * Vec3::Y;
+    assert!(pipes_arrive.abs_diff_eq(Vec3::Y, 1e-5), "{pipes_arrive}");
+    assert!(
+        flaps_towards.abs_diff_eq(Vec3::NEG_X, 1e-5),
+        "{flaps_towards}"
+    );
+    assert_eq!(
+        app.world.get::<Transform>(camera).unwrap().rotation,
+        vertical_rotation()
+    );
+
+    // pairs spawn at the top of the portrait screen
+    let config = app.world.resource::<FlappyConfig>();
+    let (width, screen) = (config.width(), config.screen());
+    assert_eq!(screen, Vec2::new(config.height(), width));
+    let pair = spawn_pair(&mut app, width / 2.0);
+    app.update();
+    let on_screen = view
+        * app
+            .world
+            .get::<GlobalTransform>(pair)
+            .unwrap()
+            .translation();
+    assert!((on_screen.y - screen.y / 2.0).abs() < 1.0, "{on_screen}");
+}
+
+/// Whether `entity` reads left to right under a camera turned by `view`.
+fn reads_across(app: &App, view: Quat, entity: Entity) -> bool {
+    let (_, rotation, _) = app
+        .world
+        .get::<GlobalTransform>(entity)
+        .unwrap()
+        .to_scale_rotation_translation();
+    (view * rotation * Vec3::X).abs_diff_eq(Vec3::X, 1e-5)
+}
+
+#[test]
+fn vertical_mode_keeps_popups_and_results_upright() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().vertical = true;
+    app.world.spawn(Camera2dBundle::default());
+    app.update();
+    let view = vertical_rotation().inverse();
+
+    let bird = bird_position(&mut app);
+    spawn_pair(&mut app, bird.x);
+    for _ in 0..3 {
+        app.update();
+    }
+    let popup = app
+        .world
+        .query_filtered::<Entity, With<ScorePopup>>()
+        .single(&app.world);
+    assert!(reads_across(&app, view, popup));
+    // and it floats up the screen
+    let from = app.world.get::<Transform>(popup).unwrap().translation;
+    advance(&mut app, 0.1);
+    let risen = view * (app.world.get::<Transform>(popup).unwrap().translation - from);
+    assert!(risen.y > 0.0 && risen.x.abs() < 1e-5, "{risen}");
+
+    app.world.resource_mut::<Score>().0 = 30;
+    game_over(&mut app);
+    // in with the medal, then done scaling up
+    advance(&mut app, 0.7);
+    advance(&mut app, 1.0);
+    let score = app
+        .world
+        .query_filtered::<Entity, (With<Digits>, Without<Node>)>()
+        .single(&app.world);
+    assert!(reads_across(&app, view, score));
+    let banner = app
+        .world
+        .query_filtered::<(Entity, &Localized), With<BitmapText>>()
+        .iter(&app.world)
+        .find(|(_, key)| key.key == "game_over")
+        .map(|(entity, _)| entity)
+        .unwrap();
+    assert!(reads_across(&app, view, banner));
+    let medal = app
+        .world
+        .query_filtered::<Entity, With<MedalDisplay>>()
+        .single(&app.world);
+    assert!(reads_across(&app, view, medal));
+}
+
+#[test]
+fn game_over_graphs_the_flight_through_the_pipes() {
+    let mut app = test_app();
+    for _ in 0..5 {
+        app.update();
+    }
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), AppState::GameOver);
+    let samples = app.world.resource::<FlightLog>().samples.len();
+    assert!(samples >= 5, "{samples} samples");
+    app.world.resource_mut::<ResultsTimeline>().elapsed += PANEL_DELAY;
+    app.update();
+    assert_eq!(
+        app.world
+            .query_filtered::<(), With<FlightGraph>>()
+            .iter(&app.world)
+            .count(),
+        1
+    );
+
+    // a climb from the floor into a gap in the middle
+    let height = app.world.resource::<FlappyConfig>().height();
+    let gap = Gap {
+        center: 0.0,
+        size: height / 4.0,
+    };
+    let flight = [
+        FlightSample {
+            time: 0.0,
+            height: -height / 2.0,
+            gap: None,
+        },
+        FlightSample {
+            time: 1.0,
+            height: 0.0,
+            gap: Some(gap),
+        },
+    ];
+    let size = UVec2::new(2, 4);
+    let pixels: Vec<_> = plot_flight(&flight, height, size)
+        .chunks(4)
+        .map(|pixel| pixel[1])
+        .collect();
+    // rows from the top, two columns each: the top pipe over the path rising
+    // into the gap, then where it started
+    assert_eq!(pixels, [0, 180, 0, 255, 0, 255, 255, 255]);
+}
+
+#[test]
+fn gaps_command_studies_the_current_difficulty() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    app.world.resource_mut::<DevConsole>().submit("gaps 500");
+    app.update();
+    let console = app.world.resource::<DevConsole>();
+    let report = console.log.last().unwrap();
+    assert!(report.starts_with("500 pairs\nsize: min "), "{report}");
+    // gaps a quarter narrower than on normal
+    assert!(report.contains("% per tenth of 30..75"), "{report}");
+    assert!(
+        report.contains("impossible transitions: 0 (0.0%)"),
+        "{report}"
+    );
+}
+
+#[test]
+fn deaths_are_recorded_and_shown_on_the_heatmap() {
+    let mut app = test_app();
+    app.world.resource_mut::<Score>().0 = 4;
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    for _ in 0..3 {
+        app.update();
+    }
+    let recorded = DeathRecord {
+        x: bird.x,
+        y: bird.y,
+        score: 4,
+    };
+    assert_eq!(app.world.resource::<DeathStats>().deaths, vec![recorded]);
+
+    app.world.resource_mut::<DeathStats>().deaths.extend([
+        recorded,
+        DeathRecord {
+            y: 80.0,
+            ..recorded
+        },
+    ]);
+    press(&mut app, KeyCode::F4);
+    let mut cells: Vec<_> = app
+        .world
+        .query::<&HeatmapCell>()
+        .iter(&app.world)
+        .map(|cell| cell.deaths)
+        .collect();
+    cells.sort();
+    assert_eq!(cells, vec![1, 2]);
+
+    press(&mut app, KeyCode::F4);
+    assert_eq!(
+        app.world.query::<&HeatmapCell>().iter(&app.world).count(),
+        0
+    );
+}
+
+#[test]
+fn ceiling_holds_the_bird_by_default() {
+    let mut app = test_app();
+    let height = app.world.resource::<FlappyConfig>().height();
+
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    app.world.get_mut::<Transform>(bird).unwrap().translation.y = height / 2.0;
+    app.world.get_mut::<Velocity>(bird).unwrap().0.y = 2.0;
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(state(&app), AppState::Game);
+    assert!(bird_position(&mut app).y < height / 2.0);
+    assert!(app.world.get::<Velocity>(bird).unwrap().0.y <= 0.0);
+}
+
+#[test]
+fn near_misses_inside_the_sprite_are_forgiven() {
+    let mut app = test_app();
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+
+    // overlaps the sprite by a unit to the right, but not the smaller hitbox
+    let position = bird_position(&mut app) + Vec3::new(BIRD_SIZE.x / 2.0 + 4.0, 0.0, 0.0);
+    spawn_collider(&mut app, position);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), AppState::Game);
+
+    app.world.get_mut::<Hitbox>(bird).unwrap().size = BIRD_SIZE;
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::GameOver);
+}
+
+#[test]
+fn pixel_perfect_collisions_ignore_transparent_corners() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().collision = CollisionMode::PixelPerfect;
+
+    // a bird sprite with its top-left pixel cleared
+    let mut pixels = vec![255; 17 * 12 * 4];
+    pixels[3] = 0;
+    let bird_texture = add_image(&mut app, 17, 12, pixels);
+    let block_texture = add_image(&mut app, 10, 10, vec![255; 10 * 10 * 4]);
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    app.world
+        .entity_mut(bird)
+        .insert((bird_texture, Hitbox { size: BIRD_SIZE }));
+    // masks are built from the asset events at the end of the frame
+    app.update();
+
+    // a block covering only that corner pixel
+    let position = bird_position(&mut app) + Vec3::new(-12.5, 10.0, 0.0);
+    let block = spawn_collider(&mut app, position);
+    app.world.entity_mut(block).insert(block_texture);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), AppState::Game);
+
+    app.world.resource_mut::<Settings>().collision = CollisionMode::Boxes;
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::GameOver);
+}
+
+#[test]
+fn offscreen_pipes_return_to_the_pool() {
+    let mut app = test_app();
+    let width = app.world.resource::<FlappyConfig>().width();
+
+    let offscreen = spawn_pair(&mut app, -width);
+    let onscreen = spawn_pair(&mut app, width / 2.0);
+    app.update();
+
+    assert_eq!(app.world.resource::<PipePool>().idle, vec![offscreen]);
+    assert!(!app.world.get::<PipePair>(offscreen).unwrap().active);
+    assert_eq!(
+        app.world.get::<Visibility>(offscreen),
+        Some(&Visibility::Hidden)
+    );
+    assert!(app.world.get::<PipePair>(onscreen).unwrap().active);
+}
+
+#[test]
+fn pooled_pairs_are_reused_instead_of_spawned() {
+    let mut app = test_app();
+    let width = app.world.resource::<FlappyConfig>().width();
+    let pipes_before = app.world.query::<&Pipe>().iter(&app.world).count();
+
+    let pair = spawn_pair(&mut app, -width);
+    app.update();
+    assert_eq!(app.world.resource::<PipePool>().idle, vec![pair]);
+
+    // wait for the pipe timer to fire
+    while !app.world.resource::<PipePool>().idle.is_empty() {
+        app.update();
+    }
+
+    assert!(app.world.get::<PipePair>(pair).unwrap().active);
+    // back at the right edge, give or take a frame of movement
+    let x = app.world.get::<Transform>(pair).unwrap().translation.x;
+    assert!((x - width / 2.0).abs() < 10.0, "pair at {x}");
+    assert_eq!(
+        app.world.query::<&Pipe>().iter(&app.world).count(),
+        pipes_before + 2
+    );
+}
+
+#[test]
+fn despawn_offscreen_removes_entities_past_the_margin() {
+    let mut app = test_app();
+    let width = app.world.resource::<FlappyConfig>().width();
+
+    let mut spawn = |x: f32| {
+        app.world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(x, 0.0, 0.0)),
+                DespawnOffscreen { margin: 10.0 },
+            ))
+            .id()
+    };
+    let inside_margin = spawn(-width / 2.0 - 5.0);
+    let past_margin = spawn(width / 2.0 + 20.0);
+    app.update();
+    app.update();
+
+    assert!(app.world.get_entity(inside_margin).is_some());
+    assert!(app.world.get_entity(past_margin).is_none());
+}
+
+#[test]
+fn score_increments_once_per_pair() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+
+    // a pair with the bird sitting in the middle of its gap
+    spawn_pair(&mut app, bird.x);
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(app.world.resource::<Score>().0, 1);
+}
+
+#[test]
+fn teleported_pairs_still_score() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+    let width = app.world.resource::<FlappyConfig>().width();
+
+    let pair = spawn_pair(&mut app, width / 2.0);
+    app.update();
+    assert_eq!(app.world.resource::<Score>().0, 0);
+
+    // jump the pair onto the bird rather than sweeping it past
+    app.world.get_mut::<Transform>(pair).unwrap().translation.x = bird.x;
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(app.world.resource::<Score>().0, 1);
+}
+
+#[test]
+fn co_op_birds_flap_apart_and_share_the_score() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::MainMenu);
+    let co_op = app
+        .world
+        .query::<(Entity, &MenuButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| **button == MenuButton::CoOp)
+        .unwrap()
+        .0;
+    *app.world.get_mut::<Interaction>(co_op).unwrap() = Interaction::Clicked;
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::Game);
+    let birds = players(&mut app);
+    assert_eq!(birds.len(), 2);
+    assert!(birds[0].1.y > birds[1].1.y, "lined up one above the other");
+
+    // both birds go through the gap, which only scores once
+    spawn_pair(&mut app, birds[0].1.x);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world.resource::<Score>().0, 1);
+
+    press(&mut app, KeyCode::Up);
+    let flying: Vec<_> = players(&mut app).iter().map(|bird| bird.2).collect();
+    assert_eq!(flying, vec![false, true], "only the second bird flapped");
+    press(&mut app, KeyCode::Space);
+    assert!(players(&mut app).iter().all(|bird| bird.2));
+
+    // losing either bird ends the run for both
+    let second = players(&mut app)[1].1;
+    spawn_collider(&mut app, second);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(players(&mut app).len(), 2, "restarts stay in co-op");
+}
+
+#[test]
+fn debug_overlay_toggles_and_draws_colliders() {
+    let mut app = test_app();
+    // stands in for `DebugLinesPlugin`, which needs a renderer
+    app.init_resource::<DebugLines>();
+    let key = app.world.resource::<FlappyConfig>().bindings.debug_overlay;
+    app.update();
+    assert!(app.world.resource::<DebugLines>().positions.is_empty());
+
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(key),
+        state: ButtonState::Pressed,
+    });
+    app.update();
+
+    assert!(app.world.resource::<DebugOverlay>().enabled);
+    assert!(!app.world.resource::<DebugLines>().positions.is_empty());
+}
+
+#[test]
+fn fixed_rate_physics_draws_bodies_between_steps() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().physics_rate = Some(20.0);
+    app.world.resource_mut::<Cheats>().invincible = true;
+    let pair = spawn_pair(&mut app, 0.0);
+    app.update();
+    assert!(app.world.get::<Interpolated>(pair).is_some());
+
+    // one and a half steps, then a frame too short for another
+    advance(&mut app, 0.075);
+    advance(&mut app, 0.01);
+    let clock = app.world.resource::<PhysicsClock>();
+    assert_eq!(clock.step, 0.05);
+    assert!(clock.alpha > 0.0 && clock.alpha < 1.0);
+    let alpha = clock.alpha;
+
+    let interpolated = *app.world.get::<Interpolated>(pair).unwrap();
+    let speed = app.world.resource::<FlappyConfig>().physics.pipe_speed;
+    let stepped = interpolated.previous.x - interpolated.current.x;
+    assert!(
+        (stepped - displacement(speed, 0.05)).abs() < 1e-3,
+        "{stepped}"
+    );
+    let drawn = app.world.get::<Transform>(pair).unwrap().translation;
+    assert_eq!(
+        drawn,
+        interpolated.previous.lerp(interpolated.current, alpha)
+    );
+}
+
+/// Plays two seconds of a seeded run at `fps`, flapping every quarter
+/// second, and returns the score, how far the ground scrolled, and where each
+/// body's simulation has it.
+fn play_fixed_rate(fps: u32) -> (u32, f32, Vec<Vec3>) {
+    let mut app = test_app();
+    // powers of two, so the frames split into steps exactly
+    app.world.resource_mut::<FlappyConfig>().physics_rate = Some(64.0);
+    app.world.resource_mut::<Cheats>().invincible = true;
+    app.world.resource_mut::<RunSeed>().queued = Some(7);
+    // nothing moves until the frames below
+    advance(&mut app, 0.0);
+    set_state(&mut app, AppState::Game);
+    for frame in 0..fps * 2 {
+        if frame % (fps / 4) == 0 {
+            app.world.resource_mut::<PendingFlaps>().0.push(Player::ONE);
+        }
+        advance(&mut app, 1.0 / fps as f32);
+    }
+    let mut bodies: Vec<_> = app
+        .world
+        .query::<&Interpolated>()
+        .iter(&app.world)
+        .map(|interpolated| interpolated.current)
+        .collect();
+    bodies.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    let score = app.world.resource::<Score>().0;
+    (score, app.world.resource::<GroundScroll>().distance, bodies)
+}
+
+#[test]
+fn fixed_rate_runs_play_out_the_same_at_any_frame_rate() {
+    let (score, scrolled, bodies) = play_fixed_rate(64);
+    assert!(bodies.len() > 1, "{bodies:?}");
+    assert_eq!(play_fixed_rate(32), (score, scrolled, bodies.clone()));
+    assert_eq!(play_fixed_rate(16), (score, scrolled, bodies));
+}
+
+#[test]
+fn shared_run_logs_replay_the_run() {
+    let fixed_rate_app = || {
+        let mut app = test_app();
+        app.world.resource_mut::<FlappyConfig>().physics_rate = Some(64.0);
+        // lives through every frame, so there's a bird to compare
+        app.world.resource_mut::<Cheats>().invincible = true;
+        advance(&mut app, 0.0);
+        app
+    };
+    let frame = 1.0 / 32.0;
+
+    let mut app = fixed_rate_app();
+    app.world.resource_mut::<RunSeed>().queued = Some(7);
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    for index in 0..96 {
+        if index % 10 == 0 {
+            app.world.resource_mut::<PendingFlaps>().0.push(Player::ONE);
+        }
+        advance(&mut app, frame);
+    }
+    let score = app.world.resource::<Score>().0;
+    let bird = bird_position(&mut app);
+    let log = app.world.resource::<RunRecorder>().log.clone().unwrap();
+    assert!(log.ticks > 0 && !log.flaps.is_empty(), "{log:?}");
+
+    // played back from the text alone, ignoring the player's own flaps
+    let mut app = fixed_rate_app();
+    app.world
+        .resource_mut::<DevConsole>()
+        .submit(format!("replay {log}"));
+    app.update();
+    app.update();
+    assert_eq!(app.world.resource::<RunSeed>().seed, 7);
+    for _ in 0..96 {
+        app.world.resource_mut::<PendingFlaps>().0.push(Player::ONE);
+        advance(&mut app, frame);
+    }
+    assert_eq!(app.world.resource::<RunRecorder>().log, Some(log.clone()));
+    assert_eq!(app.world.resource::<Score>().0, score);
+    assert_eq!(bird_position(&mut app), bird);
+
+    // refused with other settings
+    let mut app = fixed_rate_app();
+    app.world.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    app.world
+        .resource_mut::<DevConsole>()
+        .submit(format!("replay {log}"));
+    app.update();
+    assert!(app.world.resource::<RunRecorder>().queued.is_none());
+}
+
+#[test]
+fn stats_overlay_counts_pipe_pairs() {
+    let mut app = test_app();
+    let width = app.world.resource::<FlappyConfig>().width();
+    spawn_pair(&mut app, 0.0);
+    spawn_pair(&mut app, -width);
+    app.world.resource_mut::<StatsOverlay>().enabled = true;
+    // one frame to recycle the offscreen pair, one to show it
+    app.update();
+    app.update();
+
+    let text = app
+        .world
+        .query::<&Text>()
+        .iter(&app.world)
+        .map(|text| text.sections[0].value.clone())
+        .find(|value| value.contains("pipe pairs"))
+        .expect("stats text");
+    let pooled = app.world.resource::<PipePool>().idle.len();
+    let pairs = app.world.query::<&PipePair>().iter(&app.world).count();
+    let expected = format!("{} pipe pairs ({pooled} pooled)", pairs - pooled);
+    assert!(pooled > 0);
+    assert!(text.contains(&expected), "{text}");
+}
+
+#[test]
+fn stress_test_swamps_the_run_then_exits() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().stress_test = Some(StressTest {
+        pairs: 1000,
+        frames: 5,
+    });
+    app.world.resource_mut::<Settings>().frame_rate.cap = Some(30);
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(
+        app.world.query::<&StressPair>().iter(&app.world).count(),
+        1000
+    );
+    assert!(app.world.resource::<Cheats>().invincible);
+
+    for _ in 0..10 {
+        if app.world.resource::<StressRecorder>().frame_times.len() == 5 {
+            break;
+        }
+        app.update();
+    }
+    assert_eq!(app.world.resource::<StressRecorder>().frame_times.len(), 5);
+    assert!(!app.world.resource::<Events<AppExit>>().is_empty());
+    // timed as fast as they come, whatever the cap
+    assert_eq!(app.world.resource::<FrameLimiter>().waited, Duration::ZERO);
+    // still scrolling, wrapped round rather than pooled
+    assert!(app.world.resource::<PipePool>().idle.is_empty());
+    assert_eq!(state(&app), AppState::Game);
+}
+
+#[test]
+fn frame_stats_sum_up_frame_times() {
+    assert_eq!(FrameStats::from_frame_times(&[]), None);
+    let stats = FrameStats::from_frame_times(&[0.04, 0.01, 0.02, 0.03, 0.05]).unwrap();
+    assert_eq!(stats.frames, 5);
+    assert!((stats.mean - 30.0).abs() < 1e-3);
+    assert!((stats.min - 10.0).abs() < 1e-3);
+    assert!((stats.median - 30.0).abs() < 1e-3);
+    assert!((stats.max - 50.0).abs() < 1e-3);
+    assert!(stats.to_string().starts_with("5 frames, 33 fps"));
+}
+
+#[test]
+fn the_console_key_opens_the_console_without_typing_into_it() {
+    let mut app = test_app_with(|config| config.bindings.console = KeyCode::C);
+    let type_key = |app: &mut App, key_code, char| {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            app.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key_code),
+                state,
+            });
+            if state == ButtonState::Pressed {
+                app.world.send_event(ReceivedCharacter {
+                    window: Entity::PLACEHOLDER,
+                    char,
+                });
+            }
+            app.update();
+        }
+    };
+
+    type_key(&mut app, KeyCode::C, 'c');
+    assert!(app.world.resource::<DevConsole>().open);
+    assert_eq!(app.world.resource::<DevConsole>().input, "");
+    type_key(&mut app, KeyCode::Grave, '`');
+    type_key(&mut app, KeyCode::A, 'a');
+    assert_eq!(app.world.resource::<DevConsole>().input, "`a");
+    type_key(&mut app, KeyCode::C, 'c');
+    assert!(!app.world.resource::<DevConsole>().open);
+}
+
+#[test]
+fn console_commands_change_the_game() {
+    let mut app = test_app();
+    let pipes_before = app.world.query::<&Pipe>().iter(&app.world).count();
+
+    let mut console = app.world.resource_mut::<DevConsole>();
+    for line in [
+        "score 100",
+        "set gravity 9",
+        "spawn pipe 50",
+        "god on",
+        "fly away",
+    ] {
+        console.submit(line);
+    }
+    app.update();
+
+    assert_eq!(app.world.resource::<Score>().0, 100);
+    assert_eq!(app.world.resource::<FlappyConfig>().physics.gravity, 9.0);
+    assert_eq!(
+        app.world.query::<&Pipe>().iter(&app.world).count(),
+        pipes_before + 2
+    );
+    assert!(app.world.resource::<Cheats>().invincible);
+    let log = &app.world.resource::<DevConsole>().log;
+    assert!(log.last().unwrap().starts_with("error: unknown command"));
+}
+
+#[test]
+fn god_mode_ignores_deaths() {
+    let mut app = test_app();
+    app.world.resource_mut::<Cheats>().invincible = true;
+
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    assert_eq!(state(&app), AppState::Game);
+}
+
+#[test]
+fn restart_cleans_all_scene_entities() {
+    let mut app = test_app();
+    spawn_pair(&mut app, 0.0);
+
+    game_over(&mut app);
+    assert_eq!(
+        app.world.query::<&Bird>().iter(&app.world).count(),
+        0,
+        "game entities should be gone on game over"
+    );
+    assert_eq!(app.world.query::<&Pipe>().iter(&app.world).count(), 0);
+    assert!(app.world.query::<&Button>().iter(&app.world).count() > 0);
+
+    set_state(&mut app, AppState::Game);
+    assert_eq!(app.world.query::<&Button>().iter(&app.world).count(), 0);
+    assert_eq!(app.world.query::<&Pipe>().iter(&app.world).count(), 0);
+    assert_eq!(app.world.query::<&Bird>().iter(&app.world).count(), 1);
+}
+
+#[test]
+fn entities_only_despawn_when_their_own_state_exits() {
+    let mut app = test_app();
+    let outlives_game = app.world.spawn(DespawnOnExit(AppState::GameOver)).id();
+
+    set_state(&mut app, AppState::GameOver);
+    assert!(app.world.get_entity(outlives_game).is_some());
+
+    set_state(&mut app, AppState::Game);
+    assert!(app.world.get_entity(outlives_game).is_none());
+}
+
+#[test]
+fn fatal_errors_end_the_run_on_an_error_screen() {
+    let mut app = test_app();
+    app.world.send_event(FatalError {
+        message: "failed to load assets/bird.png".to_string(),
+    });
+    app.update();
+    app.update();
+
+    assert_eq!(state(&app), AppState::Error);
+    assert_eq!(
+        app.world.resource::<ErrorScreen>().message,
+        "failed to load assets/bird.png"
+    );
+    assert_eq!(app.world.query::<&Bird>().iter(&app.world).count(), 0);
+    let texts: Vec<_> = app
+        .world
+        .query::<&Text>()
+        .iter(&app.world)
+        .map(|text| text.sections[0].value.clone())
+        .collect();
+    assert!(texts.contains(&"failed to load assets/bird.png".to_string()));
+}
+
+#[test]
+fn placeholders_match_the_sprite_size() {
+    let image = placeholder_image(BIRD_SIZE, Color::YELLOW);
+
+    assert_eq!(image.size(), BIRD_SIZE);
+    assert_eq!(image.data.len(), 17 * 12 * 4);
+    assert_eq!(&image.data[..4], &rgba_bytes(Color::YELLOW));
+}
+
+#[test]
+fn clips_encode_as_looping_gifs() {
+    let app = test_app();
+    let recorder = app.world.resource::<ClipRecorder>();
+    let playfield = app.world.resource::<FlappyConfig>().playfield;
+    assert_eq!(recorder.width, (playfield.x / 2.0) as u32);
+
+    let frames = [[255, 0, 0, 255].repeat(8), [0, 0, 255, 255].repeat(8)];
+    let gif = encode_gif(4, 2, frames.iter().map(Vec::as_slice), 7).unwrap();
+
+    assert!(gif.starts_with(b"GIF89a"));
+    assert_eq!(gif.last(), Some(&0x3b), "should end with a trailer");
+}
+
+#[test]
+fn pipes_follow_the_run_seed() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Game);
+    let seed = app.world.resource::<RunSeed>().seed;
+    assert!(seed < SEED_LIMIT);
+    let height = app.world.resource::<FlappyConfig>().height();
+    let expected = GapParams::for_height(height).sample(RunSeed::new(seed).rng());
+
+    for mut timer in app.world.query::<&mut PipeTimer>().iter_mut(&mut app.world) {
+        let duration = timer.duration();
+        timer.set_elapsed(duration);
+    }
+    let mut spawned = ManualEventReader::<PipeSpawnedEvent>::default();
+    app.update();
+    let gaps: Vec<_> = spawned
+        .iter(app.world.resource::<Events<PipeSpawnedEvent>>())
+        .map(|event| event.gap)
+        .collect();
+    assert_eq!(gaps, [expected]);
+}
+
+#[test]
+fn golden_gaps_are_worth_more() {
+    let mut app = test_app();
+    let mut spawned = ManualEventReader::<PipeSpawnedEvent>::default();
+    let mut golden = None;
+    for _ in 0..200 {
+        let mut timers = app.world.query::<&mut PipeTimer>();
+        let mut timer = timers.single_mut(&mut app.world);
+        let duration = timer.duration();
+        timer.set_elapsed(duration);
+        app.update();
+        golden = spawned
+            .iter(app.world.resource::<Events<PipeSpawnedEvent>>())
+            .find_map(|event| Some((event.pair, event.gap, event.golden?)));
+        if golden.is_some() {
+            break;
+        }
+    }
+    let (pair, gap, golden) = golden.expect("a golden gap in 200 pairs");
+    assert!(golden.size < gap.size);
+    assert!(app.world.get::<GoldenGap>(pair).is_some());
+
+    // line the golden gap up with the bird
+    let bird = bird_position(&mut app);
+    let mut transform = app.world.get_mut::<Transform>(pair).unwrap();
+    transform.translation.x = bird.x;
+    transform.translation.y = bird.y - (golden.center - gap.center);
+    app.world.get_mut::<Scrolls>(pair).unwrap().0 = 0.0;
+    app.update();
+    app.update();
+    assert_eq!(app.world.resource::<Score>().0, GOLDEN_POINTS);
+    assert_eq!(app.world.resource::<Events<DeathEvent>>().len(), 0);
+}
+
+#[test]
+fn pipes_speed_up_and_come_closer_with_the_score() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().difficulty = Difficulty::Hard;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    let curve = app
+        .world
+        .resource::<DifficultyCurves>()
+        .get(Difficulty::Hard)
+        .clone();
+    let mut timers = app.world.query::<&mut PipeTimer>();
+    assert_eq!(
+        timers.single(&app.world).duration().as_secs_f32(),
+        curve.at(0).interval
+    );
+
+    // halfway between the first two keys
+    let score = curve.keys[1].score / 2;
+    app.insert_resource(Score(score));
+    let mut timer = timers.single_mut(&mut app.world);
+    let duration = timer.duration();
+    timer.set_elapsed(duration);
+    let mut spawned = ManualEventReader::<PipeSpawnedEvent>::default();
+    app.update();
+
+    let key = curve.at(score);
+    let expected = (curve.keys[0].interval + curve.keys[1].interval) / 2.0;
+    assert!((key.interval - expected).abs() < 1e-4);
+    assert_eq!(
+        timers.single(&app.world).duration().as_secs_f32(),
+        key.interval
+    );
+    let pipe_speed = app.world.resource::<FlappyConfig>().physics.pipe_speed * key.speed;
+    assert_eq!(app.world.resource::<Pace>().pipe_speed, pipe_speed);
+    let pair = spawned
+        .iter(app.world.resource::<Events<PipeSpawnedEvent>>())
+        .last()
+        .unwrap()
+        .pair;
+    let scrolls = *app.world.get::<Scrolls>(pair).unwrap();
+    let speed = *app.world.resource::<ScrollSpeed>();
+    assert_eq!(scrolls.velocity(speed).x, -pipe_speed);
+}
+
+#[test]
+fn frame_rate_settings_toggle_vsync_and_cap_frames() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    assert_eq!(
+        app.world.resource::<Settings>().frame_rate,
+        FrameRate::default()
+    );
+    let vsync = app
+        .world
+        .query_filtered::<Entity, With<VsyncButton>>()
+        .single(&app.world);
+    let cap = app
+        .world
+        .query_filtered::<Entity, With<FrameCapButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(vsync).unwrap() = Interaction::Clicked;
+    *app.world.get_mut::<Interaction>(cap).unwrap() = Interaction::Clicked;
+    app.update();
+
+    let frame_rate = app.world.resource::<Settings>().frame_rate;
+    assert!(!frame_rate.vsync);
+    assert_eq!(frame_rate.cap, Some(30));
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "settings.frame_cap")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("FPS cap: 30"));
+
+    // the rest of a thirtieth of a second is slept off after each frame
+    app.update();
+    let waited = app.world.resource::<FrameLimiter>().waited;
+    assert!(waited > Duration::ZERO && waited <= Duration::from_secs(1) / 30);
+}
+
+#[test]
+fn collision_mode_switches_from_the_settings_screen() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    let button = app
+        .world
+        .query_filtered::<Entity, With<CollisionButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+    assert_eq!(
+        app.world.resource::<Settings>().collision,
+        CollisionMode::PixelPerfect
+    );
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key.starts_with("settings.collision"))
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("Hitbox: Pixels"));
+}
+
+#[test]
+fn assist_mode_widens_gaps_and_slows_pipes() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    let button = app
+        .world
+        .query_filtered::<Entity, With<AssistButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+    assert!(app.world.resource::<Settings>().assist.enabled);
+
+    set_state(&mut app, AppState::Game);
+    app.update();
+    let key = app
+        .world
+        .resource::<DifficultyCurves>()
+        .get(Difficulty::Normal)
+        .at(0);
+    let pipe_speed = app.world.resource::<FlappyConfig>().physics.pipe_speed;
+    let pace = *app.world.resource::<Pace>();
+    assert!((pace.pipe_speed - pipe_speed * key.speed * 0.8).abs() < 1e-4);
+    assert!((pace.gaps.min_size - key.min_gap * 1.25).abs() < 1e-4);
+    assert!((pace.gaps.max_size - key.max_gap * 1.25).abs() < 1e-4);
+
+    let mut console = app.world.resource_mut::<DevConsole>();
+    console.submit("assist 50 95");
+    console.submit("assist 40 10");
+    app.update();
+    let log = &app.world.resource::<DevConsole>().log;
+    assert!(log
+        .iter()
+        .any(|line| line.contains("'95' isn't a percentage")));
+    let assist = app.world.resource::<Settings>().assist;
+    assert_eq!((assist.wider_gaps, assist.slower_pipes), (40.0, 10.0));
+}
+
+#[test]
+fn difficulty_curves_hold_past_their_ends_and_reject_bad_keys() {
+    let curves = DifficultyCurves::default();
+    let curve = curves.get(Difficulty::Normal);
+    let last = curve.keys.last().unwrap();
+    assert_eq!(curve.at(last.score + 500).interval, last.interval);
+    assert_eq!(curve.at(0).min_gap, curve.keys[0].min_gap);
+
+    let key = "(score: 0, speed: 1.0, interval: 1.0, min_gap: 40.0, max_gap: 100.0)";
+    let later = "(score: 10, speed: 1.0, interval: 1.0, min_gap: 40.0, max_gap: 100.0)";
+    let valid = format!("{{Easy: [{key}], Normal: [{key}, {later}], Hard: [{key}]}}");
+    assert!(DifficultyCurves::parse(&valid).is_ok());
+    let unordered = format!("{{Easy: [{key}], Normal: [{later}, {key}], Hard: [{key}]}}");
+    assert!(DifficultyCurves::parse(&unordered)
+        .unwrap_err()
+        .contains("order"));
+    let missing = format!("{{Easy: [{key}], Normal: [{key}]}}");
+    assert!(DifficultyCurves::parse(&missing)
+        .unwrap_err()
+        .contains("Hard"));
+}
+
+#[test]
+fn sharing_copies_the_score_and_seed() {
+    let mut app = test_app();
+    app.insert_resource(RunSeed::new(8812));
+    app.insert_resource(Score(42));
+    game_over(&mut app);
+
+    let button = app
+        .world
+        .query_filtered::<Entity, With<ShareButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    let mut shares = ManualEventReader::<ShareEvent>::default();
+    app.update();
+    let texts: Vec<_> = shares
+        .iter(app.world.resource::<Events<ShareEvent>>())
+        .map(|share| share.text.clone())
+        .collect();
+    assert_eq!(texts, ["I scored 42 in Flappy Bevy! Seed: 8812"]);
+}
+
+#[test]
+fn challenge_codes_name_the_seed_difficulty_and_modifiers() {
+    let code = ChallengeCode {
+        seed: 7 << 15 | 19 << 10 | 2 << 5 | 23,
+        difficulty: Difficulty::Hard,
+        assisted: false,
+        dash: false,
+        physics: None,
+        co_op: false,
+    };
+    assert_eq!(code.to_string(), "FB-7K2Q-HARD");
+    assert_eq!("fb-7k2q-hard".parse(), Ok(code));
+    let co_op = ChallengeCode {
+        co_op: true,
+        ..code
+    };
+    assert_eq!(co_op.to_string(), "FB-7K2Q-HARD-2P");
+    assert_eq!("fb-7k2q-hard-2p".parse(), Ok(co_op));
+    assert!("FB-7K2Q-HARD-3P".parse::<ChallengeCode>().is_err());
+    let assisted = ChallengeCode {
+        assisted: true,
+        ..co_op
+    };
+    assert_eq!(assisted.to_string(), "FB-7K2Q-HARD-A-2P");
+    assert_eq!("fb-7k2q-hard-a-2p".parse(), Ok(assisted));
+    assert!("FB-7K2Q-HARD-2P-A".parse::<ChallengeCode>().is_err());
+    assert!("FB-7K2Q-HARD-A-A".parse::<ChallengeCode>().is_err());
+    let dash = ChallengeCode {
+        dash: true,
+        ..assisted
+    };
+    assert_eq!(dash.to_string(), "FB-7K2Q-HARD-A-D-2P");
+    assert_eq!("fb-7k2q-hard-a-d-2p".parse(), Ok(dash));
+    assert!("FB-7K2Q-HARD-D-A".parse::<ChallengeCode>().is_err());
+    let physics = ChallengeCode {
+        physics: Some([11, 10, 20]),
+        ..dash
+    };
+    assert_eq!(physics.to_string(), "FB-7K2Q-HARD-A-D-PBAM-2P");
+    assert_eq!("fb-7k2q-hard-a-d-pbam-2p".parse(), Ok(physics));
+    // past the end of the slider
+    assert!("FB-7K2Q-HARD-PBAN".parse::<ChallengeCode>().is_err());
+    assert!("FB-7K2Q-HARD-PBA".parse::<ChallengeCode>().is_err());
+    // letters that look like digits read as them
+    let easy: ChallengeCode = "FB-OIL0-EASY".parse().unwrap();
+    assert_eq!(easy.seed, 1 << 10 | 1 << 5);
+    assert!("FB-7K2Q-NIGHTMARE".parse::<ChallengeCode>().is_err());
+    assert!("FB-7U2Q-HARD".parse::<ChallengeCode>().is_err());
+    assert!("FB-7K2-HARD".parse::<ChallengeCode>().is_err());
+    let largest = ChallengeCode {
+        seed: SEED_LIMIT - 1,
+        difficulty: Difficulty::Normal,
+        assisted: true,
+        dash: true,
+        physics: Some([20; 3]),
+        co_op: true,
+    };
+    assert_eq!(largest.to_string().parse(), Ok(largest));
+    assert_eq!(largest.to_string().len(), 26);
+}
+
+#[test]
+fn entering_a_challenge_code_replays_its_pipes() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Challenge);
+    for char in "fb-7k2q-hard".chars() {
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char,
+        });
+    }
+    app.update();
+    assert_eq!(app.world.resource::<ChallengeInput>().text, "FB-7K2Q-HARD");
+
+    press(&mut app, KeyCode::Return);
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(app.world.resource::<RunSeed>().seed, 248_919);
+    assert_eq!(
+        app.world.resource::<Settings>().difficulty,
+        Difficulty::Hard
+    );
+}
+
+#[test]
+fn challenge_modifiers_last_until_the_main_menu_and_arent_saved() {
+    let path = std::env::temp_dir().join(format!("flappy_challenge_{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let with_path = |config: &mut FlappyConfig| config.settings_path = Some(path.clone());
+    let mut app = test_app_with(with_path);
+    app.world.resource_mut::<Profile>().best = DASH_UNLOCK_SCORE;
+    set_state(&mut app, AppState::Challenge);
+    for char in "fb-7k2q-hard-a-d-pbam".chars() {
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char,
+        });
+    }
+    app.update();
+    press(&mut app, KeyCode::Return);
+    assert_eq!(state(&app), AppState::Game);
+    assert!(app.world.resource::<ChallengeRun>().active());
+    let settings = app.world.resource::<Settings>();
+    assert_eq!(settings.difficulty, Difficulty::Hard);
+    assert!(settings.assist.enabled && settings.air_dash);
+    assert!(!path.exists(), "nothing saved mid-challenge");
+
+    // restarting plays the same challenge
+    game_over(&mut app);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(app.world.resource::<RunSeed>().seed, 248_919);
+
+    set_state(&mut app, AppState::MainMenu);
+    assert!(!app.world.resource::<ChallengeRun>().active());
+    let settings = app.world.resource::<Settings>().clone();
+    assert_eq!(settings.difficulty, Difficulty::Normal);
+    assert!(!settings.assist.enabled && !settings.air_dash);
+    let starting = FlappyConfig::default().physics;
+    assert_eq!(app.world.resource::<FlappyConfig>().physics, starting);
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).ok();
+    assert!(saved.contains("difficulty: Normal"), "{saved}");
+
+    set_state(&mut app, AppState::Game);
+    assert_ne!(app.world.resource::<RunSeed>().seed, 247_895);
+}
+
+#[test]
+fn dash_challenges_need_the_dash_unlocked() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Challenge);
+    for char in "fb-7k2q-hard-d".chars() {
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char,
+        });
+    }
+    app.update();
+    press(&mut app, KeyCode::Return);
+    assert_eq!(state(&app), AppState::Challenge);
+    let hint = app
+        .world
+        .query::<&Localized>()
+        .iter(&app.world)
+        .any(|localized| localized.key == "challenge.dash_locked");
+    assert!(hint);
+}
+
+#[test]
+fn sky_gradient_runs_from_top_to_bottom() {
+    let sky = Sky {
+        top: Color::BLUE,
+        bottom: Color::RED,
+        tint: Color::WHITE,
+    };
+
+    assert_eq!(rgba_bytes(sky.color_at(0.0)), rgba_bytes(Color::BLUE));
+    assert_eq!(rgba_bytes(sky.color_at(1.0)), rgba_bytes(Color::RED));
+    let dimmed = Sky {
+        tint: Color::BLACK,
+        ..sky
+    };
+    assert_eq!(rgba_bytes(dimmed.color_at(0.5)), [0, 0, 0, 255]);
+}
+
+#[test]
+fn trail_leaves_fading_afterimages() {
+    let mut app = test_app();
+    app.world.resource_mut::<DevConsole>().submit("trail on");
+    app.update();
+    let trail = app.world.resource::<Settings>().trail.unwrap();
+
+    advance(&mut app, trail.interval * 1.2);
+    assert!(app.world.query::<&Afterimage>().iter(&app.world).count() > 0);
+
+    app.world.resource_mut::<Settings>().trail = None;
+    advance(&mut app, trail.lifetime * 1.2);
+    app.update();
+    assert_eq!(app.world.query::<&Afterimage>().iter(&app.world).count(), 0);
+}
+
+#[test]
+fn deaths_freeze_the_game_before_game_over() {
+    let mut app = test_app();
+    let mut config = app.world.resource_mut::<FlappyConfig>();
+    config.hit_stop = 0.1;
+    config.death_slow_mo = 0.2;
+
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    app.world
+        .query_filtered::<&mut Gravity, With<Bird>>()
+        .single_mut(&mut app.world)
+        .0 = true;
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(app.world.resource::<TimeScale>().0, 0.0);
+    let frozen_at = bird_position(&mut app);
+    advance(&mut app, 0.05);
+    assert_eq!(bird_position(&mut app), frozen_at);
+
+    advance(&mut app, 0.07);
+    assert_eq!(state(&app), AppState::Game);
+    assert!(app.world.resource::<DeathCam>().timer.is_some());
+    // the bird falls on through the death cam, past what it hit
+    advance(&mut app, 0.05);
+    assert!(bird_position(&mut app).y < frozen_at.y);
+
+    advance(&mut app, 0.25);
+    app.update();
+    assert_eq!(state(&app), AppState::GameOver);
+    assert_eq!(*app.world.resource::<TimeScale>(), TimeScale::default());
+    assert_eq!(app.world.resource::<DeathStats>().deaths.len(), 1);
+}
+
+#[test]
+fn eases_start_at_zero_and_end_at_one() {
+    for ease in [Ease::Linear, Ease::QuadOut, Ease::BackOut, Ease::ElasticOut] {
+        assert!(ease.apply(0.0).abs() < 1e-5, "{ease:?} should start at 0");
+        assert!(
+            (ease.apply(1.0) - 1.0).abs() < 1e-5,
+            "{ease:?} should end at 1"
+        );
+    }
+    assert!(Ease::BackOut.apply(0.7) > 1.0, "back out should overshoot");
+}
+
+#[test]
+fn finished_tweens_leave_the_final_value() {
+    let mut app = test_app();
+    let to = Vec3::new(10.0, 20.0, 0.0);
+    let entity = app
+        .world
+        .spawn((
+            TransformBundle::default(),
+            Tween::new(
+                TweenTarget::Translation {
+                    from: Vec3::ZERO,
+                    to,
+                },
+                0.0,
+                Ease::QuadOut,
+            ),
+        ))
+        .id();
+    app.update();
+
+    assert_eq!(app.world.get::<Transform>(entity).unwrap().translation, to);
+    assert!(app.world.get::<Tween>(entity).is_none());
+}
+
+#[test]
+fn scoring_pops_the_score_and_floats_the_points() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+    spawn_pair(&mut app, bird.x);
+    for _ in 0..3 {
+        app.update();
+    }
+
+    let popups: Vec<_> = app
+        .world
+        .query_filtered::<&Text, With<ScorePopup>>()
+        .iter(&app.world)
+        .map(|text| text.sections[0].value.clone())
+        .collect();
+    assert_eq!(popups, vec!["+1".to_string()]);
+    let scores: Vec<_> = app
+        .world
+        .query::<&Digits>()
+        .iter(&app.world)
+        .map(|digits| digits.value)
+        .collect();
+    assert_eq!(scores, vec![1], "the score should read 1");
+}
+
+#[test]
+fn medals_are_revealed_after_the_banner() {
+    let mut app = test_app();
+    app.world.resource_mut::<Score>().0 = 30;
+    game_over(&mut app);
+    assert_eq!(
+        app.world.query::<&MedalDisplay>().iter(&app.world).count(),
+        0
+    );
+
+    advance(&mut app, 0.7);
+    app.update();
+    let medals: Vec<_> = app
+        .world
+        .query::<&MedalDisplay>()
+        .iter(&app.world)
+        .map(|medal| medal.0)
+        .collect();
+    assert_eq!(medals, vec![Medal::Gold]);
+
+    set_state(&mut app, AppState::Game);
+    assert_eq!(
+        app.world.query::<&MedalDisplay>().iter(&app.world).count(),
+        0
+    );
+}
+
+#[test]
+fn game_over_panel_waits_for_the_death_animation() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    let mut game_overs = ManualEventReader::<GameOverEvent>::default();
+    let mut sent = 0;
+    for _ in 0..3 {
+        app.update();
+        sent += game_overs
+            .iter(app.world.resource::<Events<GameOverEvent>>())
+            .count();
+    }
+    assert_eq!(state(&app), AppState::GameOver);
+    assert_eq!(sent, 1);
+    let banners = |app: &mut App| {
+        app.world
+            .query::<&Localized>()
+            .iter(&app.world)
+            .filter(|localized| localized.key == "game_over")
+            .count()
+    };
+    let extras = |app: &mut App| {
+        let shares = app
+            .world
+            .query_filtered::<(), With<ShareButton>>()
+            .iter(&app.world)
+            .count();
+        let graphs = app
+            .world
+            .query_filtered::<(), With<FlightGraph>>()
+            .iter(&app.world)
+            .count();
+        let codes = app
+            .world
+            .query::<&Localized>()
+            .iter(&app.world)
+            .filter(|localized| localized.key == "challenge.code")
+            .count();
+        (shares, graphs, codes)
+    };
+    assert_eq!(banners(&mut app), 0, "the panel comes in a beat later");
+    assert_eq!(extras(&mut app), (0, 0, 0), "and the rest with it");
+
+    app.world.resource_mut::<ResultsTimeline>().elapsed += PANEL_DELAY;
+    app.update();
+    assert_eq!(banners(&mut app), 1);
+    assert_eq!(extras(&mut app), (1, 1, 1));
+}
+
+#[test]
+fn buttons_follow_their_skin() {
+    let mut app = test_app();
+    game_over(&mut app);
+    let buttons: Vec<_> = app
+        .world
+        .query_filtered::<Entity, With<ButtonSkin>>()
+        .iter(&app.world)
+        .collect();
+    assert!(
+        buttons.len() >= 2,
+        "restart and save clip should be skinned"
+    );
+
+    for &button in &buttons {
+        *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Hovered;
+    }
+    app.update();
+    for &button in &buttons {
+        let skin = app.world.get::<ButtonSkin>(button).unwrap();
+        let color = app.world.get::<BackgroundColor>(button).unwrap();
+        assert_eq!(color.0, skin.hovered.color);
+    }
+}
+
+#[test]
+fn menus_can_be_used_from_the_keyboard() {
+    let mut app = test_app();
+    game_over(&mut app);
+    assert_eq!(app.world.resource::<MenuFocus>().focused, None);
+
+    press(&mut app, KeyCode::Down);
+    let focused = app.world.resource::<MenuFocus>().focused.unwrap();
+    assert_eq!(app.world.get::<Focusable>(focused).unwrap().order, 0);
+    let skin = app.world.get::<ButtonSkin>(focused).unwrap();
+    let color = app.world.get::<BackgroundColor>(focused).unwrap();
+    assert_eq!(color.0, skin.hovered.color, "focus should look like hover");
+
+    // up wraps round to the last button, and down back to the first
+    press(&mut app, KeyCode::Up);
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Return);
+    assert_eq!(state(&app), AppState::Game);
+}
+
+#[test]
+fn closing_mid_run_asks_first() {
+    let mut app = test_app();
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    app.world.send_event(WindowCloseRequested {
+        window: Entity::PLACEHOLDER,
+    });
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(run_state(&app), RunState::Quitting);
+    assert!(app.world.resource::<Events<AppExit>>().is_empty());
+
+    // keeping playing resumes the same run
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Return);
+    assert_eq!(run_state(&app), RunState::Countdown);
+    finish_countdown(&mut app);
+    assert_eq!(run_state(&app), RunState::Running);
+    assert!(app.world.get_entity(bird).is_some());
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(run_state(&app), RunState::Quitting);
+    let quit = app
+        .world
+        .query::<(Entity, &QuitButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| **button == QuitButton::Quit)
+        .unwrap()
+        .0;
+    *app.world.get_mut::<Interaction>(quit).unwrap() = Interaction::Clicked;
+    app.update();
+    assert!(!app.world.resource::<Events<AppExit>>().is_empty());
+}
+
+#[test]
+fn game_over_restarts_from_the_keyboard() {
+    for key in [KeyCode::Space, KeyCode::Return, KeyCode::R] {
+        let mut app = test_app();
+        set_state(&mut app, AppState::GameOver);
+        press(&mut app, key);
+        assert_eq!(state(&app), AppState::Game, "{key:?} should restart");
+    }
+}
+
+#[test]
+fn holding_quick_retry_skips_game_over() {
+    let mut app = test_app();
+    app.world.send_event(KeyboardInput {
+        scan_code: 0,
+        key_code: Some(KeyCode::R),
+        state: ButtonState::Pressed,
+    });
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    let position = bird_position(&mut app);
+    spawn_collider(&mut app, position);
+    for _ in 0..4 {
+        app.update();
+        assert_eq!(state(&app), AppState::Game);
+    }
+
+    let new_bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    assert_ne!(bird, new_bird, "should be a fresh run");
+}
+
+#[test]
+fn resuming_counts_down_with_the_run_frozen() {
+    let mut app = test_app();
+    press(&mut app, KeyCode::Space);
+    press(&mut app, KeyCode::P);
+    assert_eq!(run_state(&app), RunState::Paused);
+    let paused_at = bird_position(&mut app);
+
+    press(&mut app, KeyCode::P);
+    assert_eq!(run_state(&app), RunState::Countdown);
+    assert_eq!(app.world.resource::<Countdown>().remaining(), 3);
+    advance(&mut app, 0.02);
+    assert_eq!(bird_position(&mut app), paused_at);
+
+    finish_countdown(&mut app);
+    assert_eq!(run_state(&app), RunState::Running);
+    advance(&mut app, 0.02);
+    assert_ne!(bird_position(&mut app), paused_at);
+}
+
+#[test]
+fn credits_are_reached_from_the_main_menu() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::MainMenu);
+
+    let click = |app: &mut App, wanted: MenuButton| {
+        let button = app
+            .world
+            .query::<(Entity, &MenuButton)>()
+            .iter(&app.world)
+            .find(|(_, button)| **button == wanted)
+            .unwrap()
+            .0;
+        *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+        app.update();
+        app.update();
+    };
+    click(&mut app, MenuButton::Credits);
+    assert_eq!(state(&app), AppState::Credits);
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state(&app), AppState::MainMenu);
+    click(&mut app, MenuButton::Play);
+    assert_eq!(state(&app), AppState::Game);
+}
+
+#[test]
+fn build_info_names_the_version_and_commit() {
+    let info = build_info();
+    assert!(info.starts_with(&format!("v{}", env!("CARGO_PKG_VERSION"))));
+    assert!(info.contains(GIT_HASH));
+    assert!(!GIT_HASH.is_empty());
+}
+
+#[test]
+fn idle_game_over_screens_go_back_to_the_menu() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().idle_timeout = Some(0.05);
+    set_state(&mut app, AppState::GameOver);
+
+    advance(&mut app, 0.03);
+    press(&mut app, KeyCode::Left);
+    advance(&mut app, 0.03);
+    assert_eq!(state(&app), AppState::GameOver, "input restarts the wait");
+
+    advance(&mut app, 0.06);
+    app.update();
+    assert_eq!(state(&app), AppState::MainMenu);
+}
+
+#[test]
+fn tutorial_shows_until_the_first_pipe_is_passed() {
+    let mut app = test_app();
+    let hints = |app: &mut App| {
+        app.world
+            .query_filtered::<(), With<TutorialHint>>()
+            .iter(&app.world)
+            .count()
+    };
+    assert_eq!(hints(&mut app), 1);
+
+    let bird = bird_position(&mut app);
+    spawn_pair(&mut app, bird.x);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(hints(&mut app), 0);
+    assert!(app.world.resource::<Profile>().tutorial_done);
+
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(hints(&mut app), 0);
+}
+
+#[test]
+fn profiles_round_trip_through_a_file() {
+    let path = std::env::temp_dir().join(format!("flappy_profile_{}.ron", std::process::id()));
+    let mut profile = Profile::load(&path).unwrap();
+    assert_eq!(
+        profile,
+        Profile::default(),
+        "missing files are fresh profiles"
+    );
+
+    profile.tutorial_done = true;
+    profile.save(&path).unwrap();
+    assert_eq!(Profile::load(&path), Ok(profile));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn profiles_from_before_versioning_keep_their_progress() {
+    let path = std::env::temp_dir().join(format!("flappy_old_profile_{}.ron", std::process::id()));
+    std::fs::write(
+        &path,
+        "(tutorial_done: true, best: 41, coins: 7, unlocked: [Hat])",
+    )
+    .unwrap();
+    let profile = Profile::load(&path).unwrap();
+    assert_eq!(
+        (profile.tutorial_done, profile.best, profile.coins),
+        (true, 41, 7)
+    );
+    assert_eq!(profile.unlocked, vec![Cosmetic::Hat]);
+
+    profile.save(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("version: 2"), "{text}");
+    assert_eq!(Profile::load(&path), Ok(profile));
+
+    std::fs::write(&path, "(version: 99, data: (best: 1))").unwrap();
+    let error = Profile::load(&path).unwrap_err();
+    assert!(error.contains("newer version"), "{error}");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn hand_edited_scores_fail_their_checksum() {
+    let path = std::env::temp_dir().join(format!("flappy_edited_{}.ron", std::process::id()));
+    let profile = Profile {
+        best: 12,
+        ..default()
+    };
+    profile.save(&path).unwrap();
+    let text = std::fs::read_to_string(&path).unwrap();
+    assert!(text.contains("checksum:"), "{text}");
+    // laid out differently but holding the same is fine
+    std::fs::write(&path, text.replace('\n', " ")).unwrap();
+    assert_eq!(Profile::load(&path), Ok(profile));
+
+    std::fs::write(&path, text.replace("best: 12", "best: 9999")).unwrap();
+    let error = Profile::load(&path).unwrap_err();
+    assert!(error.contains("checksum"), "{error}");
+
+    // older formats have no checksum to check, so what they hold isn't trusted
+    for old in ["(version: 1, data: (best: 9999))", "(best: 9999)"] {
+        std::fs::write(&path, old).unwrap();
+        let migrated = Profile::load(&path).unwrap();
+        assert_eq!((migrated.best, migrated.unverified), (9999, true), "{old}");
+    }
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn rebinding_onto_a_used_key_asks_to_swap_or_clear() {
+    let mut bindings = InputBindings::default();
+    // restarting is on another screen, so sharing its keys is fine
+    assert_eq!(bindings.conflicts(Action::Flap, KeyCode::Return), vec![]);
+
+    let conflict = bindings.rebind(Action::Flap, KeyCode::P, None).unwrap_err();
+    assert_eq!(conflict.clashes, vec![Action::Pause]);
+    assert_eq!(bindings.flap, vec![KeyCode::Space], "nothing changes");
+    bindings
+        .rebind(Action::Flap, KeyCode::P, Some(Resolve::Swap))
+        .unwrap();
+    assert_eq!(
+        (bindings.flap.clone(), bindings.pause),
+        (vec![KeyCode::P], KeyCode::Space)
+    );
+
+    // pause has no other key to fall back on
+    let cleared = bindings.rebind(Action::Mute, KeyCode::Space, Some(Resolve::Clear));
+    assert_eq!(
+        cleared.unwrap_err().clashes,
+        vec![Action::Restart, Action::Pause]
+    );
+    bindings
+        .rebind(Action::Quit, KeyCode::Return, Some(Resolve::Clear))
+        .unwrap();
+    assert_eq!(bindings.restart, vec![KeyCode::Space, KeyCode::R]);
+    assert_eq!(bindings.quit, KeyCode::Return);
+
+    let mut app = test_app();
+    app.world.resource_mut::<DevConsole>().submit("bind flap r");
+    app.update();
+    let log = &app.world.resource::<DevConsole>().log;
+    assert!(
+        log.iter()
+            .any(|line| line.contains("R is already quick_retry")),
+        "{log:?}"
+    );
+    app.world
+        .resource_mut::<DevConsole>()
+        .submit("bind flap r swap");
+    app.update();
+    let bindings = &app.world.resource::<FlappyConfig>().bindings;
+    assert_eq!(
+        (bindings.flap.clone(), bindings.quick_retry),
+        (vec![KeyCode::R], KeyCode::Space)
+    );
+}
+
+#[test]
+fn the_accessibility_screen_switches_its_settings() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    let button = app
+        .world
+        .query_filtered::<Entity, With<AccessibilityButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+    app.update();
+    assert_eq!(state(&app), AppState::Accessibility);
+
+    for toggle in AccessibilityToggle::ALL {
+        let entity = app
+            .world
+            .query::<(Entity, &AccessibilityToggle)>()
+            .iter(&app.world)
+            .find(|(_, found)| **found == toggle)
+            .map(|(entity, _)| entity)
+            .unwrap();
+        let enabled = toggle.enabled(app.world.resource::<Settings>());
+        *app.world.get_mut::<Interaction>(entity).unwrap() = Interaction::Clicked;
+        app.update();
+        assert_ne!(
+            toggle.enabled(app.world.resource::<Settings>()),
+            enabled,
+            "{toggle:?}"
+        );
+    }
+    assert!(app.world.resource::<Settings>().single_switch);
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "accessibility.single_switch_on")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("One key: On"));
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state(&app), AppState::Settings);
+}
+
+#[test]
+fn the_controls_screen_rebinds_keys_and_saves_them() {
+    let path = std::env::temp_dir().join(format!("flappy_bindings_{}.ron", std::process::id()));
+    let with_path = |config: &mut FlappyConfig| config.bindings_path = Some(path.clone());
+    let mut app = test_app_with(with_path);
+    set_state(&mut app, AppState::Controls);
+    let click = |app: &mut App, entity| {
+        *app.world.get_mut::<Interaction>(entity).unwrap() = Interaction::Clicked;
+        app.update();
+        // the conflict prompt's buttons go once it's settled
+        if let Some(mut interaction) = app.world.get_mut::<Interaction>(entity) {
+            *interaction = Interaction::None;
+        }
+    };
+    let row = |app: &mut App, action| {
+        app.world
+            .query::<(Entity, &RebindButton)>()
+            .iter(&app.world)
+            .find(|(_, button)| button.0 == action)
+            .unwrap()
+            .0
+    };
+
+    // R already retries during a run, so nothing changes until asked
+    let flap = row(&mut app, Action::Flap);
+    click(&mut app, flap);
+    assert_eq!(
+        app.world.resource::<Rebinding>().listening,
+        Some(Action::Flap)
+    );
+    press(&mut app, KeyCode::R);
+    let conflict = app.world.resource::<Rebinding>().conflict.clone().unwrap();
+    assert_eq!(conflict.clashes, vec![Action::QuickRetry]);
+    assert_eq!(
+        app.world.resource::<FlappyConfig>().bindings,
+        InputBindings::default()
+    );
+    let retry = row(&mut app, Action::QuickRetry);
+    let highlight = app.world.get::<ButtonSkin>(retry).unwrap().normal.color;
+    assert_ne!(highlight, ButtonSkin::panel().normal.color);
+
+    let swap = app
+        .world
+        .query::<(Entity, &ConflictButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| **button == ConflictButton::Swap)
+        .unwrap()
+        .0;
+    click(&mut app, swap);
+    app.update();
+    let bindings = app.world.resource::<FlappyConfig>().bindings.clone();
+    assert_eq!(
+        (bindings.flap.clone(), bindings.quick_retry),
+        (vec![KeyCode::R], KeyCode::Space)
+    );
+    assert!(app.world.resource::<Rebinding>().conflict.is_none());
+    assert_eq!(
+        app.world
+            .query::<&ConflictButton>()
+            .iter(&app.world)
+            .count(),
+        0
+    );
+
+    // saved, and loaded by the next session
+    assert_eq!(InputBindings::load(&path), Ok(bindings.clone()));
+    let app = test_app_with(with_path);
+    assert_eq!(app.world.resource::<FlappyConfig>().bindings, bindings);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn drifting_sticks_stay_inside_their_deadzones() {
+    let stick = StickNavigation {
+        deadzone: Vec2::new(0.2, 0.4),
+        sensitivity: 1.0,
+    };
+    assert_eq!(stick.direction(Vec2::new(0.15, -0.35)), IVec2::ZERO);
+    assert_eq!(stick.direction(Vec2::new(0.7, -0.8)), IVec2::new(1, -1));
+    assert_eq!(
+        stick.direction(Vec2::new(0.0, 0.6)),
+        IVec2::ZERO,
+        "not far enough past the wider deadzone"
+    );
+    let sensitive = StickNavigation {
+        sensitivity: 2.0,
+        ..stick
+    };
+    assert_eq!(sensitive.direction(Vec2::new(0.0, 0.6)), IVec2::Y);
+    let stick = InputBindings::default().stick;
+    assert_eq!(stick.direction(Vec2::splat(0.2)), IVec2::ZERO);
+}
+
+#[test]
+fn the_stick_moves_the_focus_past_deadzones_set_on_the_controls_screen() {
+    let path = std::env::temp_dir().join(format!("flappy_stick_{}.ron", std::process::id()));
+    let with_path = |config: &mut FlappyConfig| config.bindings_path = Some(path.clone());
+    let mut app = test_app_with(with_path);
+    set_state(&mut app, AppState::Controls);
+    let gamepad = Gamepad::new(0);
+    app.world.send_event(GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Connected(GamepadInfo {
+            name: "Pad".to_string(),
+        }),
+    ));
+    app.update();
+    let tilt = |app: &mut App, y: f32| {
+        let axis = GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY);
+        app.world.resource_mut::<Axis<GamepadAxis>>().set(axis, y);
+        app.update();
+        let focused = app.world.resource::<MenuFocus>().focused;
+        focused.map(|entity| app.world.get::<Focusable>(entity).unwrap().order)
+    };
+
+    assert_eq!(
+        tilt(&mut app, -0.5),
+        None,
+        "not far enough past the deadzone"
+    );
+    assert_eq!(tilt(&mut app, -0.7), Some(0));
+    assert_eq!(tilt(&mut app, -0.7), Some(0), "held, not pushed again");
+    assert_eq!(tilt(&mut app, 0.0), Some(0));
+
+    // widen the vertical deadzone twice, to 50%
+    let deadzone_y = app
+        .world
+        .query::<(Entity, &DeadzoneButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| **button == DeadzoneButton::Y)
+        .unwrap()
+        .0;
+    for _ in 0..2 {
+        *app.world.get_mut::<Interaction>(deadzone_y).unwrap() = Interaction::Clicked;
+        app.update();
+        *app.world.get_mut::<Interaction>(deadzone_y).unwrap() = Interaction::None;
+    }
+    app.update();
+    let stick = app.world.resource::<FlappyConfig>().bindings.stick;
+    assert_eq!(stick.deadzone, Vec2::new(0.25, 0.5));
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "controls.deadzone_y")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("Stick Y: 50%"));
+
+    assert_eq!(tilt(&mut app, -0.7), Some(0), "inside the wider deadzone");
+    assert_eq!(tilt(&mut app, 0.0), Some(0));
+    assert_eq!(tilt(&mut app, -0.9), Some(1));
+
+    // saved with the bindings, and loaded by the next session
+    assert_eq!(
+        InputBindings::load(&path).map(|bindings| bindings.stick),
+        Ok(stick)
+    );
+    let app = test_app_with(with_path);
+    assert_eq!(app.world.resource::<FlappyConfig>().bindings.stick, stick);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn every_language_has_every_key() {
+    let english = Locale::new(Language::English);
+    for language in Language::ALL {
+        let locale = Locale::new(language);
+        let keys: Vec<_> = locale.keys().collect();
+        for key in english.keys() {
+            assert!(keys.contains(&key), "{language:?} is missing {key}");
+        }
+    }
+    assert_eq!(
+        english.format("tutorial.flap", &["Space".into()]),
+        "Press Space to flap"
+    );
+    assert_eq!(english.get("no.such.key"), "no.such.key");
+}
+
+#[test]
+fn every_translation_has_bitmap_glyphs() {
+    let font = BitmapFont::default();
+    for language in Language::ALL {
+        let locale = Locale::new(language);
+        for key in locale.keys() {
+            let text = locale.format(key, &["0".into(), "0".into()]);
+            for c in text.to_uppercase().chars() {
+                assert!(
+                    font.supports(c),
+                    "{language:?} {key} needs {c:?} in the pixel font"
+                );
+            }
+        }
+    }
+    let image = font.render("Ab\nÇ");
+    // 3x5 glyphs with a pixel between, and room for marks above and below
+    assert_eq!(image.size(), Vec2::new(7.0, 17.0));
+}
+
+#[test]
+fn changing_language_retranslates_text() {
+    let mut app = test_app();
+    game_over(&mut app);
+    app.update();
+    let banner = |app: &mut App| {
+        app.world
+            .query::<(&Localized, &BitmapText)>()
+            .iter(&app.world)
+            .find(|(localized, _)| localized.key == "game_over")
+            .map(|(_, text)| text.value.clone())
+            .unwrap()
+    };
+    assert_eq!(banner(&mut app), "Game Over");
+
+    app.world.resource_mut::<Settings>().language = Language::French;
+    app.update();
+    assert_eq!(banner(&mut app), "Partie terminée");
+}
+
+#[test]
+fn outlines_follow_the_setting() {
+    let mut app = test_app();
+    let outlines = |app: &mut App| {
+        app.world
+            .query_filtered::<&Parent, With<Outline>>()
+            .iter(&app.world)
+            .map(|parent| parent.get())
+            .collect::<Vec<_>>()
+    };
+    assert!(outlines(&mut app).is_empty());
+
+    app.world.resource_mut::<Settings>().outlines = true;
+    app.update();
+    let bird = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    assert_eq!(outlines(&mut app), vec![bird]);
+
+    app.world.resource_mut::<Settings>().outlines = false;
+    app.update();
+    assert!(outlines(&mut app).is_empty());
+}
+
+#[test]
+fn high_contrast_mode_swaps_the_theme() {
+    let mut app = test_app();
+    let pair = spawn_pair(&mut app, 100.0);
+    app.update();
+    let top = app.world.get::<PipePair>(pair).unwrap().top;
+    let texture = app.world.get::<Handle<Image>>(top).unwrap().clone();
+
+    app.world.resource_mut::<Settings>().high_contrast = true;
+    app.update();
+    let theme = Theme::high_contrast();
+    assert_eq!(*app.world.resource::<Theme>(), theme);
+    assert_eq!(app.world.resource::<Sky>().top, theme.sky_top);
+    assert_eq!(
+        app.world.get::<Sprite>(top).unwrap().color,
+        theme.pipe_color
+    );
+    assert_ne!(*app.world.get::<Handle<Image>>(top).unwrap(), texture);
+
+    app.world.resource_mut::<Settings>().high_contrast = false;
+    app.update();
+    assert_eq!(*app.world.get::<Handle<Image>>(top).unwrap(), texture);
+}
+
+#[test]
+fn pipe_pairs_pick_tints_from_the_theme_palette() {
+    let classic = Theme::classic();
+    assert_eq!(classic.pipe_tint(0.0), classic.pipe_palette[0]);
+    assert_eq!(classic.pipe_tint(0.99), classic.pipe_palette[3]);
+    let high_contrast = Theme::high_contrast();
+    assert_eq!(high_contrast.pipe_tint(0.5), high_contrast.pipe_color);
+
+    let mut app = test_app();
+    let pair = spawn_pair(&mut app, 100.0);
+    let gap = Gap {
+        center: 0.0,
+        size: 100.0,
+    };
+    app.world
+        .resource_mut::<Events<PipeSpawnedEvent>>()
+        .send(PipeSpawnedEvent {
+            pair,
+            gap,
+            golden: None,
+        });
+    app.update();
+    app.update();
+    let tint = app.world.get::<PipeTint>(pair).unwrap().0;
+    let top = app.world.get::<PipePair>(pair).unwrap().top;
+    let color = app.world.get::<Sprite>(top).unwrap().color;
+    assert_eq!(color, classic.pipe_tint(tint));
+    assert!(classic.pipe_palette.contains(&color));
+
+    app.world.resource_mut::<Settings>().high_contrast = true;
+    app.update();
+    assert_eq!(
+        app.world.get::<Sprite>(top).unwrap().color,
+        high_contrast.pipe_color
+    );
+}
+
+#[test]
+fn clouds_follow_the_theme_and_wrap_around() {
+    let mut app = test_app();
+    let clouds = |app: &mut App| {
+        app.world
+            .query_filtered::<Entity, With<Cloud>>()
+            .iter(&app.world)
+            .collect::<Vec<_>>()
+    };
+    let spawned = clouds(&mut app);
+    assert_eq!(spawned.len(), Theme::classic().clouds);
+
+    // off the left edge, so it comes back on the right
+    let width = app.world.resource::<FlappyConfig>().width();
+    let cloud = spawned[0];
+    app.world.get_mut::<Transform>(cloud).unwrap().translation.x = -width;
+    app.update();
+    assert!(app.world.get::<Transform>(cloud).unwrap().translation.x > width / 2.0);
+
+    app.world.resource_mut::<Settings>().high_contrast = true;
+    app.update();
+    assert!(clouds(&mut app).is_empty());
+    app.world.resource_mut::<Settings>().high_contrast = false;
+    app.update();
+    assert_eq!(clouds(&mut app).len(), Theme::classic().clouds);
+}
+
+#[test]
+fn biomes_crossfade_every_fifty_points() {
+    assert_eq!(Biome::for_score(BIOME_POINTS - 1), Biome::City);
+    assert_eq!(Biome::for_score(BIOME_POINTS), Biome::Forest);
+    assert_eq!(Biome::for_score(BIOME_POINTS * 4), Biome::City);
+
+    let mut app = test_app();
+    let alphas = |app: &mut App, biome| {
+        app.world
+            .query::<(&Sprite, &BiomeLayer)>()
+            .iter(&app.world)
+            .filter(|(_, layer)| layer.biome == biome)
+            .map(|(sprite, _)| sprite.color.a())
+            .fold(0.0, f32::max)
+    };
+    assert_eq!(alphas(&mut app, Biome::Forest), 0.0);
+    assert!(alphas(&mut app, Biome::City) > 0.0);
+
+    app.world.resource_mut::<Score>().0 = BIOME_POINTS;
+    app.update();
+    let biomes = app.world.resource::<Biomes>();
+    assert_eq!(biomes.current, Biome::Forest);
+    assert_eq!(biomes.previous, Some(Biome::City));
+
+    // halfway through, both show
+    app.world
+        .resource_mut::<Biomes>()
+        .fade
+        .set_elapsed(Duration::from_secs(1));
+    app.update();
+    assert!(alphas(&mut app, Biome::Forest) > 0.0);
+    assert!(alphas(&mut app, Biome::City) > 0.0);
+
+    app.world
+        .resource_mut::<Biomes>()
+        .fade
+        .set_elapsed(Duration::from_secs(10));
+    app.update();
+    assert_eq!(app.world.resource::<Biomes>().previous, None);
+    assert_eq!(alphas(&mut app, Biome::City), 0.0);
+    assert_eq!(app.world.resource::<Sky>().tint, Biome::Forest.sky_tint());
+}
+
+#[test]
+fn reduced_motion_skips_the_hit_flash() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().hit_stop = 0.1;
+    app.world.resource_mut::<Settings>().reduced_motion = true;
+    app.update();
+    assert_eq!(*app.world.resource::<MotionPrefs>(), MotionPrefs::reduced());
+
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    app.update();
+    app.update();
+    assert_eq!(app.world.resource::<TimeScale>().0, 0.0);
+    assert_eq!(app.world.query::<&HitFlash>().iter(&app.world).count(), 0);
+}
+
+#[test]
+fn photosensitive_mode_keeps_only_steady_effects() {
+    let mut app = test_app();
+    let mut config = app.world.resource_mut::<FlappyConfig>();
+    config.hit_stop = 0.1;
+    config.flash_intensity = 0.5;
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    app.update();
+    app.update();
+    let alpha = app
+        .world
+        .query_filtered::<&BackgroundColor, With<HitFlash>>()
+        .single(&app.world)
+        .0
+        .a();
+    assert!(alpha <= 0.5, "flash should be capped at its intensity");
+
+    app.world.resource_mut::<Settings>().photosensitive = true;
+    app.update();
+    let prefs = *app.world.resource::<MotionPrefs>();
+    assert!(!prefs.flashes && !prefs.particles && !prefs.sky_transitions);
+    assert!(prefs.camera_motion, "nothing flickers in camera motion");
+}
+
+#[test]
+fn audio_cues_locate_the_next_gap() {
+    let mut app = test_app();
+    app.update();
+    assert_eq!(app.world.resource::<GapCue>().offset, None);
+
+    app.world.resource_mut::<Settings>().audio_cues = true;
+    let bird = bird_position(&mut app);
+    spawn_pair(&mut app, bird.x + 100.0);
+    app.update();
+    let bird = bird_position(&mut app);
+    let height = app.world.resource::<FlappyConfig>().height();
+    let cue = app.world.resource::<GapCue>();
+    let offset = cue.offset.expect("a gap is ahead of the bird");
+    assert!((offset - bird.y / height).abs() < 0.01);
+    assert!(cue.distance <= 100.0);
+    assert!(cue.pitch().is_some());
+    assert_eq!(app.world.resource::<Events<GapClickEvent>>().len(), 1);
+}
+
+#[test]
+fn single_switch_mode_flaps_by_itself() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().single_switch = true;
+    let mut flaps = ManualEventReader::<FlapEvent>::default();
+    press(&mut app, KeyCode::Space);
+    assert_eq!(
+        flaps
+            .iter(app.world.resource::<Events<FlapEvent>>())
+            .count(),
+        1
+    );
+
+    // only the first press flaps, later ones just steer
+    press(&mut app, KeyCode::Space);
+    assert_eq!(
+        flaps
+            .iter(app.world.resource::<Events<FlapEvent>>())
+            .count(),
+        0
+    );
+
+    app.world.resource_mut::<AutoFlap>().elapsed = 10.0;
+    app.update();
+    assert_eq!(
+        flaps
+            .iter(app.world.resource::<Events<FlapEvent>>())
+            .count(),
+        1
+    );
+    assert!(app.world.resource::<AutoFlap>().elapsed < 1.0);
+}
+
+#[test]
+fn volume_sliders_step_from_the_keyboard() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    app.update();
+    let music = app
+        .world
+        .query::<(Entity, &VolumeSlider)>()
+        .iter(&app.world)
+        .find(|(_, slider)| **slider == VolumeSlider::Music)
+        .map(|(entity, _)| entity)
+        .unwrap();
+    app.world.resource_mut::<MenuFocus>().focused = Some(music);
+    press(&mut app, KeyCode::Left);
+
+    let volume = app.world.resource::<Settings>().volume;
+    assert!((volume.music - 0.7).abs() < 1e-6);
+    assert_eq!(volume.master, Volume::default().master);
+    assert!((volume.gain(AudioChannel::Music) - 0.7).abs() < 1e-6);
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "volume.music")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("Music 70%"));
+}
+
+#[test]
+fn physics_sliders_change_the_game_live_until_reset() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::Settings);
+    set_state(&mut app, AppState::Physics);
+    app.update();
+    let gravity = app
+        .world
+        .query::<(Entity, &PhysicsSlider)>()
+        .iter(&app.world)
+        .find(|(_, slider)| **slider == PhysicsSlider::Gravity)
+        .map(|(entity, _)| entity)
+        .unwrap();
+    app.world.resource_mut::<MenuFocus>().focused = Some(gravity);
+    press(&mut app, KeyCode::Right);
+
+    assert_eq!(app.world.resource::<FlappyConfig>().physics.gravity, 7.5);
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "physics.gravity")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("Gravity 7.5"));
+
+    let reset = app
+        .world
+        .query_filtered::<Entity, With<PhysicsResetButton>>()
+        .single(&app.world);
+    *app.world.get_mut::<Interaction>(reset).unwrap() = Interaction::Clicked;
+    app.update();
+    assert_eq!(app.world.resource::<FlappyConfig>().physics.gravity, 7.0);
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state(&app), AppState::Settings);
+}
+
+#[test]
+fn customized_birds_spawn_tinted_and_dressed() {
+    let mut app = test_app();
+    // unlocks the hat
+    app.world.resource_mut::<Profile>().best = 10;
+    set_state(&mut app, AppState::MainMenu);
+    set_state(&mut app, AppState::Customize);
+    app.update();
+    for (slider, presses) in [(LookSlider::Hue, 2), (LookSlider::Saturation, 6)] {
+        let entity = app
+            .world
+            .query::<(Entity, &LookSlider)>()
+            .iter(&app.world)
+            .find(|(_, found)| **found == slider)
+            .map(|(entity, _)| entity)
+            .unwrap();
+        app.world.resource_mut::<MenuFocus>().focused = Some(entity);
+        for _ in 0..presses {
+            press(&mut app, KeyCode::Right);
+        }
+    }
+    let button = app
+        .world
+        .query::<(Entity, &CosmeticButton)>()
+        .iter(&app.world)
+        .find(|(_, button)| button.slot == Slot::Accessory)
+        .map(|(entity, _)| entity)
+        .unwrap();
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+
+    let look = app.world.resource::<Profile>().bird;
+    assert_eq!(look.hue, 60.0);
+    assert_eq!(look.saturation, 0.5);
+    assert_eq!(look.accessory, Accessory::Hat);
+    let label = app
+        .world
+        .query::<(&Localized, &BitmapText)>()
+        .iter(&app.world)
+        .find(|(localized, _)| localized.key == "customize.hue")
+        .map(|(_, text)| text.value.clone());
+    assert_eq!(label.as_deref(), Some("Hue 60"));
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state(&app), AppState::MainMenu);
+    set_state(&mut app, AppState::Game);
+    app.update();
+    let (bird, sprite) = app
+        .world
+        .query_filtered::<(Entity, &Sprite), With<Bird>>()
+        .single(&app.world);
+    assert_eq!(sprite.color, look.tint());
+    let children = app.world.get::<Children>(bird).unwrap();
+    assert!(children
+        .iter()
+        .any(|child| app.world.get::<Accessory>(*child) == Some(&Accessory::Hat)));
+}
+
+#[test]
+fn cosmetics_stay_locked_until_their_achievement() {
+    let mut app = test_app();
+    set_state(&mut app, AppState::MainMenu);
+    set_state(&mut app, AppState::Customize);
+    app.update();
+    let theme_button = |app: &mut App| {
+        app.world
+            .query::<(Entity, &CosmeticButton)>()
+            .iter(&app.world)
+            .find(|(_, button)| button.slot == Slot::Theme)
+            .map(|(entity, _)| entity)
+            .unwrap()
+    };
+    let button = theme_button(&mut app);
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+    assert!(!app.world.resource::<Settings>().night_theme);
+    let hint = app
+        .world
+        .query::<&Localized>()
+        .iter(&app.world)
+        .find(|localized| localized.key == "cosmetic.needs_best")
+        .map(|localized| localized.args.clone());
+    assert_eq!(hint, Some(vec!["50".to_string()]));
+    assert_eq!(
+        app.world.query::<&UnlockToast>().iter(&app.world).count(),
+        0
+    );
+
+    app.world.resource_mut::<Profile>().best = 50;
+    app.update();
+    let toasts: Vec<_> = app
+        .world
+        .query::<&UnlockToast>()
+        .iter(&app.world)
+        .map(|toast| toast.cosmetic)
+        .collect();
+    assert!(toasts.contains(&Cosmetic::NightTheme));
+    assert!(app
+        .world
+        .resource::<Profile>()
+        .unlocked
+        .contains(&Cosmetic::NightTheme));
+
+    // shown once only
+    app.world.resource_mut::<Profile>().coins += 1;
+    app.update();
+    assert_eq!(
+        app.world.query::<&UnlockToast>().iter(&app.world).count(),
+        toasts.len()
+    );
+
+    // round past day to night again, now unlocked
+    let button = theme_button(&mut app);
+    for _ in 0..2 {
+        *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+        app.update();
+        *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::None;
+        app.update();
+    }
+    assert!(app.world.resource::<Settings>().night_theme);
+    assert_eq!(*app.world.resource::<Theme>(), Theme::night());
+}
+
+#[test]
+fn locked_cosmetics_stay_off_when_set_in_the_settings() {
+    let mut app = test_app();
+    let mut settings = app.world.resource_mut::<Settings>();
+    settings.night_theme = true;
+    settings.trail = Some(BirdTrail::gold());
+    let trail = BirdTrail::gold();
+    advance(&mut app, trail.interval * 1.2);
+    assert_eq!(*app.world.resource::<Theme>(), Theme::classic());
+    assert_eq!(app.world.query::<&Afterimage>().iter(&app.world).count(), 0);
+
+    app.world.resource_mut::<Profile>().best = 50;
+    advance(&mut app, trail.interval * 1.2);
+    assert_eq!(*app.world.resource::<Theme>(), Theme::night());
+    assert!(app.world.query::<&Afterimage>().iter(&app.world).count() > 0);
+}
+
+#[test]
+fn double_tapping_dashes_once_unlocked() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().air_dash = true;
+    let double_tap = |app: &mut App| {
+        set_state(app, AppState::GameOver);
+        set_state(app, AppState::Game);
+        press(app, KeyCode::Space);
+        press(app, KeyCode::Space);
+        let dash = app.world.resource::<AirDash>();
+        (dash.remaining > 0.0, dash.charge() < 1.0)
+    };
+    app.world.resource_mut::<Profile>().best = DASH_UNLOCK_SCORE - 1;
+    assert_eq!(double_tap(&mut app), (false, false));
+    assert_eq!(app.world.query::<&DashMeter>().iter(&app.world).count(), 0);
+
+    app.world.resource_mut::<Profile>().best = DASH_UNLOCK_SCORE;
+    assert_eq!(double_tap(&mut app), (true, true));
+    // the whole level speeds up, not just the pipes
+    let pace = app.world.resource::<Pace>().pipe_speed;
+    assert!(app.world.resource::<ScrollSpeed>().0 > pace);
+    let meter = app
+        .world
+        .query_filtered::<&Style, With<DashMeter>>()
+        .single(&app.world);
+    assert!(matches!(meter.size.width, Val::Percent(width) if width < 1.0));
+
+    // no second dash while recharging
+    press(&mut app, KeyCode::Space);
+    press(&mut app, KeyCode::Space);
+    let dash = app.world.resource::<AirDash>();
+    assert!(dash.cooldown < DASH_COOLDOWN && dash.remaining < DASH_SECONDS);
+}
+
+#[test]
+fn co_op_runs_keep_their_own_best_and_dash() {
+    let mut app = test_app();
+    app.world.resource_mut::<Settings>().air_dash = true;
+    app.world.resource_mut::<Profile>().best = DASH_UNLOCK_SCORE;
+    app.world.resource_mut::<CoOp>().0 = true;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(app.world.resource::<RunRecord>().to_beat, 0);
+
+    // a flap from each player isn't a double tap
+    press(&mut app, KeyCode::Space);
+    press(&mut app, KeyCode::Up);
+    assert_eq!(app.world.resource::<AirDash>().remaining, 0.0);
+
+    app.world.resource_mut::<Score>().0 = DASH_UNLOCK_SCORE + 5;
+    app.update();
+    let profile = app.world.resource::<Profile>();
+    assert_eq!(profile.best, DASH_UNLOCK_SCORE);
+    assert_eq!(profile.co_op_best, DASH_UNLOCK_SCORE + 5);
+}
+
+#[test]
+fn magnets_pull_coins_into_the_bird() {
+    let mut app = test_app();
+    let spawn = |app: &mut App, kind, position: Vec3, velocity| {
+        let mut queue = CommandQueue::default();
+        let pickup = {
+            let mut commands = Commands::new(&mut queue, &app.world);
+            let config = app.world.resource::<FlappyConfig>();
+            spawn_pickup(&mut commands, config, kind, position.truncate(), velocity)
+        };
+        queue.apply(&mut app.world);
+        pickup
+    };
+    let bird = bird_position(&mut app);
+    let above = bird + Vec3::new(0.0, 60.0, 0.0);
+    let coin = spawn(&mut app, Pickup::Coin, above, Vec2::new(-2.0, 0.0));
+    spawn(&mut app, Pickup::Magnet, bird, Vec2::ZERO);
+    app.update();
+    assert!(app.world.resource::<Magnet>().remaining > 0.0);
+    assert_eq!(app.world.resource::<Coins>().0, 0);
+
+    app.update();
+    let pull = app.world.get::<Velocity>(coin).unwrap().0;
+    assert!(pull.y < 0.0, "{pull:?}");
+
+    // moved onto the bird
+    let bird = bird_position(&mut app);
+    app.world.get_mut::<Transform>(coin).unwrap().translation = bird;
+    app.update();
+    assert_eq!(app.world.resource::<Coins>().0, 1);
+    assert_eq!(app.world.resource::<Profile>().coins, 1);
+    assert!(app.world.get_entity(coin).is_none());
+}
+
+#[test]
+fn hazards_are_telegraphed_then_deadly() {
+    let mut app = test_app();
+    app.world
+        .resource_mut::<DevConsole>()
+        .submit("hazard icicle");
+    app.update();
+    app.update();
+    let (warning, hazard) = app
+        .world
+        .query::<(Entity, &HazardWarning)>()
+        .iter(&app.world)
+        .map(|(entity, warning)| (entity, warning.hazard))
+        .next()
+        .unwrap();
+    assert_eq!(hazard, Hazard::Icicle);
+    assert!(app
+        .world
+        .query::<&Hazard>()
+        .iter(&app.world)
+        .next()
+        .is_none());
+
+    app.world
+        .get_mut::<HazardWarning>(warning)
+        .unwrap()
+        .timer
+        .set_elapsed(Duration::from_secs_f32(WARNING_SECONDS));
+    app.update();
+    assert!(app.world.get_entity(warning).is_none());
+    let icicle = app
+        .world
+        .query_filtered::<Entity, (With<Hazard>, With<Collider>)>()
+        .single(&app.world);
+    assert!(app.world.get::<Velocity>(icicle).unwrap().0.y < 0.0);
+
+    let bird = bird_position(&mut app);
+    app.world.get_mut::<Transform>(icicle).unwrap().translation = bird;
+    let mut deaths = ManualEventReader::<DeathEvent>::default();
+    let mut causes = Vec::new();
+    for _ in 0..2 {
+        app.update();
+        let events = app.world.resource::<Events<DeathEvent>>();
+        causes.extend(deaths.iter(events).map(|death| death.cause));
+    }
+    assert_eq!(causes, vec![DeathCause::Hazard]);
+}
+
+#[test]
+fn mute_key_silences_everything() {
+    let mut app = test_app();
+    let indicator_visible = |app: &mut App| {
+        *app.world
+            .query_filtered::<&Visibility, With<MuteIndicator>>()
+            .single(&app.world)
+            != Visibility::Hidden
+    };
+    assert!(!indicator_visible(&mut app));
+
+    press(&mut app, KeyCode::M);
+    let volume = app.world.resource::<Settings>().volume;
+    assert!(volume.muted);
+    assert_eq!(volume.gain(AudioChannel::Sfx), 0.0);
+    assert_eq!(volume.gain(AudioChannel::Music), 0.0);
+    assert!(indicator_visible(&mut app));
+
+    press(&mut app, KeyCode::M);
+    assert!(!app.world.resource::<Settings>().volume.muted);
+    assert!(!indicator_visible(&mut app));
+}
+
+#[test]
+fn sound_effects_rotate_and_vary() {
+    let variants: Vec<Handle<AudioSource>> = (0..3)
+        .map(|_| Handle::weak(HandleId::random::<AudioSource>()))
+        .collect();
+    let mut effect = SoundEffect::new(variants.clone());
+    let played: Vec<_> = (0..6).filter_map(|_| effect.next_variant()).collect();
+    assert_eq!(played[..3], variants[..]);
+    assert_eq!(played[3..], variants[..]);
+
+    let rates: Vec<f64> = (0..20).map(|_| effect.variation().playback_rate).collect();
+    let jitter = f64::from(effect.pitch_jitter);
+    assert!(rates.iter().all(|rate| (rate - 1.0).abs() <= jitter + 1e-6));
+    assert!(rates.iter().any(|rate| *rate != rates[0]));
+}
+
+#[test]
+fn haptics_tick_on_points_and_buzz_on_death() {
+    let mut app = test_app();
+    let mut haptics = ManualEventReader::<HapticEvent>::default();
+    let pair = app.world.spawn_empty().id();
+    app.world.send_event(ScoreEvent { pair, points: 1 });
+    app.update();
+    let felt: Vec<_> = haptics
+        .iter(app.world.resource::<Events<HapticEvent>>())
+        .copied()
+        .collect();
+    assert_eq!(felt, vec![HapticEvent::Tick]);
+
+    app.world.resource_mut::<Settings>().haptics = Haptics::Off;
+    app.world.send_event(ScoreEvent { pair, points: 1 });
+    app.update();
+    let events = app.world.resource::<Events<HapticEvent>>();
+    assert_eq!(haptics.iter(events).count(), 0);
+
+    app.world.resource_mut::<Settings>().haptics = Haptics::On;
+    let bird = bird_position(&mut app);
+    spawn_collider(&mut app, bird);
+    let mut felt = Vec::new();
+    for _ in 0..3 {
+        app.update();
+        let events = app.world.resource::<Events<HapticEvent>>();
+        felt.extend(haptics.iter(events).copied());
+    }
+    assert_eq!(felt, vec![HapticEvent::Buzz]);
+}
+
+#[test]
+fn tapping_flaps() {
+    let mut app = test_app();
+    let mut flaps = ManualEventReader::<FlapEvent>::default();
+    tap(&mut app);
+    assert_eq!(
+        flaps
+            .iter(app.world.resource::<Events<FlapEvent>>())
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn suspending_pauses_the_run_until_a_tap() {
+    let mut app = test_app();
+    app.world.send_event(AppLifecycle::Suspended);
+    app.update();
+    app.update();
+    assert_eq!(run_state(&app), RunState::Paused);
+
+    app.world.send_event(AppLifecycle::Resumed);
+    app.update();
+    assert_eq!(run_state(&app), RunState::Paused);
+
+    tap(&mut app);
+    assert_eq!(run_state(&app), RunState::Countdown);
+}
+
+#[test]
+fn hud_keeps_inside_the_safe_area() {
+    let mut app = test_app();
+    app.update();
+    *app.world.resource_mut::<SafeAreaInsets>() = SafeAreaInsets {
+        top: 30.0,
+        left: 10.0,
+        ..default()
+    };
+    app.update();
+
+    let mute = app
+        .world
+        .query_filtered::<&Style, With<MuteIndicator>>()
+        .single(&app.world)
+        .position;
+    assert_eq!((mute.left, mute.bottom), (Val::Px(12.0), Val::Px(2.0)));
+    let tops: Vec<_> = app
+        .world
+        .query_filtered::<&Style, With<SafeArea>>()
+        .iter(&app.world)
+        .map(|style| style.position.top)
+        .collect();
+    assert!(tops.contains(&Val::Px(38.0)), "the score moves down");
+
+    // insets don't pile up
+    app.world.resource_mut::<SafeAreaInsets>().left = 0.0;
+    app.update();
+    let mute = app
+        .world
+        .query_filtered::<&Style, With<MuteIndicator>>()
+        .single(&app.world)
+        .position;
+    assert_eq!(mute.left, Val::Px(2.0));
+}
+
+#[test]
+fn live_board_lists_the_best_scores() {
+    let mut app = test_app();
+    // set after startup, so nothing tries to connect
+    app.world.resource_mut::<FlappyConfig>().live_board = Some(LiveBoardConfig {
+        url: "ws://127.0.0.1:9".to_string(),
+        player: "me".to_string(),
+    });
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    let rows = |app: &mut App| {
+        // one frame to rebuild the rows, one to translate them
+        app.update();
+        app.update();
+        let list = app
+            .world
+            .query_filtered::<&Children, With<LiveBoardList>>()
+            .single(&app.world);
+        list.iter()
+            .filter_map(|row| app.world.get::<BitmapText>(*row))
+            .map(|text| text.value.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(rows(&mut app), ["Offline"]);
+
+    let entry = |player: &str, score| LiveScore {
+        player: player.to_string(),
+        score,
+        playing: true,
+        assisted: player == "ana",
+        modified_physics: player == "cy",
+        co_op: player == "bo",
+    };
+    *app.world.resource_mut::<LiveBoard>() = LiveBoard {
+        connected: true,
+        scores: vec![
+            entry("ana", 3),
+            entry("me", 5),
+            entry("bo", 9),
+            entry("cy", 4),
+        ],
+    };
+    assert_eq!(
+        rows(&mut app),
+        ["bo 9 (2P)", "me 5", "cy 4 (M)", "ana 3 (A)"]
+    );
+}
+
+#[test]
+fn cloud_saves_keep_the_higher_score_and_newer_settings() {
+    let save = |best, difficulty, settings_modified| CloudSave {
+        profile: Profile { best, ..default() },
+        settings: Settings {
+            difficulty,
+            ..default()
+        },
+        settings_modified,
+    };
+    let local = save(12, Difficulty::Hard, 100);
+    let remote = save(30, Difficulty::Easy, 200);
+
+    let merged = local.merged(&remote);
+    assert_eq!(merged, save(30, Difficulty::Easy, 200));
+    assert_eq!(remote.merged(&local), merged);
+    // a tie keeps this side's settings
+    assert_eq!(local.merged(&save(3, Difficulty::Easy, 100)), local);
+
+    // a best no checksum vouched for stays marked, unless matched by one that was
+    let mut unverified = remote.clone();
+    unverified.profile.unverified = true;
+    assert!(local.merged(&unverified).profile.unverified);
+    assert!(!remote.merged(&unverified).profile.unverified);
+}
+
+#[test]
+fn cloud_tokens_come_from_the_environment_not_the_command_line() {
+    std::env::set_var(CLOUD_TOKEN_VAR, " secret\n");
+    let cloud = CloudSyncConfig::with_saved_token("https://example.com/save".to_string());
+    std::env::remove_var(CLOUD_TOKEN_VAR);
+    assert_eq!(cloud.url, "https://example.com/save");
+    assert_eq!(cloud.token, "secret");
+}
+
+#[test]
+fn telemetry_is_only_recorded_once_opted_in_from_settings() {
+    let mut app = test_app();
+    let die = |app: &mut App| {
+        set_state(app, AppState::GameOver);
+        set_state(app, AppState::Game);
+        app.world.resource_mut::<Score>().0 = 3;
+        let bird = bird_position(app);
+        spawn_collider(app, bird);
+        for _ in 0..3 {
+            app.update();
+        }
+    };
+
+    set_state(&mut app, AppState::Settings);
+    let shown = |app: &mut App| {
+        app.world
+            .query_filtered::<Entity, With<TelemetryButton>>()
+            .iter(&app.world)
+            .next()
+    };
+    assert_eq!(shown(&mut app), None);
+    die(&mut app);
+    assert!(app.world.resource::<Telemetry>().pending.is_empty());
+
+    // set after startup, so nothing tries to post
+    app.world.resource_mut::<FlappyConfig>().telemetry_url = Some("http://127.0.0.1:9".to_string());
+    die(&mut app);
+    assert!(!app.world.resource::<Settings>().telemetry);
+    assert!(app.world.resource::<Telemetry>().pending.is_empty());
+
+    set_state(&mut app, AppState::Settings);
+    let button = shown(&mut app).unwrap();
+    *app.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+    app.update();
+    app.update();
+    assert!(app.world.resource::<Settings>().telemetry);
+    let label = app.world.get::<Children>(button).unwrap()[0];
+    assert_eq!(
+        app.world.get::<BitmapText>(label).unwrap().value,
+        "Stats: On"
+    );
+
+    die(&mut app);
+    let pending = &app.world.resource::<Telemetry>().pending;
+    assert!(
+        matches!(
+            pending[..],
+            [TelemetryEvent::RunEnded {
+                score: 3,
+                cause: DeathCause::Pipe,
+                difficulty: Difficulty::Normal,
+                ..
+            }]
+        ),
+        "{pending:?}"
+    );
+}
+
+#[test]
+fn best_marker_shows_once_the_record_falls() {
+    let mut app = test_app();
+    app.world.resource_mut::<Profile>().best = 2;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert_eq!(app.world.resource::<RunRecord>().to_beat, 2);
+    let marker_visible = |app: &mut App| {
+        *app.world
+            .query_filtered::<&Visibility, With<BestMarker>>()
+            .single(&app.world)
+            != Visibility::Hidden
+    };
+
+    app.world.resource_mut::<Score>().0 = 2;
+    app.update();
+    assert!(!marker_visible(&mut app), "tying isn't a record");
+    assert_eq!(app.world.resource::<Profile>().best, 2);
+
+    app.world.resource_mut::<Score>().0 = 3;
+    app.update();
+    assert!(marker_visible(&mut app));
+    assert_eq!(app.world.resource::<Profile>().best, 3);
+    assert_eq!(app.world.resource::<RunRecord>().to_beat, 2);
+}
+
+#[test]
+fn beating_the_best_unfurls_a_ribbon() {
+    let mut app = test_app();
+    app.world.resource_mut::<Profile>().best = 4;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    app.world.resource_mut::<Score>().0 = 5;
+    app.update();
+    game_over(&mut app);
+
+    app.world.resource_mut::<ResultsTimeline>().elapsed = 10.0;
+    app.update();
+    app.update();
+    let ribbons = app.world.query::<&NewBestRibbon>().iter(&app.world).count();
+    assert_eq!(ribbons, 1);
+    let mut new_bests = ManualEventReader::<NewBestEvent>::default();
+    let events = app.world.resource::<Events<NewBestEvent>>();
+    let beaten: Vec<_> = new_bests
+        .iter(events)
+        .map(|event| (event.score, event.previous))
+        .collect();
+    assert_eq!(beaten, vec![(5, 4)]);
+
+    // the next run has 5 to beat
+    set_state(&mut app, AppState::Game);
+    app.world.resource_mut::<Score>().0 = 5;
+    game_over(&mut app);
+    app.world.resource_mut::<ResultsTimeline>().elapsed = 10.0;
+    app.update();
+    let ribbons = app.world.query::<&NewBestRibbon>().iter(&app.world).count();
+    assert_eq!(ribbons, 0);
+}
+
+#[test]
+fn assisted_runs_are_unranked() {
+    let mut app = test_app();
+    app.world.resource_mut::<Profile>().best = 4;
+    app.world.resource_mut::<Settings>().assist.enabled = true;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert!(!app.world.resource::<RunRecord>().ranked);
+    app.world.resource_mut::<Score>().0 = 5;
+    app.update();
+    assert_eq!(app.world.resource::<Profile>().best, 4);
+    game_over(&mut app);
+
+    app.world.resource_mut::<ResultsTimeline>().elapsed = 10.0;
+    app.update();
+    app.update();
+    let ribbons = app.world.query::<&NewBestRibbon>().iter(&app.world).count();
+    assert_eq!(ribbons, 0);
+    let events = app.world.resource::<Events<NewBestEvent>>();
+    assert!(events.is_empty());
+}
+
+#[test]
+fn cheated_runs_are_unranked() {
+    let mut app = test_app();
+    app.world.resource_mut::<Profile>().best = 4;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert!(app.world.resource::<RunRecord>().ranked);
+    app.world.resource_mut::<DevConsole>().submit("score 9");
+    app.update();
+    assert!(!app.world.resource::<RunRecord>().ranked);
+    assert_eq!(app.world.resource::<Profile>().best, 4);
+
+    // invincible partway through
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert!(app.world.resource::<RunRecord>().ranked);
+    app.world.resource_mut::<DevConsole>().submit("god on");
+    app.update();
+    app.world.resource_mut::<Score>().0 = 5;
+    app.update();
+    assert_eq!(app.world.resource::<Profile>().best, 4);
+
+    // and from the start of the next run
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert!(!app.world.resource::<RunRecord>().ranked);
+}
+
+#[test]
+fn runs_with_changed_physics_are_unranked_and_coded() {
+    let mut app = test_app();
+    app.world.resource_mut::<Profile>().best = 4;
+    app.world.resource_mut::<FlappyConfig>().physics.gravity = 7.5;
+    set_state(&mut app, AppState::GameOver);
+    set_state(&mut app, AppState::Game);
+    assert!(!app.world.resource::<RunRecord>().ranked);
+    app.world.resource_mut::<Score>().0 = 5;
+    app.update();
+    assert_eq!(app.world.resource::<Profile>().best, 4);
+    game_over(&mut app);
+    let code = app
+        .world
+        .query::<&Localized>()
+        .iter(&app.world)
+        .find(|localized| localized.key == "challenge.code")
+        .map(|localized| localized.args[0].clone())
+        .unwrap();
+    assert!(code.ends_with("-PBAA"), "{code}");
+
+    // the code plays with the same physics
+    app.world.resource_mut::<FlappyConfig>().physics.gravity = 7.0;
+    set_state(&mut app, AppState::Challenge);
+    for char in code.chars() {
+        app.world.send_event(ReceivedCharacter {
+            window: Entity::PLACEHOLDER,
+            char,
+        });
+    }
+    app.update();
+    press(&mut app, KeyCode::Return);
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(app.world.resource::<FlappyConfig>().physics.gravity, 7.5);
+}
+
+#[test]
+fn runs_are_broken_down_on_the_game_over_screen() {
+    let mut app = test_app();
+    let bird = bird_position(&mut app);
+    let behind = bird.x - PIPE_SIZE.x * 2.0;
+    let first = spawn_pair(&mut app, bird.x);
+    for _ in 0..3 {
+        app.update();
+    }
+    app.world.get_mut::<Transform>(first).unwrap().translation.x = behind;
+    for _ in 0..3 {
+        app.update();
+    }
+    let stats = app.world.resource::<RunStats>();
+    assert_eq!((stats.pipes, stats.near_misses), (1, 0));
+    assert_eq!(stats.accuracy(), Some(1.0), "dead centre");
+
+    // into the middle of the next gap, then up just under its top pipe
+    let hitbox = app
+        .world
+        .query_filtered::<&Hitbox, With<Bird>>()
+        .single(&app.world)
+        .size;
+    let bird_entity = app
+        .world
+        .query_filtered::<Entity, With<Bird>>()
+        .single(&app.world);
+    let second = spawn_pair(&mut app, bird.x);
+    for _ in 0..3 {
+        app.update();
+    }
+    let edge = 50.0 - hitbox.y / 2.0 - NEAR_MISS_MARGIN / 2.0;
+    app.world
+        .get_mut::<Transform>(bird_entity)
+        .unwrap()
+        .translation
+        .y = edge;
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(state(&app), AppState::Game);
+    let stats = app.world.resource::<RunStats>();
+    assert_eq!((stats.pipes, stats.near_misses), (2, 0), "not past yet");
+    app.world
+        .get_mut::<Transform>(second)
+        .unwrap()
+        .translation
+        .x = behind;
+    for _ in 0..3 {
+        app.update();
+    }
+    let stats = app.world.resource::<RunStats>();
+    assert_eq!((stats.pipes, stats.near_misses), (2, 1));
+    assert_eq!(stats.accuracy(), Some(1.0), "entered dead centre");
+
+    // and a hazard, just overhead
+    let above = edge + hitbox.y / 2.0 + Hazard::Icicle.size().y / 2.0 + 1.0;
+    let icicle = app
+        .world
+        .spawn((
+            TransformBundle::from_transform(Transform::from_xyz(bird.x, above, 0.0)),
+            Hazard::Icicle,
+            Collider {
+                size: Hazard::Icicle.size(),
+            },
+            Clearance::default(),
+        ))
+        .id();
+    app.update();
+    app.world
+        .get_mut::<Transform>(icicle)
+        .unwrap()
+        .translation
+        .x = behind;
+    app.update();
+    assert_eq!(state(&app), AppState::Game);
+    assert_eq!(app.world.resource::<RunStats>().near_misses, 2);
+
+    game_over(&mut app);
+    app.world.resource_mut::<ResultsTimeline>().elapsed = 10.0;
+    app.update();
+    app.update();
+    let line = |app: &mut App, key: &str| {
+        app.world
+            .query::<(&Localized, &BitmapText)>()
+            .iter(&app.world)
+            .find(|(localized, _)| localized.key == key)
+            .map(|(_, text)| text.value.clone())
+    };
+    assert_eq!(line(&mut app, "results.pipes").unwrap(), "Pipes 2");
+    assert_eq!(
+        line(&mut app, "results.near_misses").unwrap(),
+        "Close calls 2"
+    );
+
+    set_state(&mut app, AppState::Game);
+    assert_eq!(*app.world.resource::<RunStats>(), RunStats::default());
+    assert!(line(&mut app, "results.pipes").is_none());
+}
+
+#[test]
+fn digits_compose_numbers_from_sprites() {
+    let mut app = test_app();
+    let number = app
+        .world
+        .spawn((
+            SpatialBundle::default(),
+            Digits {
+                value: 42,
+                scale: 2.0,
+            },
+        ))
+        .id();
+    app.update();
+
+    let children = app.world.get::<Children>(number).unwrap().to_vec();
+    let font = app.world.resource::<DigitFont>();
+    let textures: Vec<_> = children
+        .iter()
+        .map(|child| app.world.get::<Handle<Image>>(*child).unwrap().clone())
+        .collect();
+    assert_eq!(
+        textures,
+        vec![font.digits[4].clone(), font.digits[2].clone()]
+    );
+    let xs: Vec<_> = children
+        .iter()
+        .map(|child| app.world.get::<Transform>(*child).unwrap().translation.x)
+        .collect();
+    assert_eq!(xs[0], -xs[1], "centred on the parent");
+
+    app.world.get_mut::<Digits>(number).unwrap().value = 7;
+    app.update();
+    assert_eq!(app.world.get::<Children>(number).unwrap().len(), 1);
+}
+
+#[test]
+fn stereo_pan_leans_towards_the_sound() {
+    assert_eq!(stereo_pan(0.0, 288.0), 0.5);
+    assert!(stereo_pan(40.0, 288.0) > 0.5);
+    assert!(stereo_pan(-40.0, 288.0) < 0.5);
+    // never hard left or right, however far off
+    assert_eq!(stereo_pan(144.0, 288.0), 0.75);
+    assert_eq!(stereo_pan(-1000.0, 288.0), 0.25);
+}
+
+#[test]
+fn music_crossfades_between_screens() {
+    let mut app = test_app();
+    app.world.resource_mut::<FlappyConfig>().music_crossfade = 0.05;
+    assert_eq!(
+        app.world.resource::<Music>().current,
+        Some(MusicTrack::Game)
+    );
+
+    set_state(&mut app, AppState::GameOver);
+    app.update();
+    let music = app.world.resource::<Music>();
+    assert_eq!(music.current, Some(MusicTrack::GameOverSting));
+    assert!(music.level(MusicTrack::GameOverSting) < 1.0);
+    assert_eq!(music.playing.len(), 2, "both tracks play while fading");
+
+    advance(&mut app, 0.06);
+    let music = app.world.resource::<Music>();
+    assert_eq!(music.level(MusicTrack::GameOverSting), 1.0);
+    assert_eq!(music.playing.len(), 1, "the faded out track stops");
+}
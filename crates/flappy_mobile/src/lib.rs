@@ -0,0 +1,16 @@
+//! Entry point on Android and iOS, where the game is loaded as a library
+//! rather than run from `flappy_game`'s `main.rs`. Builds for other targets
+//! have nothing to run.
+
+use bevy::prelude::*;
+use flappy_game::{install_panic_hook, standalone_app, standalone_config, StandaloneOptions};
+
+#[bevy_main]
+fn main() {
+    install_panic_hook();
+    let options = StandaloneOptions {
+        fullscreen: true,
+        ..default()
+    };
+    standalone_app(standalone_config(), options).run();
+}